@@ -0,0 +1,276 @@
+use backend::api::{
+    admin::{AuditEntryResponse, AuditQueryParams, BulkAuditEntryResponse},
+    extractors::etag,
+    idempotency::IDEMPOTENCY_KEY,
+    post::{
+        BulkPostResponse, CreatePostRequest, POSTS_PATH, PostPageParams, PostResponse, TRASH_PATH,
+        UpdatePostRequest,
+    },
+    responses::{PROBLEM_JSON, ProblemDetails},
+};
+use backend::domain::models::{
+    audit::{AUDIT_PAGE_SIZE, MAX_AUDIT_PAGE_SIZE},
+    post::MAX_POST_PAGE_SIZE,
+};
+use backend::ids::PostId;
+use futures::{Stream, TryStreamExt, stream};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, header};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::{errors::ClientError, retry::RetryPolicy};
+
+const AUDIT_PATH: &str = "/admin/audit";
+
+/// Async client of the blog API, authenticated with an API token.
+#[derive(Debug, Clone)]
+pub struct BlogClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    retry_policy: RetryPolicy,
+}
+
+impl BlogClient {
+    /// A client of the API at `base_url`, e.g. `https://blog.example.com`.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: None,
+            retry_policy: RetryPolicy::new(),
+        }
+    }
+
+    /// Sends the API token as a bearer token with every request.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Uses a preconfigured client, e.g. one with timeouts or a proxy.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub async fn create_post(
+        &self,
+        input: &CreatePostRequest,
+    ) -> Result<PostResponse, ClientError> {
+        // Retries carry the same key, so a post is created at most once.
+        let request = self
+            .request(Method::POST, POSTS_PATH)
+            .header(IDEMPOTENCY_KEY, Uuid::new_v4().to_string())
+            .json(input);
+        self.send(request, true).await
+    }
+
+    pub async fn get_all_posts(&self) -> Result<Vec<PostResponse>, ClientError> {
+        let request = self.request(Method::GET, POSTS_PATH);
+        self.send::<BulkPostResponse>(request, true)
+            .await
+            .map(|posts| posts.data)
+    }
+
+    /// Every post, oldest first, fetched `page_size` at a time as the
+    /// previous page runs out.
+    pub fn posts(
+        &self,
+        page_size: i64,
+    ) -> impl Stream<Item = Result<PostResponse, ClientError>> + '_ {
+        let first = PostPageParams {
+            after: None,
+            limit: Some(page_size.clamp(1, MAX_POST_PAGE_SIZE)),
+        };
+        let pages = stream::try_unfold(Some(first), move |params| async move {
+            let Some(mut params) = params else {
+                return Ok::<_, ClientError>(None);
+            };
+            let request = self.request(Method::GET, POSTS_PATH).query(&params);
+            let page = self.send::<BulkPostResponse>(request, true).await?;
+
+            let next = page.next_cursor.map(|cursor| {
+                params.after = Some(cursor);
+                params
+            });
+            Ok(Some((stream::iter(page.data.into_iter().map(Ok)), next)))
+        });
+
+        pages.try_flatten()
+    }
+
+    pub async fn get_post_by_id(&self, id: PostId) -> Result<PostResponse, ClientError> {
+        let request = self.request(Method::GET, &post_path(id));
+        self.send(request, true).await
+    }
+
+    /// Updates the post as last read at `version`, see
+    /// [`PostResponse::version`].
+    pub async fn update_post(
+        &self,
+        id: PostId,
+        version: i64,
+        input: &UpdatePostRequest,
+    ) -> Result<PostResponse, ClientError> {
+        let request = self
+            .request(Method::PATCH, &post_path(id))
+            .header(header::IF_MATCH, etag(version))
+            .json(input);
+        self.send(request, false).await
+    }
+
+    /// Moves the post as last read at `version` to the trash.
+    pub async fn delete_post(&self, id: PostId, version: i64) -> Result<(), ClientError> {
+        let request = self
+            .request(Method::DELETE, &post_path(id))
+            .header(header::IF_MATCH, etag(version));
+        self.send_empty(request, false).await
+    }
+
+    pub async fn delete_post_permanently(
+        &self,
+        id: PostId,
+        version: i64,
+    ) -> Result<(), ClientError> {
+        let request = self
+            .request(Method::DELETE, &post_path(id))
+            .header(header::IF_MATCH, etag(version))
+            .query(&[("permanent", true)]);
+        self.send_empty(request, false).await
+    }
+
+    pub async fn get_trash(&self) -> Result<Vec<PostResponse>, ClientError> {
+        let request = self.request(Method::GET, TRASH_PATH);
+        self.send::<BulkPostResponse>(request, true)
+            .await
+            .map(|posts| posts.data)
+    }
+
+    pub async fn restore_post(&self, id: PostId) -> Result<PostResponse, ClientError> {
+        let request = self
+            .request(Method::POST, &format!("{}/restore", post_path(id)))
+            .header(IDEMPOTENCY_KEY, Uuid::new_v4().to_string());
+        self.send(request, true).await
+    }
+
+    /// Audit entries matching `query`, newest first, fetching the next page
+    /// as the previous one runs out.
+    pub fn audit_entries(
+        &self,
+        query: AuditQueryParams,
+    ) -> impl Stream<Item = Result<AuditEntryResponse, ClientError>> + '_ {
+        // The server clamps the page size, so a page is only short of the
+        // size it was actually given at the end.
+        let limit = query
+            .limit
+            .unwrap_or(AUDIT_PAGE_SIZE)
+            .clamp(1, MAX_AUDIT_PAGE_SIZE);
+        let query = AuditQueryParams {
+            limit: Some(limit),
+            ..query
+        };
+        let pages = stream::try_unfold(Some(query), move |query| async move {
+            let Some(mut query) = query else {
+                return Ok::<_, ClientError>(None);
+            };
+            let request = self.request(Method::GET, AUDIT_PATH).query(&query);
+            let page = self
+                .send::<BulkAuditEntryResponse>(request, true)
+                .await?
+                .data;
+
+            let next = match page.last() {
+                Some(last) if page.len() as i64 >= limit => {
+                    query.before_id = Some(last.id);
+                    Some(query)
+                }
+                _ => None,
+            };
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+        });
+
+        pages.try_flatten()
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self
+            .http
+            .request(method, format!("{}{path}", self.base_url));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        replay_safe: bool,
+    ) -> Result<T, ClientError> {
+        let response = self.execute(request, replay_safe).await?;
+        Ok(response.json().await?)
+    }
+
+    async fn send_empty(
+        &self,
+        request: RequestBuilder,
+        replay_safe: bool,
+    ) -> Result<(), ClientError> {
+        self.execute(request, replay_safe).await.map(|_| ())
+    }
+
+    /// Sends the request, retrying as the [`RetryPolicy`] allows, and turns
+    /// error responses into a [`ClientError`].
+    async fn execute(
+        &self,
+        request: RequestBuilder,
+        replay_safe: bool,
+    ) -> Result<Response, ClientError> {
+        let mut retry = 0;
+        loop {
+            let attempt = request
+                .try_clone()
+                .expect("Requests have no streaming bodies");
+            let retries_left = retry < self.retry_policy.max_retries();
+
+            match attempt.send().await {
+                Ok(response)
+                    if retries_left
+                        && self
+                            .retry_policy
+                            .should_retry_status(response.status(), replay_safe) => {}
+                Ok(response) => return error_for_status(response).await,
+                Err(err)
+                    if retries_left && self.retry_policy.should_retry_error(&err, replay_safe) => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            tokio::time::sleep(self.retry_policy.backoff(retry)).await;
+            retry += 1;
+        }
+    }
+}
+
+fn post_path(id: PostId) -> String {
+    format!("{POSTS_PATH}/{id}")
+}
+
+async fn error_for_status(response: Response) -> Result<Response, ClientError> {
+    let status = response.status();
+    if status.is_success() || status == StatusCode::NOT_MODIFIED {
+        return Ok(response);
+    }
+
+    let is_problem = response.headers().get(header::CONTENT_TYPE) == Some(&PROBLEM_JSON);
+    let body = response.text().await?;
+    match serde_json::from_str::<ProblemDetails>(&body) {
+        Ok(problem) if is_problem => Err(problem.into()),
+        _ => Err(ClientError::UnexpectedResponse { status, body }),
+    }
+}
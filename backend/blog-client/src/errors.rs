@@ -0,0 +1,87 @@
+use backend::api::{responses::ErrorCode, validation::FieldError};
+use reqwest::StatusCode;
+use thiserror::Error;
+
+pub use backend::api::responses::ProblemDetails;
+
+/// Failures of a call to the API. Problems the server reports are typed by
+/// their `code`; each variant keeps the full problem for its `detail` and
+/// `request_id`.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("{}", .0.detail)]
+    PostNotFound(Box<ProblemDetails>),
+    #[error("{}", .0.detail)]
+    PostDuplicate(Box<ProblemDetails>),
+    #[error("{}", .0.detail)]
+    AuthorNotFound(Box<ProblemDetails>),
+    /// The post changed since the version sent in `If-Match`.
+    #[error("{}", .0.detail)]
+    VersionConflict(Box<ProblemDetails>),
+    #[error("{}", .0.detail)]
+    InvalidFields(Box<ProblemDetails>),
+    #[error("{}", .0.detail)]
+    Unauthenticated(Box<ProblemDetails>),
+    #[error("{}", .0.detail)]
+    Forbidden(Box<ProblemDetails>),
+    /// Any other problem.
+    #[error("{}", .0.detail)]
+    Problem(Box<ProblemDetails>),
+    /// An error response that is not a problem, e.g. from a proxy.
+    #[error("Unexpected response with status {status}")]
+    UnexpectedResponse { status: StatusCode, body: String },
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl ClientError {
+    pub fn problem(&self) -> Option<&ProblemDetails> {
+        use ClientError::*;
+
+        match self {
+            PostNotFound(problem)
+            | PostDuplicate(problem)
+            | AuthorNotFound(problem)
+            | VersionConflict(problem)
+            | InvalidFields(problem)
+            | Unauthenticated(problem)
+            | Forbidden(problem)
+            | Problem(problem) => Some(problem),
+            UnexpectedResponse { .. } | Http(_) => None,
+        }
+    }
+
+    /// The invalid fields of an [`ClientError::InvalidFields`].
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            Self::InvalidFields(problem) => &problem.errors,
+            _ => &[],
+        }
+    }
+}
+
+impl From<ProblemDetails> for ClientError {
+    fn from(problem: ProblemDetails) -> Self {
+        let is = |codes: &[ErrorCode]| codes.iter().any(|code| code.as_str() == problem.code);
+
+        let variant = if is(&[ErrorCode::PostNotFound, ErrorCode::TrashedPostNotFound]) {
+            Self::PostNotFound
+        } else if is(&[ErrorCode::PostDuplicate, ErrorCode::PostTitleTaken]) {
+            Self::PostDuplicate
+        } else if is(&[ErrorCode::PostAuthorNotFound, ErrorCode::AuthorNotFound]) {
+            Self::AuthorNotFound
+        } else if is(&[ErrorCode::VersionConflict]) {
+            Self::VersionConflict
+        } else if is(&[ErrorCode::ValidationFailed]) {
+            Self::InvalidFields
+        } else if is(&[ErrorCode::Unauthenticated, ErrorCode::ApiTokenInvalid]) {
+            Self::Unauthenticated
+        } else if is(&[ErrorCode::Forbidden, ErrorCode::ScopeMissing]) {
+            Self::Forbidden
+        } else {
+            Self::Problem
+        };
+
+        variant(Box::new(problem))
+    }
+}
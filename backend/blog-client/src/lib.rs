@@ -0,0 +1,15 @@
+//! Typed async client of the blog API, for internal tools.
+//!
+//! Requests and responses are the DTOs of the `backend` crate itself, so the
+//! two cannot drift apart.
+
+pub mod client;
+pub mod errors;
+pub mod retry;
+
+pub use backend::api::admin::{AuditEntryResponse, AuditQueryParams};
+pub use backend::api::post::{CreatePostRequest, PostResponse, UpdatePostRequest};
+pub use backend::ids::{AuthorId, PostId, UserId};
+pub use client::BlogClient;
+pub use errors::ClientError;
+pub use retry::RetryPolicy;
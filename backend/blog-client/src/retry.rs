@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+/// How often and how long to wait before a failed request is sent again.
+///
+/// A request that never reached the server is always retried. One that may
+/// have been processed is only retried if sending it again is safe: reads,
+/// and creates, which carry an `Idempotency-Key` so the server replays the
+/// first response. Changes guarded by `If-Match` are not, as a retry after a
+/// lost response would fail with a version conflict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    /// Sends every request once.
+    pub fn never() -> Self {
+        Self::new().with_max_retries(0)
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The wait doubles after every attempt, from `initial` up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// The wait before retry number `retry`, counting from 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    pub(crate) fn should_retry_error(&self, error: &reqwest::Error, replay_safe: bool) -> bool {
+        error.is_connect() || (replay_safe && (error.is_timeout() || error.is_request()))
+    }
+
+    pub(crate) fn should_retry_status(&self, status: StatusCode, replay_safe: bool) -> bool {
+        replay_safe
+            && matches!(
+                status,
+                StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            )
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy =
+            RetryPolicy::new().with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+
+        let waits: Vec<_> = (0..5).map(|retry| policy.backoff(retry)).collect();
+
+        assert_eq!(waits, [100, 200, 400, 500, 500].map(Duration::from_millis));
+    }
+}
@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+use std::collections::BTreeSet;
+
+#[path = "../../tests/common/fixture.rs"]
+mod fixture;
+
+use backend::{
+    api::cache::CacheConfig,
+    domain::{
+        models::{
+            api_token::{ApiTokenName, CreateApiTokenRequest, Scope},
+            post::PostConstraints,
+            user::Role,
+        },
+        service::Service,
+    },
+    server::{DEFAULT_SSE_HEARTBEAT, HttpServer, HttpServerConfig},
+};
+use blog_client::{BlogClient, RetryPolicy};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+pub use fixture::{TEST_USER_EMAIL, TestFixture};
+
+/// The real router served on a local port, with an admin holding an API
+/// token with every scope.
+pub struct TestServer {
+    pub base_url: String,
+    pub token: String,
+    pub fixture: TestFixture,
+    server: JoinHandle<()>,
+}
+
+impl TestServer {
+    pub async fn new() -> Self {
+        let fixture = TestFixture::new().await;
+        let user = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+        let token = fixture
+            .service
+            .create_api_token(
+                &user,
+                &CreateApiTokenRequest::new(
+                    ApiTokenName::try_new("blog-client tests").unwrap(),
+                    BTreeSet::from(Scope::ALL),
+                    None,
+                ),
+            )
+            .await
+            .expect("Failed to create API token.");
+
+        let config = HttpServerConfig {
+            port: "0",
+            secure_cookies: false,
+            oidc: None,
+            cache: CacheConfig::default(),
+            post_constraints: PostConstraints::new(),
+            graphiql: false,
+            sse_heartbeat: DEFAULT_SSE_HEARTBEAT,
+            metrics: fixture.metrics.clone(),
+            metrics_route: false,
+        };
+        let router = HttpServer::try_new(fixture.service.clone(), config)
+            .await
+            .expect("Failed to create server.")
+            .router;
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind test server.");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        Self {
+            base_url,
+            token: token.secret().expose().to_string(),
+            fixture,
+            server,
+        }
+    }
+
+    /// A client authenticated with the admin's token.
+    pub fn client(&self) -> BlogClient {
+        self.anonymous_client().with_token(&self.token)
+    }
+
+    pub fn anonymous_client(&self) -> BlogClient {
+        BlogClient::new(&self.base_url).with_retry_policy(RetryPolicy::never())
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::time::Duration;
+
+use axum::{
+    Json, Router,
+    extract::Query,
+    http::StatusCode,
+    routing::{get, patch},
+};
+use blog_client::{
+    AuditQueryParams, BlogClient, ClientError, CreatePostRequest, PostId, RetryPolicy,
+    UpdatePostRequest,
+};
+use common::TestServer;
+use futures::TryStreamExt;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+fn create_request(title: &str) -> CreatePostRequest {
+    CreatePostRequest {
+        title: title.to_string(),
+        body: "Body".to_string(),
+        authors: None,
+    }
+}
+
+#[tokio::test]
+async fn test_post_round_trip() {
+    // Arrange
+    let server = TestServer::new().await;
+    let client = server.client();
+
+    // Act
+    let created = client.create_post(&create_request("First")).await.unwrap();
+    let fetched = client.get_post_by_id(created.id).await.unwrap();
+    let updated = client
+        .update_post(
+            created.id,
+            created.version,
+            &UpdatePostRequest {
+                title: Some("First, revised".to_string()),
+                body: None,
+                authors: None,
+            },
+        )
+        .await
+        .unwrap();
+    let listed = client.get_all_posts().await.unwrap();
+    client
+        .delete_post(updated.id, updated.version)
+        .await
+        .unwrap();
+    let trash = client.get_trash().await.unwrap();
+
+    // Assert
+    assert_eq!(fetched, created);
+    assert_eq!(updated.title, "First, revised");
+    assert_eq!(listed, vec![updated.clone()]);
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].id, created.id);
+    assert!(client.get_all_posts().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_problems_are_typed_errors() {
+    // Arrange
+    let server = TestServer::new().await;
+    let client = server.client();
+    let post = client.create_post(&create_request("Taken")).await.unwrap();
+    client
+        .update_post(
+            post.id,
+            post.version,
+            &UpdatePostRequest {
+                title: None,
+                body: Some("Changed".to_string()),
+                authors: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let not_found = client.get_post_by_id(PostId::new()).await.unwrap_err();
+    let duplicate = client
+        .create_post(&create_request("Taken"))
+        .await
+        .unwrap_err();
+    let stale = client.delete_post(post.id, post.version).await.unwrap_err();
+    let invalid = client.create_post(&create_request(" ")).await.unwrap_err();
+    let anonymous = server
+        .anonymous_client()
+        .create_post(&create_request("Anonymous"))
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert!(matches!(not_found, ClientError::PostNotFound(_)));
+    assert!(matches!(duplicate, ClientError::PostDuplicate(_)));
+    assert!(matches!(stale, ClientError::VersionConflict(_)));
+    assert!(matches!(invalid, ClientError::InvalidFields(_)));
+    assert_eq!(invalid.field_errors()[0].pointer, "/title");
+    assert!(matches!(anonymous, ClientError::Unauthenticated(_)));
+    assert!(not_found.problem().unwrap().request_id.is_some());
+}
+
+#[tokio::test]
+async fn test_audit_entries_follow_pages() {
+    // Arrange
+    let server = TestServer::new().await;
+    let client = server.client();
+    for title in ["One", "Two", "Three"] {
+        client.create_post(&create_request(title)).await.unwrap();
+    }
+
+    // Act
+    let entries: Vec<_> = client
+        .audit_entries(AuditQueryParams {
+            limit: Some(2),
+            ..Default::default()
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(entries.len(), 3);
+    assert!(entries.windows(2).all(|pair| pair[0].id > pair[1].id));
+}
+
+#[tokio::test]
+async fn test_posts_follow_pages() {
+    // Arrange
+    let server = TestServer::new().await;
+    let client = server.client();
+    for title in ["One", "Two", "Three"] {
+        client.create_post(&create_request(title)).await.unwrap();
+    }
+
+    // Act
+    let posts: Vec<_> = client.posts(2).try_collect().await.unwrap();
+
+    // Assert
+    let titles: Vec<_> = posts.iter().map(|post| post.title.as_str()).collect();
+    assert_eq!(titles, ["One", "Two", "Three"]);
+}
+
+/// Serves `router` on a local port, for responses the real router does not
+/// give.
+async fn stub(router: Router) -> BlogClient {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+    BlogClient::new(&base_url).with_retry_policy(
+        RetryPolicy::new()
+            .with_max_retries(2)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1)),
+    )
+}
+
+#[tokio::test]
+async fn test_audit_pages_are_no_larger_than_the_server_serves() {
+    // Arrange
+    let limits = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = limits.clone();
+    let client = stub(Router::new().route(
+        "/admin/audit",
+        get(move |Query(params): Query<AuditQueryParams>| async move {
+            seen.lock().unwrap().push(params.limit);
+            Json(json!({ "data": [] }))
+        }),
+    ))
+    .await;
+
+    // Act
+    let entries: Vec<_> = client
+        .audit_entries(AuditQueryParams {
+            limit: Some(5000),
+            ..Default::default()
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+    // Assert
+    assert!(entries.is_empty());
+    assert_eq!(*limits.lock().unwrap(), [Some(1000)]);
+}
+
+#[tokio::test]
+async fn test_reads_are_retried_while_unavailable() {
+    // Arrange
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let client = stub(Router::new().route(
+        "/posts",
+        get(move || async move {
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(StatusCode::SERVICE_UNAVAILABLE),
+                _ => Ok(Json(json!({ "data": [] }))),
+            }
+        }),
+    ))
+    .await;
+
+    // Act
+    let posts = client.get_all_posts().await.unwrap();
+
+    // Assert
+    assert!(posts.is_empty());
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_conditional_changes_are_not_retried() {
+    // Arrange
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let client = stub(Router::new().route(
+        "/posts/{post_id}",
+        patch(move || async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            StatusCode::SERVICE_UNAVAILABLE
+        }),
+    ))
+    .await;
+
+    // Act
+    let err = client
+        .update_post(
+            PostId::new(),
+            1,
+            &UpdatePostRequest {
+                title: Some("Title".to_string()),
+                body: None,
+                authors: None,
+            },
+        )
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert!(matches!(
+        err,
+        ClientError::UnexpectedResponse {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            ..
+        }
+    ));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
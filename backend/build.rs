@@ -0,0 +1,12 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Builds do not depend on a protoc installed on the machine.
+    if std::env::var_os("PROTOC").is_none() {
+        // SAFETY: build scripts are single-threaded.
+        unsafe { std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?) };
+    }
+    // New migrations are built into the crate.
+    println!("cargo:rerun-if-changed=migrations");
+    tonic_build::configure().compile_protos(&["proto/blog/v1/blog.proto"], &["proto"])?;
+
+    Ok(())
+}
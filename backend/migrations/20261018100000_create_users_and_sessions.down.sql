@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE sessions;
+DROP TABLE users;
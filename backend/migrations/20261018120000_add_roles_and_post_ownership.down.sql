@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP INDEX posts_created_by_idx;
+ALTER TABLE posts DROP COLUMN created_by;
+ALTER TABLE users DROP COLUMN role;
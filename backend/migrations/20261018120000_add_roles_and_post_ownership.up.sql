@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+-- Accounts created before roles existed belong to the blog owner.
+ALTER TABLE users
+    ADD COLUMN role TEXT NOT NULL DEFAULT 'admin'
+    CHECK (role IN ('admin', 'editor', 'author', 'reader'));
+
+ALTER TABLE users ALTER COLUMN role SET DEFAULT 'reader';
+
+ALTER TABLE posts
+    ADD COLUMN created_by UUID REFERENCES users (id) ON DELETE SET NULL;
+
+CREATE INDEX posts_created_by_idx ON posts (created_by);
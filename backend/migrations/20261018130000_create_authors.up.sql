@@ -0,0 +1,21 @@
+-- Add up migration script here
+
+CREATE TABLE authors (
+    id UUID PRIMARY KEY,
+    user_id UUID UNIQUE REFERENCES users (id) ON DELETE SET NULL,
+    display_name TEXT NOT NULL,
+    bio TEXT,
+    avatar_url TEXT,
+    social_links JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE post_authors (
+    post_id UUID NOT NULL REFERENCES posts (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES authors (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (post_id, author_id),
+    UNIQUE (post_id, position)
+);
+
+CREATE INDEX post_authors_author_id_idx ON post_authors (author_id);
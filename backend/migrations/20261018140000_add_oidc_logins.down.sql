@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+DROP TABLE oidc_logins;
+DROP TABLE user_identities;
+DELETE FROM users WHERE password_hash IS NULL;
+ALTER TABLE users ALTER COLUMN password_hash SET NOT NULL;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE sessions DROP COLUMN second_factor_pending;
+DROP TABLE totp_recovery_codes;
+DROP TABLE user_totp;
@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+CREATE TABLE user_totp (
+    user_id UUID PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    secret BYTEA NOT NULL,
+    -- NULL until the user proves their app is set up by entering a code.
+    confirmed_at TIMESTAMPTZ,
+    -- Codes for this time step or earlier are rejected, so a code cannot be
+    -- replayed within its validity window.
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE totp_recovery_codes (
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    code_hash BYTEA NOT NULL,
+    used_at TIMESTAMPTZ,
+    PRIMARY KEY (user_id, code_hash)
+);
+
+-- Sessions started with a password only, waiting for a one-time code.
+ALTER TABLE sessions ADD COLUMN second_factor_pending BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE audit_log;
+DROP FUNCTION reject_audit_log_change;
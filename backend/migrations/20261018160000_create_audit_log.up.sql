@@ -0,0 +1,35 @@
+-- Add up migration script here
+
+-- Append-only record of every change to a post. Actor and post are kept
+-- without foreign keys so that entries outlive the rows they describe.
+CREATE TABLE audit_log (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    actor_id UUID,
+    action TEXT NOT NULL,
+    post_id UUID NOT NULL,
+    before_snapshot JSONB,
+    after_snapshot JSONB,
+    request_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    -- Each entry hashes its content together with the hash of the entry
+    -- before it, so any edit breaks the chain from that point on.
+    prev_hash BYTEA NOT NULL,
+    hash BYTEA NOT NULL UNIQUE
+);
+
+CREATE INDEX audit_log_actor_id_idx ON audit_log (actor_id, id);
+CREATE INDEX audit_log_post_id_idx ON audit_log (post_id, id);
+
+CREATE FUNCTION reject_audit_log_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_log_change();
+
+CREATE TRIGGER audit_log_no_truncate
+    BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_log_change();
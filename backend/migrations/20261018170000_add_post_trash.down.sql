@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+DELETE FROM posts WHERE deleted_at IS NOT NULL;
+DROP INDEX posts_deleted_at_idx;
+DROP INDEX posts_title_key;
+ALTER TABLE posts ADD CONSTRAINT posts_title_key UNIQUE (title);
+ALTER TABLE posts DROP COLUMN deleted_at;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+
+-- Deleted posts stay in the trash until they are restored or purged.
+ALTER TABLE posts ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- Titles only have to be unique among posts that are not in the trash.
+ALTER TABLE posts DROP CONSTRAINT posts_title_key;
+CREATE UNIQUE INDEX posts_title_key ON posts (title) WHERE deleted_at IS NULL;
+
+CREATE INDEX posts_deleted_at_idx ON posts (deleted_at) WHERE deleted_at IS NOT NULL;
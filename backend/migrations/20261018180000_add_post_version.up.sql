@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- Goes up with every change to a post; served as its ETag so that clients
+-- cannot overwrite changes they have not seen.
+ALTER TABLE posts ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
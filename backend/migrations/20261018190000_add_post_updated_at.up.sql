@@ -0,0 +1,8 @@
+-- Add up migration script here
+
+-- When a post last changed, served as its Last-Modified.
+ALTER TABLE posts ADD COLUMN updated_at TIMESTAMPTZ;
+UPDATE posts SET updated_at = created_at;
+ALTER TABLE posts
+    ALTER COLUMN updated_at SET NOT NULL,
+    ALTER COLUMN updated_at SET DEFAULT now();
@@ -0,0 +1,17 @@
+-- Add up migration script here
+
+-- Domain events written in the transaction of the change they describe and
+-- removed once relayed. Only the oldest event of a post can be claimed, so
+-- the events of one post are relayed in order; a claimed event is left
+-- alone until leased_until, after which it is relayed again.
+CREATE TABLE outbox (
+    sequence BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    id UUID NOT NULL UNIQUE,
+    aggregate_id UUID NOT NULL,
+    event_type TEXT NOT NULL,
+    payload JSONB,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    leased_until TIMESTAMPTZ
+);
+
+CREATE INDEX outbox_aggregate_id_idx ON outbox (aggregate_id, sequence);
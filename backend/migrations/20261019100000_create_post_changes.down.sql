@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE post_change_compaction;
+DROP TABLE post_changes;
+DROP SEQUENCE post_change_seq;
@@ -0,0 +1,31 @@
+-- Add up migration script here
+
+-- The latest change to each post, numbered from post_change_seq. A change
+-- takes the next number while holding the audit log lock, so numbers are
+-- handed out in commit order and a reader never sees a number before the
+-- ones below it. Posts that were deleted or trashed are kept as tombstones
+-- until they are compacted away.
+CREATE SEQUENCE post_change_seq;
+
+CREATE TABLE post_changes (
+    post_id UUID PRIMARY KEY,
+    sequence BIGINT NOT NULL UNIQUE DEFAULT nextval('post_change_seq'),
+    deleted BOOLEAN NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX post_changes_tombstones_idx ON post_changes (changed_at) WHERE deleted;
+
+-- Highest sequence of a compacted tombstone; cursors below it may have
+-- missed a deletion and have to sync again from scratch.
+CREATE TABLE post_change_compaction (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    compacted_through BIGINT NOT NULL
+);
+
+INSERT INTO post_change_compaction (compacted_through) VALUES (0);
+
+INSERT INTO post_changes (post_id, deleted, changed_at)
+SELECT id, deleted_at IS NOT NULL, COALESCE(deleted_at, updated_at)
+FROM posts
+ORDER BY COALESCE(deleted_at, updated_at), id;
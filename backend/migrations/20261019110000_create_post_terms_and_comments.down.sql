@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE post_comments;
+DROP TABLE post_terms;
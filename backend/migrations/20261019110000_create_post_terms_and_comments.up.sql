@@ -0,0 +1,27 @@
+-- Add up migration script here
+
+-- Categories and tags, in the order they were listed.
+CREATE TABLE post_terms (
+    post_id UUID NOT NULL REFERENCES posts (id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('category', 'tag')),
+    name TEXT NOT NULL,
+    slug TEXT,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (post_id, kind, name)
+);
+
+CREATE INDEX post_terms_kind_name_idx ON post_terms (kind, name);
+
+CREATE TABLE post_comments (
+    id UUID PRIMARY KEY,
+    post_id UUID NOT NULL REFERENCES posts (id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES post_comments (id) ON DELETE CASCADE,
+    author_name TEXT,
+    author_email TEXT,
+    author_url TEXT,
+    body TEXT NOT NULL,
+    approved BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX post_comments_post_id_idx ON post_comments (post_id, created_at);
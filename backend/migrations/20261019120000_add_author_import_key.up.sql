@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- Authors brought over by an import keep the key they had in the system they
+-- came from, so importing again finds them instead of creating duplicates.
+ALTER TABLE authors ADD COLUMN import_key TEXT UNIQUE;
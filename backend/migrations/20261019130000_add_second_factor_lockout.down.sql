@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+ALTER TABLE user_totp DROP COLUMN locked_until;
+ALTER TABLE user_totp DROP COLUMN failed_attempts;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+-- One-time codes are only six digits, so attempts at them are counted and a
+-- user who keeps failing is locked out for a while.
+ALTER TABLE user_totp ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE user_totp ADD COLUMN locked_until TIMESTAMPTZ;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+
+-- A claim without a response is only held until its lease runs out, so a
+-- request that died half way does not block its retries until the key
+-- expires.
+ALTER TABLE idempotency_keys ADD COLUMN locked_until TIMESTAMPTZ NOT NULL DEFAULT now();
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP INDEX webhook_deliveries_finished_idx;
+ALTER TABLE webhook_deliveries DROP COLUMN leased_until;
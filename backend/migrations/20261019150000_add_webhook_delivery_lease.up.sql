@@ -0,0 +1,9 @@
+-- Add up migration script here
+
+-- Set while a delivery is being sent, so it is not redelivered by hand in the
+-- middle of an attempt.
+ALTER TABLE webhook_deliveries ADD COLUMN leased_until TIMESTAMPTZ;
+
+-- Delivered and dead deliveries are removed once their retention runs out.
+CREATE INDEX webhook_deliveries_finished_idx ON webhook_deliveries (last_attempt_at)
+    WHERE status <> 'pending';
@@ -0,0 +1,135 @@
+use axum::{
+    Router,
+    body::Body,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{error, instrument};
+
+use crate::api::{
+    extractors::CurrentUser,
+    responses::{ApiError, ApiResult, ApiSuccess},
+};
+use crate::backup;
+use crate::domain::models::{api_token::Scope, audit::AuditQuery};
+use crate::domain::service::Service;
+use crate::ids::{PostId, UserId};
+use crate::server::AppState;
+
+/// Filters of `GET /admin/audit`. Pages are continued by passing the id of
+/// the last entry as `before_id`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AuditQueryParams {
+    pub actor_id: Option<UserId>,
+    pub post_id: Option<PostId>,
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub before_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntryResponse {
+    pub id: i64,
+    pub actor_id: Option<UserId>,
+    pub action: String,
+    pub post_id: PostId,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Hex encoded, like `hash`.
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkAuditEntryResponse {
+    pub data: Vec<AuditEntryResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditVerificationResponse {
+    pub intact: bool,
+    pub checked: u64,
+    pub first_broken_id: Option<i64>,
+    /// Hash of the newest entry; keep a copy to detect entries removed from
+    /// the end later on.
+    pub head_hash: String,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/admin/export", get(export_backup::<S>))
+        .route("/admin/audit", get(get_audit_entries::<S>))
+        .route("/admin/audit/verify", get(verify_audit_log::<S>))
+}
+
+#[instrument(name = "export_backup_handler", skip_all)]
+async fn export_backup<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+) -> Result<Response, ApiError> {
+    current_user.require_scope(Scope::PostsRead)?;
+
+    let posts = state.service().export_posts(current_user.user())?;
+    let lines = backup::export(posts).inspect_err(|err| error!(?err, "Failed to stream backup"));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"blog-backup.ndjson\"",
+            ),
+        ],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+#[instrument(name = "get_audit_entries_handler", skip_all)]
+async fn get_audit_entries<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Query(params): Query<AuditQueryParams>,
+) -> ApiResult<BulkAuditEntryResponse> {
+    current_user.require_scope(Scope::PostsRead)?;
+    let query = AuditQuery::try_from(params)?;
+
+    state
+        .service()
+        .get_audit_entries(current_user.user(), &query)
+        .await
+        .map_err(ApiError::from)
+        .map(|entries| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                BulkAuditEntryResponse {
+                    data: entries.into_iter().map(Into::into).collect(),
+                },
+            )
+        })
+}
+
+#[instrument(name = "verify_audit_log_handler", skip_all)]
+async fn verify_audit_log<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+) -> ApiResult<AuditVerificationResponse> {
+    current_user.require_scope(Scope::PostsRead)?;
+
+    state
+        .service()
+        .verify_audit_log(current_user.user())
+        .await
+        .map_err(ApiError::from)
+        .map(|verification| ApiSuccess::new(StatusCode::OK, verification.into()))
+}
@@ -0,0 +1,128 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::instrument;
+
+use crate::domain::{
+    models::api_token::{
+        ApiTokenNameEmptyError, CreateApiTokenRequest as DomainCreateApiTokenRequest,
+        ScopeInvalidError,
+    },
+    service::Service,
+};
+use crate::ids::ApiTokenId;
+use crate::server::AppState;
+
+use super::{
+    extractors::CurrentUser,
+    responses::{ApiError, ApiResult, ApiSuccess},
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Error)]
+pub(super) enum CreateApiTokenRequestError {
+    #[error(transparent)]
+    Name(#[from] ApiTokenNameEmptyError),
+    #[error(transparent)]
+    Scope(#[from] ScopeInvalidError),
+    #[error("API token needs at least one scope")]
+    NoScopes,
+    #[error("API token expiry must be in the future")]
+    ExpiryInPast,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiTokenResponse {
+    pub id: ApiTokenId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Returned once on creation; the secret cannot be retrieved again.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub token: ApiTokenResponse,
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BulkApiTokenResponse {
+    pub data: Vec<ApiTokenResponse>,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/auth/tokens", post(create_api_token::<S>))
+        .route("/auth/tokens", get(get_api_tokens::<S>))
+        .route("/auth/tokens/{token_id}", delete(revoke_api_token::<S>))
+}
+
+#[instrument(name = "create_api_token_handler", skip(state, current_user), fields(name = %payload.name))]
+async fn create_api_token<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Json(payload): Json<CreateApiTokenRequest>,
+) -> ApiResult<CreatedApiTokenResponse> {
+    current_user.require_session()?;
+    let domain_req = DomainCreateApiTokenRequest::try_from(payload)?;
+
+    state
+        .service()
+        .create_api_token(current_user.user(), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|created| ApiSuccess::new(StatusCode::CREATED, created.into()))
+}
+
+#[instrument(name = "get_api_tokens_handler", skip_all)]
+async fn get_api_tokens<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+) -> ApiResult<BulkApiTokenResponse> {
+    current_user.require_session()?;
+
+    let data = state
+        .service()
+        .get_api_tokens(current_user.user())
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        BulkApiTokenResponse { data },
+    ))
+}
+
+#[instrument(name = "revoke_api_token_handler", skip(state, current_user))]
+async fn revoke_api_token<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Path(token_id): Path<ApiTokenId>,
+) -> ApiResult<()> {
+    current_user.require_session()?;
+
+    state
+        .service()
+        .revoke_api_token(current_user.user(), token_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,117 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+};
+use axum_extra::extract::{
+    CookieJar,
+    cookie::{Cookie, SameSite},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tracing::instrument;
+
+use crate::domain::{
+    models::user::{LoginRequest as DomainLoginRequest, Session},
+    service::Service,
+};
+use crate::ids::UserId;
+use crate::server::AppState;
+
+use super::{
+    extractors::{CurrentUser, session_token},
+    responses::{ApiError, ApiResult, ApiSuccess},
+};
+
+pub const SESSION_COOKIE: &str = "blog_session";
+
+#[derive(Serialize, Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+    /// Finishes the login in one step for users with two-factor
+    /// authentication; see [`super::totp`] for the two-step flow.
+    #[serde(default)]
+    pub totp_code: Option<String>,
+    #[serde(default)]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserResponse {
+    pub id: UserId,
+    pub email: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/auth/login", post(login::<S>))
+        .route("/auth/logout", post(logout::<S>))
+        .route("/auth/me", get(me))
+}
+
+#[instrument(name = "login_handler", skip(state, jar, payload), fields(email = %payload.email))]
+async fn login<S: Service>(
+    State(state): State<AppState<S>>,
+    jar: CookieJar,
+    Json(payload): Json<LoginRequest>,
+) -> Result<(CookieJar, ApiSuccess<UserResponse>), ApiError> {
+    let domain_req = DomainLoginRequest::try_from(payload)?;
+
+    let session = state.service().login(&domain_req).await?;
+    let cookie = session_cookie(&session, state.secure_cookies())?;
+    // A pending session still needs a one-time code at `/auth/totp/verify`.
+    let status = if session.is_second_factor_pending() {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((
+        jar.add(cookie),
+        ApiSuccess::new(status, session.user().into()),
+    ))
+}
+
+/// The cookie carrying `session`, expiring together with it.
+pub(super) fn session_cookie(session: &Session, secure: bool) -> Result<Cookie<'static>, ApiError> {
+    let expires = OffsetDateTime::from_unix_timestamp(session.expires_at().timestamp())
+        .map_err(anyhow::Error::from)?;
+
+    Ok(
+        Cookie::build((SESSION_COOKIE, session.token().expose().to_string()))
+            .http_only(true)
+            .secure(secure)
+            .same_site(SameSite::Lax)
+            .path("/")
+            .expires(expires)
+            .build(),
+    )
+}
+
+#[instrument(name = "logout_handler", skip(state, jar))]
+async fn logout<S: Service>(
+    State(state): State<AppState<S>>,
+    jar: CookieJar,
+) -> Result<(CookieJar, StatusCode), ApiError> {
+    if let Some(token) = session_token(&jar) {
+        state.service().logout(&token).await?;
+    }
+
+    Ok((
+        jar.remove(Cookie::build(SESSION_COOKIE).path("/")),
+        StatusCode::NO_CONTENT,
+    ))
+}
+
+#[instrument(name = "me_handler", skip_all)]
+async fn me(current_user: CurrentUser) -> ApiResult<UserResponse> {
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        current_user.user().clone().into(),
+    ))
+}
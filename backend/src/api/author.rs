@@ -0,0 +1,127 @@
+use async_graphql::SimpleObject;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::domain::{
+    models::{
+        api_token::Scope,
+        author::{
+            AuthorNameEmptyError, AuthorUrlInvalidError,
+            CreateAuthorRequest as DomainCreateAuthorRequest, SocialLinkLabelEmptyError,
+        },
+    },
+    service::Service,
+};
+use crate::ids::{AuthorId, UserId};
+use crate::server::AppState;
+
+use super::{
+    extractors::CurrentUser,
+    post::BulkPostResponse,
+    responses::{ApiError, ApiResult, ApiSuccess},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, SimpleObject)]
+pub struct SocialLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAuthorRequest {
+    pub display_name: String,
+    /// Links the profile to an account, so that account's posts are credited
+    /// to it by default.
+    pub user_id: Option<UserId>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub social_links: Vec<SocialLink>,
+}
+
+#[derive(Debug, Clone, Error)]
+pub(super) enum CreateAuthorRequestError {
+    #[error(transparent)]
+    Name(#[from] AuthorNameEmptyError),
+    #[error(transparent)]
+    Url(#[from] AuthorUrlInvalidError),
+    #[error(transparent)]
+    SocialLinkLabel(#[from] SocialLinkLabelEmptyError),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, SimpleObject)]
+#[graphql(name = "Author", complex)]
+pub struct AuthorResponse {
+    pub id: AuthorId,
+    pub user_id: Option<UserId>,
+    pub display_name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub social_links: Vec<SocialLink>,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/authors", post(create_author::<S>))
+        .route("/authors/{author_id}", get(get_author::<S>))
+        .route("/authors/{author_id}/posts", get(get_author_posts::<S>))
+}
+
+#[instrument(name = "create_author_handler", skip(state, current_user), fields(display_name = %payload.display_name))]
+async fn create_author<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Json(payload): Json<CreateAuthorRequest>,
+) -> ApiResult<AuthorResponse> {
+    current_user.require_scope(Scope::PostsWrite)?;
+    let domain_req = DomainCreateAuthorRequest::try_from(payload)?;
+
+    state
+        .service()
+        .create_author(current_user.user(), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|author| ApiSuccess::new(StatusCode::CREATED, author.into()))
+}
+
+async fn get_author<S: Service>(
+    State(state): State<AppState<S>>,
+    Path(author_id): Path<AuthorId>,
+) -> ApiResult<AuthorResponse> {
+    state
+        .service()
+        .get_author(author_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|author| ApiSuccess::new(StatusCode::OK, author.into()))
+}
+
+async fn get_author_posts<S: Service>(
+    State(state): State<AppState<S>>,
+    Path(author_id): Path<AuthorId>,
+) -> ApiResult<BulkPostResponse> {
+    let data = state
+        .service()
+        .get_author_posts(author_id)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        BulkPostResponse {
+            data,
+            next_cursor: None,
+        },
+    ))
+}
@@ -0,0 +1,204 @@
+//! HTTP caching of read endpoints.
+//!
+//! Successful `GET` responses of routes with a [`CachePolicy`] carry an ETag,
+//! weak unless the handler set a strong one, and the `Cache-Control` and
+//! `Surrogate-Key` headers of the policy. Handlers may set `Last-Modified` and
+//! surrogate keys of their own. Requests whose `If-None-Match` or
+//! `If-Modified-Since` still hold are answered with `304 Not Modified`.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::{Body, to_bytes},
+    http::{
+        HeaderMap, HeaderName, HeaderValue, StatusCode, header, header::InvalidHeaderValue,
+        response::Parts,
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use super::mappers::to_hex;
+
+/// Tags responses for the CDN, which can then purge everything carrying a key.
+pub const SURROGATE_KEY: HeaderName = HeaderName::from_static("surrogate-key");
+
+/// How the responses of a route may be cached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePolicy {
+    cache_control: HeaderValue,
+    surrogate_keys: Vec<String>,
+}
+
+impl CachePolicy {
+    pub fn try_new(cache_control: &str) -> Result<Self, InvalidHeaderValue> {
+        Ok(Self {
+            cache_control: HeaderValue::from_str(cache_control)?,
+            surrogate_keys: Vec::new(),
+        })
+    }
+
+    /// Tags every response of the route with `key`.
+    pub fn with_surrogate_key(mut self, key: &str) -> Self {
+        self.surrogate_keys.push(key.to_string());
+        self
+    }
+}
+
+/// Cache policies by route, written as in the router, e.g. `/posts/{post_id}`.
+/// Responses of other routes are left alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheConfig {
+    routes: HashMap<String, CachePolicy>,
+}
+
+impl CacheConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_route(mut self, path: &str, policy: CachePolicy) -> Self {
+        self.routes.insert(path.to_string(), policy);
+        self
+    }
+
+    pub fn policy(&self, path: &str) -> Option<&CachePolicy> {
+        self.routes.get(path)
+    }
+}
+
+/// What the client has cached already, from the conditional headers of a
+/// request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    if_none_match: Option<String>,
+    if_modified_since: Option<DateTime<Utc>>,
+}
+
+impl CacheValidators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            if_none_match: headers
+                .get(header::IF_NONE_MATCH)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            if_modified_since: headers
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(parse_http_date),
+        }
+    }
+
+    /// Whether the response with these headers is the one the client has.
+    /// `If-Modified-Since` only counts without `If-None-Match`, which is the
+    /// more precise of the two.
+    fn match_response(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = &self.if_none_match {
+            let Some(etag) = headers
+                .get(header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+            else {
+                return false;
+            };
+            return if_none_match
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || opaque_tag(tag) == opaque_tag(etag));
+        }
+
+        match (
+            self.if_modified_since,
+            headers.get(header::LAST_MODIFIED).and_then(parse_http_date),
+        ) {
+            (Some(since), Some(last_modified)) => last_modified <= since,
+            _ => false,
+        }
+    }
+}
+
+/// Adds the headers of `policy` to a successful response, or replaces it with
+/// `304 Not Modified` if the client has it already.
+pub async fn apply(
+    policy: &CachePolicy,
+    validators: &CacheValidators,
+    response: Response,
+) -> Response {
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    // The body is needed in full to compute its ETag. Cached routes serve
+    // JSON documents, never streams.
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error!(?err, "Failed to read response body for caching");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    parts
+        .headers
+        .entry(header::ETAG)
+        .or_insert_with(|| weak_etag(&bytes));
+    parts
+        .headers
+        .insert(header::CACHE_CONTROL, policy.cache_control.clone());
+    add_surrogate_keys(&mut parts, &policy.surrogate_keys);
+
+    if validators.match_response(&parts.headers) {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_TYPE);
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Formats `at` for `Last-Modified`, to the second.
+pub fn http_date(at: DateTime<Utc>) -> HeaderValue {
+    HeaderValue::from_str(&at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+        .expect("HTTP date is a valid header value")
+}
+
+fn parse_http_date(value: &HeaderValue) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.to_str().ok()?)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Changes whenever the body does. Weak, because it is computed before any
+/// content coding a proxy may apply.
+fn weak_etag(body: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    HeaderValue::from_str(&format!("W/\"{}\"", to_hex(&digest[..16])))
+        .expect("ETag is a valid header value")
+}
+
+/// If-None-Match compares ETags weakly, ignoring whether either is weak.
+fn opaque_tag(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+/// Puts the keys of the route before those the handler set.
+fn add_surrogate_keys(parts: &mut Parts, route_keys: &[String]) {
+    if route_keys.is_empty() {
+        return;
+    }
+
+    let mut keys = route_keys.join(" ");
+    if let Some(handler_keys) = parts
+        .headers
+        .get(SURROGATE_KEY)
+        .and_then(|keys| keys.to_str().ok())
+    {
+        keys.push(' ');
+        keys.push_str(handler_keys);
+    }
+    if let Ok(keys) = HeaderValue::from_str(&keys) {
+        parts.headers.insert(SURROGATE_KEY, keys);
+    }
+}
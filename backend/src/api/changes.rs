@@ -0,0 +1,72 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::domain::{models::change_feed::ChangeFeedQuery, service::Service};
+use crate::ids::PostId;
+use crate::server::AppState;
+
+use super::{
+    post::PostResponse,
+    responses::{ApiError, ApiResult, ApiSuccess},
+};
+
+/// Parameters of `GET /changes`. Start without `since`, then pass the
+/// `next_cursor` of the previous response. A `410 Gone` with the code
+/// `resync_required` means deletions were forgotten since the cursor: drop
+/// the local copy and start again without `since`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChangeFeedParams {
+    pub since: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// The latest change to a post: its current state, or a tombstone once it
+/// was deleted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeResponse {
+    Upsert {
+        sequence: i64,
+        post: PostResponse,
+    },
+    Tombstone {
+        sequence: i64,
+        post_id: PostId,
+        deleted_at: DateTime<Utc>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChangeFeedResponse {
+    /// In the order they were committed.
+    pub changes: Vec<ChangeResponse>,
+    pub next_cursor: i64,
+    /// Whether to fetch the next page right away rather than poll later.
+    pub has_more: bool,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new().route("/changes", get(get_changes::<S>))
+}
+
+#[instrument(name = "get_changes_handler", skip(state))]
+async fn get_changes<S: Service>(
+    State(state): State<AppState<S>>,
+    Query(params): Query<ChangeFeedParams>,
+) -> ApiResult<ChangeFeedResponse> {
+    let query = ChangeFeedQuery::try_from(params)?;
+
+    state
+        .service()
+        .get_changes(&query)
+        .await
+        .map_err(ApiError::from)
+        .map(|page| ApiSuccess::new(StatusCode::OK, page.into()))
+}
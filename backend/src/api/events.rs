@@ -0,0 +1,101 @@
+//! Server-Sent Events stream of changes to posts.
+//!
+//! Every event carries its id, the number of the change in `GET /changes`,
+//! so a client that reconnects with `Last-Event-ID` gets the changes it
+//! missed, from any instance. If some of them are forgotten, a `reset` event
+//! tells it to read the posts again first. Comments are sent while nothing
+//! happens, so proxies do not close an idle connection.
+
+use axum::{
+    Router,
+    extract::State,
+    http::HeaderMap,
+    response::{
+        Sse,
+        sse::{Event, KeepAlive},
+    },
+    routing::get,
+};
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::domain::{
+    models::event::{EventId, PostChange, PostEvent, StreamEvent},
+    service::Service,
+};
+use crate::ids::PostId;
+use crate::server::AppState;
+
+use super::{post::PostResponse, responses::ErrorCode};
+
+pub const EVENTS_PATH: &str = "/events";
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+const RESET: &str = "reset";
+
+/// The data of a `post.deleted` event.
+#[derive(Debug, Serialize)]
+struct DeletedPostResponse {
+    id: PostId,
+}
+
+/// The data of a `reset` event.
+#[derive(Debug, Serialize)]
+struct ResetResponse {
+    code: &'static str,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new().route(EVENTS_PATH, get(get_events::<S>))
+}
+
+/// `post.created` and `post.updated` carry the post as `GET /posts/{id}`
+/// returns it, `post.deleted` only its id. A `Last-Event-ID` that is not an id
+/// of ours is ignored.
+#[instrument(name = "get_events_handler", skip(state, headers))]
+async fn get_events<S: Service>(
+    State(state): State<AppState<S>>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<EventId>().ok())
+        .filter(|id| *id >= 0);
+
+    let events = state
+        .service()
+        .subscribe_post_events(last_event_id)
+        .map(|event| match event {
+            StreamEvent::Post(event) => sse_event(&event),
+            StreamEvent::Reset(id) => reset_event(id),
+        });
+
+    Sse::new(events).keep_alive(KeepAlive::new().interval(state.sse_heartbeat()))
+}
+
+/// Tells the client to drop what it knows and read the posts again, then
+/// resume after `id`.
+fn reset_event(id: EventId) -> Result<Event, axum::Error> {
+    Event::default()
+        .id(id.to_string())
+        .event(RESET)
+        .json_data(ResetResponse {
+            code: ErrorCode::ResyncRequired.as_str(),
+        })
+}
+
+fn sse_event(event: &PostEvent) -> Result<Event, axum::Error> {
+    let sse_event = Event::default()
+        .id(event.id().to_string())
+        .event(event.change().kind().as_str());
+
+    match event.change() {
+        PostChange::Created(post) | PostChange::Updated(post) => {
+            sse_event.json_data(PostResponse::from(post.clone()))
+        }
+        PostChange::Deleted(post_id) => sse_event.json_data(DeletedPostResponse { id: *post_id }),
+    }
+}
@@ -0,0 +1,175 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderValue, header, request::Parts},
+};
+use axum_extra::extract::CookieJar;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    domain::{
+        models::{
+            api_token::{ApiToken, Scope},
+            user::{SessionToken, User},
+        },
+        service::Service,
+    },
+    server::AppState,
+};
+
+use super::{
+    auth::SESSION_COOKIE,
+    mappers::to_hex,
+    responses::{ApiError, ErrorCode},
+};
+
+/// The user behind the request, authenticated either by the bearer token
+/// middleware or by the session cookie. Handlers taking this extractor reject
+/// anonymous requests with `401 Unauthorized`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    user: User,
+    api_token: Option<ApiToken>,
+}
+
+impl CurrentUser {
+    pub fn from_session(user: User) -> Self {
+        Self {
+            user,
+            api_token: None,
+        }
+    }
+
+    pub fn from_api_token(user: User, api_token: ApiToken) -> Self {
+        Self {
+            user,
+            api_token: Some(api_token),
+        }
+    }
+
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    pub fn api_token(&self) -> Option<&ApiToken> {
+        self.api_token.as_ref()
+    }
+
+    /// Sessions may do anything their user may; API tokens only what they
+    /// were granted.
+    pub fn require_scope(&self, scope: Scope) -> Result<(), ApiError> {
+        match &self.api_token {
+            Some(token) if !token.has_scope(scope) => Err(ApiError::Forbidden(
+                ErrorCode::ScopeMissing,
+                format!("API token is missing the {scope} scope."),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects API tokens, for actions that need an interactive login.
+    pub fn require_session(&self) -> Result<(), ApiError> {
+        match &self.api_token {
+            Some(_) => Err(ApiError::Forbidden(
+                ErrorCode::SessionRequired,
+                "This action requires a session login.".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S: Service> FromRequestParts<AppState<S>> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState<S>,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(current_user) = parts.extensions.get::<CurrentUser>() {
+            return Ok(current_user.clone());
+        }
+
+        let token = session_token(&CookieJar::from_headers(&parts.headers)).ok_or_else(|| {
+            ApiError::Unauthorized(
+                ErrorCode::Unauthenticated,
+                "Authentication required.".to_string(),
+            )
+        })?;
+
+        state
+            .service()
+            .authenticate(&token)
+            .await
+            .map(CurrentUser::from_session)
+            .map_err(ApiError::from)
+    }
+}
+
+/// The version of a resource the client last saw, from the strong ETag in
+/// `If-Match`, or just the version in quotes. Requests that change a
+/// versioned resource take this, so that nobody overwrites changes they have
+/// not seen: without the header they are rejected with
+/// `428 Precondition Required`. A single ETag is expected; `*` would defeat
+/// the purpose and is not accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IfMatch(i64);
+
+impl IfMatch {
+    pub fn version(&self) -> i64 {
+        self.0
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let value = parts
+            .headers
+            .get(header::IF_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| *value != "*")
+            .ok_or_else(|| {
+                ApiError::PreconditionRequired(
+                    ErrorCode::IfMatchRequired,
+                    "An If-Match header with the current ETag is required.".to_string(),
+                )
+            })?;
+
+        // Weak or malformed tags can never match the strong ETag we serve.
+        // Only the version counts: what the tag says about embedded data does
+        // not change the resource itself.
+        value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .map(|tag| tag.split_once('-').map_or(tag, |(version, _)| version))
+            .and_then(|version| version.parse().ok())
+            .map(IfMatch)
+            .ok_or_else(|| {
+                ApiError::PreconditionFailed(
+                    ErrorCode::VersionConflict,
+                    format!("If-Match {value} does not match the current ETag."),
+                )
+            })
+    }
+}
+
+/// A strong ETag of `version` alone, which is all `If-Match` looks at.
+pub fn etag(version: i64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{version}\"")).expect("ETag is a valid header value")
+}
+
+/// The strong ETag served for a resource at `version`. Data embedded from
+/// other resources, such as the authors of a post, changes without the
+/// version, so a digest of it follows the version.
+pub fn embedding_etag(version: i64, embedded: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(embedded);
+    HeaderValue::from_str(&format!("\"{version}-{}\"", to_hex(&digest[..8])))
+        .expect("ETag is a valid header value")
+}
+
+pub(super) fn session_token(jar: &CookieJar) -> Option<SessionToken> {
+    jar.get(SESSION_COOKIE)
+        .map(|cookie| SessionToken::new(cookie.value()))
+}
@@ -0,0 +1,529 @@
+//! GraphQL endpoint next to the REST API, backed by the same [`Service`].
+//!
+//! Types, inputs and validation are those of the REST API, and failures carry
+//! the `code` of the equivalent problem in their extensions. The posts of an
+//! author are loaded in batches, so listing posts with their authors' other
+//! posts takes one query for all of them rather than one per author, and only
+//! loads the page of each author's posts that is asked for.
+//!
+//! Lists of posts come in pages of at most [`MAX_PAGE_SIZE`], continued by
+//! passing the `cursor` of the last post as `after`. Each list counts for as
+//! many posts as it may hold, and queries that could return more than
+//! [`MAX_QUERY_COMPLEXITY`] fields in all are rejected before they run.
+
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+use async_graphql::{
+    ComplexObject, Context, EmptySubscription, ErrorExtensions, Object, Schema,
+    dataloader::{DataLoader, Loader},
+    http::GraphiQLSource,
+};
+use async_trait::async_trait;
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    response::Html,
+    routing::{get, post},
+};
+
+use crate::domain::{
+    models::{
+        api_token::Scope,
+        post::{
+            CreatePostRequest as DomainCreatePostRequest, Post, PostCursor, PostPageQuery,
+            UpdatePostRequest as DomainUpdatePostRequest,
+        },
+    },
+    service::Service,
+};
+use crate::ids::{AuthorId, PostId};
+use crate::server::AppState;
+
+use super::{
+    author::AuthorResponse,
+    extractors::CurrentUser,
+    post::{CreatePostRequest, PostPageParams, PostResponse, UpdatePostRequest},
+    responses::{ApiError, ErrorCode, ProblemDetails},
+};
+
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+/// Deeper queries are rejected before they run.
+const MAX_QUERY_DEPTH: usize = 10;
+
+/// Queries that may resolve more fields are rejected before they run.
+pub const MAX_QUERY_COMPLEXITY: usize = 10_000;
+
+/// Posts in a list unless `first` asks for fewer or more.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Posts in a list at most, whatever `first` asks for.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+pub type BlogSchema<S> = Schema<QueryRoot<S>, MutationRoot<S>, EmptySubscription>;
+
+pub fn schema<S: Service>() -> BlogSchema<S> {
+    Schema::build(
+        QueryRoot(PhantomData),
+        MutationRoot(PhantomData),
+        EmptySubscription,
+    )
+    .limit_depth(MAX_QUERY_DEPTH)
+    .limit_complexity(MAX_QUERY_COMPLEXITY)
+    .finish()
+}
+
+/// Serves the endpoint, with the GraphiQL playground on `GET` if `graphiql`
+/// is set.
+pub fn routes<S: Service>(graphiql: bool) -> Router<AppState<S>> {
+    let router = Router::new().route(GRAPHQL_PATH, post(execute::<S>));
+    let router = if graphiql {
+        router.route(GRAPHQL_PATH, get(playground))
+    } else {
+        router
+    };
+
+    router.layer(Extension(schema::<S>()))
+}
+
+async fn execute<S: Service>(
+    State(state): State<AppState<S>>,
+    Extension(schema): Extension<BlogSchema<S>>,
+    current_user: Result<CurrentUser, ApiError>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    // Loaders live for one request, so nothing is cached across users.
+    let author_posts = DataLoader::new(
+        AuthorPostsLoader {
+            service: state.service().clone(),
+        },
+        tokio::spawn,
+    );
+    let mut request = request.data(state).data(author_posts);
+    if let Ok(current_user) = current_user {
+        request = request.data(current_user);
+    }
+
+    Json(schema.execute(request).await)
+}
+
+async fn playground() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+/// A failure as a GraphQL error, with the members of the equivalent problem
+/// as extensions.
+fn graphql_error(error: impl Into<ApiError>) -> async_graphql::Error {
+    let problem = ProblemDetails::from(error.into());
+
+    async_graphql::Error::new(problem.detail.clone()).extend_with(|_, extensions| {
+        extensions.set("code", problem.code.as_str());
+        extensions.set("status", problem.status);
+        if let Some(request_id) = &problem.request_id {
+            extensions.set("requestId", request_id.as_str());
+        }
+        if !problem.errors.is_empty() {
+            let errors = serde_json::to_value(&problem.errors).unwrap_or_default();
+            extensions.set(
+                "errors",
+                async_graphql::Value::from_json(errors).unwrap_or_default(),
+            );
+        }
+    })
+}
+
+fn state<'a, S: Service>(ctx: &Context<'a>) -> &'a AppState<S> {
+    ctx.data_unchecked::<AppState<S>>()
+}
+
+fn current_user<'a>(ctx: &Context<'a>, scope: Scope) -> async_graphql::Result<&'a CurrentUser> {
+    let current_user = ctx.data_opt::<CurrentUser>().ok_or_else(|| {
+        graphql_error(ApiError::Unauthorized(
+            ErrorCode::Unauthenticated,
+            "Authentication required.".to_string(),
+        ))
+    })?;
+    current_user.require_scope(scope).map_err(graphql_error)?;
+
+    Ok(current_user)
+}
+
+/// Posts a list of `first` holds, between one and [`MAX_PAGE_SIZE`].
+fn page_size(first: Option<i32>) -> usize {
+    first.map_or(DEFAULT_PAGE_SIZE, |first| {
+        usize::try_from(first).unwrap_or(0).clamp(1, MAX_PAGE_SIZE)
+    })
+}
+
+/// The page of posts continuing after the cursor `after`.
+fn page_query(first: Option<i32>, after: Option<String>) -> async_graphql::Result<PostPageQuery> {
+    let limit = Some(page_size(first) as i64);
+
+    PostPageQuery::try_from(PostPageParams { after, limit }).map_err(graphql_error)
+}
+
+/// The first `page.limit()` of `posts`, newest first.
+fn newest_first(mut posts: Vec<PostResponse>, page: &PostPageQuery) -> Vec<PostResponse> {
+    posts.sort_by_key(|post| std::cmp::Reverse(PostCursor::new(post.created_at, post.id)));
+    posts.truncate(page.limit() as usize);
+
+    posts
+}
+
+/// Turns a failed lookup into `null`.
+fn found<T>(result: Result<T, ApiError>) -> async_graphql::Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ApiError::NotFound(..)) => Ok(None),
+        Err(err) => Err(graphql_error(err)),
+    }
+}
+
+pub struct QueryRoot<S>(PhantomData<S>);
+
+#[Object]
+impl<S: Service> QueryRoot<S> {
+    /// Posts oldest first.
+    #[graphql(complexity = "page_size(first) * child_complexity")]
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Vec<PostResponse>> {
+        let page = state::<S>(ctx)
+            .service()
+            .get_posts_page(&page_query(first, after)?)
+            .await
+            .map_err(graphql_error)?;
+
+        Ok(page.into_posts().into_iter().map(Into::into).collect())
+    }
+
+    async fn post(
+        &self,
+        ctx: &Context<'_>,
+        id: PostId,
+    ) -> async_graphql::Result<Option<PostResponse>> {
+        let post = state::<S>(ctx)
+            .service()
+            .get_posts_by_id(id)
+            .await
+            .map_err(ApiError::from);
+
+        found(post.map(Into::into))
+    }
+
+    async fn author(
+        &self,
+        ctx: &Context<'_>,
+        id: AuthorId,
+    ) -> async_graphql::Result<Option<AuthorResponse>> {
+        let author = state::<S>(ctx)
+            .service()
+            .get_author(id)
+            .await
+            .map_err(ApiError::from);
+
+        found(author.map(Into::into))
+    }
+
+    /// The trashed posts the caller may restore.
+    async fn trash(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PostResponse>> {
+        let current_user = current_user(ctx, Scope::PostsRead)?;
+        let posts = state::<S>(ctx)
+            .service()
+            .get_trash(current_user.user())
+            .await
+            .map_err(graphql_error)?;
+
+        Ok(posts.into_iter().map(Into::into).collect())
+    }
+}
+
+pub struct MutationRoot<S>(PhantomData<S>);
+
+#[Object]
+impl<S: Service> MutationRoot<S> {
+    async fn create_post(
+        &self,
+        ctx: &Context<'_>,
+        input: CreatePostRequest,
+    ) -> async_graphql::Result<PostResponse> {
+        let current_user = current_user(ctx, Scope::PostsWrite)?;
+        let state = state::<S>(ctx);
+        let domain_req = DomainCreatePostRequest::try_from((input, state.post_constraints()))
+            .map_err(graphql_error)?;
+
+        state
+            .service()
+            .create_post(current_user.user(), &domain_req)
+            .await
+            .map(Into::into)
+            .map_err(graphql_error)
+    }
+
+    /// Changes the post if it is still at `version`, like `If-Match` does for
+    /// the REST API.
+    async fn update_post(
+        &self,
+        ctx: &Context<'_>,
+        id: PostId,
+        version: i64,
+        input: UpdatePostRequest,
+    ) -> async_graphql::Result<PostResponse> {
+        let current_user = current_user(ctx, Scope::PostsWrite)?;
+        let state = state::<S>(ctx);
+        let domain_req = DomainUpdatePostRequest::try_from((input, state.post_constraints()))
+            .map_err(graphql_error)?;
+
+        state
+            .service()
+            .update_post(current_user.user(), id, version, &domain_req)
+            .await
+            .map(Into::into)
+            .map_err(graphql_error)
+    }
+
+    /// Moves the post to the trash, or deletes it for good if `permanent` is
+    /// set.
+    async fn delete_post(
+        &self,
+        ctx: &Context<'_>,
+        id: PostId,
+        version: i64,
+        #[graphql(default)] permanent: bool,
+    ) -> async_graphql::Result<bool> {
+        let current_user = current_user(ctx, Scope::PostsDelete)?;
+        let service = state::<S>(ctx).service();
+        let result = if permanent {
+            service
+                .delete_post_permanently(current_user.user(), id, version)
+                .await
+        } else {
+            service.delete_post(current_user.user(), id, version).await
+        };
+
+        result.map(|_| true).map_err(graphql_error)
+    }
+
+    async fn restore_post(
+        &self,
+        ctx: &Context<'_>,
+        id: PostId,
+    ) -> async_graphql::Result<PostResponse> {
+        let current_user = current_user(ctx, Scope::PostsDelete)?;
+
+        state::<S>(ctx)
+            .service()
+            .restore_post(current_user.user(), id)
+            .await
+            .map(Into::into)
+            .map_err(graphql_error)
+    }
+}
+
+#[ComplexObject]
+impl PostResponse {
+    /// Pass as `after` to continue a list of posts after this one.
+    async fn cursor(&self) -> String {
+        PostCursor::new(self.created_at, self.id).to_string()
+    }
+
+    /// Other posts by any of the authors of this one, newest first.
+    #[graphql(complexity = "page_size(first) * child_complexity")]
+    async fn related_posts(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Vec<PostResponse>> {
+        let page = page_query(first, after)?;
+        // One more per author, in case this post is among them.
+        let with_this_post = page.with_limit(Some(page.limit() + 1));
+        let loader = ctx.data_unchecked::<DataLoader<AuthorPostsLoader>>();
+        let by_author = loader
+            .load_many(self.authors.iter().map(|author| AuthorPostsKey {
+                author_id: author.id,
+                page: with_this_post,
+            }))
+            .await?;
+
+        let mut related: Vec<PostResponse> = Vec::new();
+        for post in by_author.into_values().flatten() {
+            if post.id != self.id && !related.iter().any(|other| other.id == post.id) {
+                related.push(post);
+            }
+        }
+
+        Ok(newest_first(related, &page))
+    }
+}
+
+#[ComplexObject]
+impl AuthorResponse {
+    /// Posts the author is credited on, newest first.
+    #[graphql(complexity = "page_size(first) * child_complexity")]
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Vec<PostResponse>> {
+        let key = AuthorPostsKey {
+            author_id: self.id,
+            page: page_query(first, after)?,
+        };
+        let loader = ctx.data_unchecked::<DataLoader<AuthorPostsLoader>>();
+
+        Ok(loader.load_one(key).await?.unwrap_or_default())
+    }
+}
+
+/// Loads the pages of posts of all authors asked for while resolving a query
+/// with a call to the service per distinct page, rather than per author.
+pub struct AuthorPostsLoader {
+    service: Arc<dyn AuthorPosts>,
+}
+
+/// A page of the posts of an author, newest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct AuthorPostsKey {
+    author_id: AuthorId,
+    page: PostPageQuery,
+}
+
+/// The part of the [`Service`] the loader needs, so the loader and the types
+/// that use it do not depend on the service type.
+#[async_trait]
+trait AuthorPosts: Send + Sync {
+    async fn get_posts_by_authors(
+        &self,
+        ids: &[AuthorId],
+        page: &PostPageQuery,
+    ) -> Result<Vec<Post>, ApiError>;
+}
+
+#[async_trait]
+impl<S: Service> AuthorPosts for S {
+    async fn get_posts_by_authors(
+        &self,
+        ids: &[AuthorId],
+        page: &PostPageQuery,
+    ) -> Result<Vec<Post>, ApiError> {
+        Service::get_posts_by_authors(self, ids, page)
+            .await
+            .map_err(ApiError::from)
+    }
+}
+
+impl Loader<AuthorPostsKey> for AuthorPostsLoader {
+    type Value = Vec<PostResponse>;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[AuthorPostsKey],
+    ) -> Result<HashMap<AuthorPostsKey, Self::Value>, Self::Error> {
+        let mut by_page: HashMap<PostPageQuery, Vec<AuthorId>> = HashMap::new();
+        for key in keys {
+            by_page.entry(key.page).or_default().push(key.author_id);
+        }
+
+        let mut by_key: HashMap<AuthorPostsKey, Self::Value> = HashMap::new();
+        for (page, author_ids) in by_page {
+            let posts = self
+                .service
+                .get_posts_by_authors(&author_ids, &page)
+                .await
+                .map_err(graphql_error)?;
+
+            // Newest first, so each author's page is their first posts here.
+            for post in posts {
+                let response = PostResponse::from(post);
+                for author in &response.authors {
+                    if author_ids.contains(&author.id) {
+                        let key = AuthorPostsKey {
+                            author_id: author.id,
+                            page,
+                        };
+                        let posts = by_key.entry(key).or_default();
+                        if posts.len() < page.limit() as usize {
+                            posts.push(response.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(by_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use chrono::Utc;
+
+    use crate::domain::models::{
+        author::{Author, AuthorName},
+        post::{PostBody, PostTitle},
+    };
+
+    use super::*;
+
+    /// Counts calls and credits one post to each author asked for.
+    #[derive(Default)]
+    struct CountingAuthorPosts {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AuthorPosts for CountingAuthorPosts {
+        async fn get_posts_by_authors(
+            &self,
+            ids: &[AuthorId],
+            _page: &PostPageQuery,
+        ) -> Result<Vec<Post>, ApiError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ids
+                .iter()
+                .map(|id| {
+                    Post::new(
+                        PostId::new(),
+                        PostTitle::new("Title"),
+                        PostBody::new("Body"),
+                        Utc::now(),
+                    )
+                    .with_authors(vec![Author::new(*id, AuthorName::new("Author"))])
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_loads_are_batched() {
+        let service = Arc::new(CountingAuthorPosts::default());
+        let loader = DataLoader::new(
+            AuthorPostsLoader {
+                service: service.clone(),
+            },
+            tokio::spawn,
+        );
+        let ids = [AuthorId::new(), AuthorId::new(), AuthorId::new()];
+
+        let loaded = futures::future::try_join_all(ids.iter().map(|id| {
+            loader.load_one(AuthorPostsKey {
+                author_id: *id,
+                page: PostPageQuery::new(),
+            })
+        }))
+        .await;
+
+        assert_eq!(service.calls.load(Ordering::SeqCst), 1);
+        for (id, posts) in ids.iter().zip(loaded.unwrap()) {
+            assert_eq!(posts.unwrap()[0].authors[0].id, *id);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Idempotency keys for requests that create things.
+//!
+//! A `POST` by an authenticated user to one of the [`IDEMPOTENT_ROUTES`] that
+//! carries an `Idempotency-Key` header takes effect once per key. Retries with
+//! the same key and request get the stored response, marked with
+//! `Idempotent-Replayed: true`. Reusing a key for a different request is
+//! rejected with `422 Unprocessable Entity`, a retry arriving while the first
+//! request is still handled with `409 Conflict`.
+//! Server errors are not kept, so their retries are handled afresh, as are
+//! retries after the first request held the key longer than its lease
+//! without a response.
+//!
+//! Responses are stored as they are, so only routes whose responses hold no
+//! secrets belong in [`IDEMPOTENT_ROUTES`]. Creating API tokens or webhooks
+//! and enrolling authenticators are left out for that reason.
+//!
+//! Updates and deletes need no key: their `If-Match` precondition already
+//! fails a retry instead of applying the change twice.
+
+use axum::{
+    body::{Body, Bytes, to_bytes},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header, request},
+    response::{IntoResponse, Response},
+};
+use tracing::error;
+
+use crate::domain::models::idempotency::{IdempotencyKey, IdempotentResponse, RequestFingerprint};
+
+use super::{
+    post::{POSTS_PATH, RESTORE_POST_PATH},
+    responses::{ApiError, ErrorCode},
+};
+
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Routes, as written in the router, whose `POST` requests honor an
+/// `Idempotency-Key`.
+pub const IDEMPOTENT_ROUTES: [&str; 2] = [POSTS_PATH, RESTORE_POST_PATH];
+
+/// Headers that belong to a single response. Session cookies in particular
+/// are only ever stored hashed.
+const UNKEPT_HEADERS: [HeaderName; 3] = [
+    header::CONTENT_LENGTH,
+    header::SET_COOKIE,
+    HeaderName::from_static("x-request-id"),
+];
+
+pub fn key(headers: &HeaderMap) -> Result<Option<IdempotencyKey>, ApiError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY) else {
+        return Ok(None);
+    };
+
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| IdempotencyKey::try_new(value).ok())
+        .map(Some)
+        .ok_or_else(|| {
+            ApiError::UnprocessableEntity(
+                ErrorCode::IdempotencyKeyInvalid,
+                "Idempotency-Key must be 1 to 255 visible ASCII characters.".to_string(),
+            )
+        })
+}
+
+/// The request body is read in full to fingerprint it, up to the same limit
+/// as the handlers have.
+pub async fn read_body(body: Body, max_bytes: usize) -> Result<Bytes, ApiError> {
+    to_bytes(body, max_bytes).await.map_err(|_| {
+        ApiError::PayloadTooLarge(
+            ErrorCode::PayloadTooLarge,
+            format!("Request body must be at most {max_bytes} bytes."),
+        )
+    })
+}
+
+pub fn fingerprint(parts: &request::Parts, body: &[u8]) -> RequestFingerprint {
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| parts.uri.path());
+
+    RequestFingerprint::new(parts.method.as_str(), path, body)
+}
+
+/// Buffers the response to keep a copy for retries. Server errors are not
+/// kept, since a retry may well succeed.
+pub async fn keep(response: Response) -> (Response, Option<IdempotentResponse>) {
+    if response.status().is_server_error() {
+        return (response, None);
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error!(?err, "Failed to read response body for idempotency key");
+            return (StatusCode::INTERNAL_SERVER_ERROR.into_response(), None);
+        }
+    };
+
+    let headers = parts
+        .headers
+        .iter()
+        .filter(|(name, _)| !UNKEPT_HEADERS.contains(name))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let kept = IdempotentResponse::new(parts.status.as_u16(), headers, bytes.to_vec());
+
+    (Response::from_parts(parts, Body::from(bytes)), Some(kept))
+}
+
+pub fn replay(stored: &IdempotentResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body().to_vec()));
+    *response.status_mut() =
+        StatusCode::from_u16(stored.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers() {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::try_from(value.as_str()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+
+    response
+}
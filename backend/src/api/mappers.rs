@@ -1,46 +1,332 @@
+use std::collections::BTreeSet;
+
+use chrono::Utc;
 use tracing::error;
 
 use crate::domain::{
-    models::post::{
-        CreatePostRequest as DomainCreatePostRequest, Post, PostBody, PostTitle,
-        UpdatePostRequest as DomainUpdatePostRequest,
+    models::{
+        api_token::{
+            ApiToken, ApiTokenName, CreateApiTokenRequest as DomainCreateApiTokenRequest,
+            CreatedApiToken, Scope,
+        },
+        audit::{AuditAction, AuditEntry, AuditQuery, AuditVerification},
+        author::{
+            Author, AuthorName, AuthorUrl, CreateAuthorRequest as DomainCreateAuthorRequest,
+            SocialLink as DomainSocialLink,
+        },
+        change_feed::{ChangeFeedPage, ChangeFeedQuery, FeedChange},
+        event::PostChangeKind,
+        post::{
+            CreatePostRequest as DomainCreatePostRequest, Post, PostComment, PostConstraints,
+            PostCursor, PostPageQuery, PostTerm, TermKind,
+            UpdatePostRequest as DomainUpdatePostRequest,
+        },
+        totp::{RecoveryCode, SecondFactor, TotpCode, TotpEnrollment},
+        user::{LoginRequest as DomainLoginRequest, Password, User, UserEmail},
+        webhook::{
+            CreateWebhookRequest as DomainCreateWebhookRequest, CreatedWebhook, Webhook,
+            WebhookDelivery, WebhookUrl,
+        },
     },
     service::ServiceError,
 };
 
 use super::{
-    post::{CreatePostRequest, CreatePostRequestError, PostResponse, UpdatePostRequest},
-    responses::ApiError,
+    admin::{AuditEntryResponse, AuditQueryParams, AuditVerificationResponse},
+    api_token::{
+        ApiTokenResponse, CreateApiTokenRequest, CreateApiTokenRequestError,
+        CreatedApiTokenResponse,
+    },
+    auth::{LoginRequest, UserResponse},
+    author::{AuthorResponse, CreateAuthorRequest, CreateAuthorRequestError, SocialLink},
+    changes::{ChangeFeedParams, ChangeFeedResponse, ChangeResponse},
+    post::{
+        CommentResponse, CreatePostRequest, PostPageParams, PostResponse, PostTermsResponse,
+        TermResponse, UpdatePostRequest,
+    },
+    responses::{ApiError, ErrorCode},
+    totp::TotpEnrollmentResponse,
+    validation::Validator,
+    webhook::{
+        CreateWebhookRequest, CreateWebhookRequestError, CreatedWebhookResponse,
+        WebhookDeliveryResponse, WebhookResponse,
+    },
 };
 
-impl TryFrom<CreatePostRequest> for DomainCreatePostRequest {
+impl TryFrom<(CreatePostRequest, &PostConstraints)> for DomainCreatePostRequest {
     type Error = ApiError;
 
-    fn try_from(CreatePostRequest { title, body }: CreatePostRequest) -> Result<Self, Self::Error> {
-        let title = PostTitle::try_new(&title).map_err(CreatePostRequestError::from)?;
-        let body = PostBody::try_new(&body).map_err(CreatePostRequestError::from)?;
-        Ok(Self::new(title, body))
+    fn try_from(
+        (
+            CreatePostRequest {
+                title,
+                body,
+                authors,
+            },
+            constraints,
+        ): (CreatePostRequest, &PostConstraints),
+    ) -> Result<Self, Self::Error> {
+        let mut validator = Validator::new();
+        let title = validator.check("/title", constraints.title(&title));
+        let body = validator.check("/body", constraints.body(&body));
+
+        match (title, body) {
+            (Some(title), Some(body)) => Ok(Self::new(title, body).with_authors(authors)),
+            _ => Err(validator.into_error()),
+        }
     }
 }
 
-impl TryFrom<UpdatePostRequest> for DomainUpdatePostRequest {
+impl TryFrom<(UpdatePostRequest, &PostConstraints)> for DomainUpdatePostRequest {
     type Error = ApiError;
 
-    fn try_from(UpdatePostRequest { title, body }: UpdatePostRequest) -> Result<Self, Self::Error> {
-        let title = title
-            .map(|title| PostTitle::try_new(&title).map_err(CreatePostRequestError::from))
-            .transpose()?;
-        let body = body
-            .map(|body| PostBody::try_new(&body).map_err(CreatePostRequestError::from))
+    fn try_from(
+        (
+            UpdatePostRequest {
+                title,
+                body,
+                authors,
+            },
+            constraints,
+        ): (UpdatePostRequest, &PostConstraints),
+    ) -> Result<Self, Self::Error> {
+        let mut validator = Validator::new();
+        let title = title.and_then(|title| validator.check("/title", constraints.title(&title)));
+        let body = body.and_then(|body| validator.check("/body", constraints.body(&body)));
+        validator.finish()?;
+
+        Ok(Self::new(title, body).with_authors(authors))
+    }
+}
+
+impl TryFrom<CreateAuthorRequest> for DomainCreateAuthorRequest {
+    type Error = ApiError;
+
+    fn try_from(
+        CreateAuthorRequest {
+            display_name,
+            user_id,
+            bio,
+            avatar_url,
+            social_links,
+        }: CreateAuthorRequest,
+    ) -> Result<Self, Self::Error> {
+        let name = AuthorName::try_new(&display_name).map_err(CreateAuthorRequestError::from)?;
+        let avatar_url = avatar_url
+            .map(|url| AuthorUrl::try_new(&url).map_err(CreateAuthorRequestError::from))
             .transpose()?;
-        Ok(Self::new(title, body))
+        let social_links = social_links
+            .into_iter()
+            .map(|SocialLink { label, url }| {
+                let url = AuthorUrl::try_new(&url)?;
+                Ok(DomainSocialLink::try_new(&label, url)?)
+            })
+            .collect::<Result<_, CreateAuthorRequestError>>()?;
+        let bio = bio.filter(|bio| !bio.trim().is_empty());
+
+        Ok(Self::new(name, user_id, bio, avatar_url, social_links))
+    }
+}
+
+impl TryFrom<LoginRequest> for DomainLoginRequest {
+    type Error = ApiError;
+
+    fn try_from(
+        LoginRequest {
+            email,
+            password,
+            totp_code,
+            recovery_code,
+        }: LoginRequest,
+    ) -> Result<Self, Self::Error> {
+        let email = UserEmail::try_new(&email).map_err(|e| {
+            ApiError::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+        })?;
+        let second_factor = second_factor(totp_code, recovery_code)?;
+
+        Ok(Self::new(email, Password::new(&password)).with_second_factor(second_factor))
     }
 }
 
-impl From<CreatePostRequestError> for ApiError {
-    fn from(e: CreatePostRequestError) -> Self {
+impl TryFrom<AuditQueryParams> for AuditQuery {
+    type Error = ApiError;
+
+    fn try_from(
+        AuditQueryParams {
+            actor_id,
+            post_id,
+            action,
+            since,
+            until,
+            before_id,
+            limit,
+        }: AuditQueryParams,
+    ) -> Result<Self, Self::Error> {
+        let action = action
+            .map(|action| action.parse::<AuditAction>())
+            .transpose()
+            .map_err(|e| {
+                ApiError::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+            })?;
+
+        Ok(Self::new()
+            .with_actor_id(actor_id)
+            .with_post_id(post_id)
+            .with_action(action)
+            .with_period(since, until)
+            .with_before_id(before_id)
+            .with_limit(limit))
+    }
+}
+
+impl TryFrom<ChangeFeedParams> for ChangeFeedQuery {
+    type Error = ApiError;
+
+    fn try_from(ChangeFeedParams { since, limit }: ChangeFeedParams) -> Result<Self, Self::Error> {
+        if since.is_some_and(|since| since < 0) {
+            return Err(ApiError::UnprocessableEntity(
+                ErrorCode::ValidationFailed,
+                "since must be a cursor returned by a previous request".to_string(),
+            ));
+        }
+
+        Ok(Self::new().with_since(since).with_limit(limit))
+    }
+}
+
+impl TryFrom<PostPageParams> for PostPageQuery {
+    type Error = ApiError;
+
+    fn try_from(PostPageParams { after, limit }: PostPageParams) -> Result<Self, Self::Error> {
+        let after = after
+            .map(|after| after.parse::<PostCursor>())
+            .transpose()
+            .map_err(|_| {
+                ApiError::UnprocessableEntity(
+                    ErrorCode::ValidationFailed,
+                    "after must be a cursor returned by a previous request".to_string(),
+                )
+            })?;
+
+        Ok(Self::new().with_after(after).with_limit(limit))
+    }
+}
+
+impl From<FeedChange> for ChangeResponse {
+    fn from(value: FeedChange) -> Self {
+        match value {
+            FeedChange::Upsert { sequence, post } => Self::Upsert {
+                sequence,
+                post: post.into(),
+            },
+            FeedChange::Tombstone {
+                sequence,
+                post_id,
+                deleted_at,
+            } => Self::Tombstone {
+                sequence,
+                post_id,
+                deleted_at,
+            },
+        }
+    }
+}
+
+impl From<ChangeFeedPage> for ChangeFeedResponse {
+    fn from(value: ChangeFeedPage) -> Self {
+        Self {
+            next_cursor: value.next_cursor(),
+            has_more: value.has_more(),
+            changes: value.into_changes().into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A one-time code or a recovery code, but not both.
+pub(super) fn second_factor(
+    totp_code: Option<String>,
+    recovery_code: Option<String>,
+) -> Result<Option<SecondFactor>, ApiError> {
+    match (totp_code, recovery_code) {
+        (Some(_), Some(_)) => Err(ApiError::UnprocessableEntity(
+            ErrorCode::ValidationFailed,
+            "Provide either a one-time code or a recovery code, not both.".to_string(),
+        )),
+        (Some(code), None) => TotpCode::try_new(&code)
+            .map(|code| Some(SecondFactor::Totp(code)))
+            .map_err(|e| ApiError::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())),
+        (None, Some(code)) => Ok(Some(SecondFactor::RecoveryCode(RecoveryCode::new(&code)))),
+        (None, None) => Ok(None),
+    }
+}
+
+impl TryFrom<CreateApiTokenRequest> for DomainCreateApiTokenRequest {
+    type Error = ApiError;
+
+    fn try_from(
+        CreateApiTokenRequest {
+            name,
+            scopes,
+            expires_at,
+        }: CreateApiTokenRequest,
+    ) -> Result<Self, Self::Error> {
+        let name = ApiTokenName::try_new(&name).map_err(CreateApiTokenRequestError::from)?;
+        let scopes = scopes
+            .iter()
+            .map(|scope| scope.parse::<Scope>())
+            .collect::<Result<BTreeSet<_>, _>>()
+            .map_err(CreateApiTokenRequestError::from)?;
+
+        if scopes.is_empty() {
+            return Err(CreateApiTokenRequestError::NoScopes.into());
+        }
+        if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(CreateApiTokenRequestError::ExpiryInPast.into());
+        }
+
+        Ok(Self::new(name, scopes, expires_at))
+    }
+}
+
+impl From<CreateApiTokenRequestError> for ApiError {
+    fn from(e: CreateApiTokenRequestError) -> Self {
+        error!(?e, "Failed to convert API request to domain request");
+        Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+    }
+}
+
+impl From<CreateAuthorRequestError> for ApiError {
+    fn from(e: CreateAuthorRequestError) -> Self {
+        error!(?e, "Failed to convert API request to domain request");
+        Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+    }
+}
+
+impl TryFrom<CreateWebhookRequest> for DomainCreateWebhookRequest {
+    type Error = ApiError;
+
+    fn try_from(
+        CreateWebhookRequest { url, events }: CreateWebhookRequest,
+    ) -> Result<Self, Self::Error> {
+        let url = WebhookUrl::try_new(&url).map_err(CreateWebhookRequestError::from)?;
+        let events = events
+            .iter()
+            .map(|event| event.parse::<PostChangeKind>())
+            .collect::<Result<BTreeSet<_>, _>>()
+            .map_err(CreateWebhookRequestError::from)?;
+
+        if events.is_empty() {
+            return Err(CreateWebhookRequestError::NoEvents.into());
+        }
+
+        Ok(Self::new(url, events))
+    }
+}
+
+impl From<CreateWebhookRequestError> for ApiError {
+    fn from(e: CreateWebhookRequestError) -> Self {
         error!(?e, "Failed to convert API request to domain request");
-        Self::UnprocessableEntity(e.to_string())
+        Self::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
     }
 }
 
@@ -48,54 +334,339 @@ impl From<ServiceError> for ApiError {
     fn from(service_error: ServiceError) -> Self {
         use crate::domain::{
             repository::{
+                CreateApiTokenError::{
+                    Duplicate as CreateApiTokenDuplicate, Unknown as CreateApiTokenUnknown,
+                },
+                CreateAuthorError::{
+                    Duplicate as CreateAuthorDuplicate, Unknown as CreateAuthorUnknown,
+                    UserNotFound as CreateAuthorUserNotFound,
+                },
                 CreatePostError::*,
+                CreateTotpError::{AlreadyEnabled, Unknown as CreateTotpUnknown},
+                CreateUserError::{Duplicate as CreateUserDuplicate, Unknown as CreateUserUnknown},
+                DeleteApiTokenError::{
+                    ApiTokenNotFound as DeleteApiTokenNotFound, Unknown as DeleteApiTokenUnknown,
+                },
                 DeletePostError::{
                     PostNotFound as DeletePostNotFound, Unknown as DeletePostUnknown,
+                    VersionConflict as DeleteVersionConflict,
+                },
+                DeleteWebhookError::{
+                    Unknown as DeleteWebhookUnknown, WebhookNotFound as DeleteWebhookNotFound,
+                },
+                GetAuthorError::{
+                    AuthorNotFound as GetAuthorNotFound, Unknown as GetAuthorUnknown,
                 },
+                GetChangesError::{ResyncRequired, Unknown as GetChangesUnknown},
                 GetPostError::{PostNotFound, Unknown as GetPostUnknown},
+                GetSessionError::{SessionNotFound, Unknown as GetSessionUnknown},
+                GetTotpError::{TotpNotFound, Unknown as GetTotpUnknown},
+                GetUserError::{Unknown as GetUserUnknown, UserNotFound},
+                GetWebhookError::{Unknown as GetWebhookUnknown, WebhookNotFound},
+                ImportPostsError::{Duplicate as ImportDuplicate, Unknown as ImportUnknown},
+                RedeliverWebhookError::{
+                    DeliveryInProgress, DeliveryNotFound, Unknown as RedeliverWebhookUnknown,
+                },
                 RepositoryError::{
-                    CreatePostError, DeletePostError, GetPostError, Unknown as RepoUnknown,
-                    UpdatePostError,
+                    CreateApiTokenError, CreateAuthorError, CreatePostError, CreateTotpError,
+                    CreateUserError, DeleteApiTokenError, DeletePostError, DeleteWebhookError,
+                    GetAuthorError, GetChangesError, GetPostError, GetSessionError, GetTotpError,
+                    GetUserError, GetWebhookError, ImportPostsError, RedeliverWebhookError,
+                    RestorePostError, TakeOidcLoginError, Unknown as RepoUnknown, UpdatePostError,
+                    UseApiTokenError, UseSecondFactorError,
+                },
+                RestorePostError::{
+                    PostNotFound as RestorePostNotFound, TitleTaken, Unknown as RestorePostUnknown,
                 },
+                TakeOidcLoginError::{LoginNotFound, Unknown as TakeOidcLoginUnknown},
                 UpdatePostError::{
-                    Duplicate as UpdatePostDuplicate, PostNotFound as UpdatePostNotFound,
-                    Unknown as UpdatePostUnknown,
+                    AuthorNotFound as UpdatePostAuthorNotFound, Duplicate as UpdatePostDuplicate,
+                    PostNotFound as UpdatePostNotFound, Unknown as UpdatePostUnknown,
+                    VersionConflict as UpdateVersionConflict,
+                },
+                UseApiTokenError::{
+                    ApiTokenNotFound as UseApiTokenNotFound, Unknown as UseApiTokenUnknown,
                 },
+                UseSecondFactorError::{Rejected, Unknown as UseSecondFactorUnknown},
+            },
+            service::ServiceError::{
+                Forbidden, IdempotencyKeyInProgress, IdempotencyKeyReused, InvalidCredentials,
+                InvalidSecondFactor, PasswordHash, RepositoryError, SecondFactorEnrollmentRequired,
+                SecondFactorRequired, Unauthenticated, Unknown as ServiceUnknown,
+                WebhookUrlNotAllowed,
             },
-            service::ServiceError::*,
         };
 
         match service_error {
             RepositoryError(repo_error) => match repo_error {
                 CreatePostError(error) => match error {
-                    Duplicate { title } => {
-                        ApiError::Conflict(format!("Post with title {title} already exists."))
-                    }
+                    Duplicate { title } => ApiError::Conflict(
+                        ErrorCode::PostDuplicate,
+                        format!("Post with title {title} already exists."),
+                    ),
+                    AuthorNotFound => ApiError::UnprocessableEntity(
+                        ErrorCode::PostAuthorNotFound,
+                        AuthorNotFound.to_string(),
+                    ),
                     Unknown(e) => e.into(),
                 },
                 GetPostError(error) => match error {
-                    PostNotFound { id } => {
-                        ApiError::NotFound(format!("Could not find post with id {id}."))
-                    }
+                    PostNotFound { id } => ApiError::NotFound(
+                        ErrorCode::PostNotFound,
+                        format!("Could not find post with id {id}."),
+                    ),
                     GetPostUnknown(e) => e.into(),
                 },
                 UpdatePostError(error) => match error {
-                    UpdatePostDuplicate { title } => {
-                        ApiError::Conflict(format!("Post with title {title} already exists."))
-                    }
-                    UpdatePostNotFound { id } => {
-                        ApiError::NotFound(format!("Could not find post with id {id}."))
-                    }
+                    UpdatePostDuplicate { title } => ApiError::Conflict(
+                        ErrorCode::PostDuplicate,
+                        format!("Post with title {title} already exists."),
+                    ),
+                    UpdatePostNotFound { id } => ApiError::NotFound(
+                        ErrorCode::PostNotFound,
+                        format!("Could not find post with id {id}."),
+                    ),
+                    UpdatePostAuthorNotFound => ApiError::UnprocessableEntity(
+                        ErrorCode::PostAuthorNotFound,
+                        UpdatePostAuthorNotFound.to_string(),
+                    ),
+                    UpdateVersionConflict { id, .. } => ApiError::PreconditionFailed(
+                        ErrorCode::VersionConflict,
+                        format!("Post with id {id} has changed; fetch it again before updating."),
+                    ),
                     UpdatePostUnknown(e) => e.into(),
                 },
                 DeletePostError(error) => match error {
-                    DeletePostNotFound { id } => {
-                        ApiError::NotFound(format!("Could not find post with id {id}."))
-                    }
+                    DeletePostNotFound { id } => ApiError::NotFound(
+                        ErrorCode::PostNotFound,
+                        format!("Could not find post with id {id}."),
+                    ),
+                    DeleteVersionConflict { id, .. } => ApiError::PreconditionFailed(
+                        ErrorCode::VersionConflict,
+                        format!("Post with id {id} has changed; fetch it again before deleting."),
+                    ),
                     DeletePostUnknown(e) => e.into(),
                 },
+                RestorePostError(error) => match error {
+                    RestorePostNotFound { id } => ApiError::NotFound(
+                        ErrorCode::TrashedPostNotFound,
+                        format!("Could not find post with id {id} in the trash."),
+                    ),
+                    TitleTaken { id } => {
+                        ApiError::Conflict(ErrorCode::PostTitleTaken, TitleTaken { id }.to_string())
+                    }
+                    RestorePostUnknown(e) => e.into(),
+                },
+                ImportPostsError(error) => match error {
+                    ImportDuplicate { title } => ApiError::Conflict(
+                        ErrorCode::PostDuplicate,
+                        format!("Post with title {title} already exists."),
+                    ),
+                    ImportUnknown(e) => e.into(),
+                },
+                CreateAuthorError(error) => match error {
+                    CreateAuthorDuplicate { user_id } => ApiError::Conflict(
+                        ErrorCode::AuthorDuplicate,
+                        format!("User with id {user_id} already has an author profile."),
+                    ),
+                    CreateAuthorUserNotFound { user_id } => ApiError::UnprocessableEntity(
+                        ErrorCode::UserNotFound,
+                        format!("Could not find user with id {user_id}."),
+                    ),
+                    CreateAuthorUnknown(e) => e.into(),
+                },
+                GetAuthorError(error) => match error {
+                    GetAuthorNotFound { id } => ApiError::NotFound(
+                        ErrorCode::AuthorNotFound,
+                        format!("Could not find author with id {id}."),
+                    ),
+                    GetAuthorUnknown(e) => e.into(),
+                },
+                CreateUserError(error) => match error {
+                    CreateUserDuplicate { email } => ApiError::Conflict(
+                        ErrorCode::UserDuplicate,
+                        format!("User with email {email} already exists."),
+                    ),
+                    CreateUserUnknown(e) => e.into(),
+                },
+                GetUserError(error) => match error {
+                    UserNotFound { email } => ApiError::NotFound(
+                        ErrorCode::UserNotFound,
+                        format!("Could not find user with email {email}."),
+                    ),
+                    GetUserUnknown(e) => e.into(),
+                },
+                GetSessionError(error) => match error {
+                    SessionNotFound => ApiError::Unauthorized(
+                        ErrorCode::SessionNotFound,
+                        SessionNotFound.to_string(),
+                    ),
+                    GetSessionUnknown(e) => e.into(),
+                },
+                TakeOidcLoginError(error) => match error {
+                    LoginNotFound => ApiError::Unauthorized(
+                        ErrorCode::OidcLoginFailed,
+                        LoginNotFound.to_string(),
+                    ),
+                    TakeOidcLoginUnknown(e) => e.into(),
+                },
+                CreateTotpError(error) => match error {
+                    AlreadyEnabled => ApiError::Conflict(
+                        ErrorCode::TotpAlreadyEnabled,
+                        AlreadyEnabled.to_string(),
+                    ),
+                    CreateTotpUnknown(e) => e.into(),
+                },
+                GetTotpError(error) => match error {
+                    TotpNotFound => {
+                        ApiError::NotFound(ErrorCode::TotpNotFound, TotpNotFound.to_string())
+                    }
+                    GetTotpUnknown(e) => e.into(),
+                },
+                UseSecondFactorError(error) => match error {
+                    Rejected => {
+                        ApiError::Unauthorized(ErrorCode::InvalidSecondFactor, Rejected.to_string())
+                    }
+                    UseSecondFactorUnknown(e) => e.into(),
+                },
+                CreateApiTokenError(error) => match error {
+                    CreateApiTokenDuplicate { name } => ApiError::Conflict(
+                        ErrorCode::ApiTokenDuplicate,
+                        format!("API token with name {name} already exists."),
+                    ),
+                    CreateApiTokenUnknown(e) => e.into(),
+                },
+                DeleteApiTokenError(error) => match error {
+                    DeleteApiTokenNotFound { id } => ApiError::NotFound(
+                        ErrorCode::ApiTokenNotFound,
+                        format!("Could not find API token with id {id}."),
+                    ),
+                    DeleteApiTokenUnknown(e) => e.into(),
+                },
+                UseApiTokenError(error) => match error {
+                    UseApiTokenNotFound => ApiError::Unauthorized(
+                        ErrorCode::ApiTokenInvalid,
+                        UseApiTokenNotFound.to_string(),
+                    ),
+                    UseApiTokenUnknown(e) => e.into(),
+                },
+                GetWebhookError(error) => match error {
+                    WebhookNotFound { id } => ApiError::NotFound(
+                        ErrorCode::WebhookNotFound,
+                        format!("Could not find webhook with id {id}."),
+                    ),
+                    GetWebhookUnknown(e) => e.into(),
+                },
+                DeleteWebhookError(error) => match error {
+                    DeleteWebhookNotFound { id } => ApiError::NotFound(
+                        ErrorCode::WebhookNotFound,
+                        format!("Could not find webhook with id {id}."),
+                    ),
+                    DeleteWebhookUnknown(e) => e.into(),
+                },
+                RedeliverWebhookError(error) => match error {
+                    DeliveryNotFound { id } => ApiError::NotFound(
+                        ErrorCode::WebhookDeliveryNotFound,
+                        DeliveryNotFound { id }.to_string(),
+                    ),
+                    DeliveryInProgress { id } => ApiError::Conflict(
+                        ErrorCode::WebhookDeliveryInProgress,
+                        DeliveryInProgress { id }.to_string(),
+                    ),
+                    RedeliverWebhookUnknown(e) => e.into(),
+                },
+                GetChangesError(error) => match error {
+                    ResyncRequired {
+                        since,
+                        compacted_through,
+                    } => ApiError::Gone(
+                        ErrorCode::ResyncRequired,
+                        ResyncRequired {
+                            since,
+                            compacted_through,
+                        }
+                        .to_string(),
+                    ),
+                    GetChangesUnknown(e) => e.into(),
+                },
                 RepoUnknown(e) => e.into(),
             },
+            InvalidCredentials => ApiError::Unauthorized(
+                ErrorCode::InvalidCredentials,
+                InvalidCredentials.to_string(),
+            ),
+            Unauthenticated => {
+                ApiError::Unauthorized(ErrorCode::Unauthenticated, Unauthenticated.to_string())
+            }
+            Forbidden => ApiError::Forbidden(ErrorCode::Forbidden, Forbidden.to_string()),
+            SecondFactorRequired => ApiError::Unauthorized(
+                ErrorCode::SecondFactorRequired,
+                SecondFactorRequired.to_string(),
+            ),
+            InvalidSecondFactor => ApiError::Unauthorized(
+                ErrorCode::InvalidSecondFactor,
+                InvalidSecondFactor.to_string(),
+            ),
+            SecondFactorEnrollmentRequired => ApiError::Forbidden(
+                ErrorCode::SecondFactorEnrollmentRequired,
+                SecondFactorEnrollmentRequired.to_string(),
+            ),
+            IdempotencyKeyReused => ApiError::UnprocessableEntity(
+                ErrorCode::IdempotencyKeyReused,
+                IdempotencyKeyReused.to_string(),
+            ),
+            IdempotencyKeyInProgress => ApiError::Conflict(
+                ErrorCode::IdempotencyKeyInProgress,
+                IdempotencyKeyInProgress.to_string(),
+            ),
+            WebhookUrlNotAllowed(e) => {
+                ApiError::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string())
+            }
+            PasswordHash(e) => ApiError::InternalServerError(e.to_string()),
+            ServiceUnknown(e) => e.into(),
+        }
+    }
+}
+
+impl From<TotpEnrollment> for TotpEnrollmentResponse {
+    fn from(value: TotpEnrollment) -> Self {
+        Self {
+            secret: value.secret().to_base32(),
+            otpauth_uri: value.otpauth_uri().to_string(),
+        }
+    }
+}
+
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(value: AuditEntry) -> Self {
+        let record = value.record();
+
+        Self {
+            id: value.id(),
+            actor_id: record.context().actor_id(),
+            action: record.action().to_string(),
+            post_id: record.post_id(),
+            before: record.before().cloned(),
+            after: record.after().cloned(),
+            request_id: record.context().request_id().map(ToString::to_string),
+            created_at: record.created_at(),
+            prev_hash: to_hex(value.prev_hash()),
+            hash: to_hex(value.hash()),
+        }
+    }
+}
+
+impl From<AuditVerification> for AuditVerificationResponse {
+    fn from(value: AuditVerification) -> Self {
+        Self {
+            intact: value.is_intact(),
+            checked: value.checked(),
+            first_broken_id: value.first_broken_id(),
+            head_hash: to_hex(value.head_hash()),
         }
     }
 }
@@ -107,6 +678,139 @@ impl From<Post> for PostResponse {
             title: value.title().to_string(),
             body: value.body().to_string(),
             created_at: value.created_at(),
+            updated_at: value.updated_at(),
+            created_by: value.created_by(),
+            deleted_at: value.deleted_at(),
+            version: value.version(),
+            authors: value.authors().iter().cloned().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Vec<PostTerm>> for PostTermsResponse {
+    fn from(value: Vec<PostTerm>) -> Self {
+        let (categories, tags): (Vec<_>, Vec<_>) = value
+            .into_iter()
+            .partition(|term| term.kind() == TermKind::Category);
+        let response = |terms: Vec<PostTerm>| {
+            terms
+                .into_iter()
+                .map(|term| TermResponse {
+                    name: term.name().to_string(),
+                    slug: term.slug().map(ToString::to_string),
+                })
+                .collect()
+        };
+
+        Self {
+            categories: response(categories),
+            tags: response(tags),
+        }
+    }
+}
+
+impl From<PostComment> for CommentResponse {
+    fn from(value: PostComment) -> Self {
+        Self {
+            id: value.id(),
+            parent_id: value.parent_id(),
+            author_name: value.author_name().map(ToString::to_string),
+            author_url: value.author_url().map(ToString::to_string),
+            body: value.body().to_string(),
+            created_at: value.created_at(),
+        }
+    }
+}
+
+impl From<Author> for AuthorResponse {
+    fn from(value: Author) -> Self {
+        Self {
+            id: value.id(),
+            user_id: value.user_id(),
+            display_name: value.name().to_string(),
+            bio: value.bio().map(ToString::to_string),
+            avatar_url: value.avatar_url().map(ToString::to_string),
+            social_links: value
+                .social_links()
+                .iter()
+                .map(|link| SocialLink {
+                    label: link.label().to_string(),
+                    url: link.url().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<User> for UserResponse {
+    fn from(value: User) -> Self {
+        Self {
+            id: value.id(),
+            email: value.email().to_string(),
+            role: value.role().to_string(),
+            created_at: value.created_at(),
+        }
+    }
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(value: ApiToken) -> Self {
+        Self {
+            id: value.id(),
+            name: value.name().to_string(),
+            scopes: value.scopes().iter().map(ToString::to_string).collect(),
+            created_at: value.created_at(),
+            expires_at: value.expires_at(),
+            last_used_at: value.last_used_at(),
+        }
+    }
+}
+
+impl From<CreatedApiToken> for CreatedApiTokenResponse {
+    fn from(value: CreatedApiToken) -> Self {
+        Self {
+            token: value.token().into(),
+            secret: value.secret().expose().to_string(),
+        }
+    }
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(value: Webhook) -> Self {
+        Self {
+            id: value.id(),
+            url: value.url().to_string(),
+            events: value.events().iter().map(ToString::to_string).collect(),
+            created_by: value.created_by(),
+            created_at: value.created_at(),
+        }
+    }
+}
+
+impl From<CreatedWebhook> for CreatedWebhookResponse {
+    fn from(value: CreatedWebhook) -> Self {
+        Self {
+            webhook: value.webhook().into(),
+            secret: value.secret().expose().to_string(),
+        }
+    }
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(value: WebhookDelivery) -> Self {
+        let last_attempt = value.last_attempt();
+
+        Self {
+            id: value.id(),
+            event: value.event().to_string(),
+            status: value.status().to_string(),
+            attempts: value.attempts(),
+            next_attempt_at: value.next_attempt_at(),
+            last_attempt_at: last_attempt.map(|attempt| attempt.attempted_at()),
+            last_response_status: last_attempt.and_then(|attempt| attempt.response_status()),
+            last_error: last_attempt.and_then(|attempt| attempt.error().map(ToString::to_string)),
+            created_at: value.created_at(),
+            delivered_at: value.delivered_at(),
         }
     }
 }
@@ -0,0 +1,40 @@
+use axum::{
+    Router,
+    extract::State,
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use tracing::instrument;
+
+use crate::domain::service::Service;
+use crate::metrics::{METRICS_PATH, Metrics};
+use crate::server::AppState;
+
+use super::responses::ApiError;
+
+const TEXT_FORMAT: HeaderValue = HeaderValue::from_static("text/plain; version=0.0.4");
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new().route(METRICS_PATH, get(get_metrics::<S>))
+}
+
+/// Only `GET /metrics`, for a port of its own that is not exposed publicly.
+pub fn admin_routes(metrics: Metrics) -> Router {
+    Router::new()
+        .route(METRICS_PATH, get(scrape))
+        .with_state(metrics)
+}
+
+async fn get_metrics<S: Service>(State(state): State<AppState<S>>) -> Result<Response, ApiError> {
+    scrape(State(state.metrics().clone())).await
+}
+
+#[instrument(name = "get_metrics_handler", skip_all)]
+async fn scrape(State(metrics): State<Metrics>) -> Result<Response, ApiError> {
+    let body = metrics
+        .render()
+        .map_err(|err| ApiError::InternalServerError(err.to_string()))?;
+
+    Ok((StatusCode::OK, [(header::CONTENT_TYPE, TEXT_FORMAT)], body).into_response())
+}
@@ -0,0 +1,234 @@
+use std::time::Instant;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{FromRequestParts, MatchedPath, Request, State},
+    http::{HeaderMap, Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use tower_http::request_id::RequestId as RequestIdHeader;
+use tracing::error;
+
+use crate::{
+    domain::{models::api_token::ApiTokenSecret, service::Service},
+    metrics::UNMATCHED_ROUTE,
+    request_id::{self, RequestId},
+    server::AppState,
+};
+
+use super::{
+    cache::{self, CacheValidators},
+    extractors::CurrentUser,
+    idempotency,
+    responses::{ApiError, ErrorCode, ProblemDetails},
+};
+
+/// Longest plain-text error body taken over as the `detail` of a problem.
+const MAX_PLAIN_ERROR_BYTES: usize = 4 * 1024;
+
+/// Authenticates `Authorization: Bearer <token>` requests with an API token.
+/// The result is stored as a [`CurrentUser`] request extension; an invalid or
+/// expired token fails the request before it reaches a handler.
+pub async fn bearer_auth<S: Service>(
+    State(state): State<AppState<S>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(secret) = bearer_token(request.headers()) {
+        let (user, api_token) = state.service().authenticate_api_token(&secret).await?;
+        request
+            .extensions_mut()
+            .insert(CurrentUser::from_api_token(user, api_token));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Makes the id assigned by `SetRequestIdLayer` available to the service
+/// through [`request_id::current`] while the request is handled.
+pub async fn request_id_scope(request: Request, next: Next) -> Response {
+    let id = request
+        .extensions()
+        .get::<RequestIdHeader>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .map(RequestId::new);
+
+    match id {
+        Some(id) => request_id::scope(id, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+/// Applies the cache policy of the matched route, if it has one, to `GET`
+/// requests. Must be added with `Router::layer` to see the matched route.
+pub async fn http_cache<S: Service>(
+    State(state): State<AppState<S>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let policy = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| state.cache().policy(path.as_str()))
+        .filter(|_| matches!(*request.method(), Method::GET | Method::HEAD))
+        .cloned();
+    let Some(policy) = policy else {
+        return next.run(request).await;
+    };
+
+    let validators = CacheValidators::from_headers(request.headers());
+    let response = next.run(request).await;
+
+    cache::apply(&policy, &validators, response).await
+}
+
+/// Counts and times requests by method, matched route and status. Must be
+/// added with `Router::layer` to see the matched route.
+pub async fn http_metrics<S: Service>(
+    State(state): State<AppState<S>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(UNMATCHED_ROUTE, MatchedPath::as_str)
+        .to_string();
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    state
+        .metrics()
+        .observe_http_request(&method, &route, response.status(), started.elapsed());
+
+    response
+}
+
+/// Handles a `POST` to an idempotent route with an `Idempotency-Key` once per
+/// key, see [`idempotency`]. Keys belong to a user, so anonymous requests are
+/// passed on as they are. Must run after [`bearer_auth`], and be added with
+/// `Router::layer` to see the matched route.
+pub async fn idempotency_key<S: Service>(
+    State(state): State<AppState<S>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let idempotent_route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| idempotency::IDEMPOTENT_ROUTES.contains(&path.as_str()));
+    if request.method() != Method::POST || !idempotent_route {
+        return Ok(next.run(request).await);
+    }
+    let Some(key) = idempotency::key(request.headers())? else {
+        return Ok(next.run(request).await);
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let Ok(current_user) = CurrentUser::from_request_parts(&mut parts, &state).await else {
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    };
+    // Spares the handler authenticating a session again.
+    parts.extensions.insert(current_user.clone());
+    let user = current_user.user();
+
+    let body = idempotency::read_body(body, state.max_request_bytes()).await?;
+    let fingerprint = idempotency::fingerprint(&parts, &body);
+    if let Some(stored) = state
+        .service()
+        .claim_idempotency_key(user, &key, &fingerprint)
+        .await?
+    {
+        return Ok(idempotency::replay(&stored));
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (response, kept) = idempotency::keep(response).await;
+
+    // The request has taken effect either way, so its response goes out even
+    // if it cannot be kept. Retries then see a conflict until the lease ends.
+    let result = match &kept {
+        Some(kept) => {
+            state
+                .service()
+                .complete_idempotency_key(user, &key, kept)
+                .await
+        }
+        None => state.service().release_idempotency_key(user, &key).await,
+    };
+    if let Err(err) = result {
+        error!(?err, "Failed to record outcome of idempotent request");
+    }
+
+    Ok(response)
+}
+
+/// Turns error responses that are not problems into one, keeping their
+/// headers, e.g. `Allow`. These are the plain-text rejections of axum's
+/// extractors and the `405 Method Not Allowed` of the router. Must run inside
+/// [`problem_instance`].
+pub async fn plain_error_problem(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error())
+        || response.extensions().get::<ProblemDetails>().is_some()
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    // Server errors may say more than clients should know.
+    let detail = match status.is_server_error() {
+        true => None,
+        false => to_bytes(body, MAX_PLAIN_ERROR_BYTES)
+            .await
+            .ok()
+            .and_then(|body| String::from_utf8(body.to_vec()).ok())
+            .filter(|detail| !detail.trim().is_empty()),
+    }
+    .unwrap_or_else(|| status.canonical_reason().unwrap_or_default().to_string());
+
+    let problem = ProblemDetails::new(status, ErrorCode::for_status(status), detail)
+        .with_request_id(request_id::current());
+    let (problem_parts, problem_body) = problem.into_response().into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.extend(problem_parts.headers);
+    parts.extensions.extend(problem_parts.extensions);
+
+    Response::from_parts(parts, problem_body)
+}
+
+/// Fills in the `instance` of error responses with the path of the request.
+pub async fn problem_instance(request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let response = next.run(request).await;
+    let Some(problem) = response.extensions().get::<ProblemDetails>().cloned() else {
+        return response;
+    };
+
+    let problem = problem.with_instance(&path);
+    let body = match serde_json::to_vec(&problem) {
+        Ok(body) => body,
+        Err(err) => {
+            error!(?err, "Failed to serialize problem details");
+            return response;
+        }
+    };
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.extensions.insert(problem);
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<ApiTokenSecret> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| ApiTokenSecret::new(token.trim()))
+}
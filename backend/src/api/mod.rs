@@ -1,4 +1,21 @@
+pub mod admin;
+pub mod api_token;
+pub mod auth;
+pub mod author;
+pub mod cache;
+pub mod changes;
+pub mod events;
+pub mod extractors;
+pub mod graphql;
 pub mod health;
+pub mod idempotency;
 pub mod mappers;
+pub mod metrics;
+pub mod middleware;
+pub mod oidc;
+pub mod openapi;
 pub mod post;
 pub mod responses;
+pub mod totp;
+pub mod validation;
+pub mod webhook;
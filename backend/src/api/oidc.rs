@@ -0,0 +1,130 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Redirect,
+    routing::get,
+};
+use axum_extra::extract::{
+    CookieJar,
+    cookie::{Cookie, SameSite},
+};
+use serde::Deserialize;
+use time::Duration;
+use tracing::{error, instrument};
+
+use crate::domain::{models::oidc::OidcState, service::Service};
+use crate::oidc::{OidcClient, OidcError};
+use crate::server::AppState;
+
+use super::{
+    auth::session_cookie,
+    responses::{ApiError, ErrorCode},
+};
+
+/// Holds the `state` of a login in progress, so the callback only completes
+/// logins started by the same browser.
+pub const OIDC_STATE_COOKIE: &str = "blog_oidc_state";
+
+const OIDC_COOKIE_PATH: &str = "/auth/oidc";
+
+#[derive(Debug, Deserialize)]
+pub struct CallbackParams {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/auth/oidc/login", get(login::<S>))
+        .route("/auth/oidc/callback", get(callback::<S>))
+}
+
+#[instrument(name = "oidc_login_handler", skip_all)]
+async fn login<S: Service>(
+    State(state): State<AppState<S>>,
+    jar: CookieJar,
+) -> Result<(CookieJar, Redirect), ApiError> {
+    let oidc = oidc_client(&state)?;
+
+    let login = state.service().start_oidc_login().await?;
+    let url = oidc.authorization_url(&login).await?;
+
+    let cookie = Cookie::build((OIDC_STATE_COOKIE, login.state().expose().to_string()))
+        .http_only(true)
+        .secure(state.secure_cookies())
+        .same_site(SameSite::Lax)
+        .path(OIDC_COOKIE_PATH)
+        .max_age(Duration::minutes(10));
+
+    Ok((jar.add(cookie), Redirect::to(url.as_str())))
+}
+
+#[instrument(name = "oidc_callback_handler", skip_all)]
+async fn callback<S: Service>(
+    State(state): State<AppState<S>>,
+    jar: CookieJar,
+    Query(params): Query<CallbackParams>,
+) -> Result<(CookieJar, Redirect), ApiError> {
+    let oidc = oidc_client(&state)?;
+
+    if let Some(error) = params.error {
+        let description = params.error_description.unwrap_or_default();
+        return Err(ApiError::Unauthorized(
+            ErrorCode::OidcLoginFailed,
+            format!("Identity provider returned {error}. {description}"),
+        ));
+    }
+    let (Some(code), Some(returned_state)) = (params.code, params.state) else {
+        return Err(ApiError::Unauthorized(
+            ErrorCode::OidcLoginFailed,
+            "Callback is missing the code or state.".to_string(),
+        ));
+    };
+    if jar.get(OIDC_STATE_COOKIE).map(Cookie::value) != Some(returned_state.as_str()) {
+        return Err(ApiError::Unauthorized(
+            ErrorCode::OidcLoginFailed,
+            "Login was not started by this browser.".to_string(),
+        ));
+    }
+
+    let login = state
+        .service()
+        .take_oidc_login(&OidcState::new(&returned_state))
+        .await?;
+    let identity = oidc.exchange_code(&code, &login).await?;
+    let session = state
+        .service()
+        .login_with_identity(&identity, oidc.config().default_role)
+        .await?;
+
+    let jar = jar
+        .remove(Cookie::build(OIDC_STATE_COOKIE).path(OIDC_COOKIE_PATH))
+        .add(session_cookie(&session, state.secure_cookies())?);
+
+    Ok((jar, Redirect::to(&oidc.config().post_login_redirect)))
+}
+
+fn oidc_client<S: Service>(state: &AppState<S>) -> Result<&OidcClient, ApiError> {
+    state.oidc().ok_or_else(|| {
+        ApiError::NotFound(
+            ErrorCode::OidcNotConfigured,
+            "Single sign-on is not configured.".to_string(),
+        )
+    })
+}
+
+impl From<OidcError> for ApiError {
+    fn from(e: OidcError) -> Self {
+        match e {
+            OidcError::Http(_) | OidcError::IssuerMismatch { .. } => {
+                Self::InternalServerError(e.to_string())
+            }
+            _ => {
+                error!(?e, "Rejected OIDC login");
+                Self::Unauthorized(ErrorCode::OidcLoginFailed, e.to_string())
+            }
+        }
+    }
+}
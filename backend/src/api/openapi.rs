@@ -0,0 +1,84 @@
+//! OpenAPI 3.1 document of the API, generated from the handlers and their
+//! DTOs, and a Swagger UI to browse it.
+//!
+//! A test compares the document with `tests/fixtures/openapi.json`, so a
+//! change to the API shows up in review as a change to that file.
+
+use axum::Router;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::domain::service::Service;
+use crate::server::AppState;
+
+use super::{auth::SESSION_COOKIE, post, responses::ProblemDetails, validation::FieldError};
+
+pub const OPENAPI_PATH: &str = "/openapi.json";
+
+pub const DOCS_PATH: &str = "/docs";
+
+/// Name of the session cookie security scheme.
+const SESSION_SCHEME: &str = "session";
+
+/// Name of the API token security scheme.
+const API_TOKEN_SCHEME: &str = "api_token";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Tommy's Blog API",
+        description = "Errors are served as `application/problem+json` with a stable `code`."
+    ),
+    paths(
+        post::create_post,
+        post::get_posts,
+        post::get_post_by_id,
+        post::update_post,
+        post::delete_post,
+        post::restore_post,
+        post::get_post_terms,
+        post::get_post_comments,
+        post::get_trash,
+    ),
+    components(schemas(ProblemDetails, FieldError)),
+    modifiers(&SecuritySchemes, &WithoutLicense),
+    tags((name = "posts", description = "Posts and the trash"))
+)]
+pub struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            SESSION_SCHEME,
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(SESSION_COOKIE))),
+        );
+        components.add_security_scheme(
+            API_TOKEN_SCHEME,
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// The crate declares no license, which would otherwise show up as one with an
+/// empty name.
+struct WithoutLicense;
+
+impl Modify for WithoutLicense {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.info.license = None;
+    }
+}
+
+/// Serves the document at [`OPENAPI_PATH`] and the Swagger UI under
+/// [`DOCS_PATH`].
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    SwaggerUi::new(DOCS_PATH)
+        .url(OPENAPI_PATH, ApiDoc::openapi())
+        .into()
+}
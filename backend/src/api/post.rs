@@ -1,133 +1,543 @@
+use async_graphql::{InputObject, SimpleObject};
 use axum::routing::{delete, get, patch};
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderName, HeaderValue, StatusCode, header},
+    response::AppendHeaders,
     routing::post,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use tracing::{error, instrument};
+use tracing::instrument;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::domain::models::post::{PostBodyEmptyError, PostTitleEmptyError};
+use crate::domain::models::api_token::Scope;
+use crate::domain::models::post::Post;
 use crate::domain::{
     models::post::{
-        CreatePostRequest as DomainCreatePostRequest, UpdatePostRequest as DomainUpdatePostRequest,
+        CreatePostRequest as DomainCreatePostRequest, PostPageQuery,
+        UpdatePostRequest as DomainUpdatePostRequest,
     },
     service::Service,
 };
-use crate::ids::PostId;
+use crate::ids::{AuthorId, CommentId, PostId, UserId};
 use crate::server::AppState;
 
-use super::responses::{ApiError, ApiResult, ApiSuccess};
+use super::{
+    author::AuthorResponse,
+    cache::{SURROGATE_KEY, http_date},
+    extractors::{CurrentUser, IfMatch, embedding_etag},
+    responses::{ApiError, ApiResult, ApiSuccess, ProblemDetails},
+};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, InputObject)]
+#[graphql(name = "CreatePostInput")]
 pub struct CreatePostRequest {
     pub title: String,
     pub body: String,
+    /// Byline in order; defaults to the author profile of the caller.
+    #[serde(default)]
+    pub authors: Option<Vec<AuthorId>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, InputObject)]
+#[graphql(name = "UpdatePostInput")]
 pub struct UpdatePostRequest {
     pub title: Option<String>,
     pub body: Option<String>,
+    /// Replaces the byline when present.
+    #[serde(default)]
+    pub authors: Option<Vec<AuthorId>>,
 }
 
-#[derive(Debug, Clone, Error)]
-pub(super) enum CreatePostRequestError {
-    #[error(transparent)]
-    Title(#[from] PostTitleEmptyError),
-    #[error(transparent)]
-    Body(#[from] PostBodyEmptyError),
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, SimpleObject)]
+#[graphql(name = "Post", complex)]
 pub struct PostResponse {
     pub id: PostId,
     pub title: String,
     pub body: String,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<UserId>,
+    /// Only set for posts in the trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Starts the `ETag` of the post, and may be sent in `If-Match` as is.
+    pub version: i64,
+    pub authors: Vec<AuthorResponse>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
 pub struct BulkPostResponse {
     pub data: Vec<PostResponse>,
+    /// Pass as `after` to get the next page. Only set on a page that is
+    /// followed by another.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// The categories and tags a post is filed under, each in order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct PostTermsResponse {
+    pub categories: Vec<TermResponse>,
+    pub tags: Vec<TermResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct TermResponse {
+    pub name: String,
+    pub slug: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct BulkCommentResponse {
+    pub data: Vec<CommentResponse>,
+}
+
+/// An approved comment. Commenters' email addresses are never served.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct CommentResponse {
+    pub id: CommentId,
+    /// The comment on the same post this one replies to.
+    pub parent_id: Option<CommentId>,
+    pub author_name: Option<String>,
+    pub author_url: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Pages of `GET /posts`, oldest post first. Without either parameter every
+/// post comes in one response.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PostPageParams {
+    /// The `next_cursor` of the previous page.
+    pub after: Option<String>,
+    /// Posts per page, at most 1000.
+    pub limit: Option<i64>,
+}
+
+impl PostPageParams {
+    pub fn is_paged(&self) -> bool {
+        self.after.is_some() || self.limit.is_some()
+    }
+}
+
+/// Deleted posts go to the trash unless `permanent` is set.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeletePostParams {
+    #[serde(default)]
+    pub permanent: bool,
 }
 
+pub const POSTS_PATH: &str = "/posts";
+
+pub const POST_PATH: &str = "/posts/{post_id}";
+
+pub const RESTORE_POST_PATH: &str = "/posts/{post_id}/restore";
+
+pub const POST_TERMS_PATH: &str = "/posts/{post_id}/terms";
+
+pub const POST_COMMENTS_PATH: &str = "/posts/{post_id}/comments";
+
+pub const TRASH_PATH: &str = "/trash";
+
+/// Surrogate key of the post list, to purge whenever a post is created,
+/// changed or deleted.
+pub const POSTS_SURROGATE_KEY: &str = "posts";
+
+/// Surrogate keys of a post and the authors on its byline, so that changing
+/// either purges the cached post.
+pub fn post_surrogate_keys(post: &Post) -> String {
+    std::iter::once(format!("post-{}", post.id()))
+        .chain(
+            post.authors()
+                .iter()
+                .map(|author| format!("author-{}", author.id())),
+        )
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A post with its `ETag`, which the client sends back in `If-Match` to change
+/// it.
+type TaggedPostResponse = ([(HeaderName, HeaderValue); 1], ApiSuccess<PostResponse>);
+
+fn tagged(status: StatusCode, post: Post) -> TaggedPostResponse {
+    let response: PostResponse = post.into();
+    let authors = serde_json::to_vec(&response.authors).expect("Authors serialize to JSON");
+
+    (
+        [(header::ETAG, embedding_etag(response.version, &authors))],
+        ApiSuccess::new(status, response),
+    )
+}
+
+/// The post list with the date it last changed, if any post ever existed.
+type DatedPostsResponse = (
+    AppendHeaders<Option<(HeaderName, HeaderValue)>>,
+    ApiSuccess<BulkPostResponse>,
+);
+
 pub fn routes<S: Service>() -> Router<AppState<S>> {
     Router::new()
-        .route("/posts", post(create_post::<S>))
-        .route("/posts", get(get_posts::<S>))
-        .route("/posts/{post_id}", get(get_post_by_id::<S>))
-        .route("/posts/{post_id}", patch(update_post::<S>))
-        .route("/posts/{post_id}", delete(delete_post::<S>))
+        .route(POSTS_PATH, post(create_post::<S>))
+        .route(POSTS_PATH, get(get_posts::<S>))
+        .route(POST_PATH, get(get_post_by_id::<S>))
+        .route(POST_PATH, patch(update_post::<S>))
+        .route(POST_PATH, delete(delete_post::<S>))
+        .route(RESTORE_POST_PATH, post(restore_post::<S>))
+        .route(POST_TERMS_PATH, get(get_post_terms::<S>))
+        .route(POST_COMMENTS_PATH, get(get_post_comments::<S>))
+        .route(TRASH_PATH, get(get_trash::<S>))
 }
 
-#[instrument(name = "create_post_handler", skip(state), fields(title = %payload.title))]
+#[utoipa::path(
+    post,
+    path = POSTS_PATH,
+    tag = "posts",
+    security(("session" = []), ("api_token" = [])),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the first response to retries with the same key"),
+    ),
+    request_body = CreatePostRequest,
+    responses(
+        (status = 201, description = "Post created", body = PostResponse, headers(("ETag" = String, description = "Version of the post and a digest of its authors"))),
+        (status = 401, description = "Not signed in", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing the scope or role to do this", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A post with the title exists, or a request with the same Idempotency-Key is in progress", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "Request body too large", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields, unknown authors or a reused Idempotency-Key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+#[instrument(name = "create_post_handler", skip(state, current_user), fields(title = %payload.title))]
 async fn create_post<S: Service>(
     State(state): State<AppState<S>>,
+    current_user: CurrentUser,
     Json(payload): Json<CreatePostRequest>,
-) -> ApiResult<PostResponse> {
-    let domain_req = DomainCreatePostRequest::try_from(payload)?;
+) -> Result<TaggedPostResponse, ApiError> {
+    current_user.require_scope(Scope::PostsWrite)?;
+    let domain_req = DomainCreatePostRequest::try_from((payload, state.post_constraints()))?;
 
     state
         .service()
-        .create_post(&domain_req)
+        .create_post(current_user.user(), &domain_req)
         .await
         .map_err(ApiError::from)
-        .map(|post| ApiSuccess::new(StatusCode::CREATED, post.into()))
+        .map(|post| tagged(StatusCode::CREATED, post))
 }
 
+#[utoipa::path(
+    get,
+    path = POSTS_PATH,
+    tag = "posts",
+    params(PostPageParams),
+    responses(
+        (status = 200, description = "All posts, or a page of them", body = BulkPostResponse, headers(
+            ("Last-Modified" = String, description = "When a post was last created, changed or deleted"),
+        )),
+        (status = 422, description = "Malformed cursor", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 #[instrument(name = "get_posts", skip(state))]
-async fn get_posts<S: Service>(State(state): State<AppState<S>>) -> ApiResult<BulkPostResponse> {
-    let data: Vec<PostResponse> = state
+async fn get_posts<S: Service>(
+    State(state): State<AppState<S>>,
+    Query(params): Query<PostPageParams>,
+) -> Result<DatedPostsResponse, ApiError> {
+    let page_query = params
+        .is_paged()
+        .then(|| PostPageQuery::try_from(params))
+        .transpose()?;
+    // Read before the posts, so a change in between makes the date too old
+    // rather than too new.
+    let last_modified = state
         .service()
-        .get_all_posts()
+        .get_posts_last_modified()
         .await
         .map_err(ApiError::from)?
-        .into_iter()
-        .map(Into::into)
-        .collect();
+        .map(|at| (header::LAST_MODIFIED, http_date(at)));
+    let (posts, next_cursor) = match page_query {
+        Some(query) => {
+            let page = state
+                .service()
+                .get_posts_page(&query)
+                .await
+                .map_err(ApiError::from)?;
+            let next_cursor = page.next_cursor().map(|cursor| cursor.to_string());
+            (page.into_posts(), next_cursor)
+        }
+        None => (
+            state
+                .service()
+                .get_all_posts()
+                .await
+                .map_err(ApiError::from)?,
+            None,
+        ),
+    };
+    let data = posts.into_iter().map(Into::into).collect();
 
-    Ok(ApiSuccess::new(StatusCode::OK, BulkPostResponse { data }))
+    Ok((
+        AppendHeaders(last_modified),
+        ApiSuccess::new(StatusCode::OK, BulkPostResponse { data, next_cursor }),
+    ))
 }
 
+#[utoipa::path(
+    get,
+    path = POST_PATH,
+    tag = "posts",
+    params(
+        ("post_id" = PostId, Path, description = "Id of the post"),
+        ("If-None-Match" = Option<String>, Header, description = "`ETag` of a cached copy"),
+    ),
+    responses(
+        (status = 200, description = "The post", body = PostResponse, headers(
+            ("ETag" = String, description = "Version of the post and a digest of its authors"),
+            ("Last-Modified" = String, description = "When the post last changed"),
+            ("Surrogate-Key" = String, description = "Keys to purge the post from a CDN by"),
+        )),
+        (status = 304, description = "The cached copy is current"),
+        (status = 404, description = "No such post", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 async fn get_post_by_id<S: Service>(
     State(state): State<AppState<S>>,
     Path(post_id): Path<PostId>,
-) -> ApiResult<PostResponse> {
-    state
+) -> Result<([(HeaderName, HeaderValue); 2], TaggedPostResponse), ApiError> {
+    let post = state
         .service()
         .get_posts_by_id(post_id)
         .await
+        .map_err(ApiError::from)?;
+    // The ETag covers the authors as served, so a restored author profile
+    // changes it too; such edits also purge the CDN by surrogate key.
+    let cache_headers = [
+        (header::LAST_MODIFIED, http_date(post.updated_at())),
+        (
+            SURROGATE_KEY,
+            HeaderValue::from_str(&post_surrogate_keys(&post))
+                .expect("Surrogate keys are valid header values"),
+        ),
+    ];
+
+    Ok((cache_headers, tagged(StatusCode::OK, post)))
+}
+
+#[utoipa::path(
+    get,
+    path = POST_TERMS_PATH,
+    tag = "posts",
+    params(("post_id" = PostId, Path, description = "Id of the post")),
+    responses(
+        (status = 200, description = "Categories and tags of the post", body = PostTermsResponse),
+        (status = 404, description = "No such post", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+async fn get_post_terms<S: Service>(
+    State(state): State<AppState<S>>,
+    Path(post_id): Path<PostId>,
+) -> ApiResult<PostTermsResponse> {
+    state
+        .service()
+        .get_post_terms(post_id)
+        .await
         .map_err(ApiError::from)
-        .map(|post| ApiSuccess::new(StatusCode::OK, post.into()))
+        .map(|terms| ApiSuccess::new(StatusCode::OK, terms.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = POST_COMMENTS_PATH,
+    tag = "posts",
+    params(("post_id" = PostId, Path, description = "Id of the post")),
+    responses(
+        (status = 200, description = "Approved comments on the post, oldest first", body = BulkCommentResponse),
+        (status = 404, description = "No such post", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+async fn get_post_comments<S: Service>(
+    State(state): State<AppState<S>>,
+    Path(post_id): Path<PostId>,
+) -> ApiResult<BulkCommentResponse> {
+    let data = state
+        .service()
+        .get_post_comments(post_id)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        BulkCommentResponse { data },
+    ))
 }
 
+#[utoipa::path(
+    patch,
+    path = POST_PATH,
+    tag = "posts",
+    security(("session" = []), ("api_token" = [])),
+    params(
+        ("post_id" = PostId, Path, description = "Id of the post"),
+        ("If-Match" = String, Header, description = "`ETag` of the post as last read"),
+    ),
+    request_body = UpdatePostRequest,
+    responses(
+        (status = 200, description = "Post updated", body = PostResponse, headers(("ETag" = String, description = "Version of the post and a digest of its authors"))),
+        (status = 401, description = "Not signed in", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing the scope or role to do this", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such post", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A post with the title exists", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The post changed since it was read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields or unknown authors", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 async fn update_post<S: Service>(
     State(state): State<AppState<S>>,
+    current_user: CurrentUser,
     Path(post_id): Path<PostId>,
+    if_match: IfMatch,
     Json(payload): Json<UpdatePostRequest>,
-) -> ApiResult<PostResponse> {
-    let domain_req = DomainUpdatePostRequest::try_from(payload)?;
+) -> Result<TaggedPostResponse, ApiError> {
+    current_user.require_scope(Scope::PostsWrite)?;
+    let domain_req = DomainUpdatePostRequest::try_from((payload, state.post_constraints()))?;
 
     state
         .service()
-        .update_post(post_id, &domain_req)
+        .update_post(
+            current_user.user(),
+            post_id,
+            if_match.version(),
+            &domain_req,
+        )
         .await
         .map_err(ApiError::from)
-        .map(|post| ApiSuccess::new(StatusCode::OK, post.into()))
+        .map(|post| tagged(StatusCode::OK, post))
 }
 
+#[utoipa::path(
+    delete,
+    path = POST_PATH,
+    tag = "posts",
+    security(("session" = []), ("api_token" = [])),
+    params(
+        ("post_id" = PostId, Path, description = "Id of the post"),
+        ("If-Match" = String, Header, description = "`ETag` of the post as last read"),
+        DeletePostParams,
+    ),
+    responses(
+        (status = 204, description = "Post moved to the trash, or deleted for good"),
+        (status = 401, description = "Not signed in", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing the scope or role to do this", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such post", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "The post changed since it was read", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 428, description = "If-Match is missing", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 async fn delete_post<S: Service>(
     State(state): State<AppState<S>>,
+    current_user: CurrentUser,
     Path(post_id): Path<PostId>,
+    Query(params): Query<DeletePostParams>,
+    if_match: IfMatch,
 ) -> ApiResult<()> {
+    current_user.require_scope(Scope::PostsDelete)?;
+    let result = if params.permanent {
+        state
+            .service()
+            .delete_post_permanently(current_user.user(), post_id, if_match.version())
+            .await
+    } else {
+        state
+            .service()
+            .delete_post(current_user.user(), post_id, if_match.version())
+            .await
+    };
+
+    result
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
+
+#[utoipa::path(
+    post,
+    path = RESTORE_POST_PATH,
+    tag = "posts",
+    security(("session" = []), ("api_token" = [])),
+    params(
+        ("post_id" = PostId, Path, description = "Id of the post"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the first response to retries with the same key"),
+    ),
+    responses(
+        (status = 200, description = "Post restored from the trash", body = PostResponse, headers(("ETag" = String, description = "Version of the post and a digest of its authors"))),
+        (status = 401, description = "Not signed in", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing the scope or role to do this", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "No such post in the trash", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A live post has the title, or a request with the same Idempotency-Key is in progress", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "Request body too large", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "A reused Idempotency-Key", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+async fn restore_post<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Path(post_id): Path<PostId>,
+) -> Result<TaggedPostResponse, ApiError> {
+    current_user.require_scope(Scope::PostsDelete)?;
     state
         .service()
-        .delete_post(post_id)
+        .restore_post(current_user.user(), post_id)
         .await
         .map_err(ApiError::from)
-        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+        .map(|post| tagged(StatusCode::OK, post))
+}
+
+#[utoipa::path(
+    get,
+    path = TRASH_PATH,
+    tag = "posts",
+    security(("session" = []), ("api_token" = [])),
+    responses(
+        (status = 200, description = "Posts in the trash", body = BulkPostResponse),
+        (status = 401, description = "Not signed in", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Missing the scope or role to do this", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+#[instrument(name = "get_trash", skip_all)]
+async fn get_trash<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+) -> ApiResult<BulkPostResponse> {
+    current_user.require_scope(Scope::PostsRead)?;
+    let data = state
+        .service()
+        .get_trash(current_user.user())
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        BulkPostResponse {
+            data,
+            next_cursor: None,
+        },
+    ))
 }
@@ -1,9 +1,18 @@
+use std::fmt::Display;
+
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, Uri, header},
     response::{IntoResponse, Response},
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::request_id::{self, RequestId};
+
+use super::validation::FieldError;
+
+pub const PROBLEM_JSON: HeaderValue = HeaderValue::from_static("application/problem+json");
 
 pub(super) type ApiResult<T> = Result<ApiSuccess<T>, ApiError>;
 
@@ -40,24 +49,149 @@ impl<T: Serialize + PartialEq> ApiResponseBody<T> {
     }
 }
 
-impl ApiResponseBody<ApiErrorData> {
-    pub fn new_error(message: String) -> Self {
-        Self(ApiErrorData { message })
+/// Stable, machine-readable reason of an error response. Clients should match
+/// on these rather than on the wording of `detail`, which may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    MalformedRequest,
+    UnsupportedMediaType,
+    RouteNotFound,
+    MethodNotAllowed,
+    ValidationFailed,
+    PayloadTooLarge,
+    Unauthenticated,
+    InvalidCredentials,
+    SessionNotFound,
+    SecondFactorRequired,
+    InvalidSecondFactor,
+    SecondFactorEnrollmentRequired,
+    TotpAlreadyEnabled,
+    TotpNotFound,
+    OidcNotConfigured,
+    OidcLoginFailed,
+    Forbidden,
+    ScopeMissing,
+    SessionRequired,
+    ApiTokenInvalid,
+    ApiTokenDuplicate,
+    ApiTokenNotFound,
+    PostNotFound,
+    PostDuplicate,
+    PostAuthorNotFound,
+    TrashedPostNotFound,
+    PostTitleTaken,
+    AuthorNotFound,
+    AuthorDuplicate,
+    UserNotFound,
+    UserDuplicate,
+    WebhookNotFound,
+    WebhookDeliveryNotFound,
+    WebhookDeliveryInProgress,
+    ResyncRequired,
+    IfMatchRequired,
+    VersionConflict,
+    IdempotencyKeyInvalid,
+    IdempotencyKeyReused,
+    IdempotencyKeyInProgress,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MalformedRequest => "malformed_request",
+            Self::UnsupportedMediaType => "unsupported_media_type",
+            Self::RouteNotFound => "route_not_found",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::ValidationFailed => "validation_failed",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::Unauthenticated => "unauthenticated",
+            Self::InvalidCredentials => "invalid_credentials",
+            Self::SessionNotFound => "session_not_found",
+            Self::SecondFactorRequired => "second_factor_required",
+            Self::InvalidSecondFactor => "invalid_second_factor",
+            Self::SecondFactorEnrollmentRequired => "second_factor_enrollment_required",
+            Self::TotpAlreadyEnabled => "totp_already_enabled",
+            Self::TotpNotFound => "totp_not_found",
+            Self::OidcNotConfigured => "oidc_not_configured",
+            Self::OidcLoginFailed => "oidc_login_failed",
+            Self::Forbidden => "forbidden",
+            Self::ScopeMissing => "scope_missing",
+            Self::SessionRequired => "session_required",
+            Self::ApiTokenInvalid => "api_token_invalid",
+            Self::ApiTokenDuplicate => "api_token_duplicate",
+            Self::ApiTokenNotFound => "api_token_not_found",
+            Self::PostNotFound => "post_not_found",
+            Self::PostDuplicate => "post_duplicate",
+            Self::PostAuthorNotFound => "post_author_not_found",
+            Self::TrashedPostNotFound => "trashed_post_not_found",
+            Self::PostTitleTaken => "post_title_taken",
+            Self::AuthorNotFound => "author_not_found",
+            Self::AuthorDuplicate => "author_duplicate",
+            Self::UserNotFound => "user_not_found",
+            Self::UserDuplicate => "user_duplicate",
+            Self::WebhookNotFound => "webhook_not_found",
+            Self::WebhookDeliveryNotFound => "webhook_delivery_not_found",
+            Self::WebhookDeliveryInProgress => "webhook_delivery_in_progress",
+            Self::ResyncRequired => "resync_required",
+            Self::IfMatchRequired => "if_match_required",
+            Self::VersionConflict => "version_conflict",
+            Self::IdempotencyKeyInvalid => "idempotency_key_invalid",
+            Self::IdempotencyKeyReused => "idempotency_key_reused",
+            Self::IdempotencyKeyInProgress => "idempotency_key_in_progress",
+            Self::InternalError => "internal_error",
+        }
+    }
+
+    /// Identifies the problem type in `type`. There is no documentation to
+    /// point at, so it is a URN rather than a URL.
+    pub fn problem_type(&self) -> String {
+        format!("urn:problem-type:{}", self.as_str())
+    }
+
+    /// The code of an error response that was not a problem to begin with,
+    /// such as the rejection of an axum extractor.
+    pub fn for_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => Self::RouteNotFound,
+            StatusCode::METHOD_NOT_ALLOWED => Self::MethodNotAllowed,
+            StatusCode::PAYLOAD_TOO_LARGE => Self::PayloadTooLarge,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => Self::UnsupportedMediaType,
+            StatusCode::UNPROCESSABLE_ENTITY => Self::ValidationFailed,
+            status if status.is_server_error() => Self::InternalError,
+            _ => Self::MalformedRequest,
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiError {
-    Conflict(String),
-    NotFound(String),
-    UnprocessableEntity(String),
+    Unauthorized(ErrorCode, String),
+    Forbidden(ErrorCode, String),
+    Conflict(ErrorCode, String),
+    NotFound(ErrorCode, String),
+    Gone(ErrorCode, String),
+    PreconditionFailed(ErrorCode, String),
+    PreconditionRequired(ErrorCode, String),
+    PayloadTooLarge(ErrorCode, String),
+    UnprocessableEntity(ErrorCode, String),
+    /// A request body with one or more invalid fields.
+    InvalidFields(Vec<FieldError>),
     InternalServerError(String),
 }
 
-impl ApiError {
-    fn generate_response_input(status_code: StatusCode, message: String) -> Response {
-        (status_code, Json(ApiResponseBody::new_error(message))).into_response()
-    }
+/// Answers requests for paths no route matches.
+pub async fn route_not_found(uri: Uri) -> ApiError {
+    ApiError::NotFound(
+        ErrorCode::RouteNotFound,
+        format!("There is nothing at {}.", uri.path()),
+    )
 }
 
 impl From<anyhow::Error> for ApiError {
@@ -68,29 +202,107 @@ impl From<anyhow::Error> for ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        ProblemDetails::from(self).into_response()
+    }
+}
+
+impl From<ApiError> for ProblemDetails {
+    fn from(error: ApiError) -> Self {
         use ApiError::*;
 
-        match self {
+        let (status, code, detail) = match error {
+            InvalidFields(errors) => {
+                let detail = format!("Request has {} invalid field(s).", errors.len());
+                return ProblemDetails::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    ErrorCode::ValidationFailed,
+                    detail,
+                )
+                .with_errors(errors)
+                .with_request_id(request_id::current());
+            }
             InternalServerError(e) => {
                 tracing::error!("{}", e);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponseBody::new_error(
-                        "Internal server error".to_string(),
-                    )),
+                    ErrorCode::InternalError,
+                    "Internal server error".to_string(),
                 )
-                    .into_response()
-            }
-            NotFound(message) => Self::generate_response_input(StatusCode::NOT_FOUND, message),
-            UnprocessableEntity(message) => {
-                Self::generate_response_input(StatusCode::UNPROCESSABLE_ENTITY, message)
             }
-            Conflict(message) => Self::generate_response_input(StatusCode::CONFLICT, message),
+            Unauthorized(code, detail) => (StatusCode::UNAUTHORIZED, code, detail),
+            Forbidden(code, detail) => (StatusCode::FORBIDDEN, code, detail),
+            NotFound(code, detail) => (StatusCode::NOT_FOUND, code, detail),
+            Gone(code, detail) => (StatusCode::GONE, code, detail),
+            UnprocessableEntity(code, detail) => (StatusCode::UNPROCESSABLE_ENTITY, code, detail),
+            Conflict(code, detail) => (StatusCode::CONFLICT, code, detail),
+            PreconditionFailed(code, detail) => (StatusCode::PRECONDITION_FAILED, code, detail),
+            PreconditionRequired(code, detail) => (StatusCode::PRECONDITION_REQUIRED, code, detail),
+            PayloadTooLarge(code, detail) => (StatusCode::PAYLOAD_TOO_LARGE, code, detail),
+        };
+
+        ProblemDetails::new(status, code, detail).with_request_id(request_id::current())
+    }
+}
+
+/// Body of every error response, as `application/problem+json` (RFC 9457),
+/// with the error `code` and the `request_id` as extension members.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// Path of the request that failed, filled in by the `problem_instance`
+    /// middleware.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// The invalid fields of a `validation_failed` problem.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl ProblemDetails {
+    pub fn new(status: StatusCode, code: ErrorCode, detail: String) -> Self {
+        Self {
+            problem_type: code.problem_type(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail,
+            instance: None,
+            code: code.to_string(),
+            request_id: None,
+            errors: Vec::new(),
         }
     }
+
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn with_instance(mut self, instance: &str) -> Self {
+        self.instance = Some(instance.to_string());
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: Option<RequestId>) -> Self {
+        self.request_id = request_id.map(|id| id.to_string());
+        self
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ApiErrorData {
-    pub message: String,
+/// The problem travels with the response as an extension as well, so
+/// middleware can complete it without parsing the body.
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response =
+            (status, [(header::CONTENT_TYPE, PROBLEM_JSON)], Json(&self)).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
 }
@@ -0,0 +1,124 @@
+//! Two-factor authentication with an authenticator app (TOTP).
+//!
+//! Enrollment takes two steps: `POST /auth/totp/enroll` returns a secret and
+//! an `otpauth://` URI to show as a QR code, and `POST /auth/totp/confirm`
+//! turns it on once the app produces a valid code, returning recovery codes.
+//!
+//! Afterwards a password or single sign-on login only starts a pending
+//! session, answered with `202 Accepted`. It is completed with a code at
+//! `POST /auth/totp/verify`, or by sending the code along with the password.
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use axum_extra::extract::CookieJar;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::domain::{models::totp::TotpCode, service::Service};
+use crate::server::AppState;
+
+use super::{
+    auth::UserResponse,
+    extractors::{CurrentUser, session_token},
+    mappers::second_factor,
+    responses::{ApiError, ApiResult, ApiSuccess, ErrorCode},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 secret, for typing into an app by hand.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Exactly one of the two must be given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifySecondFactorRequest {
+    #[serde(default)]
+    pub totp_code: Option<String>,
+    #[serde(default)]
+    pub recovery_code: Option<String>,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/auth/totp/enroll", post(enroll::<S>))
+        .route("/auth/totp/confirm", post(confirm::<S>))
+        .route("/auth/totp/verify", post(verify::<S>))
+}
+
+#[instrument(name = "enroll_totp_handler", skip_all)]
+async fn enroll<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+) -> ApiResult<TotpEnrollmentResponse> {
+    current_user.require_session()?;
+
+    state
+        .service()
+        .enroll_totp(current_user.user())
+        .await
+        .map_err(ApiError::from)
+        .map(|enrollment| ApiSuccess::new(StatusCode::OK, enrollment.into()))
+}
+
+#[instrument(name = "confirm_totp_handler", skip_all)]
+async fn confirm<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Json(payload): Json<ConfirmTotpRequest>,
+) -> ApiResult<RecoveryCodesResponse> {
+    current_user.require_session()?;
+    let code = TotpCode::try_new(&payload.code)
+        .map_err(|e| ApiError::UnprocessableEntity(ErrorCode::ValidationFailed, e.to_string()))?;
+
+    let recovery_codes = state
+        .service()
+        .confirm_totp(current_user.user(), &code)
+        .await?;
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        RecoveryCodesResponse {
+            recovery_codes: recovery_codes.iter().map(ToString::to_string).collect(),
+        },
+    ))
+}
+
+/// Completes the pending session of the session cookie; the cookie itself
+/// stays the same.
+#[instrument(name = "verify_second_factor_handler", skip_all)]
+async fn verify<S: Service>(
+    State(state): State<AppState<S>>,
+    jar: CookieJar,
+    Json(payload): Json<VerifySecondFactorRequest>,
+) -> ApiResult<UserResponse> {
+    let token = session_token(&jar).ok_or_else(|| {
+        ApiError::Unauthorized(
+            ErrorCode::Unauthenticated,
+            "Log in with your password first.".to_string(),
+        )
+    })?;
+    let factor = second_factor(payload.totp_code, payload.recovery_code)?.ok_or_else(|| {
+        ApiError::UnprocessableEntity(
+            ErrorCode::ValidationFailed,
+            "Provide a one-time code or a recovery code.".to_string(),
+        )
+    })?;
+
+    state
+        .service()
+        .complete_login(&token, &factor)
+        .await
+        .map_err(ApiError::from)
+        .map(|session| ApiSuccess::new(StatusCode::OK, session.user().into()))
+}
@@ -0,0 +1,90 @@
+//! Field-level validation of request bodies.
+//!
+//! A [`Validator`] checks every field before giving up, so a `422` lists all
+//! that is wrong with a request at once. Each failure names its field by JSON
+//! pointer (RFC 6901) into the request body, e.g. `/title`.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::post::{PostBodyError, PostTitleError};
+
+use super::responses::ApiError;
+
+/// One invalid field, in the `errors` of a validation problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    pub pointer: String,
+    /// Stable, like the problem's own `code`, e.g. `too_long`.
+    pub code: String,
+    pub detail: String,
+}
+
+/// Errors that can be reported for a single field.
+pub(super) trait FieldErrorCode: Display {
+    fn code(&self) -> &'static str;
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the error of `result`, if any, against the field at `pointer`.
+    pub(super) fn check<T, E: FieldErrorCode>(
+        &mut self,
+        pointer: &str,
+        result: Result<T, E>,
+    ) -> Option<T> {
+        result
+            .map_err(|e| {
+                self.errors.push(FieldError {
+                    pointer: pointer.to_string(),
+                    code: e.code().to_string(),
+                    detail: e.to_string(),
+                })
+            })
+            .ok()
+    }
+
+    /// Fails with every error recorded so far.
+    pub(super) fn finish(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.into_error())
+        }
+    }
+
+    pub(super) fn into_error(self) -> ApiError {
+        ApiError::InvalidFields(self.errors)
+    }
+}
+
+impl FieldErrorCode for PostTitleError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::TooShort { .. } => "too_short",
+            Self::TooLong { .. } => "too_long",
+            Self::ControlCharacter => "control_character",
+        }
+    }
+}
+
+impl FieldErrorCode for PostBodyError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::TooLarge { .. } => "too_large",
+            Self::ControlCharacter => "control_character",
+        }
+    }
+}
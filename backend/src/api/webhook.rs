@@ -0,0 +1,191 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::instrument;
+
+use crate::domain::{
+    models::{
+        event::PostChangeKindInvalidError,
+        webhook::{CreateWebhookRequest as DomainCreateWebhookRequest, WebhookUrlInvalidError},
+    },
+    service::Service,
+};
+use crate::ids::{UserId, WebhookId};
+use crate::server::AppState;
+
+use super::{
+    extractors::CurrentUser,
+    responses::{ApiError, ApiResult, ApiSuccess},
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Any of `post.created`, `post.updated` and `post.deleted`.
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, Error)]
+pub(super) enum CreateWebhookRequestError {
+    #[error(transparent)]
+    Url(#[from] WebhookUrlInvalidError),
+    #[error(transparent)]
+    Event(#[from] PostChangeKindInvalidError),
+    #[error("Webhook needs at least one event")]
+    NoEvents,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookResponse {
+    pub id: WebhookId,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Returned once on creation; the secret cannot be retrieved again.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BulkWebhookResponse {
+    pub data: Vec<WebhookResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDeliveryResponse {
+    pub id: i64,
+    pub event: String,
+    /// `pending`, `delivered` or `dead`.
+    pub status: String,
+    pub attempts: u32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BulkWebhookDeliveryResponse {
+    pub data: Vec<WebhookDeliveryResponse>,
+}
+
+pub fn routes<S: Service>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/webhooks", post(create_webhook::<S>))
+        .route("/webhooks", get(get_webhooks::<S>))
+        .route("/webhooks/{webhook_id}", delete(delete_webhook::<S>))
+        .route(
+            "/webhooks/{webhook_id}/deliveries",
+            get(get_webhook_deliveries::<S>),
+        )
+        .route(
+            "/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+            post(redeliver_webhook::<S>),
+        )
+}
+
+#[instrument(name = "create_webhook_handler", skip(state, current_user), fields(url = %payload.url))]
+async fn create_webhook<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> ApiResult<CreatedWebhookResponse> {
+    current_user.require_session()?;
+    let domain_req = DomainCreateWebhookRequest::try_from(payload)?;
+
+    state
+        .service()
+        .create_webhook(current_user.user(), &domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|created| ApiSuccess::new(StatusCode::CREATED, created.into()))
+}
+
+#[instrument(name = "get_webhooks_handler", skip_all)]
+async fn get_webhooks<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+) -> ApiResult<BulkWebhookResponse> {
+    current_user.require_session()?;
+
+    let data = state
+        .service()
+        .get_webhooks(current_user.user())
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        BulkWebhookResponse { data },
+    ))
+}
+
+#[instrument(name = "delete_webhook_handler", skip(state, current_user))]
+async fn delete_webhook<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Path(webhook_id): Path<WebhookId>,
+) -> ApiResult<()> {
+    current_user.require_session()?;
+
+    state
+        .service()
+        .delete_webhook(current_user.user(), webhook_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
+
+#[instrument(name = "get_webhook_deliveries_handler", skip(state, current_user))]
+async fn get_webhook_deliveries<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Path(webhook_id): Path<WebhookId>,
+) -> ApiResult<BulkWebhookDeliveryResponse> {
+    current_user.require_session()?;
+
+    let data = state
+        .service()
+        .get_webhook_deliveries(current_user.user(), webhook_id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(ApiSuccess::new(
+        StatusCode::OK,
+        BulkWebhookDeliveryResponse { data },
+    ))
+}
+
+#[instrument(name = "redeliver_webhook_handler", skip(state, current_user))]
+async fn redeliver_webhook<S: Service>(
+    State(state): State<AppState<S>>,
+    current_user: CurrentUser,
+    Path((webhook_id, delivery_id)): Path<(WebhookId, i64)>,
+) -> ApiResult<WebhookDeliveryResponse> {
+    current_user.require_session()?;
+
+    state
+        .service()
+        .redeliver_webhook(current_user.user(), webhook_id, delivery_id)
+        .await
+        .map_err(ApiError::from)
+        .map(|delivery| ApiSuccess::new(StatusCode::ACCEPTED, delivery.into()))
+}
@@ -0,0 +1,697 @@
+//! Portable backups of the whole blog.
+//!
+//! A backup is a newline delimited JSON (NDJSON) document, one record per
+//! line, so it can be written and read as a stream. Every record carries a
+//! `type` discriminator.
+//!
+//! The first line is always the header:
+//!
+//! ```json
+//! {"type":"header","format":"tommys-blog-backup","version":3,"exported_at":"2025-05-04T00:27:07Z"}
+//! ```
+//!
+//! | field         | type             | description                               |
+//! |---------------|------------------|-------------------------------------------|
+//! | `format`      | string           | always `tommys-blog-backup`               |
+//! | `version`     | integer          | format version, currently `3`             |
+//! | `exported_at` | RFC 3339 string  | when the backup was taken                 |
+//!
+//! It is followed by one `post` record per post, in the trash or not, oldest
+//! first:
+//!
+//! ```json
+//! {"type":"post","id":"7f0b4d1e-5c9a-4a53-9a5e-1f4f0c2d9b11","title":"Hello","body":"World","created_at":"2025-05-04T00:27:07Z"}
+//! ```
+//!
+//! | field        | type            | description                                |
+//! |--------------|-----------------|--------------------------------------------|
+//! | `id`         | UUID string     | post ID, kept on restore                   |
+//! | `title`      | string          | non-empty, unique among posts not in trash |
+//! | `body`       | string          | non-empty                                  |
+//! | `created_at` | RFC 3339 string | original creation time, kept on restore    |
+//! | `updated_at` | RFC 3339 string | optional; last change, kept on restore     |
+//! | `created_by` | UUID string     | optional; owning user, kept if they exist  |
+//! | `deleted_at` | RFC 3339 string | optional; set for posts in the trash       |
+//! | `version`    | integer         | optional; version the `ETag` is based on   |
+//! | `authors`    | array           | optional; byline in order, see below       |
+//! | `terms`      | array           | optional; categories and tags, see below   |
+//! | `comments`   | array           | optional; comments, see below              |
+//!
+//! Each entry of `authors` is a full author profile, repeated on every post it
+//! is credited on. Profiles are created or updated by ID on restore:
+//!
+//! | field          | type        | description                                |
+//! |----------------|-------------|--------------------------------------------|
+//! | `id`           | UUID string | author ID, kept on restore                 |
+//! | `user_id`      | UUID string | optional; linked user, kept if they exist  |
+//! | `display_name` | string      | non-empty                                  |
+//! | `bio`          | string      | optional                                   |
+//! | `avatar_url`   | string      | optional; http(s) URL                      |
+//! | `social_links` | array       | `{"label": ..., "url": ...}` objects       |
+//!
+//! Each entry of `terms` is a category or tag the post is filed under, in
+//! order:
+//!
+//! | field  | type   | description                    |
+//! |--------|--------|--------------------------------|
+//! | `kind` | string | `category` or `tag`            |
+//! | `name` | string | non-empty, unique per kind     |
+//! | `slug` | string | optional                       |
+//!
+//! Each entry of `comments` is a comment on the post, approved or not:
+//!
+//! | field          | type            | description                           |
+//! |----------------|-----------------|---------------------------------------|
+//! | `id`           | UUID string     | comment ID, unique across the backup  |
+//! | `parent_id`    | UUID string     | optional; comment on the same post    |
+//! | `author_name`  | string          | optional                              |
+//! | `author_email` | string          | optional                              |
+//! | `author_url`   | string          | optional                              |
+//! | `body`         | string          |                                       |
+//! | `approved`     | boolean         | whether the comment is shown          |
+//! | `created_at`   | RFC 3339 string | when the comment was made             |
+//!
+//! Version 1 backups predate authors and version 2 backups predate the trash,
+//! post versions, terms and comments; both are still accepted, and leave the
+//! trash empty.
+//!
+//! A restored post keeps its version, unless the post it replaces has been
+//! changed past it: then it goes on from the version it replaces, so that no
+//! `ETag` handed out before the restore matches different content after it.
+//!
+//! Readers reject unknown formats and versions newer than [`BACKUP_VERSION`].
+//! Restoring replaces every post in the database, the trash included, within
+//! one transaction, so a backup that fails validation or insertion leaves the
+//! blog untouched.
+
+use std::{collections::HashSet, io::BufRead};
+
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, stream, stream::BoxStream};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, instrument};
+
+use crate::{
+    domain::{
+        models::{
+            author::{Author, AuthorName, AuthorUrl, SocialLink},
+            post::{ImportPostRequest, Post, PostBody, PostComment, PostTerm, PostTitle, TermKind},
+        },
+        service::{Service, ServiceError},
+    },
+    ids::{AuthorId, CommentId, PostId, UserId},
+};
+
+pub const BACKUP_FORMAT: &str = "tommys-blog-backup";
+pub const BACKUP_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackupRecord {
+    Header(BackupHeader),
+    Post(BackupPost),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupHeader {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupPost {
+    pub id: PostId,
+    pub title: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    /// Missing from backups taken before posts recorded it; the creation
+    /// time is used instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<UserId>,
+    /// When the post was moved to the trash, if it is there.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Missing from backups taken before posts recorded it; posts start at
+    /// the first version instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<BackupAuthor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<BackupTerm>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<BackupComment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupAuthor {
+    pub id: AuthorId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bio: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub social_links: Vec<BackupSocialLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSocialLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupTerm {
+    pub kind: BackupTermKind,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTermKind {
+    Category,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupComment {
+    pub id: CommentId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<CommentId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_url: Option<String>,
+    pub body: String,
+    pub approved: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Failed to read backup: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Line {line}: invalid backup record: {source}")]
+    Json {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[error("Failed to serialize backup record: {0}")]
+    Serialize(#[source] serde_json::Error),
+    #[error("Backup does not start with a header record.")]
+    MissingHeader,
+    #[error("Unsupported backup format {format:?}.")]
+    UnsupportedFormat { format: String },
+    #[error("Unsupported backup version {version}, expected at most {BACKUP_VERSION}.")]
+    UnsupportedVersion { version: u32 },
+    #[error("Line {line}: {reason}")]
+    InvalidPost { line: usize, reason: String },
+    #[error(transparent)]
+    Service(#[from] ServiceError),
+}
+
+impl BackupHeader {
+    pub fn new() -> Self {
+        Self {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            exported_at: Utc::now(),
+        }
+    }
+}
+
+impl Default for BackupHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ImportPostRequest> for BackupPost {
+    fn from(request: ImportPostRequest) -> Self {
+        let post = request.post();
+        Self {
+            id: post.id(),
+            title: post.title().to_string(),
+            body: post.body().to_string(),
+            created_at: post.created_at(),
+            updated_at: Some(post.updated_at()),
+            created_by: post.created_by(),
+            deleted_at: post.deleted_at(),
+            version: Some(post.version()),
+            authors: post.authors().iter().map(Into::into).collect(),
+            terms: request.terms().iter().map(Into::into).collect(),
+            comments: request.comments().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&PostTerm> for BackupTerm {
+    fn from(term: &PostTerm) -> Self {
+        Self {
+            kind: match term.kind() {
+                TermKind::Category => BackupTermKind::Category,
+                TermKind::Tag => BackupTermKind::Tag,
+            },
+            name: term.name().to_string(),
+            slug: term.slug().map(ToString::to_string),
+        }
+    }
+}
+
+impl From<BackupTerm> for PostTerm {
+    fn from(term: BackupTerm) -> Self {
+        let kind = match term.kind {
+            BackupTermKind::Category => TermKind::Category,
+            BackupTermKind::Tag => TermKind::Tag,
+        };
+        Self::new(kind, &term.name, term.slug.as_deref())
+    }
+}
+
+impl From<&PostComment> for BackupComment {
+    fn from(comment: &PostComment) -> Self {
+        Self {
+            id: comment.id(),
+            parent_id: comment.parent_id(),
+            author_name: comment.author_name().map(ToString::to_string),
+            author_email: comment.author_email().map(ToString::to_string),
+            author_url: comment.author_url().map(ToString::to_string),
+            body: comment.body().to_string(),
+            approved: comment.approved(),
+            created_at: comment.created_at(),
+        }
+    }
+}
+
+impl From<BackupComment> for PostComment {
+    fn from(comment: BackupComment) -> Self {
+        Self::new(comment.id, &comment.body, comment.created_at)
+            .with_parent_id(comment.parent_id)
+            .with_author(
+                comment.author_name,
+                comment.author_email,
+                comment.author_url,
+            )
+            .with_approved(comment.approved)
+    }
+}
+
+impl From<&Author> for BackupAuthor {
+    fn from(author: &Author) -> Self {
+        Self {
+            id: author.id(),
+            user_id: author.user_id(),
+            display_name: author.name().to_string(),
+            bio: author.bio().map(ToString::to_string),
+            avatar_url: author.avatar_url().map(ToString::to_string),
+            social_links: author
+                .social_links()
+                .iter()
+                .map(|link| BackupSocialLink {
+                    label: link.label().to_string(),
+                    url: link.url().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<BackupAuthor> for Author {
+    type Error = String;
+
+    fn try_from(author: BackupAuthor) -> Result<Self, Self::Error> {
+        let name = AuthorName::try_new(&author.display_name).map_err(|e| e.to_string())?;
+        let avatar_url = author
+            .avatar_url
+            .map(|url| AuthorUrl::try_new(&url))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let social_links = author
+            .social_links
+            .into_iter()
+            .map(|link| {
+                let url = AuthorUrl::try_new(&link.url).map_err(|e| e.to_string())?;
+                SocialLink::try_new(&link.label, url).map_err(|e| e.to_string())
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(author.id, name)
+            .with_user_id(author.user_id)
+            .with_bio(author.bio)
+            .with_avatar_url(avatar_url)
+            .with_social_links(social_links))
+    }
+}
+
+/// Streams the backup of `posts` (see [`Service::stream_backup_posts`]) as NDJSON
+/// lines, header first, without loading every post into memory.
+pub fn export(
+    posts: BoxStream<'static, Result<ImportPostRequest, ServiceError>>,
+) -> impl Stream<Item = Result<String, BackupError>> {
+    let header = stream::iter([Ok(BackupRecord::Header(BackupHeader::new()))]);
+    let posts = posts.map(|post| Ok(BackupRecord::Post(post?.into())));
+
+    header.chain(posts).map(|record: Result<_, BackupError>| {
+        let mut line = serde_json::to_string(&record?).map_err(BackupError::Serialize)?;
+        line.push('\n');
+        Ok(line)
+    })
+}
+
+/// Parses and validates a backup. Posts go through the same constructors as
+/// user input, so a backup can never restore something the API would reject.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<ImportPostRequest>, BackupError> {
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()));
+
+    let header = match lines.next() {
+        Some((line, text)) => parse_record(line, &text?)?,
+        None => return Err(BackupError::MissingHeader),
+    };
+
+    match header {
+        BackupRecord::Header(header) if header.format != BACKUP_FORMAT => {
+            return Err(BackupError::UnsupportedFormat {
+                format: header.format,
+            });
+        }
+        BackupRecord::Header(header) if header.version > BACKUP_VERSION => {
+            return Err(BackupError::UnsupportedVersion {
+                version: header.version,
+            });
+        }
+        BackupRecord::Header(_) => {}
+        BackupRecord::Post(_) => return Err(BackupError::MissingHeader),
+    }
+
+    let mut posts = Vec::new();
+    let mut ids = HashSet::new();
+    let mut titles = HashSet::new();
+    let mut comment_ids = HashSet::new();
+
+    for (line, text) in lines {
+        let post = match parse_record(line, &text?)? {
+            BackupRecord::Post(post) => post,
+            BackupRecord::Header(_) => {
+                return Err(BackupError::InvalidPost {
+                    line,
+                    reason: "unexpected second header".to_string(),
+                });
+            }
+        };
+
+        let invalid = |reason: String| BackupError::InvalidPost { line, reason };
+
+        let title = PostTitle::try_new(&post.title).map_err(|e| invalid(e.to_string()))?;
+        let body = PostBody::try_new(&post.body).map_err(|e| invalid(e.to_string()))?;
+        let version = match post.version {
+            Some(version) if version < 1 => {
+                return Err(invalid(format!("invalid version {version}")));
+            }
+            version => version.unwrap_or(1),
+        };
+
+        if !ids.insert(post.id) {
+            return Err(invalid(format!("duplicate post id {}", post.id)));
+        }
+        // Posts in the trash may share a title with one that is not.
+        if post.deleted_at.is_none() && !titles.insert(title.clone()) {
+            return Err(invalid(format!("duplicate post title {title}")));
+        }
+
+        let authors = post
+            .authors
+            .into_iter()
+            .map(Author::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(invalid)?;
+
+        let mut terms = HashSet::new();
+        for term in &post.terms {
+            if term.name.trim().is_empty() {
+                return Err(invalid("empty term name".to_string()));
+            }
+            if !terms.insert((term.kind, term.name.as_str())) {
+                return Err(invalid(format!("duplicate term {:?}", term.name)));
+            }
+        }
+
+        let post_comment_ids: HashSet<_> = post.comments.iter().map(|c| c.id).collect();
+        for comment in &post.comments {
+            if !comment_ids.insert(comment.id) {
+                return Err(invalid(format!("duplicate comment id {}", comment.id)));
+            }
+            if let Some(parent_id) = comment.parent_id
+                && !post_comment_ids.contains(&parent_id)
+            {
+                return Err(invalid(format!(
+                    "comment {} replies to unknown comment {parent_id}",
+                    comment.id
+                )));
+            }
+        }
+
+        let restored = Post::new(post.id, title, body, post.created_at)
+            .with_updated_at(post.updated_at.unwrap_or(post.created_at))
+            .with_created_by(post.created_by)
+            .with_deleted_at(post.deleted_at)
+            .with_version(version)
+            .with_authors(authors);
+
+        posts.push(
+            ImportPostRequest::new(restored)
+                .with_terms(post.terms.into_iter().map(Into::into).collect())
+                .with_comments(post.comments.into_iter().map(Into::into).collect()),
+        );
+    }
+
+    Ok(posts)
+}
+
+/// Replaces all posts with the contents of the backup.
+#[instrument(name = "backup_restore", skip(service, reader), err)]
+pub async fn restore<S: Service, R: BufRead>(
+    service: &S,
+    reader: R,
+) -> Result<Vec<Post>, BackupError> {
+    let posts = read(reader)?;
+    let restored = service.restore_posts(&posts).await?;
+
+    info!(count = restored.len(), "✅ Backup restored");
+
+    Ok(restored)
+}
+
+fn parse_record(line: usize, text: &str) -> Result<BackupRecord, BackupError> {
+    serde_json::from_str(text).map_err(|source| BackupError::Json { line, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> String {
+        serde_json::to_string(&BackupRecord::Header(BackupHeader::new())).unwrap()
+    }
+
+    fn backup_post(title: &str) -> BackupPost {
+        BackupPost {
+            id: PostId::new(),
+            title: title.to_string(),
+            body: "Body".to_string(),
+            created_at: Utc::now(),
+            updated_at: None,
+            created_by: None,
+            deleted_at: None,
+            version: Some(3),
+            authors: Vec::new(),
+            terms: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    fn post(title: &str) -> String {
+        record(backup_post(title))
+    }
+
+    fn record(post: BackupPost) -> String {
+        serde_json::to_string(&BackupRecord::Post(post)).unwrap()
+    }
+
+    fn comment(parent_id: Option<CommentId>) -> BackupComment {
+        BackupComment {
+            id: CommentId::new(),
+            parent_id,
+            author_name: Some("Reader".to_string()),
+            author_email: None,
+            author_url: None,
+            body: "Nice post".to_string(),
+            approved: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn trashed_post(title: &str) -> String {
+        post(title).replace(
+            "\"created_at\"",
+            "\"deleted_at\":\"2025-05-04T00:27:07Z\",\"created_at\"",
+        )
+    }
+
+    #[test]
+    fn test_read_accepts_valid_backup() {
+        let backup = format!("{}\n{}\n\n{}\n", header(), post("One"), post("Two"));
+
+        let posts = read(backup.as_bytes()).unwrap();
+
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[1].post().title().to_string(), "Two");
+    }
+
+    #[test]
+    fn test_read_accepts_version_1_posts_without_authors() {
+        let backup = format!(
+            "{}\n{}\n",
+            header().replace(&format!("\"version\":{BACKUP_VERSION}"), "\"version\":1"),
+            post("One"),
+        );
+
+        let posts = read(backup.as_bytes()).unwrap();
+
+        assert!(posts[0].post().authors().is_empty());
+    }
+
+    #[test]
+    fn test_read_keeps_posts_in_the_trash() {
+        let backup = format!("{}\n{}\n{}\n", header(), post("One"), trashed_post("One"));
+
+        let posts = read(backup.as_bytes()).unwrap();
+
+        assert!(posts[0].post().deleted_at().is_none());
+        assert!(posts[1].post().deleted_at().is_some());
+    }
+
+    #[test]
+    fn test_read_keeps_versions() {
+        let backup = format!("{}\n{}\n", header(), post("One"));
+
+        let posts = read(backup.as_bytes()).unwrap();
+
+        assert_eq!(posts[0].post().version(), 3);
+    }
+
+    #[test]
+    fn test_read_keeps_terms_and_comments() {
+        let first = comment(None);
+        let reply = comment(Some(first.id));
+        let post = BackupPost {
+            terms: vec![BackupTerm {
+                kind: BackupTermKind::Tag,
+                name: "Rust".to_string(),
+                slug: Some("rust".to_string()),
+            }],
+            comments: vec![reply.clone(), first.clone()],
+            ..backup_post("One")
+        };
+        let backup = format!("{}\n{}\n", header(), record(post));
+
+        let posts = read(backup.as_bytes()).unwrap();
+
+        assert_eq!(
+            posts[0].terms(),
+            [PostTerm::new(TermKind::Tag, "Rust", Some("rust"))]
+        );
+        assert_eq!(posts[0].comments(), [reply.into(), first.into()]);
+    }
+
+    #[test]
+    fn test_read_rejects_replies_to_comments_on_other_posts() {
+        let first = comment(None);
+        let other = BackupPost {
+            comments: vec![first.clone()],
+            ..backup_post("One")
+        };
+        let reply = BackupPost {
+            comments: vec![comment(Some(first.id))],
+            ..backup_post("Two")
+        };
+        let backup = format!("{}\n{}\n{}\n", header(), record(other), record(reply));
+
+        assert!(matches!(
+            read(backup.as_bytes()),
+            Err(BackupError::InvalidPost { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_rejects_versions_below_the_first() {
+        let backup = format!(
+            "{}\n{}\n",
+            header(),
+            post("One").replace("\"version\":3", "\"version\":0"),
+        );
+
+        assert!(matches!(
+            read(backup.as_bytes()),
+            Err(BackupError::InvalidPost { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_requires_header() {
+        let backup = format!("{}\n", post("One"));
+
+        assert!(matches!(
+            read(backup.as_bytes()),
+            Err(BackupError::MissingHeader)
+        ));
+        assert!(matches!(read(&b""[..]), Err(BackupError::MissingHeader)));
+    }
+
+    #[test]
+    fn test_read_rejects_newer_versions() {
+        let backup = header().replace(
+            &format!("\"version\":{BACKUP_VERSION}"),
+            &format!("\"version\":{}", BACKUP_VERSION + 1),
+        );
+
+        assert!(matches!(
+            read(backup.as_bytes()),
+            Err(BackupError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_reports_line_of_invalid_post() {
+        let backup = format!("{}\n{}\n{}\n", header(), post("One"), post("   "));
+
+        match read(backup.as_bytes()) {
+            Err(BackupError::InvalidPost { line, .. }) => assert_eq!(line, 3),
+            other => panic!("Expected invalid post error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_rejects_duplicate_titles() {
+        let backup = format!("{}\n{}\n{}\n", header(), post("One"), post("One"));
+
+        assert!(matches!(
+            read(backup.as_bytes()),
+            Err(BackupError::InvalidPost { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use futures::TryStreamExt;
+
+use crate::{
+    backup,
+    domain::{
+        models::user::{CreateUserRequest, Password, Role, UserEmail},
+        service::Service,
+    },
+    import::wordpress::WordPressImporter,
+};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Tommy's blog backend")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Default, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server (default).
+    #[default]
+    Serve,
+    /// Import posts from a WordPress WXR export file.
+    ImportWxr {
+        /// Path to the WXR export.
+        file: PathBuf,
+        /// Where to write the mapping from WordPress IDs and URLs to new post IDs.
+        #[arg(long, default_value = "wordpress-mapping.json")]
+        mapping: PathBuf,
+        /// Parse and convert the export without writing posts.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write a backup of every post as NDJSON.
+    Export {
+        /// Where to write the backup. Defaults to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Replace every post with the contents of a backup.
+    Restore {
+        /// Path to a backup written by `export`.
+        file: PathBuf,
+    },
+    /// Create a user who can log in. The password is read from stdin.
+    CreateUser {
+        /// Email address the user logs in with.
+        email: String,
+        /// One of admin, editor, author or reader.
+        #[arg(long, default_value = "author")]
+        role: Role,
+    },
+}
+
+pub async fn import_wxr<S: Service>(
+    service: &S,
+    file: PathBuf,
+    mapping: PathBuf,
+    dry_run: bool,
+) -> Result<(), anyhow::Error> {
+    let source = File::open(&file)
+        .with_context(|| format!("Failed to open WXR export at {}", file.display()))?;
+
+    let report = WordPressImporter::new(service)
+        .dry_run(dry_run)
+        .import(BufReader::new(source))
+        .await?;
+
+    let output = File::create(&mapping)
+        .with_context(|| format!("Failed to create mapping file at {}", mapping.display()))?;
+    serde_json::to_writer_pretty(output, &report)?;
+
+    println!(
+        "📥 Imported {} posts, skipped {} items. Mapping written to {}.",
+        report.posts.len(),
+        report.skipped.len(),
+        mapping.display()
+    );
+
+    Ok(())
+}
+
+pub async fn export<S: Service>(service: &S, output: Option<PathBuf>) -> Result<(), anyhow::Error> {
+    let mut writer: Box<dyn Write> = match &output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).with_context(|| {
+            format!("Failed to create backup file at {}", path.display())
+        })?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+
+    let mut lines = backup::export(service.stream_backup_posts());
+    while let Some(line) = lines.try_next().await? {
+        writer.write_all(line.as_bytes())?;
+    }
+    writer.flush()?;
+
+    if let Some(path) = output {
+        eprintln!("💾 Backup written to {}.", path.display());
+    }
+
+    Ok(())
+}
+
+pub async fn restore<S: Service>(service: &S, file: PathBuf) -> Result<(), anyhow::Error> {
+    let source = File::open(&file)
+        .with_context(|| format!("Failed to open backup at {}", file.display()))?;
+
+    let restored = backup::restore(service, BufReader::new(source)).await?;
+
+    println!(
+        "♻️ Restored {} posts from {}.",
+        restored.len(),
+        file.display()
+    );
+
+    Ok(())
+}
+
+pub async fn create_user<S: Service>(
+    service: &S,
+    email: &str,
+    role: Role,
+) -> Result<(), anyhow::Error> {
+    let email = UserEmail::try_new(email)?;
+
+    eprintln!("🔑 Password for {email}:");
+    let mut password = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut password)
+        .context("Failed to read password from stdin")?;
+    let password = Password::try_new(password.trim_end_matches(['\r', '\n']))?;
+
+    let user = service
+        .create_user(&CreateUserRequest::new(email, password, role))
+        .await?;
+
+    println!(
+        "👤 Created {} {} with id {}.",
+        user.role(),
+        user.email(),
+        user.id()
+    );
+
+    Ok(())
+}
@@ -1,8 +1,80 @@
-use std::env;
+use std::{env, time::Duration};
+
+use url::Url;
+
+use crate::{
+    domain::models::{
+        post::{DEFAULT_BODY_MAX_BYTES, DEFAULT_TITLE_MAX_CHARS, DEFAULT_TITLE_MIN_CHARS},
+        user::Role,
+        webhook::DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+    },
+    oidc::OidcConfig,
+    server::DEFAULT_SSE_HEARTBEAT,
+    service::{
+        MAX_RETENTION_DAYS, events::DEFAULT_EVENT_LOG_CAPACITY,
+        webhooks::MAX_WEBHOOK_TIMEOUT_SECONDS,
+    },
+};
 
 pub struct Config {
     pub database_url: String,
     pub port: u16,
+    /// Port of the gRPC API for internal services.
+    pub grpc_port: u16,
+    /// Serves `GET /metrics` on this port, bound to localhost.
+    pub metrics_port: Option<u16>,
+    /// Serves `GET /metrics` on the public port too, to anyone. Off unless
+    /// `METRICS_PUBLIC` is set, since metrics tell about the traffic.
+    pub metrics_public: bool,
+    /// Sessions expire after this many hours, at most `MAX_RETENTION_DAYS`
+    /// days' worth.
+    pub session_ttl_hours: i64,
+    pub cookie_secure: bool,
+    /// Single sign-on is enabled by setting `OIDC_ISSUER`.
+    pub oidc: Option<OidcConfig>,
+    /// Roles that must enroll in two-factor authentication.
+    pub totp_required_roles: Vec<Role>,
+    /// Deleted posts are purged from the trash after this many days, at most
+    /// `MAX_RETENTION_DAYS`.
+    pub trash_retention_days: i64,
+    pub trash_purge_interval_minutes: u64,
+    /// The change feed forgets deleted posts after this many days, at most
+    /// `MAX_RETENTION_DAYS`; clients that have not synced since have to
+    /// start over.
+    pub tombstone_retention_days: i64,
+    pub change_compaction_interval_minutes: u64,
+    /// Responses are replayed to retries with the same `Idempotency-Key`
+    /// for this many hours, at most `MAX_RETENTION_DAYS` days' worth.
+    pub idempotency_key_ttl_hours: i64,
+    pub idempotency_key_cleanup_interval_minutes: u64,
+    /// `Cache-Control` of the post list and of single posts.
+    pub post_list_cache_control: String,
+    pub post_cache_control: String,
+    /// Bounds on submitted post titles, in characters, and bodies, in bytes.
+    /// Requests are allowed to grow with the body limit.
+    pub post_title_min_chars: usize,
+    pub post_title_max_chars: usize,
+    pub post_body_max_bytes: usize,
+    /// Serves the GraphiQL playground, for development.
+    pub graphiql: bool,
+    /// Time between comments on an idle event stream.
+    pub sse_heartbeat: Duration,
+    /// How many post events an event stream may fall behind before it ends.
+    pub event_log_capacity: usize,
+    /// Seconds between relays of the domain events in the outbox.
+    pub outbox_dispatch_interval_seconds: u64,
+    /// Seconds between checks for webhook deliveries that are due.
+    pub webhook_delivery_interval_seconds: u64,
+    /// Failed webhook deliveries are dead after this many attempts.
+    pub webhook_max_attempts: u32,
+    /// At most `MAX_WEBHOOK_TIMEOUT_SECONDS`.
+    pub webhook_timeout_seconds: u64,
+    /// Hosts webhooks may call even though they are not on public
+    /// addresses, such as receivers on the same network.
+    pub webhook_allowed_hosts: Vec<String>,
+    /// Delivered and dead webhook deliveries are deleted after this many
+    /// days, at most `MAX_RETENTION_DAYS`.
+    pub webhook_delivery_retention_days: i64,
 }
 
 impl Config {
@@ -14,7 +86,198 @@ impl Config {
             .ok()
             .and_then(|s| s.parse::<u16>().ok())
             .unwrap_or(8080);
+        let grpc_port = env::var("GRPC_PORT")
+            .ok()
+            .and_then(|s| s.parse::<u16>().ok())
+            .unwrap_or(50051);
+        let metrics_port = env::var("METRICS_PORT")
+            .ok()
+            .and_then(|s| s.parse::<u16>().ok());
+        let metrics_public = env::var("METRICS_PUBLIC")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+        let session_ttl_hours = env::var("SESSION_TTL_HOURS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|hours| (1..=MAX_RETENTION_DAYS * 24).contains(hours))
+            .unwrap_or(24 * 7);
+        let cookie_secure = env::var("COOKIE_SECURE")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(true);
+
+        let oidc = env::var("OIDC_ISSUER").ok().map(|issuer| OidcConfig {
+            issuer,
+            client_id: env::var("OIDC_CLIENT_ID").expect("OIDC_CLIENT_ID not set."),
+            client_secret: env::var("OIDC_CLIENT_SECRET").ok(),
+            redirect_url: env::var("OIDC_REDIRECT_URL")
+                .ok()
+                .and_then(|s| Url::parse(&s).ok())
+                .expect("OIDC_REDIRECT_URL not set or not a URL."),
+            scopes: env::var("OIDC_SCOPES")
+                .unwrap_or_else(|_| "openid email profile".to_string())
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            default_role: env::var("OIDC_DEFAULT_ROLE")
+                .ok()
+                .map(|s| {
+                    s.trim()
+                        .parse::<Role>()
+                        .expect("OIDC_DEFAULT_ROLE is an unknown role.")
+                })
+                .unwrap_or_default(),
+            post_login_redirect: env::var("OIDC_POST_LOGIN_REDIRECT")
+                .unwrap_or_else(|_| "/".to_string()),
+        });
+
+        let totp_required_roles = env::var("TOTP_REQUIRED_ROLES")
+            .unwrap_or_else(|_| "admin".to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|role| !role.is_empty())
+            .map(|role| {
+                role.parse::<Role>()
+                    .expect("TOTP_REQUIRED_ROLES has an unknown role.")
+            })
+            .collect();
+
+        let trash_retention_days = env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|days| (1..=MAX_RETENTION_DAYS).contains(days))
+            .unwrap_or(30);
+        let trash_purge_interval_minutes = env::var("TRASH_PURGE_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(60);
+        let tombstone_retention_days = env::var("TOMBSTONE_RETENTION_DAYS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|days| (1..=MAX_RETENTION_DAYS).contains(days))
+            .unwrap_or(90);
+        let change_compaction_interval_minutes = env::var("CHANGE_COMPACTION_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(60);
+        let idempotency_key_ttl_hours = env::var("IDEMPOTENCY_KEY_TTL_HOURS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|hours| (1..=MAX_RETENTION_DAYS * 24).contains(hours))
+            .unwrap_or(24);
+        let idempotency_key_cleanup_interval_minutes =
+            env::var("IDEMPOTENCY_KEY_CLEANUP_INTERVAL_MINUTES")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .filter(|minutes| *minutes > 0)
+                .unwrap_or(60);
+
+        // Browsers revalidate every time, which is cheap with the ETag; the
+        // CDN keeps responses until they are purged by surrogate key or
+        // expire.
+        let post_list_cache_control = env::var("POST_LIST_CACHE_CONTROL")
+            .unwrap_or_else(|_| "public, max-age=0, s-maxage=300".to_string());
+        let post_cache_control = env::var("POST_CACHE_CONTROL")
+            .unwrap_or_else(|_| "public, max-age=0, s-maxage=3600".to_string());
+
+        let post_title_min_chars = env::var("POST_TITLE_MIN_CHARS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_TITLE_MIN_CHARS);
+        let post_title_max_chars = env::var("POST_TITLE_MAX_CHARS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|chars| *chars > 0)
+            .unwrap_or(DEFAULT_TITLE_MAX_CHARS);
+        assert!(
+            post_title_min_chars <= post_title_max_chars,
+            "POST_TITLE_MIN_CHARS is above POST_TITLE_MAX_CHARS."
+        );
+        let post_body_max_bytes = env::var("POST_BODY_MAX_BYTES")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|bytes| *bytes > 0)
+            .unwrap_or(DEFAULT_BODY_MAX_BYTES);
+        let graphiql = env::var("GRAPHIQL")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+        let sse_heartbeat = env::var("SSE_HEARTBEAT_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_SSE_HEARTBEAT);
+        let event_log_capacity = env::var("EVENT_LOG_CAPACITY")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_EVENT_LOG_CAPACITY);
+        let outbox_dispatch_interval_seconds = env::var("OUTBOX_DISPATCH_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(1);
+        let webhook_delivery_interval_seconds = env::var("WEBHOOK_DELIVERY_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(5);
+        let webhook_max_attempts = env::var("WEBHOOK_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|attempts| *attempts > 0)
+            .unwrap_or(DEFAULT_WEBHOOK_MAX_ATTEMPTS);
+        let webhook_timeout_seconds = env::var("WEBHOOK_TIMEOUT_SECONDS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|seconds| (1..=MAX_WEBHOOK_TIMEOUT_SECONDS).contains(seconds))
+            .unwrap_or(10);
+        let webhook_allowed_hosts = env::var("WEBHOOK_ALLOWED_HOSTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(str::to_string)
+            .collect();
+        let webhook_delivery_retention_days = env::var("WEBHOOK_DELIVERY_RETENTION_DAYS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|days| (1..=MAX_RETENTION_DAYS).contains(days))
+            .unwrap_or(30);
 
-        Self { database_url, port }
+        Self {
+            database_url,
+            port,
+            grpc_port,
+            metrics_port,
+            metrics_public,
+            session_ttl_hours,
+            cookie_secure,
+            oidc,
+            totp_required_roles,
+            trash_retention_days,
+            trash_purge_interval_minutes,
+            tombstone_retention_days,
+            change_compaction_interval_minutes,
+            idempotency_key_ttl_hours,
+            idempotency_key_cleanup_interval_minutes,
+            post_list_cache_control,
+            post_cache_control,
+            post_title_min_chars,
+            post_title_max_chars,
+            post_body_max_bytes,
+            graphiql,
+            sse_heartbeat,
+            event_log_capacity,
+            outbox_dispatch_interval_seconds,
+            webhook_delivery_interval_seconds,
+            webhook_max_attempts,
+            webhook_timeout_seconds,
+            webhook_allowed_hosts,
+            webhook_delivery_retention_days,
+        }
     }
 }
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+
+use crate::ids::{ApiTokenId, UserId};
+
+pub struct DbApiToken {
+    pub id: ApiTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+pub struct CreateApiTokenDbInput {
+    pub id: ApiTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub token_hash: Vec<u8>,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::ids::{PostId, UserId};
+
+pub struct DbAuditEntry {
+    pub id: i64,
+    pub actor_id: Option<UserId>,
+    pub action: String,
+    pub post_id: PostId,
+    pub before_snapshot: Option<Value>,
+    pub after_snapshot: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub prev_hash: Vec<u8>,
+    pub hash: Vec<u8>,
+}
+
+pub struct CreateAuditEntryDbInput {
+    pub actor_id: Option<UserId>,
+    pub action: String,
+    pub post_id: PostId,
+    pub before_snapshot: Option<Value>,
+    pub after_snapshot: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub prev_hash: Vec<u8>,
+    pub hash: Vec<u8>,
+}
+
+pub struct AuditFilterDbInput {
+    pub actor_id: Option<UserId>,
+    pub post_id: Option<PostId>,
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub before_id: Option<i64>,
+    pub limit: i64,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+
+use crate::ids::{AuthorId, UserId};
+
+/// Also decoded from the JSON byline aggregated into post queries, and kept
+/// with posts in the outbox, hence the serde derives.
+#[derive(Serialize, Deserialize)]
+pub struct DbAuthor {
+    pub id: AuthorId,
+    pub user_id: Option<UserId>,
+    pub display_name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub social_links: Vec<DbSocialLink>,
+    #[serde(default)]
+    pub import_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DbSocialLink {
+    pub label: String,
+    pub url: String,
+}
+
+pub struct CreateAuthorDbInput {
+    pub id: AuthorId,
+    pub user_id: Option<UserId>,
+    pub display_name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub social_links: Json<Vec<DbSocialLink>>,
+    pub import_key: Option<String>,
+}
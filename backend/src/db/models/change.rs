@@ -0,0 +1,10 @@
+use chrono::{DateTime, Utc};
+
+use crate::ids::PostId;
+
+pub struct DbPostChange {
+    pub sequence: i64,
+    pub post_id: PostId,
+    pub deleted: bool,
+    pub changed_at: DateTime<Utc>,
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+
+use crate::ids::UserId;
+
+pub struct DbIdempotencyKey {
+    pub fingerprint: Vec<u8>,
+    pub response_status: Option<i16>,
+    pub response_headers: Option<Json<Vec<(String, String)>>>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+pub struct ClaimIdempotencyKeyDbInput {
+    pub user_id: UserId,
+    pub key: String,
+    pub fingerprint: Vec<u8>,
+    pub expires_at: DateTime<Utc>,
+    pub locked_until: DateTime<Utc>,
+}
+
+pub struct CompleteIdempotencyKeyDbInput {
+    pub status: i16,
+    pub headers: Json<Vec<(String, String)>>,
+    pub body: Vec<u8>,
+}
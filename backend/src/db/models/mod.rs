@@ -1 +1,13 @@
+pub(crate) mod api_token;
+pub(crate) mod audit;
+pub(crate) mod author;
+pub(crate) mod change;
+pub(crate) mod idempotency;
+pub(crate) mod notification;
+pub(crate) mod oidc;
+pub(crate) mod outbox;
 pub(crate) mod post;
+pub(crate) mod taxonomy;
+pub(crate) mod totp;
+pub(crate) mod user;
+pub(crate) mod webhook;
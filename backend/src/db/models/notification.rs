@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ids::PostId;
+
+/// Payload of a notification on the post changes channel.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbPostNotification {
+    /// The number of the change in `post_changes`.
+    pub sequence: i64,
+    pub change: String,
+    pub post_id: PostId,
+}
+
+/// What subscribers to post change notifications receive.
+#[derive(Clone, Debug)]
+pub enum DbPostSignal {
+    Notification(DbPostNotification),
+    /// Notifications may have been missed since the last one, while the
+    /// connection was lost or the subscriber fell behind.
+    Missed,
+}
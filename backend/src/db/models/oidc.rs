@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+
+pub struct DbOidcLogin {
+    pub nonce: String,
+    pub code_verifier: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+pub struct CreateOidcLoginDbInput {
+    pub state_hash: Vec<u8>,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::ids::PostId;
+
+pub struct DbOutboxEvent {
+    pub sequence: i64,
+    pub id: Uuid,
+    pub aggregate_id: PostId,
+    pub event_type: String,
+    pub payload: Option<Value>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+pub struct CreateOutboxEventDbInput {
+    pub id: Uuid,
+    pub aggregate_id: PostId,
+    pub event_type: String,
+    pub payload: Option<Value>,
+    pub occurred_at: DateTime<Utc>,
+}
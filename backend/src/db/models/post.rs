@@ -1,22 +1,51 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::ids::PostId;
+use crate::ids::{PostId, UserId};
 
+use super::{
+    author::DbAuthor,
+    taxonomy::{DbPostComment, DbPostTerm},
+};
+
+/// Also kept as JSON in the outbox, hence the serde derives.
+#[derive(Serialize, Deserialize)]
 pub struct DbPost {
     pub id: PostId,
     pub title: String,
     pub body: String,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<UserId>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub version: i64,
+    pub authors: Vec<DbAuthor>,
+}
+
+/// A post with its categories, tags and comments, as backed up.
+pub struct DbBackupPost {
+    pub post: DbPost,
+    pub terms: Vec<DbPostTerm>,
+    pub comments: Vec<DbPostComment>,
 }
 
 pub struct CreatePostDbInput {
     title: String,
     body: String,
+    created_by: UserId,
 }
 
 impl CreatePostDbInput {
-    pub fn new(title: String, body: String) -> Self {
-        Self { title, body }
+    pub fn new(title: String, body: String, created_by: UserId) -> Self {
+        Self {
+            title,
+            body,
+            created_by,
+        }
+    }
+
+    pub(crate) fn created_by(&self) -> UserId {
+        self.created_by
     }
 
     pub(crate) fn title(&self) -> &str {
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::ids::CommentId;
+
+pub struct InsertTermDbInput {
+    pub kind: String,
+    pub name: String,
+    pub slug: Option<String>,
+}
+
+pub struct InsertCommentDbInput {
+    pub id: CommentId,
+    pub parent_id: Option<CommentId>,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub author_url: Option<String>,
+    pub body: String,
+    pub approved: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Also decoded from the JSON aggregated into backup queries, hence the
+/// serde derive.
+#[derive(Deserialize)]
+pub struct DbPostTerm {
+    pub kind: String,
+    pub name: String,
+    pub slug: Option<String>,
+}
+
+/// Also decoded from the JSON aggregated into backup queries, hence the
+/// serde derive.
+#[derive(Deserialize)]
+pub struct DbPostComment {
+    pub id: CommentId,
+    pub parent_id: Option<CommentId>,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub author_url: Option<String>,
+    pub body: String,
+    pub approved: bool,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,6 @@
+use chrono::{DateTime, Utc};
+
+pub struct DbTotp {
+    pub secret: Vec<u8>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
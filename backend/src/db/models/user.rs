@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+
+use crate::ids::UserId;
+
+pub struct DbUser {
+    pub id: UserId,
+    pub email: String,
+    pub password_hash: Option<String>,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub second_factor: bool,
+}
+
+pub struct CreateUserDbInput {
+    email: String,
+    password_hash: Option<String>,
+    role: String,
+}
+
+impl CreateUserDbInput {
+    pub fn new(email: String, password_hash: Option<String>, role: String) -> Self {
+        Self {
+            email,
+            password_hash,
+            role,
+        }
+    }
+
+    pub(crate) fn role(&self) -> &str {
+        &self.role
+    }
+
+    pub(crate) fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub(crate) fn password_hash(&self) -> Option<&str> {
+        self.password_hash.as_deref()
+    }
+}
+
+pub struct DbSession {
+    pub user: DbUser,
+    pub expires_at: DateTime<Utc>,
+    pub second_factor_pending: bool,
+}
+
+pub struct CreateSessionDbInput {
+    pub token_hash: Vec<u8>,
+    pub user_id: UserId,
+    pub expires_at: DateTime<Utc>,
+    pub second_factor_pending: bool,
+}
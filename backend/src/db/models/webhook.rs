@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+
+use crate::ids::{UserId, WebhookId};
+
+pub struct DbWebhook {
+    pub id: WebhookId,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_by: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct CreateWebhookDbInput {
+    pub id: WebhookId,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub created_by: Option<UserId>,
+}
+
+pub struct DbWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: WebhookId,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<i16>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+pub struct DbDueWebhookDelivery {
+    pub id: i64,
+    pub event: String,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+    pub payload: String,
+}
+
+pub struct RecordWebhookAttemptDbInput {
+    pub status: String,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub attempted_at: DateTime<Utc>,
+    pub response_status: Option<i16>,
+    pub error: Option<String>,
+}
@@ -1,12 +1,48 @@
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
-use sqlx::{PgPool, postgres::PgConnectOptions};
-use tracing::{debug, info, instrument};
+use futures::{StreamExt, stream::BoxStream};
+use sqlx::{
+    PgConnection, PgPool, Transaction,
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgListener},
+};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    watch,
+};
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::{ids::PostId, metrics::Metrics};
+
+use super::{
+    models::notification::{DbPostNotification, DbPostSignal},
+    query,
+};
+
+/// The schema migrations, built into the crate so that the tests of other
+/// crates in the workspace can set up a database too.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Channel on which committed changes to posts are announced.
+pub const POST_CHANGES_CHANNEL: &str = "post_changes";
+
+/// Bounds on the wait before listening again after the connection is lost.
+const LISTEN_MIN_BACKOFF: Duration = Duration::from_millis(100);
+const LISTEN_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Notifications received but not yet taken by every subscriber.
+const NOTIFICATION_BUFFER: usize = 1024;
 
 #[derive(Debug, Clone)]
 pub struct Postgres {
     pool: PgPool,
+    notifier: PostNotifier,
+    metrics: Metrics,
 }
 
 impl Postgres {
@@ -23,10 +59,170 @@ impl Postgres {
 
         info!("✅ Connected to PostgreSQL");
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            notifier: PostNotifier::new(),
+            metrics: Metrics::default(),
+        })
+    }
+
+    /// Where the waits for connections are timed.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
     }
 
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
+
+    /// Begins a transaction, timing the wait for its connection.
+    pub(crate) async fn begin(&self) -> Result<Transaction<'static, sqlx::Postgres>, sqlx::Error> {
+        let started = Instant::now();
+        let tx = self.pool.begin().await;
+        self.metrics.observe_db_acquire(started.elapsed());
+
+        tx
+    }
+
+    pub(crate) fn notifier(&self) -> &PostNotifier {
+        &self.notifier
+    }
+}
+
+/// Announces changes to posts to every instance sharing the database, this
+/// one included, in the order they were committed.
+///
+/// Each instance holds one connection listening on [`POST_CHANGES_CHANNEL`],
+/// opened when something first subscribes and reopened whenever it is lost.
+/// Notifications sent while it is down are missed; subscribers are told so
+/// once it listens again, as they are when they fall behind.
+#[derive(Debug, Clone)]
+pub(crate) struct PostNotifier {
+    listener: Arc<OnceLock<Listener>>,
+}
+
+#[derive(Debug)]
+struct Listener {
+    sender: broadcast::Sender<DbPostSignal>,
+    /// Whether the connection is listening at the moment.
+    listening: watch::Receiver<bool>,
+}
+
+impl PostNotifier {
+    fn new() -> Self {
+        Self {
+            listener: Arc::default(),
+        }
+    }
+
+    /// Announces the change numbered `sequence`, made in the transaction of
+    /// `conn`, once it commits.
+    pub(crate) async fn notify(
+        &self,
+        conn: &mut PgConnection,
+        sequence: i64,
+        change: &str,
+        post_id: PostId,
+    ) -> Result<(), sqlx::Error> {
+        let notification = DbPostNotification {
+            sequence,
+            change: change.to_string(),
+            post_id,
+        };
+        let payload =
+            serde_json::to_string(&notification).map_err(|err| sqlx::Error::Encode(err.into()))?;
+
+        query::notification::notify(conn, POST_CHANGES_CHANNEL, &payload).await
+    }
+
+    /// Changes announced from now on. Resolves once the connection is
+    /// listening, so that no change committed afterwards goes unnoticed.
+    pub(crate) async fn subscribe(&self, pool: &PgPool) -> BoxStream<'static, DbPostSignal> {
+        let listener = self.listener.get_or_init(|| {
+            let (sender, _) = broadcast::channel(NOTIFICATION_BUFFER);
+            let (listening_sender, listening) = watch::channel(false);
+            tokio::spawn(listen(pool.clone(), sender.clone(), listening_sender));
+            Listener { sender, listening }
+        });
+        let receiver = listener.sender.subscribe();
+        // Only fails once the listening task is gone, which it never is.
+        let _ = listener
+            .listening
+            .clone()
+            .wait_for(|listening| *listening)
+            .await;
+
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(signal) => Some((signal, receiver)),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed, "Post change subscriber fell behind");
+                    Some((DbPostSignal::Missed, receiver))
+                }
+                Err(RecvError::Closed) => None,
+            }
+        })
+        .boxed()
+    }
+}
+
+/// Passes notifications on to `sender`, listening again with backoff
+/// whenever the connection is lost, and tells `listening` whether it is
+/// listening. Runs until the task is dropped.
+async fn listen(
+    pool: PgPool,
+    sender: broadcast::Sender<DbPostSignal>,
+    listening: watch::Sender<bool>,
+) {
+    let mut backoff = LISTEN_MIN_BACKOFF;
+    let mut reconnecting = false;
+
+    loop {
+        let mut listener = match connect_listener(&pool).await {
+            Ok(listener) => {
+                info!("Listening for post changes on {POST_CHANGES_CHANNEL}");
+                backoff = LISTEN_MIN_BACKOFF;
+                listening.send_replace(true);
+                // Sent once listening again, so that nothing committed after
+                // subscribers catch up is missed.
+                if reconnecting {
+                    _ = sender.send(DbPostSignal::Missed);
+                }
+                reconnecting = true;
+                listener
+            }
+            Err(err) => {
+                warn!(?err, ?backoff, "Failed to listen for post changes");
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(LISTEN_MAX_BACKOFF);
+                continue;
+            }
+        };
+
+        loop {
+            let notification = match listener.recv().await {
+                Ok(notification) => notification,
+                Err(err) => {
+                    warn!(?err, "Lost connection listening for post changes");
+                    listening.send_replace(false);
+                    break;
+                }
+            };
+            match serde_json::from_str::<DbPostNotification>(notification.payload()) {
+                // Nobody subscribed at the moment is fine.
+                Ok(notification) => _ = sender.send(DbPostSignal::Notification(notification)),
+                Err(err) => error!(?err, "Failed to parse post change notification"),
+            }
+        }
+
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+async fn connect_listener(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(POST_CHANGES_CHANNEL).await?;
+
+    Ok(listener)
 }
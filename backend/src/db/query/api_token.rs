@@ -0,0 +1,131 @@
+use sqlx::{PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::{
+    db::models::{
+        api_token::{CreateApiTokenDbInput, DbApiToken},
+        user::DbUser,
+    },
+    ids::{ApiTokenId, UserId},
+};
+
+impl TryFrom<PgRow> for DbApiToken {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbApiToken {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            name: row.try_get("name")?,
+            scopes: row.try_get("scopes")?,
+            created_at: row.try_get("created_at")?,
+            expires_at: row.try_get("expires_at")?,
+            last_used_at: row.try_get("last_used_at")?,
+        })
+    }
+}
+
+pub async fn create_api_token(
+    pool: &PgPool,
+    input: CreateApiTokenDbInput,
+) -> Result<DbApiToken, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO api_tokens (id, user_id, name, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, scopes, created_at, expires_at, last_used_at
+        "#,
+    )
+    .bind(input.id)
+    .bind(input.user_id)
+    .bind(input.name)
+    .bind(input.token_hash)
+    .bind(input.scopes)
+    .bind(input.expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    DbApiToken::try_from(query_result)
+}
+
+pub async fn get_api_tokens_by_user(
+    pool: &PgPool,
+    user_id: UserId,
+) -> Result<Vec<DbApiToken>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT id, user_id, name, scopes, created_at, expires_at, last_used_at
+            FROM api_tokens
+            WHERE user_id = $1
+            ORDER BY created_at, id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbApiToken::try_from)
+    .collect()
+}
+
+pub async fn delete_api_token(
+    pool: &PgPool,
+    user_id: UserId,
+    token_id: ApiTokenId,
+) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM api_tokens WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(token_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+/// Looks up an unexpired token by the hash of its secret and records the use.
+pub async fn use_api_token(
+    pool: &PgPool,
+    token_hash: &[u8],
+) -> Result<(DbUser, DbApiToken), SqlxError> {
+    let row = sqlx::query(
+        r#"
+            WITH token AS (
+                UPDATE api_tokens SET last_used_at = now()
+                WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > now())
+                RETURNING id, user_id, name, scopes, created_at, expires_at, last_used_at
+            )
+            SELECT token.*,
+                users.email,
+                users.password_hash,
+                users.role,
+                users.created_at AS user_created_at,
+                EXISTS (
+                    SELECT 1 FROM user_totp
+                    WHERE user_totp.user_id = users.id AND user_totp.confirmed_at IS NOT NULL
+                ) AS second_factor
+            FROM token
+            JOIN users ON users.id = token.user_id
+        "#,
+    )
+    .bind(token_hash)
+    .fetch_one(pool)
+    .await?;
+
+    let user = DbUser {
+        id: row.try_get("user_id")?,
+        email: row.try_get("email")?,
+        password_hash: row.try_get("password_hash")?,
+        role: row.try_get("role")?,
+        created_at: row.try_get("user_created_at")?,
+        second_factor: row.try_get("second_factor")?,
+    };
+
+    Ok((user, DbApiToken::try_from(row)?))
+}
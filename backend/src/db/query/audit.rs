@@ -0,0 +1,132 @@
+use async_stream::try_stream;
+use futures::{TryStreamExt, stream::BoxStream};
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::db::models::audit::{AuditFilterDbInput, CreateAuditEntryDbInput, DbAuditEntry};
+
+impl TryFrom<PgRow> for DbAuditEntry {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbAuditEntry {
+            id: row.try_get("id")?,
+            actor_id: row.try_get("actor_id")?,
+            action: row.try_get("action")?,
+            post_id: row.try_get("post_id")?,
+            before_snapshot: row.try_get("before_snapshot")?,
+            after_snapshot: row.try_get("after_snapshot")?,
+            request_id: row.try_get("request_id")?,
+            created_at: row.try_get("created_at")?,
+            prev_hash: row.try_get("prev_hash")?,
+            hash: row.try_get("hash")?,
+        })
+    }
+}
+
+/// Key of the transaction-level advisory lock that serializes appends to the
+/// audit log.
+const AUDIT_HEAD_LOCK_KEY: i64 = 0x6175_6469_745f_6c67;
+
+/// Returns the hash of the newest entry, if there is one. Appending has to
+/// hold the lock until commit, so that no other entry is linked to the same
+/// hash in the meantime. The lock is advisory, so only other appends wait for
+/// it; reads and writes to other tables are not blocked.
+pub async fn lock_audit_head(conn: &mut PgConnection) -> Result<Option<Vec<u8>>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT pg_advisory_xact_lock($1)
+        "#,
+    )
+    .bind(AUDIT_HEAD_LOCK_KEY)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+            SELECT hash FROM audit_log
+            ORDER BY id DESC
+            LIMIT 1
+        "#,
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|row| row.try_get("hash"))
+    .transpose()
+}
+
+pub async fn insert_audit_entry(
+    conn: &mut PgConnection,
+    input: CreateAuditEntryDbInput,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO audit_log (
+                actor_id, action, post_id, before_snapshot, after_snapshot,
+                request_id, created_at, prev_hash, hash
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(input.actor_id)
+    .bind(input.action)
+    .bind(input.post_id)
+    .bind(input.before_snapshot)
+    .bind(input.after_snapshot)
+    .bind(input.request_id)
+    .bind(input.created_at)
+    .bind(input.prev_hash)
+    .bind(input.hash)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Newest entries first; filters left unset match everything.
+pub async fn get_audit_entries(
+    pool: &PgPool,
+    filter: AuditFilterDbInput,
+) -> Result<Vec<DbAuditEntry>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT * FROM audit_log
+            WHERE ($1::UUID IS NULL OR actor_id = $1)
+                AND ($2::UUID IS NULL OR post_id = $2)
+                AND ($3::TEXT IS NULL OR action = $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5)
+                AND ($6::BIGINT IS NULL OR id < $6)
+            ORDER BY id DESC
+            LIMIT $7
+        "#,
+    )
+    .bind(filter.actor_id)
+    .bind(filter.post_id)
+    .bind(filter.action)
+    .bind(filter.since)
+    .bind(filter.until)
+    .bind(filter.before_id)
+    .bind(filter.limit)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbAuditEntry::try_from)
+    .collect()
+}
+
+/// The whole log in the order it was written.
+pub fn stream_audit_entries(pool: PgPool) -> BoxStream<'static, Result<DbAuditEntry, SqlxError>> {
+    Box::pin(try_stream! {
+        let mut rows = sqlx::query(
+            r#"
+                SELECT * FROM audit_log
+                ORDER BY id
+            "#,
+        )
+        .fetch(&pool);
+
+        while let Some(row) = rows.try_next().await? {
+            yield DbAuditEntry::try_from(row)?;
+        }
+    })
+}
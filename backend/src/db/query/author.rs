@@ -0,0 +1,121 @@
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow, types::Json};
+
+use crate::{
+    db::models::author::{CreateAuthorDbInput, DbAuthor},
+    ids::AuthorId,
+};
+
+impl TryFrom<PgRow> for DbAuthor {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let Json(social_links) = row.try_get("social_links")?;
+
+        Ok(DbAuthor {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            display_name: row.try_get("display_name")?,
+            bio: row.try_get("bio")?,
+            avatar_url: row.try_get("avatar_url")?,
+            social_links,
+            import_key: row.try_get("import_key")?,
+        })
+    }
+}
+
+pub async fn create_author(
+    pool: &PgPool,
+    input: CreateAuthorDbInput,
+) -> Result<DbAuthor, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO authors (id, user_id, display_name, bio, avatar_url, social_links)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, display_name, bio, avatar_url, social_links, import_key
+        "#,
+    )
+    .bind(input.id)
+    .bind(input.user_id)
+    .bind(input.display_name)
+    .bind(input.bio)
+    .bind(input.avatar_url)
+    .bind(input.social_links)
+    .fetch_one(pool)
+    .await?;
+
+    DbAuthor::try_from(query_result)
+}
+
+/// Inserts or refreshes an author from a backup or an import, returning the
+/// ID they are stored under.
+///
+/// Authors with an import key are matched on it, so importing the same
+/// export twice finds the profile created the first time rather than adding
+/// another; profiles found this way are left as they are. Others are matched
+/// on their ID. A linked user that does not exist in this database, or that
+/// another author is already linked to, is dropped.
+pub async fn upsert_author(
+    conn: &mut PgConnection,
+    input: CreateAuthorDbInput,
+) -> Result<AuthorId, SqlxError> {
+    let sql = match input.import_key {
+        Some(_) => {
+            r#"
+                INSERT INTO authors (
+                    id, user_id, display_name, bio, avatar_url, social_links, import_key
+                )
+                VALUES ($1, NULL, $3, $4, $5, $6, $7)
+                ON CONFLICT (import_key) DO UPDATE SET import_key = EXCLUDED.import_key
+                RETURNING id
+            "#
+        }
+        None => {
+            r#"
+                INSERT INTO authors (id, user_id, display_name, bio, avatar_url, social_links)
+                VALUES (
+                    $1,
+                    (
+                        SELECT id FROM users
+                        WHERE id = $2
+                            AND NOT EXISTS (
+                                SELECT 1 FROM authors WHERE user_id = $2 AND id <> $1
+                            )
+                    ),
+                    $3, $4, $5, $6
+                )
+                ON CONFLICT (id) DO UPDATE SET
+                    display_name = EXCLUDED.display_name,
+                    bio = EXCLUDED.bio,
+                    avatar_url = EXCLUDED.avatar_url,
+                    social_links = EXCLUDED.social_links
+                RETURNING id
+            "#
+        }
+    };
+
+    sqlx::query_scalar(sql)
+        .bind(input.id)
+        .bind(input.user_id)
+        .bind(input.display_name)
+        .bind(input.bio)
+        .bind(input.avatar_url)
+        .bind(input.social_links)
+        .bind(input.import_key)
+        .fetch_one(conn)
+        .await
+}
+
+pub async fn get_author_by_id(pool: &PgPool, id: AuthorId) -> Result<DbAuthor, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            SELECT id, user_id, display_name, bio, avatar_url, social_links, import_key
+            FROM authors
+            WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    DbAuthor::try_from(query_result)
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::{db::models::change::DbPostChange, ids::PostId};
+
+impl TryFrom<PgRow> for DbPostChange {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbPostChange {
+            sequence: row.try_get("sequence")?,
+            post_id: row.try_get("post_id")?,
+            deleted: row.try_get("deleted")?,
+            changed_at: row.try_get("changed_at")?,
+        })
+    }
+}
+
+/// Gives the post the next change number, replacing its previous change,
+/// and returns the number. Must run under the audit log lock, so numbers
+/// follow commit order.
+pub async fn record_post_change(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    deleted: bool,
+    changed_at: DateTime<Utc>,
+) -> Result<i64, SqlxError> {
+    sqlx::query_scalar(
+        r#"
+            INSERT INTO post_changes (post_id, deleted, changed_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (post_id) DO UPDATE
+            SET sequence = nextval('post_change_seq'),
+                deleted = EXCLUDED.deleted,
+                changed_at = EXCLUDED.changed_at
+            RETURNING sequence
+        "#,
+    )
+    .bind(post_id)
+    .bind(deleted)
+    .bind(changed_at)
+    .fetch_one(conn)
+    .await
+}
+
+/// When any post was last created, changed or deleted, if ever. Tombstones
+/// compacted away no longer count.
+pub async fn get_last_changed_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>, SqlxError> {
+    sqlx::query_scalar(
+        r#"
+            SELECT MAX(changed_at) FROM post_changes
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// The number of the latest change, or of the latest compacted away if that
+/// is later, so that the changes after it can be read. Zero before the first.
+pub async fn get_latest_sequence(pool: &PgPool) -> Result<i64, SqlxError> {
+    sqlx::query_scalar(
+        r#"
+            SELECT GREATEST(
+                (SELECT COALESCE(MAX(sequence), 0) FROM post_changes),
+                (SELECT compacted_through FROM post_change_compaction)
+            )
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Makes the rest of the transaction read from one snapshot, so a page of
+/// changes matches the posts loaded for it.
+pub async fn read_from_snapshot(conn: &mut PgConnection) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY
+        "#,
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_compacted_through(conn: &mut PgConnection) -> Result<i64, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT compacted_through FROM post_change_compaction
+        "#,
+    )
+    .fetch_one(conn)
+    .await?
+    .try_get("compacted_through")
+}
+
+/// Up to `limit` changes numbered above `since`, in order.
+pub async fn get_post_changes(
+    conn: &mut PgConnection,
+    since: i64,
+    limit: i64,
+) -> Result<Vec<DbPostChange>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT * FROM post_changes
+            WHERE sequence > $1
+            ORDER BY sequence
+            LIMIT $2
+        "#,
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .map(DbPostChange::try_from)
+    .collect()
+}
+
+/// Removes the tombstones of posts deleted before `changed_before`, raising
+/// the compaction mark past them, and returns how many there were.
+pub async fn compact_post_changes(
+    pool: &PgPool,
+    changed_before: DateTime<Utc>,
+) -> Result<u64, SqlxError> {
+    let compacted: i64 = sqlx::query(
+        r#"
+            WITH removed AS (
+                DELETE FROM post_changes
+                WHERE deleted AND changed_at < $1
+                RETURNING sequence
+            )
+            UPDATE post_change_compaction
+            SET compacted_through = GREATEST(
+                compacted_through,
+                (SELECT MAX(sequence) FROM removed)
+            )
+            RETURNING (SELECT COUNT(*) FROM removed) AS compacted
+        "#,
+    )
+    .bind(changed_before)
+    .fetch_one(pool)
+    .await?
+    .try_get("compacted")?;
+
+    Ok(compacted as u64)
+}
@@ -0,0 +1,137 @@
+use sqlx::{PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::{
+    db::models::idempotency::{
+        ClaimIdempotencyKeyDbInput, CompleteIdempotencyKeyDbInput, DbIdempotencyKey,
+    },
+    ids::UserId,
+};
+
+impl TryFrom<PgRow> for DbIdempotencyKey {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbIdempotencyKey {
+            fingerprint: row.try_get("fingerprint")?,
+            response_status: row.try_get("response_status")?,
+            response_headers: row.try_get("response_headers")?,
+            response_body: row.try_get("response_body")?,
+        })
+    }
+}
+
+/// Claims the key for a new request, returning `None`, or returns the request
+/// that claimed it before. An expired key is claimed afresh, and so is a
+/// claim for the same request whose lease ran out without a response.
+pub async fn claim_idempotency_key(
+    pool: &PgPool,
+    input: ClaimIdempotencyKeyDbInput,
+) -> Result<Option<DbIdempotencyKey>, SqlxError> {
+    loop {
+        let claimed = sqlx::query(
+            r#"
+                INSERT INTO idempotency_keys (user_id, key, fingerprint, expires_at, locked_until)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (user_id, key) DO UPDATE SET
+                    fingerprint = EXCLUDED.fingerprint,
+                    response_status = NULL,
+                    response_headers = NULL,
+                    response_body = NULL,
+                    created_at = now(),
+                    expires_at = EXCLUDED.expires_at,
+                    locked_until = EXCLUDED.locked_until
+                WHERE idempotency_keys.expires_at <= now()
+                    OR (
+                        idempotency_keys.response_status IS NULL
+                        AND idempotency_keys.locked_until <= now()
+                        AND idempotency_keys.fingerprint = EXCLUDED.fingerprint
+                    )
+                RETURNING user_id
+            "#,
+        )
+        .bind(input.user_id)
+        .bind(&input.key)
+        .bind(&input.fingerprint)
+        .bind(input.expires_at)
+        .bind(input.locked_until)
+        .fetch_optional(pool)
+        .await?;
+        if claimed.is_some() {
+            return Ok(None);
+        }
+
+        // Released in the meantime if not found, so try claiming again.
+        let existing = sqlx::query(
+            r#"
+                SELECT fingerprint, response_status, response_headers, response_body
+                FROM idempotency_keys
+                WHERE user_id = $1 AND key = $2
+            "#,
+        )
+        .bind(input.user_id)
+        .bind(&input.key)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(existing) = existing {
+            return DbIdempotencyKey::try_from(existing).map(Some);
+        }
+    }
+}
+
+/// Deletes keys that have expired, returning how many there were.
+pub async fn delete_expired_idempotency_keys(pool: &PgPool) -> Result<u64, SqlxError> {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM idempotency_keys WHERE expires_at <= now()
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn complete_idempotency_key(
+    pool: &PgPool,
+    user_id: UserId,
+    key: &str,
+    input: CompleteIdempotencyKeyDbInput,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            UPDATE idempotency_keys
+            SET response_status = $3, response_headers = $4, response_body = $5
+            WHERE user_id = $1 AND key = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(input.status)
+    .bind(input.headers)
+    .bind(input.body)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Gives up a claim whose request has no response worth keeping, so that a
+/// retry is handled afresh.
+pub async fn release_idempotency_key(
+    pool: &PgPool,
+    user_id: UserId,
+    key: &str,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            DELETE FROM idempotency_keys
+            WHERE user_id = $1 AND key = $2 AND response_status IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
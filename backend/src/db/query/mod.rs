@@ -1 +1,14 @@
+pub mod api_token;
+pub mod audit;
+pub mod author;
+pub mod change;
+pub mod idempotency;
+pub mod notification;
+pub mod oidc;
+pub mod outbox;
 pub mod post;
+pub mod session;
+pub mod taxonomy;
+pub mod totp;
+pub mod user;
+pub mod webhook;
@@ -0,0 +1,21 @@
+use sqlx::{PgConnection, error::Error as SqlxError};
+
+/// Sends `payload` to the listeners of `channel` once the transaction of
+/// `conn` commits, or never if it rolls back.
+pub async fn notify(
+    conn: &mut PgConnection,
+    channel: &str,
+    payload: &str,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT pg_notify($1, $2)
+        "#,
+    )
+    .bind(channel)
+    .bind(payload)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
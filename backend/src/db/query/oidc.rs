@@ -0,0 +1,65 @@
+use sqlx::{PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::db::models::oidc::{CreateOidcLoginDbInput, DbOidcLogin};
+
+impl TryFrom<PgRow> for DbOidcLogin {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbOidcLogin {
+            nonce: row.try_get("nonce")?,
+            code_verifier: row.try_get("code_verifier")?,
+            expires_at: row.try_get("expires_at")?,
+        })
+    }
+}
+
+/// Also clears abandoned logins, which are otherwise never taken.
+pub async fn create_oidc_login(
+    pool: &PgPool,
+    input: CreateOidcLoginDbInput,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            DELETE FROM oidc_logins WHERE expires_at <= now()
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+            INSERT INTO oidc_logins (state_hash, nonce, code_verifier, expires_at)
+            VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(input.state_hash)
+    .bind(input.nonce)
+    .bind(input.code_verifier)
+    .bind(input.expires_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Deletes and returns a pending login, so each state can be used only once.
+pub async fn take_oidc_login(pool: &PgPool, state_hash: &[u8]) -> Result<DbOidcLogin, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            DELETE FROM oidc_logins
+            WHERE state_hash = $1
+            RETURNING nonce, code_verifier, expires_at
+        "#,
+    )
+    .bind(state_hash)
+    .fetch_one(pool)
+    .await?;
+
+    let login = DbOidcLogin::try_from(query_result)?;
+    if login.expires_at <= chrono::Utc::now() {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(login)
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::db::models::outbox::{CreateOutboxEventDbInput, DbOutboxEvent};
+
+impl TryFrom<PgRow> for DbOutboxEvent {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbOutboxEvent {
+            sequence: row.try_get("sequence")?,
+            id: row.try_get("id")?,
+            aggregate_id: row.try_get("aggregate_id")?,
+            event_type: row.try_get("event_type")?,
+            payload: row.try_get("payload")?,
+            occurred_at: row.try_get("occurred_at")?,
+        })
+    }
+}
+
+/// Runs in the transaction of the change, so the event is written if and
+/// only if it is committed. Changes to a post hold its row lock until they
+/// commit, so the events of a post are numbered in commit order.
+pub async fn insert_outbox_event(
+    conn: &mut PgConnection,
+    input: CreateOutboxEventDbInput,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO outbox (id, aggregate_id, event_type, payload, occurred_at)
+            VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(input.id)
+    .bind(input.aggregate_id)
+    .bind(input.event_type)
+    .bind(input.payload)
+    .bind(input.occurred_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Claims up to `limit` events in order, leaving them alone for other
+/// callers until `lease_until`. Only the oldest event of each post is
+/// claimed, so a later one waits until the one before has been relayed.
+pub async fn claim_outbox_events(
+    pool: &PgPool,
+    limit: i64,
+    lease_until: DateTime<Utc>,
+) -> Result<Vec<DbOutboxEvent>, SqlxError> {
+    sqlx::query(
+        r#"
+            WITH due AS (
+                SELECT sequence FROM outbox AS event
+                WHERE (leased_until IS NULL OR leased_until <= now())
+                    AND NOT EXISTS (
+                        SELECT 1 FROM outbox AS earlier
+                        WHERE earlier.aggregate_id = event.aggregate_id
+                            AND earlier.sequence < event.sequence
+                    )
+                ORDER BY sequence
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            ),
+            claimed AS (
+                UPDATE outbox SET leased_until = $2
+                FROM due
+                WHERE outbox.sequence = due.sequence
+                RETURNING outbox.sequence, id, aggregate_id, event_type, payload, occurred_at
+            )
+            SELECT * FROM claimed ORDER BY sequence
+        "#,
+    )
+    .bind(limit)
+    .bind(lease_until)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbOutboxEvent::try_from)
+    .collect()
+}
+
+/// Removes an event from the outbox; `false` if it was already removed.
+pub async fn delete_outbox_event(
+    conn: &mut PgConnection,
+    sequence: i64,
+) -> Result<bool, SqlxError> {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM outbox WHERE sequence = $1
+        "#,
+    )
+    .bind(sequence)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
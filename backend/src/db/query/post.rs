@@ -1,48 +1,120 @@
-use sqlx::{PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+use async_stream::try_stream;
+use chrono::{DateTime, Utc};
+use futures::{TryStreamExt, stream::BoxStream};
+use serde_json::Value;
+use sqlx::{
+    PgConnection, PgExecutor, PgPool, Row, error::Error as SqlxError, postgres::PgRow, types::Json,
+};
 
 use crate::{
-    db::models::post::{CreatePostDbInput, DbPost, UpdatePostDbInput},
-    ids::PostId,
+    db::models::post::{CreatePostDbInput, DbBackupPost, DbPost, UpdatePostDbInput},
+    ids::{AuthorId, PostId, UserId},
 };
 
+/// Selects every post column plus the byline as a JSON array of authors in
+/// order, so posts are loaded with their authors in a single query.
+const POST_COLUMNS: &str = r#"
+    posts.*,
+    COALESCE(
+        (
+            SELECT jsonb_agg(
+                jsonb_build_object(
+                    'id', authors.id,
+                    'user_id', authors.user_id,
+                    'display_name', authors.display_name,
+                    'bio', authors.bio,
+                    'avatar_url', authors.avatar_url,
+                    'social_links', authors.social_links,
+                    'import_key', authors.import_key
+                )
+                ORDER BY post_authors.position
+            )
+            FROM post_authors
+            JOIN authors ON authors.id = post_authors.author_id
+            WHERE post_authors.post_id = posts.id
+        ),
+        '[]'
+    ) AS authors
+"#;
+
 impl TryFrom<PgRow> for DbPost {
     type Error = SqlxError;
 
+    /// Expects the columns of [`POST_COLUMNS`].
     fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let Json(authors) = row.try_get("authors")?;
+
         Ok(DbPost {
             id: row.try_get("id")?,
             title: row.try_get("title")?,
             body: row.try_get("body")?,
             created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            created_by: row.try_get("created_by")?,
+            deleted_at: row.try_get("deleted_at")?,
+            version: row.try_get("version")?,
+            authors,
         })
     }
 }
 
-pub async fn create_post(pool: &PgPool, input: CreatePostDbInput) -> Result<DbPost, SqlxError> {
+/// Returns the ID of the new post, which is read back with its byline once
+/// that is set.
+pub async fn create_post(
+    conn: &mut PgConnection,
+    input: CreatePostDbInput,
+) -> Result<PostId, SqlxError> {
     let id = PostId::new();
 
-    let query_result = sqlx::query(
+    sqlx::query(
         r#"
-            INSERT INTO posts (id, title, body)
-            VALUES ($1, $2, $3)
-            RETURNING id, title, body, created_at
+            INSERT INTO posts (id, title, body, created_by)
+            VALUES ($1, $2, $3, $4)
         "#,
     )
     .bind(id)
     .bind(input.title())
     .bind(input.body())
-    .fetch_one(pool)
-    .await;
+    .bind(input.created_by())
+    .execute(conn)
+    .await?;
 
-    DbPost::try_from(query_result?)
+    Ok(id)
 }
 
-pub async fn get_all_posts(pool: &PgPool) -> Result<Vec<DbPost>, SqlxError> {
-    let query_results = sqlx::query(
+/// Inserts a post with a known ID, e.g. from an import or a backup, in the
+/// trash if it was there. An owner that does not exist in this database is
+/// dropped instead of failing.
+pub async fn insert_post(conn: &mut PgConnection, post: &DbPost) -> Result<(), SqlxError> {
+    sqlx::query(
         r#"
-            SELECT * FROM posts
+            INSERT INTO posts (
+                id, title, body, created_at, updated_at, created_by, deleted_at, version
+            )
+            VALUES ($1, $2, $3, $4, $5, (SELECT id FROM users WHERE id = $6), $7, $8)
         "#,
     )
+    .bind(post.id)
+    .bind(&post.title)
+    .bind(&post.body)
+    .bind(post.created_at)
+    .bind(post.updated_at)
+    .bind(post.created_by)
+    .bind(post.deleted_at)
+    .bind(post.version)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_all_posts(pool: &PgPool) -> Result<Vec<DbPost>, SqlxError> {
+    let query_results = sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE deleted_at IS NULL
+        "#
+    ))
     .fetch_all(pool)
     .await?;
 
@@ -52,27 +124,234 @@ pub async fn get_all_posts(pool: &PgPool) -> Result<Vec<DbPost>, SqlxError> {
         .collect::<Result<Vec<DbPost>, SqlxError>>()
 }
 
-pub async fn get_post_by_id(pool: &PgPool, id: PostId) -> Result<DbPost, SqlxError> {
-    let query_result = sqlx::query(
+/// Up to `limit` posts, oldest first, created after the post at
+/// `(created_at, id)` if given.
+pub async fn get_posts_page(
+    pool: &PgPool,
+    after: Option<(DateTime<Utc>, PostId)>,
+    limit: i64,
+) -> Result<Vec<DbPost>, SqlxError> {
+    let (after_created_at, after_id) = after.unzip();
+
+    sqlx::query(&format!(
         r#"
-            SELECT * FROM posts
-            WHERE id=($1)
-        "#,
-    )
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE deleted_at IS NULL
+                AND ($1::timestamptz IS NULL OR (created_at, id) > ($1, $2))
+            ORDER BY created_at, id
+            LIMIT $3
+        "#
+    ))
+    .bind(after_created_at)
+    .bind(after_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbPost::try_from)
+    .collect()
+}
+
+pub fn stream_posts(pool: PgPool) -> BoxStream<'static, Result<DbPost, SqlxError>> {
+    Box::pin(try_stream! {
+        let sql = format!(
+            r#"
+                SELECT {POST_COLUMNS} FROM posts
+                WHERE deleted_at IS NULL
+                ORDER BY created_at, id
+            "#
+        );
+        let mut rows = sqlx::query(&sql).fetch(&pool);
+
+        while let Some(row) = rows.try_next().await? {
+            yield DbPost::try_from(row)?;
+        }
+    })
+}
+
+/// Like [`stream_posts`], with the posts in the trash as well, and the
+/// categories, tags and comments of every post as JSON arrays.
+pub fn stream_backup_posts(pool: PgPool) -> BoxStream<'static, Result<DbBackupPost, SqlxError>> {
+    Box::pin(try_stream! {
+        let sql = format!(
+            r#"
+                SELECT
+                    {POST_COLUMNS},
+                    COALESCE(
+                        (
+                            SELECT jsonb_agg(
+                                jsonb_build_object('kind', kind, 'name', name, 'slug', slug)
+                                ORDER BY position
+                            )
+                            FROM post_terms
+                            WHERE post_terms.post_id = posts.id
+                        ),
+                        '[]'
+                    ) AS terms,
+                    COALESCE(
+                        (
+                            SELECT jsonb_agg(
+                                to_jsonb(post_comments) - 'post_id'
+                                ORDER BY created_at, id
+                            )
+                            FROM post_comments
+                            WHERE post_comments.post_id = posts.id
+                        ),
+                        '[]'
+                    ) AS comments
+                FROM posts
+                ORDER BY created_at, id
+            "#
+        );
+        let mut rows = sqlx::query(&sql).fetch(&pool);
+
+        while let Some(row) = rows.try_next().await? {
+            let Json(terms) = row.try_get("terms")?;
+            let Json(comments) = row.try_get("comments")?;
+            yield DbBackupPost {
+                post: DbPost::try_from(row)?,
+                terms,
+                comments,
+            };
+        }
+    })
+}
+
+pub async fn get_post_by_id(
+    executor: impl PgExecutor<'_>,
+    id: PostId,
+) -> Result<DbPost, SqlxError> {
+    let query_result = sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE id=($1) AND deleted_at IS NULL
+        "#
+    ))
     .bind(id)
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await?;
 
     DbPost::try_from(query_result)
 }
 
-pub async fn get_post_by_title(pool: &PgPool, title: &str) -> Result<DbPost, SqlxError> {
-    let query_result = sqlx::query(
+/// The posts with the given ids that are not in the trash, in no particular
+/// order.
+pub async fn get_posts_by_ids(
+    executor: impl PgExecutor<'_>,
+    ids: &[PostId],
+) -> Result<Vec<DbPost>, SqlxError> {
+    let ids: Vec<_> = ids.iter().map(PostId::inner).collect();
+
+    sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE id = ANY($1) AND deleted_at IS NULL
+        "#
+    ))
+    .bind(ids)
+    .fetch_all(executor)
+    .await?
+    .into_iter()
+    .map(DbPost::try_from)
+    .collect()
+}
+
+/// The post as JSON, byline included, for the audit log. Locks the row so
+/// that the snapshot taken before a change is the state it replaced.
+pub async fn get_post_snapshot(conn: &mut PgConnection, id: PostId) -> Result<Value, SqlxError> {
+    sqlx::query(&format!(
         r#"
-            SELECT * FROM posts
-            WHERE title=($1)
+            SELECT to_jsonb(post) AS snapshot
+            FROM (SELECT {POST_COLUMNS} FROM posts WHERE id = $1 FOR UPDATE OF posts) post
+        "#
+    ))
+    .bind(id)
+    .fetch_one(conn)
+    .await?
+    .try_get("snapshot")
+}
+
+/// Locks the post, in or out of the trash, and returns its version. Changes
+/// made on the strength of a version a client has seen check it with this
+/// first.
+pub async fn lock_post_version(conn: &mut PgConnection, id: PostId) -> Result<i64, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT version FROM posts
+            WHERE id = $1
+            FOR UPDATE
+        "#,
+    )
+    .bind(id)
+    .fetch_one(conn)
+    .await?
+    .try_get("version")
+}
+
+/// Snapshots of the posts moved to the trash before `deleted_before`, for
+/// purging them.
+pub async fn get_trashed_post_snapshots(
+    conn: &mut PgConnection,
+    deleted_before: DateTime<Utc>,
+) -> Result<Vec<(PostId, Value)>, SqlxError> {
+    sqlx::query(&format!(
+        r#"
+            SELECT post.id, to_jsonb(post) AS snapshot
+            FROM (
+                SELECT {POST_COLUMNS} FROM posts
+                WHERE deleted_at < $1
+                FOR UPDATE OF posts
+            ) post
+            ORDER BY post.deleted_at, post.id
+        "#
+    ))
+    .bind(deleted_before)
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .map(|row| Ok((row.try_get("id")?, row.try_get("snapshot")?)))
+    .collect()
+}
+
+/// The version of every post, in the trash or not.
+pub async fn get_post_versions(conn: &mut PgConnection) -> Result<Vec<(PostId, i64)>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT id, version FROM posts
         "#,
     )
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .map(|row| Ok((row.try_get("id")?, row.try_get("version")?)))
+    .collect()
+}
+
+/// Like [`get_post_snapshot`], for every post.
+pub async fn get_all_post_snapshots(
+    conn: &mut PgConnection,
+) -> Result<Vec<(PostId, Value)>, SqlxError> {
+    sqlx::query(&format!(
+        r#"
+            SELECT post.id, to_jsonb(post) AS snapshot
+            FROM (SELECT {POST_COLUMNS} FROM posts FOR UPDATE OF posts) post
+            ORDER BY post.created_at, post.id
+        "#
+    ))
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .map(|row| Ok((row.try_get("id")?, row.try_get("snapshot")?)))
+    .collect()
+}
+
+pub async fn get_post_by_title(pool: &PgPool, title: &str) -> Result<DbPost, SqlxError> {
+    let query_result = sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE title=($1) AND deleted_at IS NULL
+        "#
+    ))
     .bind(title)
     .fetch_one(pool)
     .await?;
@@ -81,37 +360,250 @@ pub async fn get_post_by_title(pool: &PgPool, title: &str) -> Result<DbPost, Sql
 }
 
 pub async fn update_post(
-    pool: &PgPool,
+    conn: &mut PgConnection,
     id: PostId,
     UpdatePostDbInput { title, body }: UpdatePostDbInput,
-) -> Result<DbPost, SqlxError> {
-    let query_result = sqlx::query(
+) -> Result<(), SqlxError> {
+    sqlx::query(
         r#"
             UPDATE posts
             SET 
                 title = COALESCE($1, title),
-                body = COALESCE($2, body)
-            WHERE id = $3
-            RETURNING id, title, body, created_at
+                body = COALESCE($2, body),
+                updated_at = now(),
+                version = version + 1
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id
         "#,
     )
     .bind(title)
     .bind(body)
     .bind(id)
-    .fetch_one(pool)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves a post to the trash. Returns `RowNotFound` if it does not exist or
+/// is in the trash already.
+pub async fn trash_post(conn: &mut PgConnection, id: PostId) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE posts SET deleted_at = now(), updated_at = now(), version = version + 1
+            WHERE id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+/// Takes a post out of the trash. Fails with a unique violation if another
+/// post has taken its title in the meantime.
+pub async fn restore_post(conn: &mut PgConnection, id: PostId) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE posts SET deleted_at = NULL, updated_at = now(), version = version + 1
+            WHERE id = $1 AND deleted_at IS NOT NULL
+        "#,
+    )
+    .bind(id)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn get_trashed_posts(pool: &PgPool) -> Result<Vec<DbPost>, SqlxError> {
+    sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, id
+        "#
+    ))
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbPost::try_from)
+    .collect()
+}
+
+pub async fn get_trashed_post_by_id(
+    executor: impl PgExecutor<'_>,
+    id: PostId,
+) -> Result<DbPost, SqlxError> {
+    let query_result = sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE id = $1 AND deleted_at IS NOT NULL
+        "#
+    ))
+    .bind(id)
+    .fetch_one(executor)
     .await?;
 
     DbPost::try_from(query_result)
 }
 
-pub async fn delete_post(pool: &PgPool, id: PostId) -> Result<(), SqlxError> {
-    sqlx::query(
+/// Deletes a post for good, whether or not it is in the trash.
+pub async fn delete_post(conn: &mut PgConnection, id: PostId) -> Result<(), SqlxError> {
+    let result = sqlx::query(
         r#"
             DELETE FROM posts WHERE id = $1
         "#,
     )
     .bind(id)
-    .execute(pool)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn delete_all_posts(conn: &mut PgConnection) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            DELETE FROM posts
+        "#,
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_posts_by_author(
+    pool: &PgPool,
+    author_id: AuthorId,
+) -> Result<Vec<DbPost>, SqlxError> {
+    sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            JOIN post_authors ON post_authors.post_id = posts.id
+            WHERE post_authors.author_id = $1 AND posts.deleted_at IS NULL
+            ORDER BY posts.created_at DESC, posts.id
+        "#
+    ))
+    .bind(author_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbPost::try_from)
+    .collect()
+}
+
+/// The newest `limit` posts of each of the authors, older than `before` if
+/// given, newest first, for loading the posts of many authors at once. A post
+/// by several of them is returned once.
+pub async fn get_posts_by_authors(
+    pool: &PgPool,
+    author_ids: &[AuthorId],
+    before: Option<(DateTime<Utc>, PostId)>,
+    limit: i64,
+) -> Result<Vec<DbPost>, SqlxError> {
+    let author_ids: Vec<_> = author_ids.iter().map(AuthorId::inner).collect();
+    let (before_created_at, before_id) = before.unzip();
+
+    sqlx::query(&format!(
+        r#"
+            SELECT {POST_COLUMNS} FROM posts
+            WHERE posts.id IN (
+                SELECT ranked.post_id FROM (
+                    SELECT
+                        post_authors.post_id,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY post_authors.author_id
+                            ORDER BY candidates.created_at DESC, candidates.id DESC
+                        ) AS position
+                    FROM post_authors
+                    JOIN posts AS candidates ON candidates.id = post_authors.post_id
+                    WHERE post_authors.author_id = ANY($1)
+                        AND candidates.deleted_at IS NULL
+                        AND (
+                            $2::timestamptz IS NULL
+                            OR (candidates.created_at, candidates.id) < ($2, $3)
+                        )
+                ) AS ranked
+                WHERE ranked.position <= $4
+            )
+            ORDER BY posts.created_at DESC, posts.id DESC
+        "#
+    ))
+    .bind(author_ids)
+    .bind(before_created_at)
+    .bind(before_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbPost::try_from)
+    .collect()
+}
+
+/// Replaces the byline of a post, keeping the given order. An author listed
+/// more than once keeps their first position.
+pub async fn set_post_authors(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    author_ids: &[AuthorId],
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            DELETE FROM post_authors WHERE post_id = $1
+        "#,
+    )
+    .bind(post_id)
+    .execute(&mut *conn)
+    .await?;
+
+    let author_ids: Vec<_> = author_ids.iter().map(AuthorId::inner).collect();
+
+    sqlx::query(
+        r#"
+            INSERT INTO post_authors (post_id, author_id, position)
+            SELECT $1, author_id, (ROW_NUMBER() OVER (ORDER BY MIN(position)) - 1)::INTEGER
+            FROM unnest($2::UUID[]) WITH ORDINALITY AS byline (author_id, position)
+            GROUP BY author_id
+        "#,
+    )
+    .bind(post_id)
+    .bind(author_ids)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Credits a post to the author profile of `user_id`, if there is one.
+pub async fn set_default_post_author(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    user_id: UserId,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO post_authors (post_id, author_id, position)
+            SELECT $1, id, 0 FROM authors WHERE user_id = $2
+        "#,
+    )
+    .bind(post_id)
+    .bind(user_id)
+    .execute(conn)
     .await?;
 
     Ok(())
@@ -0,0 +1,85 @@
+use sqlx::{PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::db::{
+    models::user::{CreateSessionDbInput, DbSession, DbUser},
+    query::user::USER_COLUMNS,
+};
+
+impl TryFrom<PgRow> for DbSession {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbSession {
+            expires_at: row.try_get("session_expires_at")?,
+            second_factor_pending: row.try_get("second_factor_pending")?,
+            user: DbUser::try_from(row)?,
+        })
+    }
+}
+
+pub async fn create_session(pool: &PgPool, input: CreateSessionDbInput) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO sessions (token_hash, user_id, expires_at, second_factor_pending)
+            VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(input.token_hash)
+    .bind(input.user_id)
+    .bind(input.expires_at)
+    .bind(input.second_factor_pending)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_session(pool: &PgPool, token_hash: &[u8]) -> Result<DbSession, SqlxError> {
+    let query_result = sqlx::query(&format!(
+        r#"
+            SELECT {USER_COLUMNS},
+                sessions.expires_at AS session_expires_at,
+                sessions.second_factor_pending
+            FROM sessions
+            JOIN users ON users.id = sessions.user_id
+            WHERE sessions.token_hash = $1 AND sessions.expires_at > now()
+        "#
+    ))
+    .bind(token_hash)
+    .fetch_one(pool)
+    .await?;
+
+    DbSession::try_from(query_result)
+}
+
+/// Marks a pending session as fully authenticated.
+pub async fn complete_session(pool: &PgPool, token_hash: &[u8]) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE sessions SET second_factor_pending = false
+            WHERE token_hash = $1 AND expires_at > now()
+        "#,
+    )
+    .bind(token_hash)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn delete_session(pool: &PgPool, token_hash: &[u8]) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            DELETE FROM sessions WHERE token_hash = $1 OR expires_at <= now()
+        "#,
+    )
+    .bind(token_hash)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::{
+    db::models::taxonomy::{DbPostComment, DbPostTerm, InsertCommentDbInput, InsertTermDbInput},
+    ids::{CommentId, PostId},
+};
+
+impl TryFrom<PgRow> for DbPostTerm {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbPostTerm {
+            kind: row.try_get("kind")?,
+            name: row.try_get("name")?,
+            slug: row.try_get("slug")?,
+        })
+    }
+}
+
+impl TryFrom<PgRow> for DbPostComment {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbPostComment {
+            id: row.try_get("id")?,
+            parent_id: row.try_get("parent_id")?,
+            author_name: row.try_get("author_name")?,
+            author_email: row.try_get("author_email")?,
+            author_url: row.try_get("author_url")?,
+            body: row.try_get("body")?,
+            approved: row.try_get("approved")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// Files a post under the given categories and tags, keeping their order. A
+/// term listed more than once keeps its first position.
+pub async fn insert_post_terms(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    terms: Vec<InsertTermDbInput>,
+) -> Result<(), SqlxError> {
+    let (kinds, names, slugs): (Vec<_>, Vec<_>, Vec<_>) = terms
+        .into_iter()
+        .map(|term| (term.kind, term.name, term.slug))
+        .collect();
+
+    sqlx::query(
+        r#"
+            INSERT INTO post_terms (post_id, kind, name, slug, position)
+            SELECT $1, kind, name, (ARRAY_AGG(slug ORDER BY position))[1],
+                (ROW_NUMBER() OVER (ORDER BY MIN(position)) - 1)::INTEGER
+            FROM unnest($2::TEXT[], $3::TEXT[], $4::TEXT[])
+                WITH ORDINALITY AS terms (kind, name, slug, position)
+            GROUP BY kind, name
+        "#,
+    )
+    .bind(post_id)
+    .bind(kinds)
+    .bind(names)
+    .bind(slugs)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Inserts the comments of a post in one statement, so replies may be listed
+/// before the comments they answer.
+pub async fn insert_post_comments(
+    conn: &mut PgConnection,
+    post_id: PostId,
+    comments: Vec<InsertCommentDbInput>,
+) -> Result<(), SqlxError> {
+    let mut ids = Vec::with_capacity(comments.len());
+    let mut parent_ids = Vec::with_capacity(comments.len());
+    let mut author_names = Vec::with_capacity(comments.len());
+    let mut author_emails = Vec::with_capacity(comments.len());
+    let mut author_urls = Vec::with_capacity(comments.len());
+    let mut bodies = Vec::with_capacity(comments.len());
+    let mut approved = Vec::with_capacity(comments.len());
+    let mut created_at: Vec<DateTime<Utc>> = Vec::with_capacity(comments.len());
+
+    for comment in comments {
+        ids.push(comment.id.inner());
+        parent_ids.push(comment.parent_id.as_ref().map(CommentId::inner));
+        author_names.push(comment.author_name);
+        author_emails.push(comment.author_email);
+        author_urls.push(comment.author_url);
+        bodies.push(comment.body);
+        approved.push(comment.approved);
+        created_at.push(comment.created_at);
+    }
+
+    sqlx::query(
+        r#"
+            INSERT INTO post_comments (
+                id, post_id, parent_id, author_name, author_email, author_url, body, approved,
+                created_at
+            )
+            SELECT id, $1, parent_id, author_name, author_email, author_url, body, approved,
+                created_at
+            FROM unnest(
+                $2::UUID[], $3::UUID[], $4::TEXT[], $5::TEXT[], $6::TEXT[], $7::TEXT[],
+                $8::BOOLEAN[], $9::TIMESTAMPTZ[]
+            ) AS comments (
+                id, parent_id, author_name, author_email, author_url, body, approved, created_at
+            )
+        "#,
+    )
+    .bind(post_id)
+    .bind(ids)
+    .bind::<Vec<Option<Uuid>>>(parent_ids)
+    .bind(author_names)
+    .bind(author_emails)
+    .bind(author_urls)
+    .bind(bodies)
+    .bind(approved)
+    .bind(created_at)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The categories and tags of a post, in the order they were listed.
+pub async fn get_post_terms(pool: &PgPool, post_id: PostId) -> Result<Vec<DbPostTerm>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT kind, name, slug FROM post_terms
+            WHERE post_id = $1
+            ORDER BY position
+        "#,
+    )
+    .bind(post_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbPostTerm::try_from)
+    .collect()
+}
+
+/// The approved comments on a post, oldest first.
+pub async fn get_approved_post_comments(
+    pool: &PgPool,
+    post_id: PostId,
+) -> Result<Vec<DbPostComment>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT id, parent_id, author_name, author_email, author_url, body, approved,
+                created_at
+            FROM post_comments
+            WHERE post_id = $1 AND approved
+            ORDER BY created_at, id
+        "#,
+    )
+    .bind(post_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbPostComment::try_from)
+    .collect()
+}
@@ -0,0 +1,210 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::{db::models::totp::DbTotp, ids::UserId};
+
+impl TryFrom<PgRow> for DbTotp {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbTotp {
+            secret: row.try_get("secret")?,
+            confirmed_at: row.try_get("confirmed_at")?,
+        })
+    }
+}
+
+/// Stores a new secret awaiting confirmation, replacing an earlier
+/// unconfirmed one. Returns `RowNotFound` if the user already has a confirmed
+/// authenticator.
+pub async fn upsert_totp(pool: &PgPool, user_id: UserId, secret: &[u8]) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO user_totp (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = now()
+            WHERE user_totp.confirmed_at IS NULL
+            RETURNING user_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(secret)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_totp(pool: &PgPool, user_id: UserId) -> Result<DbTotp, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            SELECT secret, confirmed_at FROM user_totp
+            WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    DbTotp::try_from(query_result)
+}
+
+/// Confirms a pending secret with the code of `step`, which counts as used.
+pub async fn confirm_totp(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    step: i64,
+) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE user_totp SET confirmed_at = now(), last_used_step = $2
+            WHERE user_id = $1 AND confirmed_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(step)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn replace_recovery_codes(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    code_hashes: &[Vec<u8>],
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            DELETE FROM totp_recovery_codes WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+            INSERT INTO totp_recovery_codes (user_id, code_hash)
+            SELECT $1, unnest($2::BYTEA[])
+        "#,
+    )
+    .bind(user_id)
+    .bind(code_hashes)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Counts an attempt at a one-time or recovery code before it is checked.
+/// The attempt that reaches `max_attempts` locks further ones out until
+/// `locked_until`; once that has passed, counting starts over. Returns
+/// `RowNotFound` while the user is locked out.
+pub async fn start_second_factor_attempt(
+    pool: &PgPool,
+    user_id: UserId,
+    max_attempts: i32,
+    locked_until: DateTime<Utc>,
+) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE user_totp SET
+                failed_attempts = CASE
+                    WHEN locked_until IS NULL THEN failed_attempts + 1
+                    ELSE 1
+                END,
+                locked_until = CASE
+                    WHEN (CASE WHEN locked_until IS NULL THEN failed_attempts + 1 ELSE 1 END) >= $2
+                    THEN $3
+                END
+            WHERE user_id = $1
+                AND confirmed_at IS NOT NULL
+                AND (locked_until IS NULL OR locked_until <= now())
+        "#,
+    )
+    .bind(user_id)
+    .bind(max_attempts)
+    .bind(locked_until)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+/// Forgets the failed attempts of a user who got a code right.
+pub async fn reset_second_factor_attempts(
+    conn: &mut PgConnection,
+    user_id: UserId,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            UPDATE user_totp SET failed_attempts = 0, locked_until = NULL
+            WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Records `step` as used. Returns `RowNotFound` if a code of this or a later
+/// step was already accepted, which makes every code single-use.
+pub async fn use_totp_step(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    step: i64,
+) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE user_totp SET last_used_step = $2
+            WHERE user_id = $1
+                AND confirmed_at IS NOT NULL
+                AND (last_used_step IS NULL OR last_used_step < $2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(step)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+pub async fn use_recovery_code(
+    conn: &mut PgConnection,
+    user_id: UserId,
+    code_hash: &[u8],
+) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            UPDATE totp_recovery_codes SET used_at = now()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(code_hash)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use sqlx::{PgExecutor, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::{
+    db::models::user::{CreateUserDbInput, DbUser},
+    ids::UserId,
+};
+
+/// Every user column plus whether the user has confirmed an authenticator
+/// app, for queries selecting from `users`.
+pub(crate) const USER_COLUMNS: &str = r#"
+    users.id, users.email, users.password_hash, users.role, users.created_at,
+    EXISTS (
+        SELECT 1 FROM user_totp
+        WHERE user_totp.user_id = users.id AND user_totp.confirmed_at IS NOT NULL
+    ) AS second_factor
+"#;
+
+impl TryFrom<PgRow> for DbUser {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbUser {
+            id: row.try_get("id")?,
+            email: row.try_get("email")?,
+            password_hash: row.try_get("password_hash")?,
+            role: row.try_get("role")?,
+            created_at: row.try_get("created_at")?,
+            second_factor: row.try_get("second_factor")?,
+        })
+    }
+}
+
+pub async fn create_user(
+    executor: impl PgExecutor<'_>,
+    input: CreateUserDbInput,
+) -> Result<DbUser, SqlxError> {
+    let id = UserId::new();
+
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO users (id, email, password_hash, role)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, email, password_hash, role, created_at, false AS second_factor
+        "#,
+    )
+    .bind(id)
+    .bind(input.email())
+    .bind(input.password_hash())
+    .bind(input.role())
+    .fetch_one(executor)
+    .await?;
+
+    DbUser::try_from(query_result)
+}
+
+pub async fn get_user_by_email(
+    executor: impl PgExecutor<'_>,
+    email: &str,
+) -> Result<DbUser, SqlxError> {
+    let query_result = sqlx::query(&format!(
+        r#"
+            SELECT {USER_COLUMNS} FROM users
+            WHERE email = $1
+        "#
+    ))
+    .bind(email)
+    .fetch_one(executor)
+    .await?;
+
+    DbUser::try_from(query_result)
+}
+
+pub async fn get_user_by_identity(
+    executor: impl PgExecutor<'_>,
+    issuer: &str,
+    subject: &str,
+) -> Result<DbUser, SqlxError> {
+    let query_result = sqlx::query(&format!(
+        r#"
+            SELECT {USER_COLUMNS}
+            FROM user_identities
+            JOIN users ON users.id = user_identities.user_id
+            WHERE user_identities.issuer = $1 AND user_identities.subject = $2
+        "#
+    ))
+    .bind(issuer)
+    .bind(subject)
+    .fetch_one(executor)
+    .await?;
+
+    DbUser::try_from(query_result)
+}
+
+pub async fn create_user_identity(
+    executor: impl PgExecutor<'_>,
+    issuer: &str,
+    subject: &str,
+    user_id: UserId,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO user_identities (issuer, subject, user_id)
+            VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(issuer)
+    .bind(subject)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool, Row, error::Error as SqlxError, postgres::PgRow};
+
+use crate::{
+    db::models::webhook::{
+        CreateWebhookDbInput, DbDueWebhookDelivery, DbWebhook, DbWebhookDelivery,
+        RecordWebhookAttemptDbInput,
+    },
+    ids::WebhookId,
+};
+
+/// How many of the most recent deliveries the delivery log of a webhook
+/// shows.
+const DELIVERY_LOG_LIMIT: i64 = 100;
+
+const DELIVERY_COLUMNS: &str = r#"
+    id, webhook_id, event, status, attempts, next_attempt_at, last_attempt_at,
+    last_response_status, last_error, created_at, delivered_at
+"#;
+
+impl TryFrom<PgRow> for DbWebhook {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbWebhook {
+            id: row.try_get("id")?,
+            url: row.try_get("url")?,
+            events: row.try_get("events")?,
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl TryFrom<PgRow> for DbWebhookDelivery {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbWebhookDelivery {
+            id: row.try_get("id")?,
+            webhook_id: row.try_get("webhook_id")?,
+            event: row.try_get("event")?,
+            status: row.try_get("status")?,
+            attempts: row.try_get("attempts")?,
+            next_attempt_at: row.try_get("next_attempt_at")?,
+            last_attempt_at: row.try_get("last_attempt_at")?,
+            last_response_status: row.try_get("last_response_status")?,
+            last_error: row.try_get("last_error")?,
+            created_at: row.try_get("created_at")?,
+            delivered_at: row.try_get("delivered_at")?,
+        })
+    }
+}
+
+impl TryFrom<PgRow> for DbDueWebhookDelivery {
+    type Error = SqlxError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(DbDueWebhookDelivery {
+            id: row.try_get("id")?,
+            event: row.try_get("event")?,
+            attempts: row.try_get("attempts")?,
+            url: row.try_get("url")?,
+            secret: row.try_get("secret")?,
+            payload: row.try_get("payload")?,
+        })
+    }
+}
+
+pub async fn create_webhook(
+    pool: &PgPool,
+    input: CreateWebhookDbInput,
+) -> Result<DbWebhook, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            INSERT INTO webhooks (id, url, secret, events, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, url, events, created_by, created_at
+        "#,
+    )
+    .bind(input.id)
+    .bind(input.url)
+    .bind(input.secret)
+    .bind(input.events)
+    .bind(input.created_by)
+    .fetch_one(pool)
+    .await?;
+
+    DbWebhook::try_from(query_result)
+}
+
+pub async fn get_webhooks(pool: &PgPool) -> Result<Vec<DbWebhook>, SqlxError> {
+    sqlx::query(
+        r#"
+            SELECT id, url, events, created_by, created_at
+            FROM webhooks
+            ORDER BY created_at, id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbWebhook::try_from)
+    .collect()
+}
+
+pub async fn get_webhook_by_id(pool: &PgPool, id: WebhookId) -> Result<DbWebhook, SqlxError> {
+    let query_result = sqlx::query(
+        r#"
+            SELECT id, url, events, created_by, created_at
+            FROM webhooks
+            WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    DbWebhook::try_from(query_result)
+}
+
+/// Deletes the webhook together with its deliveries, including those not
+/// sent yet.
+pub async fn delete_webhook(pool: &PgPool, id: WebhookId) -> Result<(), SqlxError> {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM webhooks WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(SqlxError::RowNotFound);
+    }
+
+    Ok(())
+}
+
+/// Queues `payload` for every webhook subscribed to `event`.
+pub async fn enqueue_webhook_deliveries(
+    conn: &mut PgConnection,
+    event: &str,
+    payload: &str,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT id, $1, $2 FROM webhooks
+            WHERE $1 = ANY (events)
+        "#,
+    )
+    .bind(event)
+    .bind(payload)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The most recent deliveries to a webhook, newest first.
+pub async fn get_webhook_deliveries(
+    pool: &PgPool,
+    webhook_id: WebhookId,
+) -> Result<Vec<DbWebhookDelivery>, SqlxError> {
+    sqlx::query(&format!(
+        r#"
+            SELECT {DELIVERY_COLUMNS}
+            FROM webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+        "#
+    ))
+    .bind(webhook_id)
+    .bind(DELIVERY_LOG_LIMIT)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbWebhookDelivery::try_from)
+    .collect()
+}
+
+/// Makes a delivery due right away with a fresh set of attempts, whatever
+/// became of it before. `None` if there is no such delivery, or it is being
+/// sent.
+pub async fn redeliver_webhook_delivery(
+    pool: &PgPool,
+    webhook_id: WebhookId,
+    delivery_id: i64,
+) -> Result<Option<DbWebhookDelivery>, SqlxError> {
+    sqlx::query(&format!(
+        r#"
+            UPDATE webhook_deliveries
+            SET status = 'pending', attempts = 0, next_attempt_at = now(),
+                delivered_at = NULL
+            WHERE id = $1 AND webhook_id = $2
+                AND (leased_until IS NULL OR leased_until <= now())
+            RETURNING {DELIVERY_COLUMNS}
+        "#
+    ))
+    .bind(delivery_id)
+    .bind(webhook_id)
+    .fetch_optional(pool)
+    .await?
+    .map(DbWebhookDelivery::try_from)
+    .transpose()
+}
+
+pub async fn webhook_delivery_exists(
+    pool: &PgPool,
+    webhook_id: WebhookId,
+    delivery_id: i64,
+) -> Result<bool, SqlxError> {
+    sqlx::query_scalar(
+        r#"
+            SELECT EXISTS (
+                SELECT 1 FROM webhook_deliveries WHERE id = $1 AND webhook_id = $2
+            )
+        "#,
+    )
+    .bind(delivery_id)
+    .bind(webhook_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Claims up to `limit` due deliveries, oldest first, counting the attempt
+/// about to be made. They are leased, and not due again, until
+/// `lease_until`, so other instances leave them alone while they are sent,
+/// yet pick them up should this one never record the outcome.
+pub async fn claim_due_webhook_deliveries(
+    pool: &PgPool,
+    limit: i64,
+    lease_until: DateTime<Utc>,
+) -> Result<Vec<DbDueWebhookDelivery>, SqlxError> {
+    sqlx::query(
+        r#"
+            WITH due AS (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= now()
+                ORDER BY next_attempt_at, id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE webhook_deliveries AS delivery
+            SET attempts = delivery.attempts + 1, next_attempt_at = $2, leased_until = $2
+            FROM due, webhooks
+            WHERE delivery.id = due.id AND webhooks.id = delivery.webhook_id
+            RETURNING delivery.id, delivery.event, delivery.attempts, delivery.payload,
+                webhooks.url, webhooks.secret
+        "#,
+    )
+    .bind(limit)
+    .bind(lease_until)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(DbDueWebhookDelivery::try_from)
+    .collect()
+}
+
+pub async fn record_webhook_attempt(
+    pool: &PgPool,
+    delivery_id: i64,
+    input: RecordWebhookAttemptDbInput,
+) -> Result<(), SqlxError> {
+    sqlx::query(
+        r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                next_attempt_at = $3,
+                last_attempt_at = $4,
+                last_response_status = $5,
+                last_error = $6,
+                delivered_at = CASE WHEN $2 = 'delivered' THEN $4 END,
+                leased_until = NULL
+            WHERE id = $1
+        "#,
+    )
+    .bind(delivery_id)
+    .bind(input.status)
+    .bind(input.next_attempt_at)
+    .bind(input.attempted_at)
+    .bind(input.response_status)
+    .bind(input.error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Deletes the delivered and dead deliveries last attempted before
+/// `finished_before`, returning how many there were.
+pub async fn delete_finished_webhook_deliveries(
+    pool: &PgPool,
+    finished_before: DateTime<Utc>,
+) -> Result<u64, SqlxError> {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM webhook_deliveries
+            WHERE status <> 'pending' AND last_attempt_at < $1
+        "#,
+    )
+    .bind(finished_before)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -1,3 +1,4 @@
 pub mod models;
+pub mod policy;
 pub mod repository;
 pub mod service;
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("API token name cannot be empty")]
+pub struct ApiTokenNameEmptyError;
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown scope {0:?}, expected one of posts:read, posts:write, posts:delete")]
+pub struct ScopeInvalidError(pub String);
@@ -0,0 +1,229 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::ids::{ApiTokenId, UserId};
+
+use super::errors::{ApiTokenNameEmptyError, ScopeInvalidError};
+
+/// Prefix of every API token secret, so leaked tokens are easy to recognise
+/// for secret scanners and in logs.
+pub const API_TOKEN_PREFIX: &str = "blog_pat_";
+
+const API_TOKEN_BYTES: usize = 32;
+
+/// What an API token is allowed to do. Session logins carry every scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    PostsRead,
+    PostsWrite,
+    PostsDelete,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::PostsRead, Scope::PostsWrite, Scope::PostsDelete];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PostsRead => "posts:read",
+            Self::PostsWrite => "posts:write",
+            Self::PostsDelete => "posts:delete",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ScopeInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| ScopeInvalidError(s.to_string()))
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiToken {
+    id: ApiTokenId,
+    user_id: UserId,
+    name: ApiTokenName,
+    scopes: BTreeSet<Scope>,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub fn new(
+        id: ApiTokenId,
+        user_id: UserId,
+        name: ApiTokenName,
+        scopes: BTreeSet<Scope>,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        last_used_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            scopes,
+            created_at,
+            expires_at,
+            last_used_at,
+        }
+    }
+
+    pub fn id(&self) -> ApiTokenId {
+        self.id
+    }
+
+    pub fn user_id(&self) -> UserId {
+        self.user_id
+    }
+
+    pub fn name(&self) -> ApiTokenName {
+        self.name.clone()
+    }
+
+    pub fn scopes(&self) -> &BTreeSet<Scope> {
+        &self.scopes
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiTokenName(String);
+
+impl ApiTokenName {
+    pub fn try_new(raw: &str) -> Result<Self, ApiTokenNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return Err(ApiTokenNameEmptyError);
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+}
+
+impl Display for ApiTokenName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The bearer secret of an API token. Like session tokens only its SHA-256
+/// digest is stored; the plaintext is shown once, when the token is created.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiTokenSecret(String);
+
+impl ApiTokenSecret {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; API_TOKEN_BYTES];
+        rand::rng().fill_bytes(&mut bytes);
+        Self(format!(
+            "{API_TOKEN_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(bytes)
+        ))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Sha256::digest(self.0.as_bytes()).to_vec()
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for ApiTokenSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiTokenSecret(***)")
+    }
+}
+
+/// A freshly created token together with its secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatedApiToken {
+    token: ApiToken,
+    secret: ApiTokenSecret,
+}
+
+impl CreatedApiToken {
+    pub fn new(token: ApiToken, secret: ApiTokenSecret) -> Self {
+        Self { token, secret }
+    }
+
+    pub fn token(&self) -> ApiToken {
+        self.token.clone()
+    }
+
+    pub fn secret(&self) -> ApiTokenSecret {
+        self.secret.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trips_through_string() {
+        for scope in Scope::ALL {
+            assert_eq!(scope.as_str().parse::<Scope>().unwrap(), scope);
+        }
+        assert!("posts:publish".parse::<Scope>().is_err());
+    }
+
+    #[test]
+    fn test_api_token_name_validation() {
+        assert!(ApiTokenName::try_new("   ").is_err());
+        assert_eq!(ApiTokenName::try_new(" CI ").unwrap().to_string(), "CI");
+    }
+
+    #[test]
+    fn test_api_token_secret_is_prefixed_and_unique() {
+        let first = ApiTokenSecret::generate();
+        let second = ApiTokenSecret::generate();
+
+        assert!(first.expose().starts_with(API_TOKEN_PREFIX));
+        assert_ne!(first, second);
+        assert_eq!(first.hash().len(), 32);
+    }
+}
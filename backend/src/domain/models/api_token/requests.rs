@@ -0,0 +1,38 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+
+use super::model::{ApiTokenName, Scope};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateApiTokenRequest {
+    name: ApiTokenName,
+    scopes: BTreeSet<Scope>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl CreateApiTokenRequest {
+    pub fn new(
+        name: ApiTokenName,
+        scopes: BTreeSet<Scope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            name,
+            scopes,
+            expires_at,
+        }
+    }
+
+    pub fn name(&self) -> ApiTokenName {
+        self.name.clone()
+    }
+
+    pub fn scopes(&self) -> BTreeSet<Scope> {
+        self.scopes.clone()
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+}
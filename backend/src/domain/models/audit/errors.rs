@@ -0,0 +1,7 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error(
+    "Unknown audit action {0:?}, expected one of post.created, post.updated, post.trashed, post.restored, post.deleted"
+)]
+pub struct AuditActionInvalidError(pub String);
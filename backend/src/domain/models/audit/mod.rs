@@ -0,0 +1,7 @@
+pub mod errors;
+pub mod model;
+pub mod requests;
+
+pub use errors::*;
+pub use model::*;
+pub use requests::*;
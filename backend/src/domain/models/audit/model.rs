@@ -0,0 +1,357 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ids::{PostId, UserId},
+    request_id::RequestId,
+};
+
+use super::errors::AuditActionInvalidError;
+
+/// The first entry of the log links to this instead of a previous hash.
+pub const AUDIT_GENESIS_HASH: [u8; 32] = [0; 32];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AuditAction {
+    PostCreated,
+    PostUpdated,
+    PostTrashed,
+    PostRestored,
+    /// Deleted for good, either directly or when purged from the trash.
+    PostDeleted,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 5] = [
+        AuditAction::PostCreated,
+        AuditAction::PostUpdated,
+        AuditAction::PostTrashed,
+        AuditAction::PostRestored,
+        AuditAction::PostDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PostCreated => "post.created",
+            Self::PostUpdated => "post.updated",
+            Self::PostTrashed => "post.trashed",
+            Self::PostRestored => "post.restored",
+            Self::PostDeleted => "post.deleted",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = AuditActionInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str() == s)
+            .ok_or_else(|| AuditActionInvalidError(s.to_string()))
+    }
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Who caused a change. Changes made outside an HTTP request, e.g. by an
+/// import from the CLI, have neither an actor nor a request id.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditContext {
+    actor_id: Option<UserId>,
+    request_id: Option<RequestId>,
+}
+
+impl AuditContext {
+    pub fn new(actor_id: Option<UserId>, request_id: Option<RequestId>) -> Self {
+        Self {
+            actor_id,
+            request_id,
+        }
+    }
+
+    pub fn actor_id(&self) -> Option<UserId> {
+        self.actor_id
+    }
+
+    pub fn request_id(&self) -> Option<&RequestId> {
+        self.request_id.as_ref()
+    }
+}
+
+/// What happened to a post. The snapshots are the post as stored, byline
+/// included; a created post has no `before`, a deleted one no `after`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditRecord {
+    context: AuditContext,
+    action: AuditAction,
+    post_id: PostId,
+    before: Option<Value>,
+    after: Option<Value>,
+    created_at: DateTime<Utc>,
+}
+
+impl AuditRecord {
+    pub fn new(
+        context: AuditContext,
+        action: AuditAction,
+        post_id: PostId,
+        before: Option<Value>,
+        after: Option<Value>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            context,
+            action,
+            post_id,
+            before,
+            after,
+            // The database keeps microseconds; anything finer would change
+            // the hash once the record is read back.
+            created_at: created_at.trunc_subsecs(6),
+        }
+    }
+
+    pub fn context(&self) -> &AuditContext {
+        &self.context
+    }
+
+    pub fn action(&self) -> AuditAction {
+        self.action
+    }
+
+    pub fn post_id(&self) -> PostId {
+        self.post_id
+    }
+
+    pub fn before(&self) -> Option<&Value> {
+        self.before.as_ref()
+    }
+
+    pub fn after(&self) -> Option<&Value> {
+        self.after.as_ref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// SHA-256 of the previous entry's hash followed by the record as JSON,
+    /// so changing, removing or reordering an entry breaks every later link.
+    pub fn chain_hash(&self, prev_hash: &[u8]) -> Vec<u8> {
+        let record = json!({
+            "actor_id": self.context.actor_id,
+            "request_id": self.context.request_id.as_ref().map(RequestId::as_str),
+            "action": self.action.as_str(),
+            "post_id": self.post_id,
+            "before": self.before,
+            "after": self.after,
+            "created_at": self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        });
+
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash);
+        hasher.update(record.to_string().as_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+/// A record as stored in the log, linked to the entry before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    id: i64,
+    record: AuditRecord,
+    prev_hash: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl AuditEntry {
+    pub fn new(id: i64, record: AuditRecord, prev_hash: Vec<u8>, hash: Vec<u8>) -> Self {
+        Self {
+            id,
+            record,
+            prev_hash,
+            hash,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn record(&self) -> &AuditRecord {
+        &self.record
+    }
+
+    pub fn prev_hash(&self) -> &[u8] {
+        &self.prev_hash
+    }
+
+    pub fn hash(&self) -> &[u8] {
+        &self.hash
+    }
+
+    /// Whether the stored hash still matches the record.
+    pub fn is_intact(&self) -> bool {
+        self.record.chain_hash(&self.prev_hash) == self.hash
+    }
+}
+
+/// Walks the log from its first entry, checking every hash and link.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditVerification {
+    checked: u64,
+    head_hash: Vec<u8>,
+    first_broken_id: Option<i64>,
+}
+
+impl AuditVerification {
+    pub fn new() -> Self {
+        Self {
+            checked: 0,
+            head_hash: AUDIT_GENESIS_HASH.to_vec(),
+            first_broken_id: None,
+        }
+    }
+
+    /// Checks the next entry in order of id.
+    pub fn check(&mut self, entry: &AuditEntry) {
+        let linked = entry.prev_hash() == self.head_hash.as_slice();
+
+        if self.first_broken_id.is_none() && !(linked && entry.is_intact()) {
+            self.first_broken_id = Some(entry.id());
+        }
+        self.checked += 1;
+        self.head_hash = entry.hash().to_vec();
+    }
+
+    pub fn checked(&self) -> u64 {
+        self.checked
+    }
+
+    /// Hash of the newest entry. Comparing it with an earlier copy also
+    /// reveals entries removed from the end, which the chain itself cannot.
+    pub fn head_hash(&self) -> &[u8] {
+        &self.head_hash
+    }
+
+    pub fn first_broken_id(&self) -> Option<i64> {
+        self.first_broken_id
+    }
+
+    pub fn is_intact(&self) -> bool {
+        self.first_broken_id.is_none()
+    }
+}
+
+impl Default for AuditVerification {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(action: AuditAction, after: Value) -> AuditRecord {
+        AuditRecord::new(
+            AuditContext::new(Some(UserId::new()), Some(RequestId::new("req-1"))),
+            action,
+            PostId::new(),
+            None,
+            Some(after),
+            Utc::now(),
+        )
+    }
+
+    fn chain(records: Vec<AuditRecord>) -> Vec<AuditEntry> {
+        let mut prev_hash = AUDIT_GENESIS_HASH.to_vec();
+
+        records
+            .into_iter()
+            .zip(1..)
+            .map(|(record, id)| {
+                let hash = record.chain_hash(&prev_hash);
+                let entry = AuditEntry::new(id, record, prev_hash.clone(), hash.clone());
+                prev_hash = hash;
+                entry
+            })
+            .collect()
+    }
+
+    fn verify(entries: &[AuditEntry]) -> AuditVerification {
+        let mut verification = AuditVerification::new();
+        entries.iter().for_each(|entry| verification.check(entry));
+        verification
+    }
+
+    #[test]
+    fn test_action_round_trips_through_str() {
+        for action in AuditAction::ALL {
+            assert_eq!(action.as_str().parse::<AuditAction>().unwrap(), action);
+        }
+        assert!("post.published".parse::<AuditAction>().is_err());
+    }
+
+    #[test]
+    fn test_untouched_chain_verifies() {
+        let entries = chain(vec![
+            record(AuditAction::PostCreated, json!({ "title": "A" })),
+            record(AuditAction::PostUpdated, json!({ "title": "B" })),
+        ]);
+
+        let verification = verify(&entries);
+
+        assert!(verification.is_intact());
+        assert_eq!(verification.checked(), 2);
+        assert_eq!(verification.head_hash(), entries[1].hash());
+    }
+
+    #[test]
+    fn test_changed_record_breaks_chain() {
+        let mut entries = chain(vec![
+            record(AuditAction::PostCreated, json!({ "title": "A" })),
+            record(AuditAction::PostUpdated, json!({ "title": "B" })),
+        ]);
+        entries[0].record.after = Some(json!({ "title": "Forged" }));
+
+        assert_eq!(verify(&entries).first_broken_id(), Some(1));
+    }
+
+    #[test]
+    fn test_removed_entry_breaks_chain() {
+        let mut entries = chain(vec![
+            record(AuditAction::PostCreated, json!({ "title": "A" })),
+            record(AuditAction::PostUpdated, json!({ "title": "B" })),
+            record(AuditAction::PostDeleted, json!(null)),
+        ]);
+        entries.remove(1);
+
+        assert_eq!(verify(&entries).first_broken_id(), Some(3));
+    }
+
+    #[test]
+    fn test_timestamps_are_kept_to_microseconds() {
+        let created_at = DateTime::parse_from_rfc3339("2026-10-18T10:00:00.123456789Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let record = AuditRecord::new(
+            AuditContext::default(),
+            AuditAction::PostDeleted,
+            PostId::new(),
+            None,
+            None,
+            created_at,
+        );
+
+        assert_eq!(record.created_at().timestamp_subsec_nanos(), 123_456_000);
+    }
+}
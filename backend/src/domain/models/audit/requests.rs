@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+
+use crate::ids::{PostId, UserId};
+
+use super::model::AuditAction;
+
+pub const AUDIT_PAGE_SIZE: i64 = 100;
+
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 1000;
+
+/// A page of the audit log, newest entries first. Every filter that is set
+/// must match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditQuery {
+    actor_id: Option<UserId>,
+    post_id: Option<PostId>,
+    action: Option<AuditAction>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    before_id: Option<i64>,
+    limit: i64,
+}
+
+impl AuditQuery {
+    pub fn new() -> Self {
+        Self {
+            actor_id: None,
+            post_id: None,
+            action: None,
+            since: None,
+            until: None,
+            before_id: None,
+            limit: AUDIT_PAGE_SIZE,
+        }
+    }
+
+    pub fn with_actor_id(mut self, actor_id: Option<UserId>) -> Self {
+        self.actor_id = actor_id;
+        self
+    }
+
+    pub fn with_post_id(mut self, post_id: Option<PostId>) -> Self {
+        self.post_id = post_id;
+        self
+    }
+
+    pub fn with_action(mut self, action: Option<AuditAction>) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Entries at or after `since` and before `until`.
+    pub fn with_period(
+        mut self,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    /// Continues a previous page, whose last entry had id `before_id`.
+    pub fn with_before_id(mut self, before_id: Option<i64>) -> Self {
+        self.before_id = before_id;
+        self
+    }
+
+    /// Clamped to between one and [`MAX_AUDIT_PAGE_SIZE`].
+    pub fn with_limit(mut self, limit: Option<i64>) -> Self {
+        self.limit = limit
+            .unwrap_or(AUDIT_PAGE_SIZE)
+            .clamp(1, MAX_AUDIT_PAGE_SIZE);
+        self
+    }
+
+    pub fn actor_id(&self) -> Option<UserId> {
+        self.actor_id
+    }
+
+    pub fn post_id(&self) -> Option<PostId> {
+        self.post_id
+    }
+
+    pub fn action(&self) -> Option<AuditAction> {
+        self.action
+    }
+
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.since
+    }
+
+    pub fn until(&self) -> Option<DateTime<Utc>> {
+        self.until
+    }
+
+    pub fn before_id(&self) -> Option<i64> {
+        self.before_id
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+}
+
+impl Default for AuditQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
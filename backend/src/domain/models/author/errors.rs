@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("Author name cannot be empty")]
+pub struct AuthorNameEmptyError;
+
+#[derive(Clone, Debug, Error)]
+#[error("{0:?} is not a valid http(s) URL")]
+pub struct AuthorUrlInvalidError(pub String);
+
+#[derive(Clone, Debug, Error)]
+#[error("Social link label cannot be empty")]
+pub struct SocialLinkLabelEmptyError;
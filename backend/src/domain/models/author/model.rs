@@ -0,0 +1,176 @@
+use std::fmt::Display;
+
+use url::Url;
+
+use crate::ids::{AuthorId, UserId};
+
+use super::errors::{AuthorNameEmptyError, AuthorUrlInvalidError, SocialLinkLabelEmptyError};
+
+/// The public profile shown on posts. Authors may be linked to a user
+/// account, but do not have to be, e.g. guest writers or imported authors.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Author {
+    id: AuthorId,
+    user_id: Option<UserId>,
+    name: AuthorName,
+    bio: Option<String>,
+    avatar_url: Option<AuthorUrl>,
+    social_links: Vec<SocialLink>,
+    import_key: Option<String>,
+}
+
+impl Author {
+    pub fn new(id: AuthorId, name: AuthorName) -> Self {
+        Self {
+            id,
+            user_id: None,
+            name,
+            bio: None,
+            avatar_url: None,
+            social_links: Vec::new(),
+            import_key: None,
+        }
+    }
+
+    pub fn with_user_id(mut self, user_id: Option<UserId>) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    pub fn with_bio(mut self, bio: Option<String>) -> Self {
+        self.bio = bio;
+        self
+    }
+
+    pub fn with_avatar_url(mut self, avatar_url: Option<AuthorUrl>) -> Self {
+        self.avatar_url = avatar_url;
+        self
+    }
+
+    pub fn with_social_links(mut self, social_links: Vec<SocialLink>) -> Self {
+        self.social_links = social_links;
+        self
+    }
+
+    pub fn with_import_key(mut self, import_key: Option<String>) -> Self {
+        self.import_key = import_key;
+        self
+    }
+
+    pub fn id(&self) -> AuthorId {
+        self.id
+    }
+
+    pub fn user_id(&self) -> Option<UserId> {
+        self.user_id
+    }
+
+    pub fn name(&self) -> AuthorName {
+        self.name.clone()
+    }
+
+    pub fn bio(&self) -> Option<&str> {
+        self.bio.as_deref()
+    }
+
+    pub fn avatar_url(&self) -> Option<&AuthorUrl> {
+        self.avatar_url.as_ref()
+    }
+
+    pub fn social_links(&self) -> &[SocialLink] {
+        &self.social_links
+    }
+
+    /// Identifies an imported author in the system they came from, e.g. a
+    /// WordPress login, so importing again credits the same profile.
+    pub fn import_key(&self) -> Option<&str> {
+        self.import_key.as_deref()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AuthorName(String);
+
+impl AuthorName {
+    pub fn try_new(raw: &str) -> Result<Self, AuthorNameEmptyError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return Err(AuthorNameEmptyError);
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+}
+
+impl Display for AuthorName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// An absolute `http` or `https` URL, for avatars and social links.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AuthorUrl(Url);
+
+impl AuthorUrl {
+    pub fn try_new(raw: &str) -> Result<Self, AuthorUrlInvalidError> {
+        match Url::parse(raw.trim()) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(Self(url)),
+            _ => Err(AuthorUrlInvalidError(raw.to_string())),
+        }
+    }
+}
+
+impl Display for AuthorUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SocialLink {
+    label: String,
+    url: AuthorUrl,
+}
+
+impl SocialLink {
+    pub fn try_new(label: &str, url: AuthorUrl) -> Result<Self, SocialLinkLabelEmptyError> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(SocialLinkLabelEmptyError);
+        }
+        Ok(Self {
+            label: label.to_string(),
+            url,
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn url(&self) -> &AuthorUrl {
+        &self.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_author_name_validation() {
+        assert!(AuthorName::try_new("  ").is_err());
+        assert_eq!(AuthorName::try_new(" Tommy ").unwrap().to_string(), "Tommy");
+    }
+
+    #[test]
+    fn test_author_url_requires_http_scheme() {
+        assert!(AuthorUrl::try_new("https://example.com/avatar.png").is_ok());
+        assert!(AuthorUrl::try_new("javascript:alert(1)").is_err());
+        assert!(AuthorUrl::try_new("/relative.png").is_err());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::ids::UserId;
+
+use super::model::{AuthorName, AuthorUrl, SocialLink};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateAuthorRequest {
+    name: AuthorName,
+    user_id: Option<UserId>,
+    bio: Option<String>,
+    avatar_url: Option<AuthorUrl>,
+    social_links: Vec<SocialLink>,
+}
+
+impl CreateAuthorRequest {
+    pub fn new(
+        name: AuthorName,
+        user_id: Option<UserId>,
+        bio: Option<String>,
+        avatar_url: Option<AuthorUrl>,
+        social_links: Vec<SocialLink>,
+    ) -> Self {
+        Self {
+            name,
+            user_id,
+            bio,
+            avatar_url,
+            social_links,
+        }
+    }
+
+    pub fn name(&self) -> AuthorName {
+        self.name.clone()
+    }
+
+    pub fn user_id(&self) -> Option<UserId> {
+        self.user_id
+    }
+
+    pub fn bio(&self) -> Option<String> {
+        self.bio.clone()
+    }
+
+    pub fn avatar_url(&self) -> Option<AuthorUrl> {
+        self.avatar_url.clone()
+    }
+
+    pub fn social_links(&self) -> Vec<SocialLink> {
+        self.social_links.clone()
+    }
+}
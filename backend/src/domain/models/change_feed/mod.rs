@@ -0,0 +1,5 @@
+pub mod model;
+pub mod requests;
+
+pub use model::*;
+pub use requests::*;
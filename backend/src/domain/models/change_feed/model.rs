@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+
+use crate::{domain::models::post::Post, ids::PostId};
+
+/// The latest change to a post, numbered in the order changes were
+/// committed. Only the latest change of each post is kept, so a post changed
+/// twice since a cursor shows up once.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeedChange {
+    /// The post was created, changed or restored, and is now as in `post`.
+    Upsert { sequence: i64, post: Post },
+    /// The post was deleted or moved to the trash.
+    Tombstone {
+        sequence: i64,
+        post_id: PostId,
+        deleted_at: DateTime<Utc>,
+    },
+}
+
+impl FeedChange {
+    pub fn sequence(&self) -> i64 {
+        match self {
+            Self::Upsert { sequence, .. } | Self::Tombstone { sequence, .. } => *sequence,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeFeedPage {
+    changes: Vec<FeedChange>,
+    next_cursor: i64,
+    has_more: bool,
+}
+
+impl ChangeFeedPage {
+    /// The page continuing after `since`. The cursor for the next page is the
+    /// number of the last change, or `since` again if there was none.
+    pub fn new(changes: Vec<FeedChange>, since: i64, has_more: bool) -> Self {
+        let next_cursor = changes.last().map_or(since, FeedChange::sequence);
+
+        Self {
+            changes,
+            next_cursor,
+            has_more,
+        }
+    }
+
+    pub fn changes(&self) -> &[FeedChange] {
+        &self.changes
+    }
+
+    pub fn into_changes(self) -> Vec<FeedChange> {
+        self.changes
+    }
+
+    pub fn next_cursor(&self) -> i64 {
+        self.next_cursor
+    }
+
+    /// Whether more changes follow right away; otherwise the feed is caught
+    /// up until posts change again.
+    pub fn has_more(&self) -> bool {
+        self.has_more
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_cursor_is_the_last_change_or_since() {
+        let post_id = PostId::new();
+        let tombstone = FeedChange::Tombstone {
+            sequence: 7,
+            post_id,
+            deleted_at: Utc::now(),
+        };
+
+        let page = ChangeFeedPage::new(vec![tombstone], 3, false);
+        let caught_up = ChangeFeedPage::new(Vec::new(), 7, false);
+
+        assert_eq!(page.next_cursor(), 7);
+        assert_eq!(caught_up.next_cursor(), 7);
+        assert!(caught_up.changes().is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+pub const CHANGE_FEED_PAGE_SIZE: i64 = 100;
+
+pub const MAX_CHANGE_FEED_PAGE_SIZE: i64 = 1000;
+
+/// A page of the change feed, continuing after the change numbered `since`,
+/// or from the start without it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeFeedQuery {
+    since: Option<i64>,
+    limit: i64,
+}
+
+impl ChangeFeedQuery {
+    pub fn new() -> Self {
+        Self {
+            since: None,
+            limit: CHANGE_FEED_PAGE_SIZE,
+        }
+    }
+
+    /// Continues from the cursor returned with a previous page.
+    pub fn with_since(mut self, since: Option<i64>) -> Self {
+        self.since = since;
+        self
+    }
+
+    /// Clamped to between one and [`MAX_CHANGE_FEED_PAGE_SIZE`].
+    pub fn with_limit(mut self, limit: Option<i64>) -> Self {
+        self.limit = limit
+            .unwrap_or(CHANGE_FEED_PAGE_SIZE)
+            .clamp(1, MAX_CHANGE_FEED_PAGE_SIZE);
+        self
+    }
+
+    pub fn since(&self) -> Option<i64> {
+        self.since
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+}
+
+impl Default for ChangeFeedQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
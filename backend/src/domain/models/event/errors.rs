@@ -0,0 +1,5 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown post change {0:?}, expected one of post.created, post.updated, post.deleted")]
+pub struct PostChangeKindInvalidError(pub String);
@@ -0,0 +1,171 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Utc};
+
+use crate::{domain::models::post::Post, ids::PostId};
+
+use super::errors::PostChangeKindInvalidError;
+
+/// Identifies an event by the number of its change in the change feed, so
+/// ids only grow, are the same on every instance and survive restarts. A
+/// subscriber resumes after the last id it saw.
+pub type EventId = i64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PostChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl PostChangeKind {
+    pub const ALL: [PostChangeKind; 3] = [
+        PostChangeKind::Created,
+        PostChangeKind::Updated,
+        PostChangeKind::Deleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "post.created",
+            Self::Updated => "post.updated",
+            Self::Deleted => "post.deleted",
+        }
+    }
+
+    /// How a write looks to readers, given whether they could see the post
+    /// before and after it. Restoring from the trash creates a post for
+    /// them, trashing deletes it, and changes to trashed posts are unseen.
+    pub fn between(visible_before: bool, visible_after: bool) -> Option<Self> {
+        match (visible_before, visible_after) {
+            (false, true) => Some(Self::Created),
+            (true, true) => Some(Self::Updated),
+            (true, false) => Some(Self::Deleted),
+            (false, false) => None,
+        }
+    }
+}
+
+impl FromStr for PostChangeKind {
+    type Err = PostChangeKindInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| PostChangeKindInvalidError(s.to_string()))
+    }
+}
+
+impl Display for PostChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostChange {
+    /// Created, or restored from the trash.
+    Created(Post),
+    Updated(Post),
+    /// Moved to the trash or deleted for good.
+    Deleted(PostId),
+}
+
+impl PostChange {
+    pub fn kind(&self) -> PostChangeKind {
+        match self {
+            Self::Created(_) => PostChangeKind::Created,
+            Self::Updated(_) => PostChangeKind::Updated,
+            Self::Deleted(_) => PostChangeKind::Deleted,
+        }
+    }
+
+    pub fn post_id(&self) -> PostId {
+        match self {
+            Self::Created(post) | Self::Updated(post) => post.id(),
+            Self::Deleted(post_id) => *post_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostEvent {
+    id: EventId,
+    change: PostChange,
+    occurred_at: DateTime<Utc>,
+}
+
+impl PostEvent {
+    pub fn new(id: EventId, change: PostChange, occurred_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            change,
+            occurred_at,
+        }
+    }
+
+    pub fn id(&self) -> EventId {
+        self.id
+    }
+
+    pub fn change(&self) -> &PostChange {
+        &self.change
+    }
+
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        self.occurred_at
+    }
+}
+
+/// What a stream of post events carries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamEvent {
+    Post(PostEvent),
+    /// Some events after the id the subscriber resumed from are forgotten.
+    /// It should drop what it built from earlier events and read the posts
+    /// again; the stream goes on from the id given.
+    Reset(EventId),
+}
+
+/// What watching the changes to posts yields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostChangeSignal {
+    Notice(PostChangeNotice),
+    /// Notices may have been missed since the last one, while the database
+    /// connection was lost or the watcher fell behind. The changes since are
+    /// in the change feed.
+    Missed,
+}
+
+/// A committed change to a post, by any instance, which only carries what is
+/// needed to look the post up again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PostChangeNotice {
+    sequence: EventId,
+    kind: PostChangeKind,
+    post_id: PostId,
+}
+
+impl PostChangeNotice {
+    pub fn new(sequence: EventId, kind: PostChangeKind, post_id: PostId) -> Self {
+        Self {
+            sequence,
+            kind,
+            post_id,
+        }
+    }
+
+    /// The number of the change in the change feed.
+    pub fn sequence(&self) -> EventId {
+        self.sequence
+    }
+
+    pub fn kind(&self) -> PostChangeKind {
+        self.kind
+    }
+
+    pub fn post_id(&self) -> PostId {
+        self.post_id
+    }
+}
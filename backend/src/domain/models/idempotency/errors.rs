@@ -0,0 +1,5 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("Idempotency key must be 1 to 255 visible ASCII characters")]
+pub struct IdempotencyKeyInvalidError;
@@ -0,0 +1,139 @@
+use sha2::{Digest, Sha256};
+
+use super::errors::IdempotencyKeyInvalidError;
+
+const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+
+/// Chosen by the client for a request it may retry. Every retry carries the
+/// same key, so the request takes effect only once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn try_new(input: &str) -> Result<Self, IdempotencyKeyInvalidError> {
+        let valid = (1..=IDEMPOTENCY_KEY_MAX_LEN).contains(&input.len())
+            && input.bytes().all(|byte| byte.is_ascii_graphic());
+
+        if valid {
+            Ok(Self(input.to_string()))
+        } else {
+            Err(IdempotencyKeyInvalidError)
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// SHA-256 of what a request asks for, to tell a retry from a different
+/// request that reuses its key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestFingerprint(Vec<u8>);
+
+impl RequestFingerprint {
+    /// Each part is prefixed with its length, so moving bytes from one part
+    /// to the next changes the fingerprint.
+    pub fn new(method: &str, path: &str, body: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        for part in [method.as_bytes(), path.as_bytes(), body] {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        Self(hasher.finalize().to_vec())
+    }
+
+    pub fn from_hash(hash: Vec<u8>) -> Self {
+        Self(hash)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The response to a request, kept to be replayed to its retries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotentResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl IdempotentResponse {
+    pub fn new(status: u16, headers: Vec<(String, String)>, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+/// A request made earlier with the same key. It has no response while it is
+/// still being handled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    fingerprint: RequestFingerprint,
+    response: Option<IdempotentResponse>,
+}
+
+impl IdempotencyRecord {
+    pub fn new(fingerprint: RequestFingerprint, response: Option<IdempotentResponse>) -> Self {
+        Self {
+            fingerprint,
+            response,
+        }
+    }
+
+    pub fn fingerprint(&self) -> &RequestFingerprint {
+        &self.fingerprint
+    }
+
+    pub fn response(&self) -> Option<&IdempotentResponse> {
+        self.response.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_must_be_visible_ascii() {
+        assert!(IdempotencyKey::try_new("2f1c9a7e-publish-42").is_ok());
+        assert!(IdempotencyKey::try_new("").is_err());
+        assert!(IdempotencyKey::try_new("with space").is_err());
+        assert!(IdempotencyKey::try_new(&"k".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_separates_parts() {
+        let fingerprint = RequestFingerprint::new("POST", "/posts", b"{}");
+
+        assert_eq!(
+            fingerprint,
+            RequestFingerprint::new("POST", "/posts", b"{}")
+        );
+        assert_ne!(
+            fingerprint,
+            RequestFingerprint::new("POST", "/posts{", b"}")
+        );
+        assert_ne!(
+            fingerprint,
+            RequestFingerprint::new("POST", "/authors", b"{}")
+        );
+    }
+}
@@ -1 +1,12 @@
+pub mod api_token;
+pub mod audit;
+pub mod author;
+pub mod change_feed;
+pub mod event;
+pub mod idempotency;
+pub mod oidc;
+pub mod outbox;
 pub mod post;
+pub mod totp;
+pub mod user;
+pub mod webhook;
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{event::PostChangeKind, post::Post},
+    ids::PostId,
+};
+
+/// A committed change to a post, as seen by readers. Created and updated
+/// posts carry the post as it is after the change.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DomainEvent {
+    PostCreated { post_id: PostId, post: Post },
+    PostUpdated { post_id: PostId, post: Post },
+    PostDeleted { post_id: PostId },
+}
+
+impl DomainEvent {
+    /// The event for a change of `kind` leaving the post as in `post`.
+    pub fn new(kind: PostChangeKind, post_id: PostId, post: Option<Post>) -> Self {
+        match (kind, post) {
+            (PostChangeKind::Created, Some(post)) => Self::PostCreated { post_id, post },
+            (PostChangeKind::Updated, Some(post)) => Self::PostUpdated { post_id, post },
+            _ => Self::PostDeleted { post_id },
+        }
+    }
+
+    pub fn kind(&self) -> PostChangeKind {
+        match self {
+            Self::PostCreated { .. } => PostChangeKind::Created,
+            Self::PostUpdated { .. } => PostChangeKind::Updated,
+            Self::PostDeleted { .. } => PostChangeKind::Deleted,
+        }
+    }
+
+    /// The post the event belongs to; events of one post are relayed in the
+    /// order they were committed.
+    pub fn aggregate_id(&self) -> PostId {
+        match self {
+            Self::PostCreated { post_id, .. }
+            | Self::PostUpdated { post_id, .. }
+            | Self::PostDeleted { post_id } => *post_id,
+        }
+    }
+
+    pub fn post(&self) -> Option<&Post> {
+        match self {
+            Self::PostCreated { post, .. } | Self::PostUpdated { post, .. } => Some(post),
+            Self::PostDeleted { .. } => None,
+        }
+    }
+}
+
+/// A domain event waiting in the outbox to be relayed. `sequence` orders the
+/// events of a post; `id` stays the same should it be relayed more than
+/// once, so consumers can drop duplicates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutboxEvent {
+    sequence: i64,
+    id: Uuid,
+    event: DomainEvent,
+    occurred_at: DateTime<Utc>,
+}
+
+impl OutboxEvent {
+    pub fn new(sequence: i64, id: Uuid, event: DomainEvent, occurred_at: DateTime<Utc>) -> Self {
+        Self {
+            sequence,
+            id,
+            event,
+            occurred_at,
+        }
+    }
+
+    pub fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn event(&self) -> &DomainEvent {
+        &self.event
+    }
+
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        self.occurred_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::post::{PostBody, PostTitle};
+
+    use super::*;
+
+    #[test]
+    fn test_deleted_posts_carry_no_snapshot() {
+        let post_id = PostId::new();
+        let post = Post::new(
+            post_id,
+            PostTitle::new("Title"),
+            PostBody::new("Body"),
+            Utc::now(),
+        );
+
+        let created = DomainEvent::new(PostChangeKind::Created, post_id, Some(post.clone()));
+        let deleted = DomainEvent::new(PostChangeKind::Deleted, post_id, Some(post.clone()));
+
+        assert_eq!(created.kind(), PostChangeKind::Created);
+        assert_eq!(created.post(), Some(&post));
+        assert_eq!(deleted, DomainEvent::PostDeleted { post_id });
+        assert_eq!(deleted.aggregate_id(), post_id);
+    }
+}
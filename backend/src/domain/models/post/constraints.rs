@@ -0,0 +1,168 @@
+use unicode_normalization::UnicodeNormalization;
+
+use super::{
+    errors::{PostBodyError, PostTitleError},
+    model::{PostBody, PostTitle},
+};
+
+pub const DEFAULT_TITLE_MIN_CHARS: usize = 1;
+
+pub const DEFAULT_TITLE_MAX_CHARS: usize = 200;
+
+pub const DEFAULT_BODY_MAX_BYTES: usize = 1024 * 1024;
+
+/// What authors may submit as a post. Titles and bodies are put in Unicode
+/// normalization form C first, and titles are trimmed, so the limits apply to
+/// what is stored and visually identical titles are equal. Bodies keep their
+/// whitespace, which is significant in Markdown, e.g. indented code blocks
+/// and trailing double spaces for line breaks.
+///
+/// Only new input is held to these; imported and restored posts are not, so
+/// tightening a limit never locks out existing posts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostConstraints {
+    title_min_chars: usize,
+    title_max_chars: usize,
+    body_max_bytes: usize,
+}
+
+impl PostConstraints {
+    pub fn new() -> Self {
+        Self {
+            title_min_chars: DEFAULT_TITLE_MIN_CHARS,
+            title_max_chars: DEFAULT_TITLE_MAX_CHARS,
+            body_max_bytes: DEFAULT_BODY_MAX_BYTES,
+        }
+    }
+
+    /// Bounds on the length of titles in characters, not bytes. `min_chars`
+    /// must not exceed `max_chars`.
+    pub fn with_title_length(mut self, min_chars: usize, max_chars: usize) -> Self {
+        self.title_min_chars = min_chars;
+        self.title_max_chars = max_chars;
+        self
+    }
+
+    pub fn with_body_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.body_max_bytes = max_bytes;
+        self
+    }
+
+    pub fn body_max_bytes(&self) -> usize {
+        self.body_max_bytes
+    }
+
+    /// Titles are a single line: no control characters at all.
+    pub fn title(&self, raw: &str) -> Result<PostTitle, PostTitleError> {
+        let title = normalize(raw.trim());
+        let chars = title.chars().count();
+
+        if title.is_empty() {
+            Err(PostTitleError::Empty)
+        } else if title.chars().any(char::is_control) {
+            Err(PostTitleError::ControlCharacter)
+        } else if chars < self.title_min_chars {
+            Err(PostTitleError::TooShort {
+                min: self.title_min_chars,
+            })
+        } else if chars > self.title_max_chars {
+            Err(PostTitleError::TooLong {
+                max: self.title_max_chars,
+            })
+        } else {
+            Ok(PostTitle::new(&title))
+        }
+    }
+
+    pub fn body(&self, raw: &str) -> Result<PostBody, PostBodyError> {
+        let body = normalize(raw);
+
+        if body.trim().is_empty() {
+            Err(PostBodyError::Empty)
+        } else if body
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+        {
+            Err(PostBodyError::ControlCharacter)
+        } else if body.len() > self.body_max_bytes {
+            Err(PostBodyError::TooLarge {
+                max_bytes: self.body_max_bytes,
+            })
+        } else {
+            Ok(PostBody::new(&body))
+        }
+    }
+}
+
+impl Default for PostConstraints {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalize(raw: &str) -> String {
+    raw.nfc().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_is_normalized() {
+        // "é" as "e" followed by a combining acute accent
+        let title = PostConstraints::new().title("  Cafe\u{301}  ").unwrap();
+
+        assert_eq!(title.to_string(), "Caf\u{e9}");
+    }
+
+    #[test]
+    fn test_body_keeps_its_whitespace() {
+        let constraints = PostConstraints::new();
+        let markdown = "    let indented = true;\n\nLine  \nbreak\n";
+
+        assert_eq!(constraints.body(markdown).unwrap().to_string(), markdown);
+        assert_eq!(constraints.body(" \n\t"), Err(PostBodyError::Empty));
+    }
+
+    #[test]
+    fn test_title_length_counts_characters() {
+        let constraints = PostConstraints::new().with_title_length(3, 4);
+
+        assert!(constraints.title("ÄÖÜß").is_ok());
+        assert_eq!(
+            constraints.title("Ab"),
+            Err(PostTitleError::TooShort { min: 3 })
+        );
+        assert_eq!(
+            constraints.title("Abcde"),
+            Err(PostTitleError::TooLong { max: 4 })
+        );
+    }
+
+    #[test]
+    fn test_control_characters_are_rejected() {
+        let constraints = PostConstraints::new();
+
+        assert_eq!(
+            constraints.title("Two\nlines"),
+            Err(PostTitleError::ControlCharacter)
+        );
+        assert!(constraints.body("Two\r\nlines\tand a tab").is_ok());
+        assert_eq!(
+            constraints.body("Bell\u{7}"),
+            Err(PostBodyError::ControlCharacter)
+        );
+    }
+
+    #[test]
+    fn test_body_size_is_limited_in_bytes() {
+        let constraints = PostConstraints::new().with_body_max_bytes(4);
+
+        assert!(constraints.body("abcd").is_ok());
+        assert_eq!(
+            constraints.body("ab€"),
+            Err(PostBodyError::TooLarge { max_bytes: 4 })
+        );
+    }
+}
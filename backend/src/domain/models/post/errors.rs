@@ -7,3 +7,35 @@ pub struct PostTitleEmptyError;
 #[derive(Clone, Debug, Error)]
 #[error("Blog post body cannot be empty")]
 pub struct PostBodyEmptyError;
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Not a cursor returned with a previous page of posts")]
+pub struct PostCursorError;
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown term kind {0:?}, expected category or tag")]
+pub struct TermKindInvalidError(pub String);
+
+/// Why a submitted title breaks the [`PostConstraints`](super::PostConstraints).
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum PostTitleError {
+    #[error("Blog post title cannot be empty")]
+    Empty,
+    #[error("Blog post title must be at least {min} characters")]
+    TooShort { min: usize },
+    #[error("Blog post title must be at most {max} characters")]
+    TooLong { max: usize },
+    #[error("Blog post title cannot contain control characters")]
+    ControlCharacter,
+}
+
+/// Why a submitted body breaks the [`PostConstraints`](super::PostConstraints).
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum PostBodyError {
+    #[error("Blog post body cannot be empty")]
+    Empty,
+    #[error("Blog post body must be at most {max_bytes} bytes")]
+    TooLarge { max_bytes: usize },
+    #[error("Blog post body cannot contain control characters other than tabs and line breaks")]
+    ControlCharacter,
+}
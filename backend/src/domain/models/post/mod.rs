@@ -1,7 +1,11 @@
+pub mod constraints;
 pub mod errors;
 pub mod model;
 pub mod requests;
+pub mod taxonomy;
 
+pub use constraints::*;
 pub use errors::*;
 pub use model::*;
 pub use requests::*;
+pub use taxonomy::*;
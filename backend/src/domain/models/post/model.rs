@@ -1,10 +1,13 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use chrono::{DateTime, Utc};
 
-use crate::ids::PostId;
+use crate::ids::{PostId, UserId};
 
-use super::errors::{PostBodyEmptyError, PostTitleEmptyError};
+use super::super::author::Author;
+
+use super::errors::{PostBodyEmptyError, PostCursorError, PostTitleEmptyError};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Post {
@@ -12,6 +15,11 @@ pub struct Post {
     title: PostTitle,
     body: PostBody,
     created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    created_by: Option<UserId>,
+    deleted_at: Option<DateTime<Utc>>,
+    version: i64,
+    authors: Vec<Author>,
 }
 
 impl Post {
@@ -21,9 +29,39 @@ impl Post {
             title,
             body,
             created_at,
+            updated_at: created_at,
+            created_by: None,
+            deleted_at: None,
+            version: 1,
+            authors: Vec::new(),
         }
     }
 
+    pub fn with_authors(mut self, authors: Vec<Author>) -> Self {
+        self.authors = authors;
+        self
+    }
+
+    pub fn with_updated_at(mut self, updated_at: DateTime<Utc>) -> Self {
+        self.updated_at = updated_at;
+        self
+    }
+
+    pub fn with_created_by(mut self, created_by: Option<UserId>) -> Self {
+        self.created_by = created_by;
+        self
+    }
+
+    pub fn with_deleted_at(mut self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        self.deleted_at = deleted_at;
+        self
+    }
+
+    pub fn with_version(mut self, version: i64) -> Self {
+        self.version = version;
+        self
+    }
+
     pub fn id(&self) -> PostId {
         self.id
     }
@@ -39,6 +77,33 @@ impl Post {
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
+
+    /// When the post last changed, including moves in and out of the trash.
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    /// The user who wrote the post. `None` for imported posts and posts whose
+    /// author account was deleted.
+    pub fn created_by(&self) -> Option<UserId> {
+        self.created_by
+    }
+
+    /// When the post was moved to the trash, if it is there.
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    /// Starts at one and goes up with every change, so a client can tell
+    /// whether the post changed since it read it.
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+
+    /// Co-authors in byline order.
+    pub fn authors(&self) -> &[Author] {
+        &self.authors
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -64,8 +129,14 @@ impl Display for PostTitle {
 pub struct PostBody(String);
 
 impl PostBody {
+    /// Unlike titles, bodies are not trimmed: leading and trailing whitespace
+    /// is significant in Markdown.
     pub fn try_new(raw: &str) -> Result<Self, PostBodyEmptyError> {
-        validate_non_empty(raw).map(Self).ok_or(PostBodyEmptyError)
+        if raw.trim().is_empty() {
+            return Err(PostBodyEmptyError);
+        }
+
+        Ok(Self(raw.to_string()))
     }
 
     pub fn new(input: &str) -> Self {
@@ -88,6 +159,89 @@ fn validate_non_empty(input: &str) -> Option<String> {
     }
 }
 
+/// Where a page of posts, oldest first, left off. Opaque to clients, and
+/// valid even after the post it was taken from is deleted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PostCursor {
+    created_at: DateTime<Utc>,
+    id: PostId,
+}
+
+impl PostCursor {
+    /// Continues after the post created at `created_at` with id `id`.
+    pub fn new(created_at: DateTime<Utc>, id: PostId) -> Self {
+        Self { created_at, id }
+    }
+
+    /// Continues after `post`.
+    pub fn after(post: &Post) -> Self {
+        Self::new(post.created_at(), post.id())
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn id(&self) -> PostId {
+        self.id
+    }
+}
+
+/// Postgres keeps microseconds, so they are all the cursor needs.
+impl Display for PostCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let raw = format!("{}.{}", self.created_at.timestamp_micros(), self.id);
+        f.write_str(&URL_SAFE_NO_PAD.encode(raw))
+    }
+}
+
+impl FromStr for PostCursor {
+    type Err = PostCursorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = URL_SAFE_NO_PAD.decode(s).map_err(|_| PostCursorError)?;
+        let raw = String::from_utf8(raw).map_err(|_| PostCursorError)?;
+        let (micros, id) = raw.split_once('.').ok_or(PostCursorError)?;
+
+        Ok(Self {
+            created_at: micros
+                .parse()
+                .ok()
+                .and_then(DateTime::from_timestamp_micros)
+                .ok_or(PostCursorError)?,
+            id: id.parse().map_err(|_| PostCursorError)?,
+        })
+    }
+}
+
+/// Posts oldest first, with the cursor of the next page if there is one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostPage {
+    posts: Vec<Post>,
+    next_cursor: Option<PostCursor>,
+}
+
+impl PostPage {
+    /// The page of `posts`, continued after the last of them if `has_more`.
+    pub fn new(posts: Vec<Post>, has_more: bool) -> Self {
+        let next_cursor = posts.last().filter(|_| has_more).map(PostCursor::after);
+
+        Self { posts, next_cursor }
+    }
+
+    pub fn posts(&self) -> &[Post] {
+        &self.posts
+    }
+
+    pub fn into_posts(self) -> Vec<Post> {
+        self.posts
+    }
+
+    pub fn next_cursor(&self) -> Option<PostCursor> {
+        self.next_cursor
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +310,16 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_post_body_keeps_surrounding_whitespace() {
+        let body = "    indented code\n";
+
+        let post_body = PostBody::try_new(body).unwrap();
+
+        assert_eq!(post_body.to_string(), body);
+        assert!(PostBody::try_new(" \n ").is_err());
+    }
+
     #[test]
     fn test_validate_non_empty_function() {
         assert_eq!(validate_non_empty("test"), Some("test".to_string()));
@@ -163,4 +327,23 @@ mod tests {
         assert_eq!(validate_non_empty(""), None);
         assert_eq!(validate_non_empty("   "), None);
     }
+
+    #[test]
+    fn test_post_cursor_round_trips() {
+        // Given the cursor after a post
+        let post = Post::new(
+            PostId::new(),
+            PostTitle::try_new("Title").unwrap(),
+            PostBody::try_new("Body").unwrap(),
+            DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap(),
+        );
+        let cursor = PostCursor::after(&post);
+
+        // When it is written out and read back
+        let parsed = cursor.to_string().parse::<PostCursor>();
+
+        // Then it is the same cursor, and garbage is not a cursor
+        assert_eq!(parsed.unwrap(), cursor);
+        assert!("not a cursor".parse::<PostCursor>().is_err());
+    }
 }
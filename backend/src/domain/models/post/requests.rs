@@ -1,15 +1,33 @@
-use super::model::{PostBody, PostTitle};
+use crate::ids::AuthorId;
+
+use super::{
+    model::{Post, PostBody, PostCursor, PostTitle},
+    taxonomy::{PostComment, PostTerm},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CreatePostRequest {
     title: PostTitle,
     body: PostBody,
+    authors: Option<Vec<AuthorId>>,
 }
 
 impl CreatePostRequest {
     pub fn new(title: PostTitle, body: PostBody) -> Self {
-        Self { title, body }
+        Self {
+            title,
+            body,
+            authors: None,
+        }
+    }
+
+    /// Byline in order. Without it the post is credited to the author profile
+    /// of the user creating it, if they have one.
+    pub fn with_authors(mut self, authors: Option<Vec<AuthorId>>) -> Self {
+        self.authors = authors;
+        self
     }
+
     pub fn title(&self) -> PostTitle {
         self.title.clone()
     }
@@ -17,17 +35,32 @@ impl CreatePostRequest {
     pub fn body(&self) -> PostBody {
         self.body.clone()
     }
+
+    pub fn authors(&self) -> Option<&[AuthorId]> {
+        self.authors.as_deref()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct UpdatePostRequest {
     title: Option<PostTitle>,
     body: Option<PostBody>,
+    authors: Option<Vec<AuthorId>>,
 }
 
 impl UpdatePostRequest {
     pub fn new(title: Option<PostTitle>, body: Option<PostBody>) -> Self {
-        Self { title, body }
+        Self {
+            title,
+            body,
+            authors: None,
+        }
+    }
+
+    /// Replaces the byline when set.
+    pub fn with_authors(mut self, authors: Option<Vec<AuthorId>>) -> Self {
+        self.authors = authors;
+        self
     }
 
     pub fn title(&self) -> Option<PostTitle> {
@@ -37,4 +70,96 @@ impl UpdatePostRequest {
     pub fn body(&self) -> Option<PostBody> {
         self.body.clone()
     }
+
+    pub fn authors(&self) -> Option<&[AuthorId]> {
+        self.authors.as_deref()
+    }
+}
+
+/// A post brought over from another blog or back from a backup, with what it
+/// was filed under and the comments it collected.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ImportPostRequest {
+    post: Post,
+    terms: Vec<PostTerm>,
+    comments: Vec<PostComment>,
+}
+
+impl ImportPostRequest {
+    pub fn new(post: Post) -> Self {
+        Self {
+            post,
+            terms: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    pub fn with_terms(mut self, terms: Vec<PostTerm>) -> Self {
+        self.terms = terms;
+        self
+    }
+
+    /// Replies may come before the comments they answer.
+    pub fn with_comments(mut self, comments: Vec<PostComment>) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    pub fn post(&self) -> &Post {
+        &self.post
+    }
+
+    pub fn terms(&self) -> &[PostTerm] {
+        &self.terms
+    }
+
+    pub fn comments(&self) -> &[PostComment] {
+        &self.comments
+    }
+}
+
+pub const POST_PAGE_SIZE: i64 = 100;
+
+pub const MAX_POST_PAGE_SIZE: i64 = 1000;
+
+/// A page of posts, oldest first, continuing after the cursor returned with
+/// the previous page, or from the start without it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PostPageQuery {
+    after: Option<PostCursor>,
+    limit: i64,
+}
+
+impl PostPageQuery {
+    pub fn new() -> Self {
+        Self {
+            after: None,
+            limit: POST_PAGE_SIZE,
+        }
+    }
+
+    pub fn with_after(mut self, after: Option<PostCursor>) -> Self {
+        self.after = after;
+        self
+    }
+
+    /// Clamped to between one and [`MAX_POST_PAGE_SIZE`].
+    pub fn with_limit(mut self, limit: Option<i64>) -> Self {
+        self.limit = limit.unwrap_or(POST_PAGE_SIZE).clamp(1, MAX_POST_PAGE_SIZE);
+        self
+    }
+
+    pub fn after(&self) -> Option<PostCursor> {
+        self.after
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+}
+
+impl Default for PostPageQuery {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::ids::CommentId;
+
+use super::errors::TermKindInvalidError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TermKind {
+    Category,
+    Tag,
+}
+
+impl TermKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Category => "category",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+impl FromStr for TermKind {
+    type Err = TermKindInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Self::Category, Self::Tag]
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| TermKindInvalidError(s.to_string()))
+    }
+}
+
+/// A category or tag a post is filed under.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PostTerm {
+    kind: TermKind,
+    name: String,
+    slug: Option<String>,
+}
+
+impl PostTerm {
+    pub fn new(kind: TermKind, name: &str, slug: Option<&str>) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            slug: slug.map(str::to_string),
+        }
+    }
+
+    pub fn kind(&self) -> TermKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
+}
+
+/// A reader's comment on a post, possibly in reply to another comment on the
+/// same post.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PostComment {
+    id: CommentId,
+    parent_id: Option<CommentId>,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    author_url: Option<String>,
+    body: String,
+    approved: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl PostComment {
+    pub fn new(id: CommentId, body: &str, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            parent_id: None,
+            author_name: None,
+            author_email: None,
+            author_url: None,
+            body: body.to_string(),
+            approved: false,
+            created_at,
+        }
+    }
+
+    pub fn with_parent_id(mut self, parent_id: Option<CommentId>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    pub fn with_author(
+        mut self,
+        name: Option<String>,
+        email: Option<String>,
+        url: Option<String>,
+    ) -> Self {
+        self.author_name = name;
+        self.author_email = email;
+        self.author_url = url;
+        self
+    }
+
+    pub fn with_approved(mut self, approved: bool) -> Self {
+        self.approved = approved;
+        self
+    }
+
+    pub fn id(&self) -> CommentId {
+        self.id
+    }
+
+    pub fn parent_id(&self) -> Option<CommentId> {
+        self.parent_id
+    }
+
+    pub fn author_name(&self) -> Option<&str> {
+        self.author_name.as_deref()
+    }
+
+    pub fn author_email(&self) -> Option<&str> {
+        self.author_email.as_deref()
+    }
+
+    pub fn author_url(&self) -> Option<&str> {
+        self.author_url.as_deref()
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Unapproved comments are kept, like WordPress keeps them, but are not
+    /// meant to be shown.
+    pub fn approved(&self) -> bool {
+        self.approved
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
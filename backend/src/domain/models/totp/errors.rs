@@ -0,0 +1,5 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("One-time code must be {digits} digits", digits = super::model::TOTP_DIGITS)]
+pub struct TotpCodeInvalidError;
@@ -0,0 +1,5 @@
+pub mod errors;
+pub mod model;
+
+pub use errors::*;
+pub use model::*;
@@ -0,0 +1,357 @@
+use std::fmt::{Debug, Display};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use super::super::user::UserEmail;
+use super::errors::TotpCodeInvalidError;
+
+/// Shown next to the account name in authenticator apps.
+pub const TOTP_ISSUER: &str = "Tommy's Blog";
+
+pub const TOTP_DIGITS: u32 = 6;
+
+/// Length of a time step in seconds (RFC 6238 default).
+pub const TOTP_PERIOD: i64 = 30;
+
+/// Codes from this many steps before or after the current one are accepted,
+/// to allow for clock drift between server and phone.
+const TOTP_SKEW: i64 = 1;
+
+/// 160 bits, the HMAC-SHA1 block recommended by RFC 4226.
+const TOTP_SECRET_BYTES: usize = 20;
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Failed attempts at a second factor after which a user is locked out. With
+/// a million six-digit codes, guessing one stays hopeless.
+pub const MAX_SECOND_FACTOR_ATTEMPTS: u32 = 5;
+
+/// How long a user who used up their attempts has to wait, in minutes.
+pub const SECOND_FACTOR_LOCKOUT_MINUTES: i64 = 15;
+
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 4648 base32 without padding, the encoding authenticator apps expect.
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[usize::from((buffer >> bits) & 0x1f)] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)] as char);
+    }
+
+    encoded
+}
+
+/// Shared secret of an authenticator app. Stored as is, since the server
+/// needs it to compute the expected codes.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpSecret(Vec<u8>);
+
+impl TotpSecret {
+    pub fn generate() -> Self {
+        let mut bytes = vec![0u8; TOTP_SECRET_BYTES];
+        rand::rng().fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn new(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The secret as users type it into an authenticator app.
+    pub fn to_base32(&self) -> String {
+        base32_encode(&self.0)
+    }
+
+    /// The code for time step `step` (RFC 6238 with HMAC-SHA1).
+    pub fn code_at(&self, step: i64) -> TotpCode {
+        let mut mac =
+            Hmac::<Sha1>::new_from_slice(&self.0).expect("HMAC accepts keys of any length");
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        // Dynamic truncation (RFC 4226, section 5.3).
+        let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+        let binary = u32::from_be_bytes([
+            digest[offset],
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]) & 0x7fff_ffff;
+
+        TotpCode(format!(
+            "{:0width$}",
+            binary % 10u32.pow(TOTP_DIGITS),
+            width = TOTP_DIGITS as usize
+        ))
+    }
+
+    /// Returns the time step `code` belongs to if it is valid at `now`. The
+    /// caller must reject steps that were already used.
+    pub fn verify(&self, code: &TotpCode, now: DateTime<Utc>) -> Option<i64> {
+        let current = now.timestamp().div_euclid(TOTP_PERIOD);
+
+        (current - TOTP_SKEW..=current + TOTP_SKEW)
+            .find(|&step| constant_time_eq(self.code_at(step).as_str(), code.as_str()))
+    }
+}
+
+impl Debug for TotpSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpSecret(***)")
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpCode(String);
+
+impl TotpCode {
+    pub fn try_new(raw: &str) -> Result<Self, TotpCodeInvalidError> {
+        let code: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if code.len() == TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+            Ok(Self(code))
+        } else {
+            Err(TotpCodeInvalidError)
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for TotpCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpCode(***)")
+    }
+}
+
+/// A user's authenticator. It only counts as a second factor once confirmed
+/// with a code, which proves the app was set up correctly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Totp {
+    secret: TotpSecret,
+    confirmed_at: Option<DateTime<Utc>>,
+}
+
+impl Totp {
+    pub fn new(secret: TotpSecret, confirmed_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            secret,
+            confirmed_at,
+        }
+    }
+
+    pub fn secret(&self) -> &TotpSecret {
+        &self.secret
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+/// A freshly generated secret, returned once so it can be added to an
+/// authenticator app.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TotpEnrollment {
+    secret: TotpSecret,
+    otpauth_uri: String,
+}
+
+impl TotpEnrollment {
+    pub fn new(secret: TotpSecret, account: &UserEmail) -> Self {
+        let mut uri = Url::parse("otpauth://totp/").expect("Static URL is valid");
+        uri.set_path(&format!("{TOTP_ISSUER}:{account}"));
+        uri.query_pairs_mut()
+            .append_pair("secret", &secret.to_base32())
+            .append_pair("issuer", TOTP_ISSUER)
+            .append_pair("algorithm", "SHA1")
+            .append_pair("digits", &TOTP_DIGITS.to_string())
+            .append_pair("period", &TOTP_PERIOD.to_string());
+
+        Self {
+            secret,
+            otpauth_uri: uri.to_string(),
+        }
+    }
+
+    pub fn secret(&self) -> &TotpSecret {
+        &self.secret
+    }
+
+    /// Key URI understood by authenticator apps, usually shown as a QR code.
+    pub fn otpauth_uri(&self) -> &str {
+        &self.otpauth_uri
+    }
+}
+
+/// Single-use code for logging in without the authenticator. Only its hash
+/// is stored.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RecoveryCode(String);
+
+impl RecoveryCode {
+    /// Codes are generated in sets, replacing any earlier set.
+    pub fn generate_set() -> Vec<Self> {
+        let mut rng = rand::rng();
+
+        (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let code: String = (0..RECOVERY_CODE_LENGTH)
+                    .map(|_| BASE32_ALPHABET[rng.random_range(0..32)].to_ascii_lowercase() as char)
+                    .collect();
+                Self(code)
+            })
+            .collect()
+    }
+
+    /// Accepts codes as displayed, in any case and with or without the dash.
+    pub fn new(raw: &str) -> Self {
+        Self(
+            raw.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .map(|c| c.to_ascii_lowercase())
+                .collect(),
+        )
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Sha256::digest(self.0.as_bytes()).to_vec()
+    }
+}
+
+impl Display for RecoveryCode {
+    /// Split in two halves for readability, e.g. `abcde-fghij`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (first, second) = self.0.split_at(self.0.len() / 2);
+        write!(f, "{first}-{second}")
+    }
+}
+
+impl Debug for RecoveryCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RecoveryCode(***)")
+    }
+}
+
+/// What a user presents on top of their password.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SecondFactor {
+    Totp(TotpCode),
+    RecoveryCode(RecoveryCode),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret::new(b"12345678901234567890")
+    }
+
+    #[test]
+    fn test_codes_match_rfc_6238_vectors() {
+        // SHA1 vectors from RFC 6238 appendix B, truncated to six digits.
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ];
+
+        for (time, expected) in vectors {
+            assert_eq!(rfc_secret().code_at(time / TOTP_PERIOD).as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_verify_allows_one_step_of_drift() {
+        let secret = rfc_secret();
+        let now = Utc.timestamp_opt(1234567890, 0).unwrap();
+        let current = 1234567890 / TOTP_PERIOD;
+
+        assert_eq!(secret.verify(&secret.code_at(current), now), Some(current));
+        assert_eq!(
+            secret.verify(&secret.code_at(current - 1), now),
+            Some(current - 1)
+        );
+        assert_eq!(
+            secret.verify(&secret.code_at(current + 1), now),
+            Some(current + 1)
+        );
+        assert_eq!(secret.verify(&secret.code_at(current - 2), now), None);
+    }
+
+    #[test]
+    fn test_secret_is_base32_encoded() {
+        assert_eq!(rfc_secret().to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(TotpSecret::new(b"f").to_base32(), "MY");
+    }
+
+    #[test]
+    fn test_enrollment_uri_carries_secret_and_issuer() {
+        let account = UserEmail::new("tommy@example.com");
+        let enrollment = TotpEnrollment::new(rfc_secret(), &account);
+        let uri = Url::parse(enrollment.otpauth_uri()).unwrap();
+        let query: Vec<_> = uri.query_pairs().into_owned().collect();
+
+        assert_eq!(uri.scheme(), "otpauth");
+        assert_eq!(uri.host_str(), Some("totp"));
+        assert!(uri.path().ends_with(":tommy@example.com"));
+        assert!(query.contains(&("secret".into(), rfc_secret().to_base32())));
+        assert!(query.contains(&("issuer".into(), TOTP_ISSUER.into())));
+    }
+
+    #[test]
+    fn test_code_validation() {
+        assert!(TotpCode::try_new("123 456").is_ok());
+        assert!(TotpCode::try_new("12345").is_err());
+        assert!(TotpCode::try_new("12345a").is_err());
+    }
+
+    #[test]
+    fn test_recovery_codes_are_normalized_before_hashing() {
+        let codes = RecoveryCode::generate_set();
+        let displayed = codes[0].to_string();
+
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(
+            RecoveryCode::new(&displayed.to_uppercase()).hash(),
+            codes[0].hash()
+        );
+        assert_eq!(RecoveryCode::new(&displayed.replace('-', "")), codes[0]);
+    }
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("User email must be a valid email address")]
+pub struct UserEmailInvalidError;
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown role {0:?}, expected one of admin, editor, author, reader")]
+pub struct RoleInvalidError(pub String);
+
+#[derive(Clone, Debug, Error)]
+#[error("Password must be at least {min} characters long", min = super::model::PASSWORD_MIN_LENGTH)]
+pub struct PasswordTooShortError;
+
+#[derive(Debug, Error)]
+#[error("Failed to hash password: {0}")]
+pub struct PasswordHashError(pub argon2::password_hash::Error);
@@ -0,0 +1,340 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use argon2::{
+    Argon2,
+    password_hash::{
+        PasswordHash as ParsedPasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+        rand_core::OsRng,
+    },
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::ids::UserId;
+
+use super::errors::{
+    PasswordHashError, PasswordTooShortError, RoleInvalidError, UserEmailInvalidError,
+};
+
+pub const PASSWORD_MIN_LENGTH: usize = 12;
+
+const SESSION_TOKEN_BYTES: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct User {
+    id: UserId,
+    email: UserEmail,
+    role: Role,
+    created_at: DateTime<Utc>,
+    second_factor: bool,
+}
+
+impl User {
+    pub fn new(id: UserId, email: UserEmail, role: Role, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            email,
+            role,
+            created_at,
+            second_factor: false,
+        }
+    }
+
+    /// Whether the user has a confirmed authenticator app.
+    pub fn with_second_factor(mut self, second_factor: bool) -> Self {
+        self.second_factor = second_factor;
+        self
+    }
+
+    pub fn id(&self) -> UserId {
+        self.id
+    }
+
+    pub fn email(&self) -> UserEmail {
+        self.email.clone()
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn has_second_factor(&self) -> bool {
+        self.second_factor
+    }
+}
+
+/// What a user may do is decided by their role, see [`crate::domain::policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    Admin,
+    Editor,
+    Author,
+    #[default]
+    Reader,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::Editor, Role::Author, Role::Reader];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Editor => "editor",
+            Self::Author => "author",
+            Self::Reader => "reader",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = RoleInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| RoleInvalidError(s.to_string()))
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UserEmail(String);
+
+impl UserEmail {
+    pub fn try_new(raw: &str) -> Result<Self, UserEmailInvalidError> {
+        let email = raw.trim().to_lowercase();
+
+        match email.split_once('@') {
+            Some((local, domain))
+                if !local.is_empty()
+                    && !domain.is_empty()
+                    && !domain.contains('@')
+                    && !email.contains(char::is_whitespace) =>
+            {
+                Ok(Self(email))
+            }
+            _ => Err(UserEmailInvalidError),
+        }
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+}
+
+impl Display for UserEmail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A plaintext password as typed by the user. Never logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Password(String);
+
+impl Password {
+    pub fn try_new(raw: &str) -> Result<Self, PasswordTooShortError> {
+        if raw.chars().count() < PASSWORD_MIN_LENGTH {
+            return Err(PasswordTooShortError);
+        }
+        Ok(Self(raw.to_string()))
+    }
+
+    /// Wraps a password without checking the length policy, for login
+    /// attempts where the policy may have changed since the password was set.
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+
+    fn expose(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl Debug for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Password(***)")
+    }
+}
+
+/// Argon2id hash of a [`Password`] in PHC string format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHash(String);
+
+impl PasswordHash {
+    pub fn generate(password: &Password) -> Result<Self, PasswordHashError> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        Argon2::default()
+            .hash_password(password.expose(), &salt)
+            .map(|hash| Self(hash.to_string()))
+            .map_err(PasswordHashError)
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+
+    pub fn verify(&self, password: &Password) -> bool {
+        ParsedPasswordHash::new(&self.0)
+            .and_then(|hash| Argon2::default().verify_password(password.expose(), &hash))
+            .is_ok()
+    }
+}
+
+impl Display for PasswordHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Opaque bearer secret identifying a session. Only its SHA-256 digest is
+/// stored, so a leaked database does not hand out live sessions.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; SESSION_TOKEN_BYTES];
+        rand::rng().fill_bytes(&mut bytes);
+        Self(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        Sha256::digest(self.0.as_bytes()).to_vec()
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for SessionToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SessionToken(***)")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    token: SessionToken,
+    user: User,
+    expires_at: DateTime<Utc>,
+    second_factor_pending: bool,
+}
+
+impl Session {
+    pub fn new(token: SessionToken, user: User, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            token,
+            user,
+            expires_at,
+            second_factor_pending: false,
+        }
+    }
+
+    /// A pending session only proves the first factor. It authenticates
+    /// nothing until the user completes the login with a one-time code.
+    pub fn with_second_factor_pending(mut self, pending: bool) -> Self {
+        self.second_factor_pending = pending;
+        self
+    }
+
+    pub fn token(&self) -> SessionToken {
+        self.token.clone()
+    }
+
+    pub fn user(&self) -> User {
+        self.user.clone()
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    pub fn is_second_factor_pending(&self) -> bool {
+        self.second_factor_pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_email_is_normalized() {
+        let email = UserEmail::try_new("  Tommy@Example.COM ").unwrap();
+
+        assert_eq!(email.to_string(), "tommy@example.com");
+    }
+
+    #[test]
+    fn test_user_email_validation_rejects_malformed_input() {
+        for raw in [
+            "",
+            "tommy",
+            "@example.com",
+            "tommy@",
+            "a@b@c",
+            "to mmy@example.com",
+        ] {
+            assert!(
+                UserEmail::try_new(raw).is_err(),
+                "{raw:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_role_round_trips_through_string() {
+        for role in Role::ALL {
+            assert_eq!(role.as_str().parse::<Role>().unwrap(), role);
+        }
+        assert!("owner".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_password_validation_enforces_min_length() {
+        assert!(Password::try_new("short").is_err());
+        assert!(Password::try_new("long enough password").is_ok());
+    }
+
+    #[test]
+    fn test_password_hash_verifies_only_original_password() {
+        let password = Password::new("correct horse battery staple");
+        let hash = PasswordHash::generate(&password).unwrap();
+
+        assert!(hash.to_string().starts_with("$argon2id$"));
+        assert!(hash.verify(&password));
+        assert!(!hash.verify(&Password::new("wrong password")));
+    }
+
+    #[test]
+    fn test_session_tokens_are_unique_and_hashed() {
+        let first = SessionToken::generate();
+        let second = SessionToken::generate();
+
+        assert_ne!(first, second);
+        assert_eq!(first.hash().len(), 32);
+        assert_ne!(first.hash(), first.expose().as_bytes());
+    }
+}
@@ -0,0 +1,67 @@
+use super::super::totp::SecondFactor;
+use super::model::{Password, Role, UserEmail};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateUserRequest {
+    email: UserEmail,
+    password: Password,
+    role: Role,
+}
+
+impl CreateUserRequest {
+    pub fn new(email: UserEmail, password: Password, role: Role) -> Self {
+        Self {
+            email,
+            password,
+            role,
+        }
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn email(&self) -> UserEmail {
+        self.email.clone()
+    }
+
+    pub fn password(&self) -> Password {
+        self.password.clone()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoginRequest {
+    email: UserEmail,
+    password: Password,
+    second_factor: Option<SecondFactor>,
+}
+
+impl LoginRequest {
+    pub fn new(email: UserEmail, password: Password) -> Self {
+        Self {
+            email,
+            password,
+            second_factor: None,
+        }
+    }
+
+    /// Completes the login in one step for users with an authenticator.
+    /// Without it their session stays pending until a code is presented.
+    pub fn with_second_factor(mut self, second_factor: Option<SecondFactor>) -> Self {
+        self.second_factor = second_factor;
+        self
+    }
+
+    pub fn email(&self) -> UserEmail {
+        self.email.clone()
+    }
+
+    pub fn password(&self) -> Password {
+        self.password.clone()
+    }
+
+    pub fn second_factor(&self) -> Option<&SecondFactor> {
+        self.second_factor.as_ref()
+    }
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("{0:?} is not a valid http(s) URL")]
+pub struct WebhookUrlInvalidError(pub String);
+
+#[derive(Clone, Debug, Error)]
+#[error("{0:?} is not a public address; webhooks may only call private hosts that are allowed")]
+pub struct WebhookUrlNotAllowedError(pub String);
+
+#[derive(Clone, Debug, Error)]
+#[error("Unknown delivery status {0:?}, expected one of pending, delivered, dead")]
+pub struct DeliveryStatusInvalidError(pub String);
@@ -0,0 +1,705 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{Debug, Display},
+    net::IpAddr,
+    str::FromStr,
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde_json::{Value, json};
+use sha2::Sha256;
+use url::{Host, Url};
+use uuid::Uuid;
+
+use crate::{
+    domain::models::{event::PostChangeKind, outbox::OutboxEvent, post::Post},
+    ids::{UserId, WebhookId},
+};
+
+use super::errors::{
+    DeliveryStatusInvalidError, WebhookUrlInvalidError, WebhookUrlNotAllowedError,
+};
+
+/// Prefix of every signing secret, so leaked secrets are easy to recognise.
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+const WEBHOOK_SECRET_BYTES: usize = 32;
+
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 8;
+
+/// Wait before the second attempt; every further attempt waits twice as long
+/// as the one before, so eight attempts span a little over an hour.
+pub const DEFAULT_WEBHOOK_INITIAL_BACKOFF: Duration = Duration::seconds(30);
+
+/// A receiver of post changes, called with a signed `POST` for every change
+/// it subscribed to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Webhook {
+    id: WebhookId,
+    url: WebhookUrl,
+    events: BTreeSet<PostChangeKind>,
+    created_by: Option<UserId>,
+    created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn new(
+        id: WebhookId,
+        url: WebhookUrl,
+        events: BTreeSet<PostChangeKind>,
+        created_by: Option<UserId>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            url,
+            events,
+            created_by,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> WebhookId {
+        self.id
+    }
+
+    pub fn url(&self) -> &WebhookUrl {
+        &self.url
+    }
+
+    pub fn events(&self) -> &BTreeSet<PostChangeKind> {
+        &self.events
+    }
+
+    pub fn created_by(&self) -> Option<UserId> {
+        self.created_by
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookUrl(Url);
+
+impl WebhookUrl {
+    pub fn try_new(raw: &str) -> Result<Self, WebhookUrlInvalidError> {
+        match Url::parse(raw.trim()) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(Self(url)),
+            _ => Err(WebhookUrlInvalidError(raw.to_string())),
+        }
+    }
+
+    pub fn as_url(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl Display for WebhookUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+/// Key of the HMAC-SHA256 signature on every delivery. Unlike API token
+/// secrets it is stored as is, since signing needs it; it is shown once, when
+/// the webhook is created.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WebhookSecret(String);
+
+impl WebhookSecret {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; WEBHOOK_SECRET_BYTES];
+        rand::rng().fill_bytes(&mut bytes);
+        Self(format!(
+            "{WEBHOOK_SECRET_PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(bytes)
+        ))
+    }
+
+    pub fn new(input: &str) -> Self {
+        Self(input.to_string())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// The hex encoded signature of `body` sent at `timestamp`, in Unix
+    /// seconds. The timestamp is signed too, so receivers can reject old
+    /// deliveries that are replayed.
+    pub fn sign(&self, timestamp: i64, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.0.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+impl Debug for WebhookSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WebhookSecret(***)")
+    }
+}
+
+/// A freshly created webhook together with its secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatedWebhook {
+    webhook: Webhook,
+    secret: WebhookSecret,
+}
+
+impl CreatedWebhook {
+    pub fn new(webhook: Webhook, secret: WebhookSecret) -> Self {
+        Self { webhook, secret }
+    }
+
+    pub fn webhook(&self) -> Webhook {
+        self.webhook.clone()
+    }
+
+    pub fn secret(&self) -> WebhookSecret {
+        self.secret.clone()
+    }
+}
+
+/// The body sent for a change, shared by the deliveries to every webhook
+/// subscribed to it. `data` is the post as it is after the change, or only
+/// its id once deleted. `id` is that of the outbox event, so it stays the
+/// same should the event be relayed more than once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookEvent {
+    id: Uuid,
+    kind: PostChangeKind,
+    data: Value,
+    occurred_at: DateTime<Utc>,
+}
+
+impl WebhookEvent {
+    pub fn kind(&self) -> PostChangeKind {
+        self.kind
+    }
+
+    /// The JSON body, serialized once so that every attempt sends, and signs,
+    /// the same bytes.
+    pub fn to_payload(&self) -> String {
+        json!({
+            "id": self.id,
+            "type": self.kind.as_str(),
+            "occurred_at": self.occurred_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            "data": self.data,
+        })
+        .to_string()
+    }
+}
+
+impl From<&OutboxEvent> for WebhookEvent {
+    fn from(outbox_event: &OutboxEvent) -> Self {
+        let event = outbox_event.event();
+
+        Self {
+            id: outbox_event.id(),
+            kind: event.kind(),
+            data: event
+                .post()
+                .map(post_data)
+                .unwrap_or_else(|| json!({ "id": event.aggregate_id() })),
+            occurred_at: outbox_event.occurred_at(),
+        }
+    }
+}
+
+/// The post as `GET /posts/{post_id}` serves it, which is what subscribers
+/// are promised. Posts in the trash are never sent, so there is no
+/// `deleted_at`.
+fn post_data(post: &Post) -> Value {
+    let authors: Vec<_> = post
+        .authors()
+        .iter()
+        .map(|author| {
+            let social_links: Vec<_> = author
+                .social_links()
+                .iter()
+                .map(|link| json!({ "label": link.label(), "url": link.url().to_string() }))
+                .collect();
+            json!({
+                "id": author.id(),
+                "user_id": author.user_id(),
+                "display_name": author.name().to_string(),
+                "bio": author.bio(),
+                "avatar_url": author.avatar_url().map(ToString::to_string),
+                "social_links": social_links,
+            })
+        })
+        .collect();
+
+    json!({
+        "id": post.id(),
+        "title": post.title().to_string(),
+        "body": post.body().to_string(),
+        "created_at": post.created_at(),
+        "updated_at": post.updated_at(),
+        "created_by": post.created_by(),
+        "version": post.version(),
+        "authors": authors,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or for a retry.
+    Pending,
+    Delivered,
+    /// Failed on every attempt; only sent again when redelivered by hand.
+    Dead,
+}
+
+impl DeliveryStatus {
+    pub const ALL: [DeliveryStatus; 3] = [
+        DeliveryStatus::Pending,
+        DeliveryStatus::Delivered,
+        DeliveryStatus::Dead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Dead => "dead",
+        }
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = DeliveryStatusInvalidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| DeliveryStatusInvalidError(s.to_string()))
+    }
+}
+
+impl Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The outcome of sending a delivery once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookAttempt {
+    attempted_at: DateTime<Utc>,
+    response_status: Option<u16>,
+    error: Option<String>,
+}
+
+impl WebhookAttempt {
+    /// The receiver answered with `response_status`.
+    pub fn responded(attempted_at: DateTime<Utc>, response_status: u16) -> Self {
+        Self {
+            attempted_at,
+            response_status: Some(response_status),
+            error: None,
+        }
+    }
+
+    /// No response, e.g. because the receiver could not be reached or timed
+    /// out.
+    pub fn failed(attempted_at: DateTime<Utc>, error: String) -> Self {
+        Self {
+            attempted_at,
+            response_status: None,
+            error: Some(error),
+        }
+    }
+
+    pub fn attempted_at(&self) -> DateTime<Utc> {
+        self.attempted_at
+    }
+
+    pub fn response_status(&self) -> Option<u16> {
+        self.response_status
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Any `2xx` response counts as delivered.
+    pub fn is_success(&self) -> bool {
+        self.response_status
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// One change to be sent to one webhook, with the outcome of its latest
+/// attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    id: i64,
+    webhook_id: WebhookId,
+    event: PostChangeKind,
+    status: DeliveryStatus,
+    attempts: u32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_attempt: Option<WebhookAttempt>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    pub fn new(
+        id: i64,
+        webhook_id: WebhookId,
+        event: PostChangeKind,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            webhook_id,
+            event,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Some(created_at),
+            last_attempt: None,
+            created_at,
+            delivered_at: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: DeliveryStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Only pending deliveries are attempted again.
+    pub fn with_next_attempt_at(mut self, next_attempt_at: Option<DateTime<Utc>>) -> Self {
+        self.next_attempt_at = next_attempt_at;
+        self
+    }
+
+    pub fn with_last_attempt(mut self, last_attempt: Option<WebhookAttempt>) -> Self {
+        self.last_attempt = last_attempt;
+        self
+    }
+
+    pub fn with_delivered_at(mut self, delivered_at: Option<DateTime<Utc>>) -> Self {
+        self.delivered_at = delivered_at;
+        self
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn webhook_id(&self) -> WebhookId {
+        self.webhook_id
+    }
+
+    pub fn event(&self) -> PostChangeKind {
+        self.event
+    }
+
+    pub fn status(&self) -> DeliveryStatus {
+        self.status
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn next_attempt_at(&self) -> Option<DateTime<Utc>> {
+        self.next_attempt_at
+    }
+
+    pub fn last_attempt(&self) -> Option<&WebhookAttempt> {
+        self.last_attempt.as_ref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn delivered_at(&self) -> Option<DateTime<Utc>> {
+        self.delivered_at
+    }
+}
+
+/// A delivery claimed for sending, with what is needed to send it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DueWebhookDelivery {
+    id: i64,
+    event: PostChangeKind,
+    /// Counting this one, which has already been recorded.
+    attempt: u32,
+    url: WebhookUrl,
+    secret: WebhookSecret,
+    payload: String,
+}
+
+impl DueWebhookDelivery {
+    pub fn new(
+        id: i64,
+        event: PostChangeKind,
+        attempt: u32,
+        url: WebhookUrl,
+        secret: WebhookSecret,
+        payload: String,
+    ) -> Self {
+        Self {
+            id,
+            event,
+            attempt,
+            url,
+            secret,
+            payload,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn event(&self) -> PostChangeKind {
+        self.event
+    }
+
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    pub fn url(&self) -> &WebhookUrl {
+        &self.url
+    }
+
+    pub fn secret(&self) -> &WebhookSecret {
+        &self.secret
+    }
+
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+}
+
+/// How often, and how far apart, a failing delivery is attempted before it
+/// is given up on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WebhookRetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+}
+
+impl WebhookRetryPolicy {
+    pub fn new(max_attempts: u32, initial_backoff: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff,
+        }
+    }
+
+    /// When to try again after `attempt` failed, or `None` if that was the
+    /// last one.
+    pub fn retry_at(&self, attempt: u32, failed_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        // Capped well before the multiplication could overflow.
+        let factor = 1i32 << attempt.saturating_sub(1).min(20);
+
+        Some(failed_at + self.initial_backoff * factor)
+    }
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+            DEFAULT_WEBHOOK_INITIAL_BACKOFF,
+        )
+    }
+}
+
+/// Where deliveries may be sent: only to public addresses, so that webhooks
+/// cannot reach into the network the blog runs in, unless the host is
+/// allowed by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WebhookTargetPolicy {
+    allowed_hosts: BTreeSet<String>,
+}
+
+impl WebhookTargetPolicy {
+    /// Allows `allowed_hosts`, names or addresses as they appear in URLs,
+    /// whatever they resolve to.
+    pub fn new(allowed_hosts: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            allowed_hosts: allowed_hosts
+                .into_iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Checks the host of `url` when it is saved. Where a name resolves to
+    /// is checked on every delivery, with [`Self::allows_address`].
+    pub fn check(&self, url: &WebhookUrl) -> Result<(), WebhookUrlNotAllowedError> {
+        let url = url.as_url();
+        let allowed = match url.host() {
+            _ if url.host_str().is_some_and(|host| self.allows_host(host)) => true,
+            Some(Host::Domain(domain)) => !is_local_name(domain),
+            Some(Host::Ipv4(address)) => is_public_address(address.into()),
+            Some(Host::Ipv6(address)) => is_public_address(address.into()),
+            None => false,
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(WebhookUrlNotAllowedError(url.to_string()))
+        }
+    }
+
+    /// Whether a delivery to `host` may be sent to `address`, one it
+    /// resolved to.
+    pub fn allows_address(&self, host: &str, address: IpAddr) -> bool {
+        self.allows_host(host) || is_public_address(address)
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        self.allowed_hosts.contains(&host.to_ascii_lowercase())
+    }
+}
+
+fn is_local_name(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    domain == "localhost" || domain.ends_with(".localhost")
+}
+
+/// Whether `address` is on the public internet: not loopback, private
+/// (RFC 1918 or unique local), link-local, which cloud metadata endpoints
+/// such as 169.254.169.254 are, shared, unspecified, broadcast or multicast.
+pub fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            let [first, second, ..] = address.octets();
+            let shared = first == 100 && second & 0b1100_0000 == 64;
+
+            !(address.is_loopback()
+                || address.is_private()
+                || address.is_link_local()
+                || address.is_unspecified()
+                || address.is_broadcast()
+                || address.is_multicast()
+                || address.is_documentation()
+                || shared)
+        }
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(mapped) => is_public_address(mapped.into()),
+            None => {
+                !(address.is_loopback()
+                    || address.is_unspecified()
+                    || address.is_multicast()
+                    || address.is_unique_local()
+                    || address.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_secret_signs_timestamp_and_body() {
+        let secret = WebhookSecret::new("whsec_test");
+
+        let signature = secret.sign(1_700_000_000, br#"{"type":"post.created"}"#);
+
+        // echo -n '1700000000.{"type":"post.created"}' |
+        //     openssl dgst -sha256 -hmac whsec_test
+        assert_eq!(
+            signature,
+            "8020928e64dc654828a8ea2e66232cc69f67efe53b98b4fe16e889d9da478976"
+        );
+        assert_ne!(
+            signature,
+            secret.sign(1_700_000_001, br#"{"type":"post.created"}"#)
+        );
+    }
+
+    #[test]
+    fn test_webhook_url_must_be_http() {
+        assert!(WebhookUrl::try_new("https://hooks.slack.com/services/T0/B0/x").is_ok());
+        assert!(WebhookUrl::try_new("ftp://example.com/hook").is_err());
+        assert!(WebhookUrl::try_new("not a url").is_err());
+    }
+
+    #[test]
+    fn test_target_policy_refuses_private_addresses_unless_allowed() {
+        let policy = WebhookTargetPolicy::new(["Hooks.internal".to_string()]);
+        let check = |raw: &str| policy.check(&WebhookUrl::try_new(raw).unwrap());
+
+        assert!(check("https://example.com/hook").is_ok());
+        assert!(check("https://93.184.215.14/hook").is_ok());
+        assert!(check("http://hooks.internal/hook").is_ok());
+        for raw in [
+            "http://localhost/hook",
+            "http://127.0.0.1:8080/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.1/hook",
+            "http://192.168.1.1/hook",
+            "http://[::1]/hook",
+            "http://[::ffff:172.16.0.1]/hook",
+            "http://[fd00::1]/hook",
+        ] {
+            assert!(check(raw).is_err(), "{raw} was allowed");
+        }
+        assert!(policy.allows_address("hooks.internal", "10.0.0.1".parse().unwrap()));
+        assert!(!policy.allows_address("example.com", "10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_retry_policy_backs_off_exponentially_until_max_attempts() {
+        let policy = WebhookRetryPolicy::new(4, Duration::seconds(10));
+        let failed_at = Utc::now();
+
+        let waits: Vec<_> = (1..=4)
+            .map(|attempt| {
+                policy
+                    .retry_at(attempt, failed_at)
+                    .map(|retry_at| (retry_at - failed_at).num_seconds())
+            })
+            .collect();
+
+        assert_eq!(waits, [Some(10), Some(20), Some(40), None]);
+    }
+
+    #[test]
+    fn test_only_2xx_responses_count_as_delivered() {
+        let now = Utc::now();
+
+        assert!(WebhookAttempt::responded(now, 204).is_success());
+        assert!(!WebhookAttempt::responded(now, 301).is_success());
+        assert!(!WebhookAttempt::responded(now, 500).is_success());
+        assert!(!WebhookAttempt::failed(now, "timed out".to_string()).is_success());
+    }
+}
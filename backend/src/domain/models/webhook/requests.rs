@@ -0,0 +1,25 @@
+use std::collections::BTreeSet;
+
+use crate::domain::models::event::PostChangeKind;
+
+use super::model::WebhookUrl;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateWebhookRequest {
+    url: WebhookUrl,
+    events: BTreeSet<PostChangeKind>,
+}
+
+impl CreateWebhookRequest {
+    pub fn new(url: WebhookUrl, events: BTreeSet<PostChangeKind>) -> Self {
+        Self { url, events }
+    }
+
+    pub fn url(&self) -> WebhookUrl {
+        self.url.clone()
+    }
+
+    pub fn events(&self) -> BTreeSet<PostChangeKind> {
+        self.events.clone()
+    }
+}
@@ -0,0 +1,169 @@
+//! Who may do what.
+//!
+//! Every operation that changes content goes through [`can`] before
+//! [`BlogService`](crate::service::BlogService) touches the repository:
+//!
+//! | action                | admin | editor | author    | reader |
+//! |-----------------------|-------|--------|-----------|--------|
+//! | create a post         | yes   | yes    | yes       | no     |
+//! | update/delete a post  | yes   | yes    | own posts | no     |
+//! | restore a post        | yes   | yes    | own posts | no     |
+//! | manage author profiles| yes   | yes    | no        | no     |
+//! | export a backup       | yes   | no     | no        | no     |
+//! | view the audit log    | yes   | no     | no        | no     |
+//! | manage webhooks       | yes   | no     | no        | no     |
+//!
+//! API token scopes are checked on top of this at the HTTP layer; a token
+//! never grants more than its owner's role.
+//!
+//! Roles covered by the [`SecondFactorPolicy`] may not do any of the above
+//! until they have confirmed an authenticator app.
+
+use std::collections::BTreeSet;
+
+use super::models::{
+    post::Post,
+    user::{Role, User},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action<'a> {
+    CreatePost,
+    UpdatePost(&'a Post),
+    DeletePost(&'a Post),
+    RestorePost(&'a Post),
+    ManageAuthors,
+    ExportBackup,
+    ViewAuditLog,
+    ManageWebhooks,
+}
+
+pub fn can(actor: &User, action: Action<'_>) -> bool {
+    match (actor.role(), action) {
+        (Role::Admin, _) => true,
+        (Role::Editor, Action::ExportBackup | Action::ViewAuditLog | Action::ManageWebhooks) => {
+            false
+        }
+        (Role::Editor, _) => true,
+        (Role::Author, Action::CreatePost) => true,
+        (
+            Role::Author,
+            Action::UpdatePost(post) | Action::DeletePost(post) | Action::RestorePost(post),
+        ) => post.created_by() == Some(actor.id()),
+        (
+            Role::Author,
+            Action::ManageAuthors
+            | Action::ExportBackup
+            | Action::ViewAuditLog
+            | Action::ManageWebhooks,
+        ) => false,
+        (Role::Reader, _) => false,
+    }
+}
+
+/// Roles that must use two-factor authentication.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SecondFactorPolicy {
+    required: BTreeSet<Role>,
+}
+
+impl SecondFactorPolicy {
+    pub fn new(required: impl IntoIterator<Item = Role>) -> Self {
+        Self {
+            required: required.into_iter().collect(),
+        }
+    }
+
+    /// Whether `actor` has to enroll before acting.
+    pub fn blocks(&self, actor: &User) -> bool {
+        self.required.contains(&actor.role()) && !actor.has_second_factor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::{
+        domain::models::{
+            post::{PostBody, PostTitle},
+            user::UserEmail,
+        },
+        ids::{PostId, UserId},
+    };
+
+    use super::*;
+
+    fn user(role: Role) -> User {
+        User::new(
+            UserId::new(),
+            UserEmail::new("tommy@example.com"),
+            role,
+            Utc::now(),
+        )
+    }
+
+    fn post_by(created_by: Option<UserId>) -> Post {
+        Post::new(
+            PostId::new(),
+            PostTitle::new("Title"),
+            PostBody::new("Body"),
+            Utc::now(),
+        )
+        .with_created_by(created_by)
+    }
+
+    #[test]
+    fn test_authors_can_only_change_their_own_posts() {
+        let author = user(Role::Author);
+        let own = post_by(Some(author.id()));
+        let foreign = post_by(Some(UserId::new()));
+        let imported = post_by(None);
+
+        assert!(can(&author, Action::CreatePost));
+        assert!(can(&author, Action::UpdatePost(&own)));
+        assert!(can(&author, Action::DeletePost(&own)));
+        assert!(!can(&author, Action::UpdatePost(&foreign)));
+        assert!(!can(&author, Action::DeletePost(&foreign)));
+        assert!(can(&author, Action::RestorePost(&own)));
+        assert!(!can(&author, Action::RestorePost(&foreign)));
+        assert!(!can(&author, Action::UpdatePost(&imported)));
+        assert!(!can(&author, Action::ManageAuthors));
+    }
+
+    #[test]
+    fn test_editors_can_change_any_post_but_not_export_audit_or_manage_webhooks() {
+        let editor = user(Role::Editor);
+        let foreign = post_by(Some(UserId::new()));
+
+        assert!(can(&editor, Action::UpdatePost(&foreign)));
+        assert!(can(&editor, Action::DeletePost(&foreign)));
+        assert!(can(&editor, Action::ManageAuthors));
+        assert!(!can(&editor, Action::ExportBackup));
+        assert!(!can(&editor, Action::ViewAuditLog));
+        assert!(!can(&editor, Action::ManageWebhooks));
+        assert!(can(&user(Role::Admin), Action::ExportBackup));
+        assert!(can(&user(Role::Admin), Action::ViewAuditLog));
+        assert!(can(&user(Role::Admin), Action::ManageWebhooks));
+    }
+
+    #[test]
+    fn test_second_factor_policy_blocks_unenrolled_users_of_required_roles() {
+        let policy = SecondFactorPolicy::new([Role::Admin]);
+
+        assert!(policy.blocks(&user(Role::Admin)));
+        assert!(!policy.blocks(&user(Role::Admin).with_second_factor(true)));
+        assert!(!policy.blocks(&user(Role::Editor)));
+        assert!(!SecondFactorPolicy::default().blocks(&user(Role::Admin)));
+    }
+
+    #[test]
+    fn test_readers_cannot_change_anything() {
+        let reader = user(Role::Reader);
+        let own = post_by(Some(reader.id()));
+
+        assert!(!can(&reader, Action::CreatePost));
+        assert!(!can(&reader, Action::UpdatePost(&own)));
+        assert!(!can(&reader, Action::ExportBackup));
+    }
+}
@@ -1,25 +1,361 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 use thiserror::Error;
 
-use crate::ids::PostId;
+use crate::ids::{ApiTokenId, AuthorId, PostId, UserId, WebhookId};
 
-use super::models::post::{CreatePostRequest, Post, PostTitle, UpdatePostRequest};
+use super::models::{
+    api_token::{ApiToken, ApiTokenName, ApiTokenSecret},
+    audit::{AuditContext, AuditEntry, AuditQuery},
+    author::{Author, CreateAuthorRequest},
+    change_feed::{ChangeFeedPage, ChangeFeedQuery},
+    event::{EventId, PostChangeSignal},
+    idempotency::{IdempotencyKey, IdempotencyRecord, IdempotentResponse, RequestFingerprint},
+    oidc::{OidcIdentity, OidcLogin, OidcState},
+    outbox::OutboxEvent,
+    post::{
+        CreatePostRequest, ImportPostRequest, Post, PostComment, PostPage, PostPageQuery, PostTerm,
+        PostTitle, UpdatePostRequest,
+    },
+    totp::{RecoveryCode, Totp, TotpSecret},
+    user::{PasswordHash, Role, Session, SessionToken, User, UserEmail},
+    webhook::{DueWebhookDelivery, Webhook, WebhookAttempt, WebhookDelivery, WebhookSecret},
+};
 
+/// Every method that changes posts takes an [`AuditContext`] and appends to
+/// the audit log in the same transaction as the change. Changes readers see
+/// are also written to the outbox there, as [`DomainEvent`]s to be relayed.
+///
+/// [`DomainEvent`]: super::models::outbox::DomainEvent
 #[async_trait]
 pub trait Repository: Send + Sync + Clone + 'static {
-    async fn create_post(&self, input: &CreatePostRequest) -> Result<Post, CreatePostError>;
+    async fn create_post(
+        &self,
+        input: &CreatePostRequest,
+        created_by: UserId,
+        audit: &AuditContext,
+    ) -> Result<Post, CreatePostError>;
 
     async fn get_all_posts(&self) -> Result<Vec<Post>, RepositoryError>;
 
+    /// A page of the posts not in the trash, oldest first.
+    async fn get_posts_page(&self, query: &PostPageQuery) -> Result<PostPage, RepositoryError>;
+
+    /// When any post was last created, changed or deleted, if ever.
+    async fn get_posts_last_modified(&self) -> Result<Option<DateTime<Utc>>, RepositoryError>;
+
+    /// Posts in the trash are not found, here or by any other read.
     async fn get_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError>;
 
+    /// The categories and tags a post is filed under, in order.
+    async fn get_post_terms(&self, post_id: PostId) -> Result<Vec<PostTerm>, GetPostError>;
+
+    /// The approved comments on a post, oldest first.
+    async fn get_post_comments(&self, post_id: PostId) -> Result<Vec<PostComment>, GetPostError>;
+
+    /// Fails with `VersionConflict` unless the post is still at
+    /// `expected_version`, so changes made in the meantime are not lost.
     async fn update_post(
         &self,
         post_id: PostId,
+        expected_version: i64,
         input: &UpdatePostRequest,
+        audit: &AuditContext,
     ) -> Result<Post, UpdatePostError>;
 
-    async fn delete_post(&self, post_id: PostId) -> Result<(), DeletePostError>;
+    /// Moves a post to the trash if it is still at `expected_version`.
+    async fn trash_post(
+        &self,
+        post_id: PostId,
+        expected_version: i64,
+        audit: &AuditContext,
+    ) -> Result<(), DeletePostError>;
+
+    /// Trashed posts, most recently deleted first.
+    async fn get_trashed_posts(&self) -> Result<Vec<Post>, RepositoryError>;
+
+    async fn get_trashed_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError>;
+
+    async fn restore_post(
+        &self,
+        post_id: PostId,
+        audit: &AuditContext,
+    ) -> Result<Post, RestorePostError>;
+
+    /// Deletes a post for good, whether or not it is in the trash, if it is
+    /// still at `expected_version`.
+    async fn delete_post(
+        &self,
+        post_id: PostId,
+        expected_version: i64,
+        audit: &AuditContext,
+    ) -> Result<(), DeletePostError>;
+
+    /// Deletes the posts moved to the trash before `deleted_before` for good,
+    /// returning how many there were.
+    async fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+        audit: &AuditContext,
+    ) -> Result<u64, RepositoryError>;
+
+    /// Inserts the posts with their categories, tags and comments, all or
+    /// nothing.
+    async fn import_posts(
+        &self,
+        posts: &[ImportPostRequest],
+        audit: &AuditContext,
+    ) -> Result<Vec<Post>, ImportPostsError>;
+
+    fn stream_posts(&self) -> BoxStream<'static, Result<Post, RepositoryError>>;
+
+    /// Every post, the trash included, oldest first, with its categories,
+    /// tags and comments, as a backup holds them.
+    fn stream_backup_posts(&self)
+    -> BoxStream<'static, Result<ImportPostRequest, RepositoryError>>;
+
+    /// Changes to posts committed by any instance from now on, in commit
+    /// order. Resolves once changes are being listened for.
+    async fn watch_post_changes(&self) -> BoxStream<'static, PostChangeSignal>;
+
+    /// The number of the latest change, from which the change feed can be
+    /// read; zero before the first.
+    async fn get_latest_change_id(&self) -> Result<EventId, RepositoryError>;
+
+    /// The latest change to each post changed after the cursor in `query`,
+    /// read from a single snapshot. Fails with `ResyncRequired` if tombstones
+    /// the caller has not seen were compacted away.
+    async fn get_changes(&self, query: &ChangeFeedQuery)
+    -> Result<ChangeFeedPage, GetChangesError>;
+
+    /// Removes the tombstones of posts deleted before `deleted_before`,
+    /// returning how many there were.
+    async fn compact_changes(&self, deleted_before: DateTime<Utc>) -> Result<u64, RepositoryError>;
+
+    /// Replaces every post, the trash included, with `posts`. A post whose
+    /// predecessor has been changed past its version is restored at the
+    /// version after that one.
+    async fn restore_posts(
+        &self,
+        posts: &[ImportPostRequest],
+        audit: &AuditContext,
+    ) -> Result<Vec<Post>, ImportPostsError>;
+
+    async fn get_audit_entries(
+        &self,
+        query: &AuditQuery,
+    ) -> Result<Vec<AuditEntry>, RepositoryError>;
+
+    /// The whole audit log, oldest entry first.
+    fn stream_audit_entries(&self) -> BoxStream<'static, Result<AuditEntry, RepositoryError>>;
+
+    async fn create_author(&self, input: &CreateAuthorRequest)
+    -> Result<Author, CreateAuthorError>;
+
+    async fn get_author_by_id(&self, author_id: AuthorId) -> Result<Author, GetAuthorError>;
+
+    /// Posts the author is credited on, newest first.
+    async fn get_posts_by_author(&self, author_id: AuthorId) -> Result<Vec<Post>, GetAuthorError>;
+
+    /// The newest `page.limit()` posts of each of the authors, older than
+    /// `page.after()` if set, newest first. A post by several of them is
+    /// returned once.
+    async fn get_posts_by_authors(
+        &self,
+        author_ids: &[AuthorId],
+        page: &PostPageQuery,
+    ) -> Result<Vec<Post>, RepositoryError>;
+
+    async fn create_user(
+        &self,
+        email: &UserEmail,
+        password_hash: &PasswordHash,
+        role: Role,
+    ) -> Result<User, CreateUserError>;
+
+    /// Users who only sign in through an identity provider have no password.
+    async fn get_user_credentials(
+        &self,
+        email: &UserEmail,
+    ) -> Result<(User, Option<PasswordHash>), GetUserError>;
+
+    /// Finds the user linked to `identity`. On the first login a user with the
+    /// same email is linked, or a new one without a password is created with
+    /// `role`.
+    async fn get_or_create_identity_user(
+        &self,
+        identity: &OidcIdentity,
+        role: Role,
+    ) -> Result<User, RepositoryError>;
+
+    async fn create_oidc_login(&self, login: &OidcLogin) -> Result<(), RepositoryError>;
+
+    /// Removes and returns a pending login, so its state cannot be replayed.
+    async fn take_oidc_login(&self, state: &OidcState) -> Result<OidcLogin, TakeOidcLoginError>;
+
+    async fn create_session(&self, session: &Session) -> Result<(), RepositoryError>;
+
+    async fn get_session(&self, token: &SessionToken) -> Result<Session, GetSessionError>;
+
+    /// Clears the pending second factor of a session.
+    async fn complete_session(&self, token: &SessionToken) -> Result<(), GetSessionError>;
+
+    async fn delete_session(&self, token: &SessionToken) -> Result<(), RepositoryError>;
+
+    /// Stores a secret awaiting confirmation, replacing an unconfirmed one.
+    async fn create_totp(
+        &self,
+        user_id: UserId,
+        secret: &TotpSecret,
+    ) -> Result<(), CreateTotpError>;
+
+    async fn get_totp(&self, user_id: UserId) -> Result<Totp, GetTotpError>;
+
+    /// Confirms the pending secret, whose code for `step` counts as used,
+    /// together with a fresh set of recovery codes.
+    async fn confirm_totp(
+        &self,
+        user_id: UserId,
+        step: i64,
+        recovery_codes: &[RecoveryCode],
+    ) -> Result<(), GetTotpError>;
+
+    /// Counts an attempt at a second factor of the user, failing with
+    /// `Rejected` while they are locked out. The attempt that makes
+    /// `max_attempts` locks them out until `locked_until`; getting a code
+    /// right starts the count over.
+    async fn start_second_factor_attempt(
+        &self,
+        user_id: UserId,
+        max_attempts: u32,
+        locked_until: DateTime<Utc>,
+    ) -> Result<(), UseSecondFactorError>;
+
+    /// Accepts the code for `step` only if no code of this or a later step
+    /// was accepted before.
+    async fn use_totp_step(&self, user_id: UserId, step: i64) -> Result<(), UseSecondFactorError>;
+
+    async fn use_recovery_code(
+        &self,
+        user_id: UserId,
+        code: &RecoveryCode,
+    ) -> Result<(), UseSecondFactorError>;
+
+    async fn create_api_token(
+        &self,
+        token: &ApiToken,
+        secret: &ApiTokenSecret,
+    ) -> Result<ApiToken, CreateApiTokenError>;
+
+    async fn get_api_tokens(&self, user_id: UserId) -> Result<Vec<ApiToken>, RepositoryError>;
+
+    async fn delete_api_token(
+        &self,
+        user_id: UserId,
+        token_id: ApiTokenId,
+    ) -> Result<(), DeleteApiTokenError>;
+
+    /// Resolves a secret to its token and owner, recording it as used.
+    async fn use_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+    ) -> Result<(User, ApiToken), UseApiTokenError>;
+
+    /// Claims `key` for a request of `user_id` until `expires_at`. If the key
+    /// is claimed already and has not expired, that claim is returned
+    /// instead, unless it is for the same request, has no response and is
+    /// past its lease. The new claim is leased until `locked_until`.
+    async fn claim_idempotency_key(
+        &self,
+        user_id: UserId,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+        expires_at: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError>;
+
+    /// Deletes keys past their expiry, returning how many.
+    async fn delete_expired_idempotency_keys(&self) -> Result<u64, RepositoryError>;
+
+    async fn complete_idempotency_key(
+        &self,
+        user_id: UserId,
+        key: &IdempotencyKey,
+        response: &IdempotentResponse,
+    ) -> Result<(), RepositoryError>;
+
+    /// Removes a claim that has no response yet.
+    async fn release_idempotency_key(
+        &self,
+        user_id: UserId,
+        key: &IdempotencyKey,
+    ) -> Result<(), RepositoryError>;
+
+    async fn create_webhook(
+        &self,
+        webhook: &Webhook,
+        secret: &WebhookSecret,
+    ) -> Result<Webhook, RepositoryError>;
+
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, RepositoryError>;
+
+    async fn delete_webhook(&self, webhook_id: WebhookId) -> Result<(), DeleteWebhookError>;
+
+    /// The most recent deliveries to a webhook, newest first.
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookId,
+    ) -> Result<Vec<WebhookDelivery>, GetWebhookError>;
+
+    /// Queues a delivery again, with a fresh set of attempts, unless it is
+    /// being sent.
+    async fn redeliver_webhook(
+        &self,
+        webhook_id: WebhookId,
+        delivery_id: i64,
+    ) -> Result<WebhookDelivery, RedeliverWebhookError>;
+
+    /// Claims up to `limit` due deliveries for sending, leaving them alone
+    /// for other callers until `lease_until`.
+    async fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<DueWebhookDelivery>, RepositoryError>;
+
+    /// Records the outcome of sending a claimed delivery. A failed one is due
+    /// again at `retry_at`, or is dead without it.
+    async fn record_webhook_attempt(
+        &self,
+        delivery_id: i64,
+        attempt: &WebhookAttempt,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), RepositoryError>;
+
+    /// Deletes the delivered and dead deliveries last attempted before
+    /// `finished_before`, returning how many there were.
+    async fn purge_webhook_deliveries(
+        &self,
+        finished_before: DateTime<Utc>,
+    ) -> Result<u64, RepositoryError>;
+
+    /// Claims up to `limit` outbox events for relaying, in order, leaving
+    /// them alone for other callers until `lease_until`. Only the oldest
+    /// event of each post is claimed; the next one can be claimed once it is
+    /// completed.
+    async fn claim_outbox_events(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<OutboxEvent>, RepositoryError>;
+
+    /// Queues the deliveries of a relayed event for every webhook subscribed
+    /// to its kind and removes it from the outbox, in one transaction, so
+    /// they are queued once however often the event is relayed. Returns
+    /// whether this call completed it, rather than one that relayed it
+    /// again after its lease ran out.
+    async fn complete_outbox_event(&self, event: &OutboxEvent) -> Result<bool, RepositoryError>;
 }
 
 pub trait IntoRepositoryError {
@@ -37,6 +373,42 @@ pub enum RepositoryError {
     #[error(transparent)]
     DeletePostError(DeletePostError),
     #[error(transparent)]
+    RestorePostError(RestorePostError),
+    #[error(transparent)]
+    ImportPostsError(ImportPostsError),
+    #[error(transparent)]
+    CreateAuthorError(CreateAuthorError),
+    #[error(transparent)]
+    GetAuthorError(GetAuthorError),
+    #[error(transparent)]
+    CreateUserError(CreateUserError),
+    #[error(transparent)]
+    GetUserError(GetUserError),
+    #[error(transparent)]
+    GetSessionError(GetSessionError),
+    #[error(transparent)]
+    TakeOidcLoginError(TakeOidcLoginError),
+    #[error(transparent)]
+    CreateTotpError(CreateTotpError),
+    #[error(transparent)]
+    GetTotpError(GetTotpError),
+    #[error(transparent)]
+    UseSecondFactorError(UseSecondFactorError),
+    #[error(transparent)]
+    CreateApiTokenError(CreateApiTokenError),
+    #[error(transparent)]
+    DeleteApiTokenError(DeleteApiTokenError),
+    #[error(transparent)]
+    UseApiTokenError(UseApiTokenError),
+    #[error(transparent)]
+    GetWebhookError(GetWebhookError),
+    #[error(transparent)]
+    DeleteWebhookError(DeleteWebhookError),
+    #[error(transparent)]
+    RedeliverWebhookError(RedeliverWebhookError),
+    #[error(transparent)]
+    GetChangesError(GetChangesError),
+    #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
 
@@ -52,6 +424,8 @@ pub enum GetPostError {
 pub enum CreatePostError {
     #[error("Blog post with title {title} already exists.")]
     Duplicate { title: PostTitle },
+    #[error("One or more authors of the blog post do not exist.")]
+    AuthorNotFound,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -62,6 +436,10 @@ pub enum UpdatePostError {
     PostNotFound { id: PostId },
     #[error("Blog post with title {title} already exists.")]
     Duplicate { title: PostTitle },
+    #[error("One or more authors of the blog post do not exist.")]
+    AuthorNotFound,
+    #[error("Blog post with id {id} has changed since version {expected_version}.")]
+    VersionConflict { id: PostId, expected_version: i64 },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -70,6 +448,160 @@ pub enum UpdatePostError {
 pub enum DeletePostError {
     #[error("Could not find blog post with id {id}.")]
     PostNotFound { id: PostId },
+    #[error("Blog post with id {id} has changed since version {expected_version}.")]
+    VersionConflict { id: PostId, expected_version: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum RestorePostError {
+    #[error("Could not find blog post with id {id} in the trash.")]
+    PostNotFound { id: PostId },
+    #[error("Cannot restore blog post with id {id}, another post has its title.")]
+    TitleTaken { id: PostId },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ImportPostsError {
+    #[error("Blog post with title {title} already exists.")]
+    Duplicate { title: PostTitle },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum CreateAuthorError {
+    #[error("User with id {user_id} already has an author profile.")]
+    Duplicate { user_id: UserId },
+    #[error("Could not find user with id {user_id}.")]
+    UserNotFound { user_id: UserId },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetAuthorError {
+    #[error("Could not find author with id {id}.")]
+    AuthorNotFound { id: AuthorId },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum CreateUserError {
+    #[error("User with email {email} already exists.")]
+    Duplicate { email: UserEmail },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetUserError {
+    #[error("Could not find user with email {email}.")]
+    UserNotFound { email: UserEmail },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetSessionError {
+    #[error("Session does not exist or has expired.")]
+    SessionNotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum TakeOidcLoginError {
+    #[error("Login attempt does not exist or has expired.")]
+    LoginNotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum CreateTotpError {
+    #[error("Two-factor authentication is already enabled.")]
+    AlreadyEnabled,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetTotpError {
+    #[error("No two-factor enrollment is in progress.")]
+    TotpNotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum UseSecondFactorError {
+    #[error("One-time code is invalid or was already used.")]
+    Rejected,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum CreateApiTokenError {
+    #[error("API token with name {name} already exists.")]
+    Duplicate { name: ApiTokenName },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteApiTokenError {
+    #[error("Could not find API token with id {id}.")]
+    ApiTokenNotFound { id: ApiTokenId },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum UseApiTokenError {
+    #[error("API token does not exist or has expired.")]
+    ApiTokenNotFound,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetWebhookError {
+    #[error("Could not find webhook with id {id}.")]
+    WebhookNotFound { id: WebhookId },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteWebhookError {
+    #[error("Could not find webhook with id {id}.")]
+    WebhookNotFound { id: WebhookId },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum RedeliverWebhookError {
+    #[error("Could not find delivery {id} of the webhook.")]
+    DeliveryNotFound { id: i64 },
+    #[error("Delivery {id} is being sent; redeliver it once the attempt is over.")]
+    DeliveryInProgress { id: i64 },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum GetChangesError {
+    #[error(
+        "Changes up to {compacted_through} were compacted past cursor {since}; sync again from the start."
+    )]
+    ResyncRequired { since: i64, compacted_through: i64 },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -97,3 +629,111 @@ impl IntoRepositoryError for DeletePostError {
         RepositoryError::DeletePostError(self)
     }
 }
+
+impl IntoRepositoryError for RestorePostError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::RestorePostError(self)
+    }
+}
+
+impl IntoRepositoryError for ImportPostsError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::ImportPostsError(self)
+    }
+}
+
+impl IntoRepositoryError for CreateAuthorError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::CreateAuthorError(self)
+    }
+}
+
+impl IntoRepositoryError for GetAuthorError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::GetAuthorError(self)
+    }
+}
+
+impl IntoRepositoryError for CreateUserError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::CreateUserError(self)
+    }
+}
+
+impl IntoRepositoryError for GetUserError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::GetUserError(self)
+    }
+}
+
+impl IntoRepositoryError for GetSessionError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::GetSessionError(self)
+    }
+}
+
+impl IntoRepositoryError for TakeOidcLoginError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::TakeOidcLoginError(self)
+    }
+}
+
+impl IntoRepositoryError for CreateTotpError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::CreateTotpError(self)
+    }
+}
+
+impl IntoRepositoryError for GetTotpError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::GetTotpError(self)
+    }
+}
+
+impl IntoRepositoryError for UseSecondFactorError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::UseSecondFactorError(self)
+    }
+}
+
+impl IntoRepositoryError for CreateApiTokenError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::CreateApiTokenError(self)
+    }
+}
+
+impl IntoRepositoryError for DeleteApiTokenError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::DeleteApiTokenError(self)
+    }
+}
+
+impl IntoRepositoryError for UseApiTokenError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::UseApiTokenError(self)
+    }
+}
+
+impl IntoRepositoryError for GetWebhookError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::GetWebhookError(self)
+    }
+}
+
+impl IntoRepositoryError for DeleteWebhookError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::DeleteWebhookError(self)
+    }
+}
+
+impl IntoRepositoryError for RedeliverWebhookError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::RedeliverWebhookError(self)
+    }
+}
+
+impl IntoRepositoryError for GetChangesError {
+    fn into_repository_error(self) -> RepositoryError {
+        RepositoryError::GetChangesError(self)
+    }
+}
@@ -1,32 +1,300 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 use thiserror::Error;
 
-use crate::ids::PostId;
+use crate::ids::{ApiTokenId, AuthorId, PostId, WebhookId};
 
 use super::{
-    models::post::{CreatePostRequest, Post, UpdatePostRequest},
+    models::{
+        api_token::{ApiToken, ApiTokenSecret, CreateApiTokenRequest, CreatedApiToken},
+        audit::{AuditEntry, AuditQuery, AuditVerification},
+        author::{Author, CreateAuthorRequest},
+        change_feed::{ChangeFeedPage, ChangeFeedQuery},
+        event::{EventId, StreamEvent},
+        idempotency::{IdempotencyKey, IdempotentResponse, RequestFingerprint},
+        oidc::{OidcIdentity, OidcLogin, OidcState},
+        post::{
+            CreatePostRequest, ImportPostRequest, Post, PostComment, PostPage, PostPageQuery,
+            PostTerm, UpdatePostRequest,
+        },
+        totp::{RecoveryCode, SecondFactor, TotpCode, TotpEnrollment},
+        user::{
+            CreateUserRequest, LoginRequest, PasswordHashError, Role, Session, SessionToken, User,
+        },
+        webhook::{
+            CreateWebhookRequest, CreatedWebhook, Webhook, WebhookDelivery,
+            WebhookUrlNotAllowedError,
+        },
+    },
     repository::RepositoryError,
 };
 
 #[async_trait]
 pub trait Service: Send + Sync + Clone + 'static {
-    async fn create_post(&self, input: &CreatePostRequest) -> Result<Post, ServiceError>;
+    async fn create_post(
+        &self,
+        actor: &User,
+        input: &CreatePostRequest,
+    ) -> Result<Post, ServiceError>;
 
     async fn get_all_posts(&self) -> Result<Vec<Post>, ServiceError>;
 
+    async fn get_posts_page(&self, query: &PostPageQuery) -> Result<PostPage, ServiceError>;
+
+    /// When the list of all posts last changed, if ever.
+    async fn get_posts_last_modified(&self) -> Result<Option<DateTime<Utc>>, ServiceError>;
+
     async fn get_posts_by_id(&self, id: PostId) -> Result<Post, ServiceError>;
 
+    /// The categories and tags a post is filed under, in order.
+    async fn get_post_terms(&self, id: PostId) -> Result<Vec<PostTerm>, ServiceError>;
+
+    /// The approved comments on a post, oldest first.
+    async fn get_post_comments(&self, id: PostId) -> Result<Vec<PostComment>, ServiceError>;
+
+    /// Changes the post if it is still at `expected_version`.
     async fn update_post(
         &self,
+        actor: &User,
         post_id: PostId,
+        expected_version: i64,
         input: &UpdatePostRequest,
     ) -> Result<Post, ServiceError>;
 
-    async fn delete_post(&self, post_id: PostId) -> Result<(), ServiceError>;
+    /// Moves a post to the trash, from where it can be restored until it is
+    /// purged. Like updates, deletions require the version the caller last
+    /// saw.
+    async fn delete_post(
+        &self,
+        actor: &User,
+        post_id: PostId,
+        expected_version: i64,
+    ) -> Result<(), ServiceError>;
+
+    /// Deletes a post for good, whether or not it is in the trash.
+    async fn delete_post_permanently(
+        &self,
+        actor: &User,
+        post_id: PostId,
+        expected_version: i64,
+    ) -> Result<(), ServiceError>;
+
+    /// The trashed posts `actor` may restore.
+    async fn get_trash(&self, actor: &User) -> Result<Vec<Post>, ServiceError>;
+
+    async fn restore_post(&self, actor: &User, post_id: PostId) -> Result<Post, ServiceError>;
+
+    /// Deletes posts that have been in the trash longer than the retention
+    /// period, returning how many.
+    async fn purge_trash(&self) -> Result<u64, ServiceError>;
+
+    async fn import_posts(&self, posts: &[ImportPostRequest]) -> Result<Vec<Post>, ServiceError>;
+
+    fn stream_posts(&self) -> BoxStream<'static, Result<Post, ServiceError>>;
+
+    /// Changes to posts as they happen, after those since `last_event_id`.
+    /// Starts with a reset if some of those are no longer known.
+    fn subscribe_post_events(
+        &self,
+        last_event_id: Option<EventId>,
+    ) -> BoxStream<'static, StreamEvent>;
+
+    /// The posts created, changed or deleted since the cursor in `query`,
+    /// for clients keeping a copy in sync.
+    async fn get_changes(&self, query: &ChangeFeedQuery) -> Result<ChangeFeedPage, ServiceError>;
+
+    /// Forgets deleted posts whose tombstones are past the retention period,
+    /// returning how many. Clients behind them have to sync from the start.
+    async fn compact_changes(&self) -> Result<u64, ServiceError>;
+
+    /// Every post, the trash included, with its categories, tags and
+    /// comments, for a backup.
+    fn stream_backup_posts(&self) -> BoxStream<'static, Result<ImportPostRequest, ServiceError>>;
+
+    /// Like [`Service::stream_backup_posts`], for a backup requested by
+    /// `actor`.
+    fn export_posts(
+        &self,
+        actor: &User,
+    ) -> Result<BoxStream<'static, Result<ImportPostRequest, ServiceError>>, ServiceError>;
+
+    async fn restore_posts(&self, posts: &[ImportPostRequest]) -> Result<Vec<Post>, ServiceError>;
+
+    async fn get_audit_entries(
+        &self,
+        actor: &User,
+        query: &AuditQuery,
+    ) -> Result<Vec<AuditEntry>, ServiceError>;
+
+    /// Recomputes the hash chain of the whole audit log.
+    async fn verify_audit_log(&self, actor: &User) -> Result<AuditVerification, ServiceError>;
+
+    async fn create_author(
+        &self,
+        actor: &User,
+        input: &CreateAuthorRequest,
+    ) -> Result<Author, ServiceError>;
+
+    async fn get_author(&self, id: AuthorId) -> Result<Author, ServiceError>;
+
+    async fn get_author_posts(&self, id: AuthorId) -> Result<Vec<Post>, ServiceError>;
+
+    /// The newest `page.limit()` posts of each of the authors, older than
+    /// `page.after()` if set, newest first. Lets callers load a page of posts
+    /// for many authors in one go.
+    async fn get_posts_by_authors(
+        &self,
+        ids: &[AuthorId],
+        page: &PostPageQuery,
+    ) -> Result<Vec<Post>, ServiceError>;
+
+    async fn create_user(&self, input: &CreateUserRequest) -> Result<User, ServiceError>;
+
+    async fn login(&self, input: &LoginRequest) -> Result<Session, ServiceError>;
+
+    /// Records a new login attempt against the identity provider.
+    async fn start_oidc_login(&self) -> Result<OidcLogin, ServiceError>;
+
+    /// Completes the login attempt for `state`; each attempt can be taken once.
+    async fn take_oidc_login(&self, state: &OidcState) -> Result<OidcLogin, ServiceError>;
+
+    /// Starts a session for the local user behind a verified identity,
+    /// creating one with `role` on their first login.
+    async fn login_with_identity(
+        &self,
+        identity: &OidcIdentity,
+        role: Role,
+    ) -> Result<Session, ServiceError>;
+
+    /// Completes a login that is waiting for a second factor.
+    async fn complete_login(
+        &self,
+        token: &SessionToken,
+        factor: &SecondFactor,
+    ) -> Result<Session, ServiceError>;
+
+    /// Generates a new authenticator secret, which only takes effect once
+    /// confirmed with [`Service::confirm_totp`].
+    async fn enroll_totp(&self, user: &User) -> Result<TotpEnrollment, ServiceError>;
+
+    /// Turns on two-factor authentication once a code proves the app is set
+    /// up. The returned recovery codes are never shown again.
+    async fn confirm_totp(
+        &self,
+        user: &User,
+        code: &TotpCode,
+    ) -> Result<Vec<RecoveryCode>, ServiceError>;
+
+    async fn logout(&self, token: &SessionToken) -> Result<(), ServiceError>;
+
+    async fn authenticate(&self, token: &SessionToken) -> Result<User, ServiceError>;
+
+    async fn create_api_token(
+        &self,
+        user: &User,
+        input: &CreateApiTokenRequest,
+    ) -> Result<CreatedApiToken, ServiceError>;
+
+    async fn get_api_tokens(&self, user: &User) -> Result<Vec<ApiToken>, ServiceError>;
+
+    async fn revoke_api_token(&self, user: &User, token_id: ApiTokenId)
+    -> Result<(), ServiceError>;
+
+    async fn authenticate_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+    ) -> Result<(User, ApiToken), ServiceError>;
+
+    /// Claims `key` for a request of `actor`. If a request with the key was
+    /// handled before, its response is returned to be replayed instead.
+    async fn claim_idempotency_key(
+        &self,
+        actor: &User,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+    ) -> Result<Option<IdempotentResponse>, ServiceError>;
+
+    /// Keeps the response to the request that claimed `key` for its retries.
+    async fn complete_idempotency_key(
+        &self,
+        actor: &User,
+        key: &IdempotencyKey,
+        response: &IdempotentResponse,
+    ) -> Result<(), ServiceError>;
+
+    /// Frees `key` after a request failed in a way worth retrying.
+    async fn release_idempotency_key(
+        &self,
+        actor: &User,
+        key: &IdempotencyKey,
+    ) -> Result<(), ServiceError>;
+
+    /// Deletes idempotency keys past their expiry, returning how many.
+    async fn expire_idempotency_keys(&self) -> Result<u64, ServiceError>;
+
+    async fn create_webhook(
+        &self,
+        actor: &User,
+        input: &CreateWebhookRequest,
+    ) -> Result<CreatedWebhook, ServiceError>;
+
+    async fn get_webhooks(&self, actor: &User) -> Result<Vec<Webhook>, ServiceError>;
+
+    async fn delete_webhook(&self, actor: &User, webhook_id: WebhookId)
+    -> Result<(), ServiceError>;
+
+    async fn get_webhook_deliveries(
+        &self,
+        actor: &User,
+        webhook_id: WebhookId,
+    ) -> Result<Vec<WebhookDelivery>, ServiceError>;
+
+    /// Sends a delivery again, typically one that is dead.
+    async fn redeliver_webhook(
+        &self,
+        actor: &User,
+        webhook_id: WebhookId,
+        delivery_id: i64,
+    ) -> Result<WebhookDelivery, ServiceError>;
+
+    /// Relays the events waiting in the outbox, returning how many were
+    /// completed by this call rather than elsewhere.
+    async fn dispatch_outbox(&self) -> Result<u64, ServiceError>;
+
+    /// Sends the webhook deliveries that are due, returning how many were
+    /// attempted.
+    async fn deliver_webhooks(&self) -> Result<u64, ServiceError>;
+
+    /// Deletes the delivered and dead webhook deliveries whose retention has
+    /// run out, returning how many.
+    async fn purge_webhook_deliveries(&self) -> Result<u64, ServiceError>;
 }
 
 #[derive(Debug, Error)]
 pub enum ServiceError {
     #[error(transparent)]
     RepositoryError(RepositoryError),
+    #[error("Invalid email or password.")]
+    InvalidCredentials,
+    #[error("Authentication required.")]
+    Unauthenticated,
+    #[error("You are not allowed to perform this action.")]
+    Forbidden,
+    #[error("A one-time code is required to finish logging in.")]
+    SecondFactorRequired,
+    #[error("Invalid or already used one-time code.")]
+    InvalidSecondFactor,
+    #[error("Your role requires two-factor authentication. Enroll an authenticator app first.")]
+    SecondFactorEnrollmentRequired,
+    #[error("Idempotency key was already used for a different request.")]
+    IdempotencyKeyReused,
+    #[error("A request with this idempotency key is still being handled.")]
+    IdempotencyKeyInProgress,
+    #[error(transparent)]
+    WebhookUrlNotAllowed(#[from] WebhookUrlNotAllowedError),
+    #[error(transparent)]
+    PasswordHash(#[from] PasswordHashError),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
 }
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use tonic::{Code, Status, metadata::MetadataValue};
+
+use crate::api::{
+    post::{CreatePostRequest as ApiCreatePostRequest, UpdatePostRequest as ApiUpdatePostRequest},
+    responses::{ApiError, ErrorCode, ProblemDetails},
+};
+use crate::domain::models::{author::Author, post::Post};
+use crate::domain::service::ServiceError;
+
+use super::proto;
+
+/// Metadata key of the stable error code, the `code` of the equivalent
+/// problem of the HTTP API.
+pub const ERROR_CODE: &str = "x-error-code";
+
+impl From<Post> for proto::Post {
+    fn from(value: Post) -> Self {
+        Self {
+            id: value.id().to_string(),
+            title: value.title().to_string(),
+            body: value.body().to_string(),
+            created_at: Some(timestamp(value.created_at())),
+            updated_at: Some(timestamp(value.updated_at())),
+            created_by: value.created_by().map(|id| id.to_string()),
+            version: value.version(),
+            authors: value.authors().iter().cloned().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Author> for proto::Author {
+    fn from(value: Author) -> Self {
+        Self {
+            id: value.id().to_string(),
+            user_id: value.user_id().map(|id| id.to_string()),
+            display_name: value.name().to_string(),
+        }
+    }
+}
+
+impl TryFrom<proto::CreatePostRequest> for ApiCreatePostRequest {
+    type Error = ApiError;
+
+    fn try_from(value: proto::CreatePostRequest) -> Result<Self, Self::Error> {
+        let authors = (!value.author_ids.is_empty())
+            .then(|| parse_ids(&value.author_ids, "author_ids"))
+            .transpose()?;
+
+        Ok(Self {
+            title: value.title,
+            body: value.body,
+            authors,
+        })
+    }
+}
+
+impl TryFrom<proto::UpdatePostRequest> for ApiUpdatePostRequest {
+    type Error = ApiError;
+
+    fn try_from(value: proto::UpdatePostRequest) -> Result<Self, Self::Error> {
+        let authors = value
+            .authors
+            .map(|authors| parse_ids(&authors.ids, "authors"))
+            .transpose()?;
+
+        Ok(Self {
+            title: value.title,
+            body: value.body,
+            authors,
+        })
+    }
+}
+
+impl From<ApiError> for Status {
+    fn from(error: ApiError) -> Self {
+        let code = match &error {
+            ApiError::Unauthorized(..) => Code::Unauthenticated,
+            ApiError::Forbidden(..) => Code::PermissionDenied,
+            ApiError::NotFound(..) => Code::NotFound,
+            // Whatever was asked for is gone for good; start over instead.
+            ApiError::Gone(..) => Code::FailedPrecondition,
+            ApiError::Conflict(..) => Code::AlreadyExists,
+            // Another writer got there first; read the post again and retry.
+            ApiError::PreconditionFailed(..) => Code::Aborted,
+            ApiError::PreconditionRequired(..) => Code::FailedPrecondition,
+            ApiError::PayloadTooLarge(..) => Code::ResourceExhausted,
+            ApiError::UnprocessableEntity(..) | ApiError::InvalidFields(_) => Code::InvalidArgument,
+            ApiError::InternalServerError(_) => Code::Internal,
+        };
+        let problem = ProblemDetails::from(error);
+
+        let mut status = Status::new(code, problem.detail);
+        if let Ok(value) = MetadataValue::try_from(problem.code.as_str()) {
+            status.metadata_mut().insert(ERROR_CODE, value);
+        }
+        status
+    }
+}
+
+impl From<ServiceError> for Status {
+    fn from(error: ServiceError) -> Self {
+        ApiError::from(error).into()
+    }
+}
+
+pub(super) fn parse_id<T: std::str::FromStr>(raw: &str, field: &str) -> Result<T, ApiError> {
+    raw.parse().map_err(|_| {
+        ApiError::UnprocessableEntity(
+            ErrorCode::ValidationFailed,
+            format!("{field} is not a valid id."),
+        )
+    })
+}
+
+fn parse_ids<T: std::str::FromStr>(raw: &[String], field: &str) -> Result<Vec<T>, ApiError> {
+    raw.iter().map(|id| parse_id(id, field)).collect()
+}
+
+fn timestamp(value: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: value.timestamp(),
+        nanos: value.timestamp_subsec_nanos() as i32,
+    }
+}
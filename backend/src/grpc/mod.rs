@@ -0,0 +1,70 @@
+//! gRPC API for other services, next to the HTTP API and backed by the same
+//! [`Service`]. Definitions are in `proto/blog/v1/blog.proto`.
+
+pub mod mappers;
+pub mod service;
+
+use std::{future::Future, net::SocketAddr};
+
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Server, server::Router};
+
+use crate::{
+    domain::{models::post::PostConstraints, service::Service},
+    server::max_request_bytes,
+};
+
+use self::{proto::blog_service_server::BlogServiceServer, service::GrpcBlogService};
+
+pub mod proto {
+    tonic::include_proto!("blog.v1");
+}
+
+/// The message size limit tonic applies by default.
+const DEFAULT_MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+pub struct GrpcServerConfig<'a> {
+    pub port: &'a str,
+    /// Limits on the title and body of posts submitted through the API.
+    pub post_constraints: PostConstraints,
+}
+
+pub struct GrpcServer {
+    router: Router,
+    listener: TcpListener,
+}
+
+impl GrpcServer {
+    pub async fn try_new<S: Service>(
+        service: S,
+        config: GrpcServerConfig<'_>,
+    ) -> Result<Self, anyhow::Error> {
+        let max_message_bytes = max_request_bytes(&config.post_constraints);
+        let router = Server::builder().add_service(
+            BlogServiceServer::new(GrpcBlogService::new(service, config.post_constraints))
+                .max_decoding_message_size(max_message_bytes.max(DEFAULT_MAX_MESSAGE_BYTES)),
+        );
+
+        let addr = format!("127.0.0.1:{}", config.port);
+
+        let listener = TcpListener::bind(&addr).await?;
+
+        Ok(Self { router, listener })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, anyhow::Error> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serves until `shutdown` completes, then lets calls in flight finish.
+    pub async fn run(self, shutdown: impl Future<Output = ()>) -> Result<(), anyhow::Error> {
+        tracing::info!("gRPC server listening on {}", self.listener.local_addr()?);
+
+        self.router
+            .serve_with_incoming_shutdown(TcpListenerStream::new(self.listener), shutdown)
+            .await?;
+
+        Ok(())
+    }
+}
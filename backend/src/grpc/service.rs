@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt, stream::BoxStream};
+use tonic::{Request, Response, Status};
+
+use crate::api::{
+    extractors::CurrentUser,
+    middleware::bearer_token,
+    post::{CreatePostRequest, UpdatePostRequest},
+    responses::{ApiError, ErrorCode},
+};
+use crate::domain::{
+    models::{
+        api_token::Scope,
+        post::{
+            CreatePostRequest as DomainCreatePostRequest, PostConstraints,
+            UpdatePostRequest as DomainUpdatePostRequest,
+        },
+    },
+    service::Service,
+};
+use crate::ids::PostId;
+
+use super::{
+    mappers::parse_id,
+    proto::{self, blog_service_server::BlogService},
+};
+
+/// [`BlogService`] over a [`Service`]. Calls authenticate with an API token
+/// in the `authorization` metadata; reads are open to anyone, as over HTTP.
+pub struct GrpcBlogService<S: Service> {
+    service: Arc<S>,
+    post_constraints: Arc<PostConstraints>,
+}
+
+impl<S: Service> GrpcBlogService<S> {
+    pub fn new(service: S, post_constraints: PostConstraints) -> Self {
+        Self {
+            service: Arc::new(service),
+            post_constraints: Arc::new(post_constraints),
+        }
+    }
+
+    async fn current_user<T>(
+        &self,
+        request: &Request<T>,
+        scope: Scope,
+    ) -> Result<CurrentUser, Status> {
+        let headers = request.metadata().clone().into_headers();
+        let secret = bearer_token(&headers).ok_or_else(|| {
+            ApiError::Unauthorized(
+                ErrorCode::Unauthenticated,
+                "Authentication required.".to_string(),
+            )
+        })?;
+        let (user, api_token) = self.service.authenticate_api_token(&secret).await?;
+        let current_user = CurrentUser::from_api_token(user, api_token);
+        current_user.require_scope(scope)?;
+
+        Ok(current_user)
+    }
+}
+
+#[tonic::async_trait]
+impl<S: Service> BlogService for GrpcBlogService<S> {
+    type ListPostsStream = BoxStream<'static, Result<proto::Post, Status>>;
+
+    async fn create_post(
+        &self,
+        request: Request<proto::CreatePostRequest>,
+    ) -> Result<Response<proto::Post>, Status> {
+        let current_user = self.current_user(&request, Scope::PostsWrite).await?;
+        let input = CreatePostRequest::try_from(request.into_inner())?;
+        let domain_req =
+            DomainCreatePostRequest::try_from((input, self.post_constraints.as_ref()))?;
+
+        let post = self
+            .service
+            .create_post(current_user.user(), &domain_req)
+            .await?;
+
+        Ok(Response::new(post.into()))
+    }
+
+    async fn get_post(
+        &self,
+        request: Request<proto::GetPostRequest>,
+    ) -> Result<Response<proto::Post>, Status> {
+        let id: PostId = parse_id(&request.get_ref().id, "id")?;
+
+        let post = self.service.get_posts_by_id(id).await?;
+
+        Ok(Response::new(post.into()))
+    }
+
+    async fn list_posts(
+        &self,
+        _request: Request<proto::ListPostsRequest>,
+    ) -> Result<Response<Self::ListPostsStream>, Status> {
+        let posts = self
+            .service
+            .stream_posts()
+            .map_ok(proto::Post::from)
+            .map_err(Status::from)
+            .boxed();
+
+        Ok(Response::new(posts))
+    }
+
+    async fn update_post(
+        &self,
+        request: Request<proto::UpdatePostRequest>,
+    ) -> Result<Response<proto::Post>, Status> {
+        let current_user = self.current_user(&request, Scope::PostsWrite).await?;
+        let request = request.into_inner();
+        let id: PostId = parse_id(&request.id, "id")?;
+        let version = request.version;
+        let input = UpdatePostRequest::try_from(request)?;
+        let domain_req =
+            DomainUpdatePostRequest::try_from((input, self.post_constraints.as_ref()))?;
+
+        let post = self
+            .service
+            .update_post(current_user.user(), id, version, &domain_req)
+            .await?;
+
+        Ok(Response::new(post.into()))
+    }
+
+    async fn delete_post(
+        &self,
+        request: Request<proto::DeletePostRequest>,
+    ) -> Result<Response<proto::DeletePostResponse>, Status> {
+        let current_user = self.current_user(&request, Scope::PostsDelete).await?;
+        let request = request.into_inner();
+        let id: PostId = parse_id(&request.id, "id")?;
+
+        if request.permanent {
+            self.service
+                .delete_post_permanently(current_user.user(), id, request.version)
+                .await?;
+        } else {
+            self.service
+                .delete_post(current_user.user(), id, request.version)
+                .await?;
+        }
+
+        Ok(Response::new(proto::DeletePostResponse {}))
+    }
+}
@@ -1,3 +1,8 @@
 use crate::uuid_key;
 
 uuid_key!(PostId);
+uuid_key!(UserId);
+uuid_key!(ApiTokenId);
+uuid_key!(AuthorId);
+uuid_key!(WebhookId);
+uuid_key!(CommentId);
@@ -0,0 +1,140 @@
+const BLOCK_TAGS: [&str; 16] = [
+    "<p",
+    "<div",
+    "<h1",
+    "<h2",
+    "<h3",
+    "<h4",
+    "<h5",
+    "<h6",
+    "<ul",
+    "<ol",
+    "<li",
+    "<pre",
+    "<blockquote",
+    "<table",
+    "<figure",
+    "<hr",
+];
+
+/// Converts WordPress post content into the Markdown we store as a post body.
+pub fn to_markdown(content: &str) -> String {
+    html2md::parse_html(&autop(content)).trim().to_string()
+}
+
+/// WordPress stores classic editor content without paragraph tags and adds
+/// them on render (`wpautop`). Do the same so blank lines survive conversion.
+fn autop(content: &str) -> String {
+    content
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|chunk| !chunk.is_empty())
+        .map(|chunk| {
+            let lowercase = chunk.to_ascii_lowercase();
+            if lowercase.starts_with("<!--")
+                || BLOCK_TAGS.iter().any(|tag| lowercase.starts_with(tag))
+            {
+                chunk.to_string()
+            } else {
+                format!("<p>{}</p>", chunk.replace('\n', "<br />"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rewrites the targets of Markdown links and images through `resolve`,
+/// leaving every target it returns `None` for untouched.
+pub fn rewrite_links(markdown: &str, resolve: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find("](") {
+        let (before, after) = rest.split_at(start + 2);
+        output.push_str(before);
+
+        let Some(end) = link_target_end(after) else {
+            rest = after;
+            continue;
+        };
+
+        let (target, remainder) = after.split_at(end);
+        let (url, title) = match target.split_once(' ') {
+            Some((url, title)) => (url, Some(title)),
+            None => (target, None),
+        };
+
+        match resolve(url) {
+            Some(rewritten) => output.push_str(&rewritten),
+            None => output.push_str(url),
+        }
+        if let Some(title) = title {
+            output.push(' ');
+            output.push_str(title);
+        }
+
+        rest = remainder;
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Finds the `)` closing a link target, allowing balanced parentheses inside.
+fn link_target_end(target: &str) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (index, ch) in target.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            '\n' => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown_wraps_classic_editor_paragraphs() {
+        let content = "First paragraph\nwith a break.\n\nSecond <strong>bold</strong>.";
+
+        let markdown = to_markdown(content);
+
+        assert!(markdown.starts_with("First paragraph"));
+        assert!(markdown.contains("Second **bold**."));
+        assert!(markdown.contains("\n\n"));
+    }
+
+    #[test]
+    fn test_to_markdown_keeps_block_editor_markup() {
+        let content = "<!-- wp:heading -->\n<h2>Title</h2>\n<!-- /wp:heading -->\n\n<!-- wp:paragraph -->\n<p>See <a href=\"https://example.com\">this</a>.</p>\n<!-- /wp:paragraph -->";
+
+        let markdown = to_markdown(content);
+
+        assert!(markdown.contains("Title"));
+        assert!(markdown.contains("[this](https://example.com)"));
+        assert!(!markdown.contains("wp:paragraph"));
+    }
+
+    #[test]
+    fn test_rewrite_links_only_touches_resolved_targets() {
+        let markdown = "[old](https://old.example.com/hello/) and [ext](https://rust-lang.org \"Rust\") ![img](https://old.example.com/a_(b).png)";
+
+        let rewritten = rewrite_links(markdown, |url| {
+            (url == "https://old.example.com/hello/").then(|| "/posts/1".to_string())
+        });
+
+        assert_eq!(
+            rewritten,
+            "[old](/posts/1) and [ext](https://rust-lang.org \"Rust\") ![img](https://old.example.com/a_(b).png)"
+        );
+    }
+}
@@ -0,0 +1,498 @@
+//! Importer for WordPress eXtended RSS (WXR) exports.
+//!
+//! The export is streamed twice: the first pass decides which items become
+//! posts and assigns their [`PostId`]s, so the second pass can rewrite links
+//! between imported posts while converting their content. All posts are then
+//! written in a single transaction.
+//!
+//! Every WordPress author becomes an author profile, found again by login when
+//! a later export of the same site is imported, and each post is credited to
+//! the profile of its `dc:creator`. Categories, tags and comments are saved
+//! with their post, and listed in the [`ImportReport`] next to the ID and URL
+//! mapping.
+//!
+//! Items whose title is already taken by a post in the blog are skipped, so an
+//! export can be imported again to pick up what failed the first time. Links
+//! to them, and their entry in the report, point at the post that is already
+//! there.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, Seek},
+};
+
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{info, instrument};
+use url::Url;
+
+use crate::{
+    domain::{
+        models::{
+            author::{Author, AuthorName},
+            post::{ImportPostRequest, Post, PostBody, PostComment, PostTerm, PostTitle, TermKind},
+        },
+        service::{Service, ServiceError},
+    },
+    ids::{AuthorId, CommentId, PostId},
+};
+
+use self::wxr::{WxrAuthor, WxrComment, WxrEntry, WxrError, WxrItem, WxrReader, WxrTermKind};
+
+pub mod html;
+pub mod wxr;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Failed to read WXR export: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Wxr(#[from] WxrError),
+    #[error(transparent)]
+    Service(#[from] ServiceError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    pub site_url: Option<String>,
+    pub dry_run: bool,
+    pub authors: Vec<WxrAuthor>,
+    pub posts: Vec<ImportedPost>,
+    pub skipped: Vec<SkippedItem>,
+}
+
+/// Maps a WordPress item onto the post created for it. `urls` holds every
+/// address the item was reachable under, ready to be turned into redirects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedPost {
+    pub wordpress_id: Option<u64>,
+    pub post_id: PostId,
+    pub path: String,
+    pub title: String,
+    pub slug: Option<String>,
+    pub urls: Vec<String>,
+    pub author: Option<String>,
+    pub author_id: Option<AuthorId>,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    pub comments: Vec<WxrComment>,
+}
+
+/// `post_id` is the post already in the blog that the item was matched to,
+/// if any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedItem {
+    pub wordpress_id: Option<u64>,
+    pub title: String,
+    pub reason: SkipReason,
+    pub post_id: Option<PostId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    NotAPublishedPost,
+    EmptyTitle,
+    EmptyBody,
+    DuplicateTitle,
+    TitleTaken,
+}
+
+pub struct WordPressImporter<'a, S: Service> {
+    service: &'a S,
+    dry_run: bool,
+}
+
+impl<'a, S: Service> WordPressImporter<'a, S> {
+    pub fn new(service: &'a S) -> Self {
+        Self {
+            service,
+            dry_run: false,
+        }
+    }
+
+    /// Parses and converts the export without writing anything.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    #[instrument(name = "wordpress_import", skip(self, source), fields(dry_run = self.dry_run))]
+    pub async fn import<R: BufRead + Seek>(
+        &self,
+        mut source: R,
+    ) -> Result<ImportReport, ImportError> {
+        let existing = self.existing_titles().await?;
+        let index = LinkIndex::build(&mut source, &existing)?;
+        source.rewind()?;
+
+        let mut reader = WxrReader::new(source);
+        let mut report = ImportReport {
+            site_url: index.site.as_ref().map(Url::to_string),
+            dry_run: self.dry_run,
+            authors: Vec::new(),
+            posts: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let mut posts = Vec::new();
+        let mut authors = HashMap::new();
+        let mut decisions = index.decisions.iter();
+
+        for entry in reader.by_ref() {
+            let item = match entry? {
+                WxrEntry::Author(author) => {
+                    authors.insert(
+                        author.login.clone(),
+                        author_profile(&author, index.site.as_ref()),
+                    );
+                    report.authors.push(author);
+                    continue;
+                }
+                WxrEntry::Item(item) => item,
+            };
+
+            // Both passes read the same document, so every item has a decision.
+            match decisions
+                .next()
+                .copied()
+                .unwrap_or(Err((SkipReason::NotAPublishedPost, None)))
+            {
+                Ok(post_id) => {
+                    let body = html::rewrite_links(&html::to_markdown(&item.content), |url| {
+                        index.resolve(url)
+                    });
+                    let (title, body) = match validate(&item.title, &body) {
+                        Ok(validated) => validated,
+                        Err(reason) => {
+                            report.skipped.push(SkippedItem {
+                                wordpress_id: item.wordpress_id,
+                                title: item.title,
+                                reason,
+                                post_id: None,
+                            });
+                            continue;
+                        }
+                    };
+                    // Authors missing from the export's author list still get
+                    // a profile, named after their login.
+                    let author = item.creator.as_ref().and_then(|login| {
+                        authors
+                            .entry(login.clone())
+                            .or_insert_with(|| {
+                                author_profile(
+                                    &WxrAuthor {
+                                        login: login.clone(),
+                                        ..Default::default()
+                                    },
+                                    index.site.as_ref(),
+                                )
+                            })
+                            .clone()
+                    });
+                    let published_at = item.published_at.unwrap_or_else(Utc::now);
+                    let post = Post::new(post_id, title.clone(), body, published_at)
+                        .with_authors(author.iter().cloned().collect());
+
+                    posts.push(
+                        ImportPostRequest::new(post)
+                            .with_terms(post_terms(&item))
+                            .with_comments(post_comments(&item, published_at)),
+                    );
+                    report.posts.push(ImportedPost::new(
+                        post_id,
+                        title,
+                        author.map(|a| a.id()),
+                        *item,
+                    ));
+                }
+                Err((reason, post_id)) => report.skipped.push(SkippedItem {
+                    wordpress_id: item.wordpress_id,
+                    title: item.title,
+                    reason,
+                    post_id,
+                }),
+            }
+        }
+
+        if !self.dry_run {
+            let imported = self.service.import_posts(&posts).await?;
+
+            // Authors imported before keep the profile created back then.
+            let bylines: HashMap<_, _> = imported
+                .iter()
+                .map(|post| (post.id(), post.authors().first().map(Author::id)))
+                .collect();
+            for post in &mut report.posts {
+                if let Some(author_id) = bylines.get(&post.post_id) {
+                    post.author_id = *author_id;
+                }
+            }
+        }
+
+        info!(
+            imported = report.posts.len(),
+            skipped = report.skipped.len(),
+            "✅ WordPress import finished"
+        );
+
+        Ok(report)
+    }
+
+    /// The titles of the posts already in the blog, which imported posts
+    /// cannot take.
+    async fn existing_titles(&self) -> Result<HashMap<String, PostId>, ImportError> {
+        Ok(self
+            .service
+            .stream_posts()
+            .map_ok(|post| (post.title().to_string(), post.id()))
+            .try_collect()
+            .await?)
+    }
+}
+
+impl ImportedPost {
+    fn new(post_id: PostId, title: PostTitle, author_id: Option<AuthorId>, item: WxrItem) -> Self {
+        let terms = |kind| {
+            item.terms_of(kind)
+                .map(|term| term.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let categories = terms(WxrTermKind::Category);
+        let tags = terms(WxrTermKind::Tag);
+
+        let mut urls: Vec<String> = item.link.iter().chain(item.guid.iter()).cloned().collect();
+        urls.dedup();
+
+        Self {
+            wordpress_id: item.wordpress_id,
+            post_id,
+            path: post_path(post_id),
+            title: title.to_string(),
+            slug: item.slug,
+            urls,
+            author: item.creator,
+            author_id,
+            categories,
+            tags,
+            comments: item.comments,
+        }
+    }
+}
+
+fn post_terms(item: &WxrItem) -> Vec<PostTerm> {
+    item.terms
+        .iter()
+        .map(|term| {
+            let kind = match term.kind {
+                WxrTermKind::Category => TermKind::Category,
+                WxrTermKind::Tag => TermKind::Tag,
+            };
+            PostTerm::new(kind, &term.name, term.slug.as_deref())
+        })
+        .collect()
+}
+
+/// Replies are linked to the comment they answer through its WordPress ID.
+/// Comments without a date are dated like their post.
+fn post_comments(item: &WxrItem, published_at: DateTime<Utc>) -> Vec<PostComment> {
+    let ids: Vec<CommentId> = item.comments.iter().map(|_| CommentId::new()).collect();
+    let mut by_wordpress_id = HashMap::new();
+    for (comment, id) in item.comments.iter().zip(&ids) {
+        if let Some(wordpress_id) = comment.wordpress_id {
+            by_wordpress_id.entry(wordpress_id).or_insert(*id);
+        }
+    }
+
+    item.comments
+        .iter()
+        .zip(ids)
+        .map(|(comment, id)| {
+            PostComment::new(
+                id,
+                &html::to_markdown(&comment.content),
+                comment.date.unwrap_or(published_at),
+            )
+            .with_parent_id(
+                comment
+                    .parent_id
+                    .and_then(|parent_id| by_wordpress_id.get(&parent_id).copied()),
+            )
+            .with_author(
+                comment.author.clone(),
+                comment.author_email.clone(),
+                comment.author_url.clone(),
+            )
+            .with_approved(comment.approved)
+        })
+        .collect()
+}
+
+/// Prefers the display name, then the full name, then the login. Authors are
+/// known by their login on the site they were exported from.
+fn author_profile(author: &WxrAuthor, site: Option<&Url>) -> Option<Author> {
+    let full_name = [&author.first_name, &author.last_name]
+        .into_iter()
+        .flatten()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let name = [author.display_name.as_deref(), Some(full_name.as_str())]
+        .into_iter()
+        .flatten()
+        .chain([author.login.as_str()])
+        .find_map(|name| AuthorName::try_new(name).ok())?;
+
+    let import_key = match site.and_then(Url::host_str) {
+        Some(host) => format!("wordpress:{host}:{}", author.login),
+        None => format!("wordpress:{}", author.login),
+    };
+
+    Some(Author::new(AuthorId::new(), name).with_import_key(Some(import_key)))
+}
+
+fn post_path(post_id: PostId) -> String {
+    format!("/posts/{post_id}")
+}
+
+/// Result of the first pass: which items get imported under which ID, and
+/// every old address that should now point at the new post.
+struct LinkIndex {
+    site: Option<Url>,
+    decisions: Vec<Result<PostId, (SkipReason, Option<PostId>)>>,
+    by_wordpress_id: HashMap<u64, PostId>,
+    by_address: HashMap<String, PostId>,
+}
+
+impl LinkIndex {
+    fn build<R: BufRead>(
+        source: R,
+        existing: &HashMap<String, PostId>,
+    ) -> Result<Self, ImportError> {
+        let mut reader = WxrReader::new(source);
+        let mut decisions = Vec::new();
+        let mut titles = HashSet::new();
+        let mut by_wordpress_id = HashMap::new();
+        let mut urls = Vec::new();
+
+        for entry in reader.by_ref() {
+            let WxrEntry::Item(item) = entry? else {
+                continue;
+            };
+
+            let decision = classify(&item, &mut titles, existing);
+
+            // Links to a post that is already in the blog lead to that post.
+            if let Ok(post_id) | Err((_, Some(post_id))) = decision {
+                if let Some(wordpress_id) = item.wordpress_id {
+                    by_wordpress_id.insert(wordpress_id, post_id);
+                }
+                urls.extend(
+                    item.link
+                        .into_iter()
+                        .chain(item.guid)
+                        .map(|url| (url, post_id)),
+                );
+            }
+
+            decisions.push(decision);
+        }
+
+        let site = reader
+            .channel()
+            .site_url()
+            .and_then(|url| Url::parse(url).ok());
+
+        // `?p=<id>` style guids are resolved through the WordPress ID instead;
+        // keyed by address they would all collapse onto the site root.
+        let by_address = urls
+            .into_iter()
+            .filter_map(|(url, post_id)| Url::parse(&url).ok().map(|url| (url, post_id)))
+            .filter(|(url, _)| wordpress_id_param(url).is_none())
+            .map(|(url, post_id)| (address_key(&url), post_id))
+            .collect();
+
+        Ok(Self {
+            site,
+            decisions,
+            by_wordpress_id,
+            by_address,
+        })
+    }
+
+    fn resolve(&self, target: &str) -> Option<String> {
+        let url = match &self.site {
+            Some(site) => site.join(target).ok()?,
+            None => Url::parse(target).ok()?,
+        };
+
+        if let Some(site) = &self.site
+            && url.host_str() != site.host_str()
+        {
+            return None;
+        }
+
+        let post_id = match wordpress_id_param(&url) {
+            Some(wordpress_id) => self.by_wordpress_id.get(&wordpress_id)?,
+            None => self.by_address.get(&address_key(&url))?,
+        };
+
+        Some(match url.fragment() {
+            Some(fragment) => format!("{}#{fragment}", post_path(*post_id)),
+            None => post_path(*post_id),
+        })
+    }
+}
+
+/// Picks the ID of the post an item becomes, or why it is skipped along with
+/// the existing post it matches.
+fn classify(
+    item: &WxrItem,
+    titles: &mut HashSet<String>,
+    existing: &HashMap<String, PostId>,
+) -> Result<PostId, (SkipReason, Option<PostId>)> {
+    if !item.is_published_post() {
+        return Err((SkipReason::NotAPublishedPost, None));
+    }
+
+    let (title, _) = validate(&item.title, &html::to_markdown(&item.content))
+        .map_err(|reason| (reason, None))?;
+
+    let title = title.to_string();
+    if let Some(post_id) = existing.get(&title) {
+        return Err((SkipReason::TitleTaken, Some(*post_id)));
+    }
+    if !titles.insert(title) {
+        return Err((SkipReason::DuplicateTitle, None));
+    }
+
+    Ok(PostId::new())
+}
+
+/// The title and body of an item as they are stored, which is also the form
+/// titles are compared in for duplicates.
+fn validate(title: &str, body: &str) -> Result<(PostTitle, PostBody), SkipReason> {
+    let title = PostTitle::try_new(title).map_err(|_| SkipReason::EmptyTitle)?;
+    let body = PostBody::try_new(body).map_err(|_| SkipReason::EmptyBody)?;
+
+    Ok((title, body))
+}
+
+fn wordpress_id_param(url: &Url) -> Option<u64> {
+    url.query_pairs()
+        .find(|(key, _)| key == "p" || key == "page_id")
+        .and_then(|(_, value)| value.parse().ok())
+}
+
+/// Scheme, query and trailing slash do not change which post a permalink
+/// points to, so they are left out of the lookup key.
+fn address_key(url: &Url) -> String {
+    format!(
+        "{}{}",
+        url.host_str().unwrap_or_default(),
+        url.path().trim_end_matches('/')
+    )
+}
@@ -0,0 +1,442 @@
+use std::io::BufRead;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::{
+    Reader, XmlVersion,
+    escape::resolve_predefined_entity,
+    events::{BytesStart, Event},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WxrError {
+    #[error("Malformed WXR document: {0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("Malformed WXR document: unknown entity &{0};")]
+    UnknownEntity(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WxrChannel {
+    pub link: Option<String>,
+    pub base_site_url: Option<String>,
+    pub base_blog_url: Option<String>,
+}
+
+impl WxrChannel {
+    /// The URL internal links are resolved against, preferring the blog URL
+    /// WordPress itself reports over the feed's `<link>`.
+    pub fn site_url(&self) -> Option<&str> {
+        self.base_blog_url
+            .as_deref()
+            .or(self.link.as_deref())
+            .or(self.base_site_url.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WxrAuthor {
+    pub login: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WxrTermKind {
+    Category,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WxrTerm {
+    pub kind: WxrTermKind,
+    pub slug: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WxrComment {
+    pub wordpress_id: Option<u64>,
+    pub parent_id: Option<u64>,
+    pub author: Option<String>,
+    pub author_email: Option<String>,
+    pub author_url: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub content: String,
+    pub approved: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WxrItem {
+    pub wordpress_id: Option<u64>,
+    pub title: String,
+    pub link: Option<String>,
+    pub guid: Option<String>,
+    pub creator: Option<String>,
+    pub content: String,
+    pub excerpt: String,
+    pub slug: Option<String>,
+    pub status: Option<String>,
+    pub post_type: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub terms: Vec<WxrTerm>,
+    pub comments: Vec<WxrComment>,
+}
+
+impl WxrItem {
+    pub fn is_published_post(&self) -> bool {
+        self.post_type.as_deref() == Some("post") && self.status.as_deref() == Some("publish")
+    }
+
+    pub fn terms_of(&self, kind: WxrTermKind) -> impl Iterator<Item = &WxrTerm> {
+        self.terms.iter().filter(move |term| term.kind == kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WxrEntry {
+    Author(WxrAuthor),
+    Item(Box<WxrItem>),
+}
+
+/// Pull parser over a WordPress eXtended RSS export.
+///
+/// Entries are yielded one at a time as their closing tag is read, so only a
+/// single item is ever held in memory. Channel level metadata is collected
+/// along the way and exposed through [`WxrReader::channel`].
+pub struct WxrReader<R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    path: Vec<String>,
+    text: String,
+    channel: WxrChannel,
+    author: Option<WxrAuthor>,
+    item: Option<WxrItem>,
+    comment: Option<WxrComment>,
+    term: Option<(WxrTermKind, Option<String>)>,
+    pub_date: Option<DateTime<Utc>>,
+    done: bool,
+}
+
+impl<R: BufRead> WxrReader<R> {
+    pub fn new(source: R) -> Self {
+        Self {
+            reader: Reader::from_reader(source),
+            buf: Vec::new(),
+            path: Vec::new(),
+            text: String::new(),
+            channel: WxrChannel::default(),
+            author: None,
+            item: None,
+            comment: None,
+            term: None,
+            pub_date: None,
+            done: false,
+        }
+    }
+
+    pub fn channel(&self) -> &WxrChannel {
+        &self.channel
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.path.iter().rev().nth(1).map(String::as_str)
+    }
+
+    fn read_entry(&mut self) -> Result<Option<WxrEntry>, WxrError> {
+        loop {
+            self.buf.clear();
+
+            match self.reader.read_event_into(&mut self.buf)?.into_owned() {
+                Event::Start(start) => {
+                    let name = start.name().as_ref().to_string();
+                    self.start_element(&name, &start);
+                    self.path.push(name);
+                    self.text.clear();
+                }
+                Event::Text(text) => self.text.push_str(&text.xml10_content()),
+                Event::CData(cdata) => self.text.push_str(&cdata.xml10_content()),
+                Event::GeneralRef(reference) => match reference.resolve_char_ref()? {
+                    Some(ch) => self.text.push(ch),
+                    None => {
+                        let entity = resolve_predefined_entity(&reference)
+                            .ok_or_else(|| WxrError::UnknownEntity(reference.to_string()))?;
+                        self.text.push_str(entity);
+                    }
+                },
+                Event::End(_) => {
+                    let entry = self.end_element();
+                    self.path.pop();
+                    self.text.clear();
+
+                    if entry.is_some() {
+                        return Ok(entry);
+                    }
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    fn start_element(&mut self, name: &str, start: &BytesStart) {
+        let parent = self.path.last().map(String::as_str);
+
+        match (parent, name) {
+            (Some("channel"), "item") => {
+                self.item = Some(WxrItem::default());
+                self.pub_date = None;
+            }
+            (Some("channel"), "wp:author") => self.author = Some(WxrAuthor::default()),
+            (Some("item"), "wp:comment") => self.comment = Some(WxrComment::default()),
+            (Some("item"), "category") => {
+                let attribute = |key: &str| {
+                    start
+                        .try_get_attribute(key)
+                        .ok()
+                        .flatten()
+                        .and_then(|attr| attr.normalized_value(XmlVersion::Implicit1_0).ok())
+                        .map(|value| value.into_owned())
+                };
+
+                self.term = match attribute("domain").as_deref() {
+                    Some("category") => Some((WxrTermKind::Category, attribute("nicename"))),
+                    Some("post_tag") => Some((WxrTermKind::Tag, attribute("nicename"))),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn end_element(&mut self) -> Option<WxrEntry> {
+        let name = self.path.last()?.clone();
+        let text = std::mem::take(&mut self.text);
+
+        match (self.parent(), name.as_str()) {
+            (Some("channel"), "item") => {
+                let mut item = self.item.take()?;
+                item.published_at = item.published_at.or(self.pub_date.take());
+                return Some(WxrEntry::Item(Box::new(item)));
+            }
+            (Some("channel"), "wp:author") => return self.author.take().map(WxrEntry::Author),
+            (Some("channel"), "link") => self.channel.link = non_empty(text),
+            (Some("channel"), "wp:base_site_url") => self.channel.base_site_url = non_empty(text),
+            (Some("channel"), "wp:base_blog_url") => self.channel.base_blog_url = non_empty(text),
+            (Some("wp:author"), field) => {
+                if let Some(author) = self.author.as_mut() {
+                    match field {
+                        "wp:author_login" => author.login = text.trim().to_string(),
+                        "wp:author_email" => author.email = non_empty(text),
+                        "wp:author_display_name" => author.display_name = non_empty(text),
+                        "wp:author_first_name" => author.first_name = non_empty(text),
+                        "wp:author_last_name" => author.last_name = non_empty(text),
+                        _ => {}
+                    }
+                }
+            }
+            (Some("item"), "wp:comment") => {
+                if let (Some(item), Some(comment)) = (self.item.as_mut(), self.comment.take()) {
+                    item.comments.push(comment);
+                }
+            }
+            (Some("item"), "category") => {
+                if let (Some(item), Some((kind, slug))) = (self.item.as_mut(), self.term.take()) {
+                    item.terms.push(WxrTerm {
+                        kind,
+                        slug,
+                        name: text.trim().to_string(),
+                    });
+                }
+            }
+            (Some("item"), "pubDate") => self.pub_date = parse_rfc2822(&text),
+            (Some("item"), field) => {
+                if let Some(item) = self.item.as_mut() {
+                    match field {
+                        "title" => item.title = text.trim().to_string(),
+                        "link" => item.link = non_empty(text),
+                        "guid" => item.guid = non_empty(text),
+                        "dc:creator" => item.creator = non_empty(text),
+                        "content:encoded" => item.content = text,
+                        "excerpt:encoded" => item.excerpt = text,
+                        "wp:post_id" => item.wordpress_id = text.trim().parse().ok(),
+                        "wp:post_name" => item.slug = non_empty(text),
+                        "wp:status" => item.status = non_empty(text),
+                        "wp:post_type" => item.post_type = non_empty(text),
+                        "wp:post_date_gmt" => item.published_at = parse_wordpress_date(&text),
+                        _ => {}
+                    }
+                }
+            }
+            (Some("wp:comment"), field) => {
+                if let Some(comment) = self.comment.as_mut() {
+                    match field {
+                        "wp:comment_id" => comment.wordpress_id = text.trim().parse().ok(),
+                        "wp:comment_parent" => {
+                            comment.parent_id = text.trim().parse().ok().filter(|id| *id != 0)
+                        }
+                        "wp:comment_author" => comment.author = non_empty(text),
+                        "wp:comment_author_email" => comment.author_email = non_empty(text),
+                        "wp:comment_author_url" => comment.author_url = non_empty(text),
+                        "wp:comment_date_gmt" => comment.date = parse_wordpress_date(&text),
+                        "wp:comment_content" => comment.content = text,
+                        "wp:comment_approved" => comment.approved = text.trim() == "1",
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for WxrReader<R> {
+    type Item = Result<WxrEntry, WxrError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let entry = self.read_entry().transpose();
+        self.done = !matches!(entry, Some(Ok(_)));
+        entry
+    }
+}
+
+fn non_empty(text: String) -> Option<String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+/// WordPress writes `0000-00-00 00:00:00` for posts that were never published.
+fn parse_wordpress_date(raw: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(raw.trim(), "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+fn parse_rfc2822(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(raw.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<rss version="2.0"
+    xmlns:excerpt="http://wordpress.org/export/1.2/excerpt/"
+    xmlns:content="http://purl.org/rss/1.0/modules/content/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+    <title>Legacy</title>
+    <link>https://legacy.example.com</link>
+    <wp:base_blog_url>https://legacy.example.com</wp:base_blog_url>
+    <wp:author>
+        <wp:author_id>1</wp:author_id>
+        <wp:author_login><![CDATA[tommy]]></wp:author_login>
+        <wp:author_display_name><![CDATA[Tommy N]]></wp:author_display_name>
+    </wp:author>
+    <item>
+        <title>Fish &amp; Chips</title>
+        <link>https://legacy.example.com/2020/01/fish-and-chips/</link>
+        <pubDate>Thu, 02 Jan 2020 10:00:00 +0000</pubDate>
+        <dc:creator><![CDATA[tommy]]></dc:creator>
+        <content:encoded><![CDATA[<p>Hello</p>]]></content:encoded>
+        <wp:post_id>12</wp:post_id>
+        <wp:post_date_gmt><![CDATA[2020-01-02 09:30:00]]></wp:post_date_gmt>
+        <wp:post_name><![CDATA[fish-and-chips]]></wp:post_name>
+        <wp:status><![CDATA[publish]]></wp:status>
+        <wp:post_type><![CDATA[post]]></wp:post_type>
+        <category domain="category" nicename="food"><![CDATA[Food]]></category>
+        <category domain="post_tag" nicename="uk"><![CDATA[UK]]></category>
+        <wp:postmeta>
+            <wp:meta_key><![CDATA[_edit_last]]></wp:meta_key>
+            <wp:meta_value><![CDATA[1]]></wp:meta_value>
+        </wp:postmeta>
+        <wp:comment>
+            <wp:comment_id>3</wp:comment_id>
+            <wp:comment_author><![CDATA[Reader]]></wp:comment_author>
+            <wp:comment_date_gmt><![CDATA[2020-01-03 08:00:00]]></wp:comment_date_gmt>
+            <wp:comment_content><![CDATA[Nice!]]></wp:comment_content>
+            <wp:comment_approved><![CDATA[1]]></wp:comment_approved>
+            <wp:comment_parent>0</wp:comment_parent>
+        </wp:comment>
+    </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn test_wxr_reader_yields_authors_and_items() {
+        let mut reader = WxrReader::new(EXPORT.as_bytes());
+
+        let author = reader.next().unwrap().unwrap();
+        assert_eq!(
+            author,
+            WxrEntry::Author(WxrAuthor {
+                login: "tommy".to_string(),
+                display_name: Some("Tommy N".to_string()),
+                ..Default::default()
+            })
+        );
+
+        let WxrEntry::Item(item) = reader.next().unwrap().unwrap() else {
+            panic!("Expected an item");
+        };
+
+        assert_eq!(item.title, "Fish & Chips");
+        assert_eq!(item.wordpress_id, Some(12));
+        assert_eq!(item.content, "<p>Hello</p>");
+        assert!(item.is_published_post());
+        assert_eq!(
+            item.published_at,
+            parse_wordpress_date("2020-01-02 09:30:00")
+        );
+        assert_eq!(item.terms_of(WxrTermKind::Category).count(), 1);
+        assert_eq!(item.terms_of(WxrTermKind::Tag).next().unwrap().name, "UK");
+        assert_eq!(item.comments.len(), 1);
+        assert_eq!(item.comments[0].content, "Nice!");
+        assert!(item.comments[0].approved);
+        assert_eq!(item.comments[0].parent_id, None);
+
+        assert!(reader.next().is_none());
+        assert_eq!(
+            reader.channel().site_url(),
+            Some("https://legacy.example.com")
+        );
+    }
+
+    #[test]
+    fn test_wxr_reader_falls_back_to_pub_date_for_unpublished_gmt_date() {
+        let export = EXPORT.replace("2020-01-02 09:30:00", "0000-00-00 00:00:00");
+        let item = WxrReader::new(export.as_bytes())
+            .find_map(|entry| match entry.unwrap() {
+                WxrEntry::Item(item) => Some(item),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            item.published_at,
+            parse_rfc2822("Thu, 02 Jan 2020 10:00:00 +0000")
+        );
+    }
+}
@@ -1,9 +1,16 @@
 pub mod api;
+pub mod backup;
+pub mod cli;
 pub mod config;
 pub mod db;
 pub mod domain;
+pub mod grpc;
 pub mod ids;
+pub mod import;
 pub mod macros;
+pub mod metrics;
+pub mod oidc;
 pub mod repository;
+pub mod request_id;
 pub mod server;
 pub mod service;
@@ -114,5 +114,22 @@ macro_rules! uuid_key {
                 self.0.encode_by_ref(buf)
             }
         }
+
+        // OpenAPI schema, a string in UUID format
+        impl utoipa::PartialSchema for $TypeName {
+            fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+                utoipa::openapi::ObjectBuilder::new()
+                    .schema_type(utoipa::openapi::schema::Type::String)
+                    .format(Some(utoipa::openapi::SchemaFormat::KnownFormat(
+                        utoipa::openapi::KnownFormat::Uuid,
+                    )))
+                    .into()
+            }
+        }
+
+        impl utoipa::ToSchema for $TypeName {}
+
+        // GraphQL scalar, a UUID string like in JSON
+        async_graphql::scalar!($TypeName);
     };
 }
@@ -1,20 +1,178 @@
+use anyhow::anyhow;
 use backend::{
+    api::{
+        cache::{CacheConfig, CachePolicy},
+        post::{POST_PATH, POSTS_PATH, POSTS_SURROGATE_KEY},
+    },
+    cli::{self, Cli, Command},
     config::Config,
     db::postgres::Postgres,
-    server::{HttpServer, HttpServerConfig},
-    service::BlogService,
+    domain::{
+        models::{
+            post::PostConstraints,
+            webhook::{DEFAULT_WEBHOOK_INITIAL_BACKOFF, WebhookRetryPolicy, WebhookTargetPolicy},
+        },
+        policy::SecondFactorPolicy,
+    },
+    grpc::{GrpcServer, GrpcServerConfig},
+    metrics::Metrics,
+    server::{HttpServer, HttpServerConfig, MetricsServer},
+    service::{
+        BlogService,
+        changes::compact_changes_periodically,
+        idempotency::expire_idempotency_keys_periodically,
+        outbox::dispatch_outbox_periodically,
+        purge::purge_trash_periodically,
+        webhooks::{
+            WEBHOOK_DELIVERY_PURGE_INTERVAL, deliver_webhooks_periodically,
+            purge_webhook_deliveries_periodically,
+        },
+    },
 };
+use chrono::Duration;
+use clap::Parser;
+use tokio::sync::oneshot;
+use tracing::error;
+
+/// Logs of every server and background task, from the start. A backup may
+/// be exported to stdout, so commands other than `serve` log to stderr.
+fn init_tracing(command: &Command) {
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter("info")
+        .with_target(false);
+    match command {
+        Command::Serve => subscriber.with_writer(std::io::stdout).init(),
+        _ => subscriber.with_writer(std::io::stderr).init(),
+    }
+
+    tracing::info!("🔧 Tracing initialized");
+}
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or_default();
+    init_tracing(&command);
     let config = Config::from_env();
     let postgres = Postgres::try_new(&config.database_url).await?;
-    let blog_service = BlogService::new(postgres);
+    let metrics = Metrics::new().with_pool(postgres.pool().clone());
+    let blog_service = BlogService::new(postgres.with_metrics(metrics.clone()))
+        .with_metrics(metrics.clone())
+        .with_session_ttl(Duration::hours(config.session_ttl_hours))
+        .with_trash_retention(Duration::days(config.trash_retention_days))
+        .with_tombstone_retention(Duration::days(config.tombstone_retention_days))
+        .with_idempotency_key_ttl(Duration::hours(config.idempotency_key_ttl_hours))
+        .with_second_factor_policy(SecondFactorPolicy::new(config.totp_required_roles))
+        .with_event_log_capacity(config.event_log_capacity)
+        .with_webhook_retry_policy(WebhookRetryPolicy::new(
+            config.webhook_max_attempts,
+            DEFAULT_WEBHOOK_INITIAL_BACKOFF,
+        ))
+        .with_webhook_timeout(std::time::Duration::from_secs(
+            config.webhook_timeout_seconds,
+        ))
+        .with_webhook_target_policy(WebhookTargetPolicy::new(config.webhook_allowed_hosts))
+        .with_webhook_delivery_retention(Duration::days(config.webhook_delivery_retention_days));
+
+    match command {
+        Command::Serve => {
+            let port_str = config.port.to_string();
+            let grpc_port_str = config.grpc_port.to_string();
+            let post_constraints = PostConstraints::new()
+                .with_title_length(config.post_title_min_chars, config.post_title_max_chars)
+                .with_body_max_bytes(config.post_body_max_bytes);
+            let server_config = HttpServerConfig {
+                port: &port_str,
+                secure_cookies: config.cookie_secure,
+                oidc: config.oidc,
+                cache: CacheConfig::new()
+                    .with_route(
+                        POSTS_PATH,
+                        CachePolicy::try_new(&config.post_list_cache_control)?
+                            .with_surrogate_key(POSTS_SURROGATE_KEY),
+                    )
+                    .with_route(POST_PATH, CachePolicy::try_new(&config.post_cache_control)?),
+                post_constraints: post_constraints.clone(),
+                graphiql: config.graphiql,
+                sse_heartbeat: config.sse_heartbeat,
+                metrics: metrics.clone(),
+                metrics_route: config.metrics_public,
+            };
+
+            if let Some(metrics_port) = config.metrics_port {
+                let metrics_server =
+                    MetricsServer::try_new(metrics, &metrics_port.to_string()).await?;
+                tokio::spawn(async move {
+                    if let Err(err) = metrics_server.run().await {
+                        error!(?err, "Metrics server failed");
+                    }
+                });
+            }
+
+            tokio::spawn(blog_service.clone().relay_post_changes().await);
+            tokio::spawn(purge_trash_periodically(
+                blog_service.clone(),
+                std::time::Duration::from_secs(config.trash_purge_interval_minutes * 60),
+            ));
+            tokio::spawn(compact_changes_periodically(
+                blog_service.clone(),
+                std::time::Duration::from_secs(config.change_compaction_interval_minutes * 60),
+            ));
+            tokio::spawn(expire_idempotency_keys_periodically(
+                blog_service.clone(),
+                std::time::Duration::from_secs(
+                    config.idempotency_key_cleanup_interval_minutes * 60,
+                ),
+            ));
+            tokio::spawn(dispatch_outbox_periodically(
+                blog_service.clone(),
+                std::time::Duration::from_secs(config.outbox_dispatch_interval_seconds),
+            ));
+            tokio::spawn(deliver_webhooks_periodically(
+                blog_service.clone(),
+                std::time::Duration::from_secs(config.webhook_delivery_interval_seconds),
+            ));
+            tokio::spawn(purge_webhook_deliveries_periodically(
+                blog_service.clone(),
+                WEBHOOK_DELIVERY_PURGE_INTERVAL,
+            ));
+            let grpc_server = GrpcServer::try_new(
+                blog_service.clone(),
+                GrpcServerConfig {
+                    port: &grpc_port_str,
+                    post_constraints,
+                },
+            )
+            .await?;
+            let http_server = HttpServer::try_new(blog_service, server_config).await?;
 
-    let port_str = config.port.to_string();
-    let server_config = HttpServerConfig { port: &port_str };
+            // The gRPC server stops once the HTTP server has drained, and
+            // only stops by itself if it fails, which stops the process.
+            let (shutdown, stopped) = oneshot::channel();
+            let mut grpc = tokio::spawn(grpc_server.run(async {
+                stopped.await.ok();
+            }));
+            tokio::select! {
+                result = http_server.run() => {
+                    let _ = shutdown.send(());
+                    grpc.await??;
 
-    let http_server = HttpServer::try_new(blog_service, server_config).await?;
+                    result
+                }
+                result = &mut grpc => {
+                    result??;
 
-    http_server.run().await
+                    Err(anyhow!("gRPC server stopped before the HTTP server"))
+                }
+            }
+        }
+        Command::ImportWxr {
+            file,
+            mapping,
+            dry_run,
+        } => cli::import_wxr(&blog_service, file, mapping, dry_run).await,
+        Command::Export { output } => cli::export(&blog_service, output).await,
+        Command::Restore { file } => cli::restore(&blog_service, file).await,
+        Command::CreateUser { email, role } => cli::create_user(&blog_service, &email, role).await,
+    }
 }
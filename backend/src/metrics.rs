@@ -0,0 +1,212 @@
+//! Prometheus metrics of the HTTP API, the database pool and changes to
+//! posts, served in the text format on `GET /metrics`.
+
+use std::time::Duration;
+
+use axum::http::{Method, StatusCode};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder, core::Collector,
+};
+use sqlx::PgPool;
+
+use crate::domain::models::event::PostChangeKind;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Route label of requests that matched no route, so unknown paths do not
+/// each get a series of their own.
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Cheap to clone; clones share the same series.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    posts_created: IntCounter,
+    posts_updated: IntCounter,
+    posts_deleted: IntCounter,
+    db_pool_connections: IntGauge,
+    db_pool_idle_connections: IntGauge,
+    db_pool_max_connections: IntGauge,
+    db_pool_acquire_seconds: Histogram,
+    pool: Option<PgPool>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let http_labels = &["method", "route", "status"];
+
+        Self {
+            http_requests: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("http_requests_total", "HTTP requests handled."),
+                    http_labels,
+                ),
+            ),
+            http_request_duration: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new(
+                        "http_request_duration_seconds",
+                        "Time until the response to an HTTP request was ready.",
+                    ),
+                    http_labels,
+                ),
+            ),
+            posts_created: register(
+                &registry,
+                IntCounter::new(
+                    "posts_created_total",
+                    "Posts created, imported or restored.",
+                ),
+            ),
+            posts_updated: register(
+                &registry,
+                IntCounter::new("posts_updated_total", "Changes to existing posts."),
+            ),
+            posts_deleted: register(
+                &registry,
+                IntCounter::new("posts_deleted_total", "Posts trashed or deleted for good."),
+            ),
+            db_pool_connections: register(
+                &registry,
+                IntGauge::new("db_pool_connections", "Open database connections."),
+            ),
+            db_pool_idle_connections: register(
+                &registry,
+                IntGauge::new(
+                    "db_pool_idle_connections",
+                    "Database connections not in use.",
+                ),
+            ),
+            db_pool_max_connections: register(
+                &registry,
+                IntGauge::new(
+                    "db_pool_max_connections",
+                    "Database connections the pool may open.",
+                ),
+            ),
+            db_pool_acquire_seconds: register(
+                &registry,
+                Histogram::with_opts(HistogramOpts::new(
+                    "db_pool_acquire_seconds",
+                    "Time transactions waited for a database connection.",
+                )),
+            ),
+            registry,
+            pool: None,
+        }
+    }
+
+    /// Reports the connections of `pool` on every scrape.
+    pub fn with_pool(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// `route` is the matched route, like `/posts/{post_id}`, rather than the
+    /// path of the request.
+    pub fn observe_http_request(
+        &self,
+        method: &Method,
+        route: &str,
+        status: StatusCode,
+        elapsed: Duration,
+    ) {
+        let labels = [method.as_str(), route, status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Times the wait for a database connection, as the repository gets
+    /// them.
+    pub fn observe_db_acquire(&self, elapsed: Duration) {
+        self.db_pool_acquire_seconds.observe(elapsed.as_secs_f64());
+    }
+
+    /// Counts a change to a post relayed from the outbox by this instance.
+    pub fn record_post_change(&self, kind: PostChangeKind) {
+        match kind {
+            PostChangeKind::Created => self.posts_created.inc(),
+            PostChangeKind::Updated => self.posts_updated.inc(),
+            PostChangeKind::Deleted => self.posts_deleted.inc(),
+        }
+    }
+
+    /// Every metric in the Prometheus text format, with the pool gauges
+    /// brought up to date.
+    pub fn render(&self) -> Result<String, prometheus::Error> {
+        if let Some(pool) = &self.pool {
+            self.observe_pool(pool);
+        }
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+
+        String::from_utf8(buffer).map_err(|err| prometheus::Error::Msg(err.to_string()))
+    }
+
+    /// Reads what the pool knows without taking a connection from it.
+    fn observe_pool(&self, pool: &PgPool) {
+        self.db_pool_connections.set(pool.size().into());
+        self.db_pool_idle_connections.set(pool.num_idle() as i64);
+        self.db_pool_max_connections
+            .set(pool.options().get_max_connections().into());
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The names and labels above are fixed, so failing to create or register
+/// them is a bug.
+fn register<C>(registry: &Registry, collector: Result<C, prometheus::Error>) -> C
+where
+    C: Collector + Clone + 'static,
+{
+    let collector = collector.expect("Failed to create metric");
+    registry
+        .register(Box::new(collector.clone()))
+        .expect("Failed to register metric");
+    collector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_reports_requests_by_route_and_post_changes() {
+        let metrics = Metrics::new();
+
+        metrics.observe_http_request(
+            &Method::GET,
+            "/posts/{post_id}",
+            StatusCode::NOT_FOUND,
+            Duration::from_millis(5),
+        );
+        metrics.record_post_change(PostChangeKind::Created);
+        metrics.record_post_change(PostChangeKind::Created);
+        metrics.observe_db_acquire(Duration::from_millis(2));
+        let rendered = metrics.render().unwrap();
+
+        assert!(rendered.contains(
+            r#"http_requests_total{method="GET",route="/posts/{post_id}",status="404"} 1"#
+        ));
+        assert!(rendered.contains(
+            r#"http_request_duration_seconds_count{method="GET",route="/posts/{post_id}",status="404"} 1"#
+        ));
+        assert!(rendered.contains("posts_created_total 2"));
+        assert!(rendered.contains("posts_deleted_total 0"));
+        assert!(rendered.contains("db_pool_acquire_seconds_count 1"));
+    }
+}
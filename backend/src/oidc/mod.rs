@@ -0,0 +1,292 @@
+//! OpenID Connect login against an external identity provider.
+//!
+//! The blog acts as a confidential or public client using the authorization
+//! code flow with PKCE:
+//!
+//! 1. [`OidcClient::authorization_url`] sends the browser to the provider with
+//!    a fresh `state`, `nonce` and S256 code challenge from an [`OidcLogin`].
+//! 2. The provider redirects back with a code, which
+//!    [`OidcClient::exchange_code`] trades for an ID token at the token
+//!    endpoint, proving possession of the code verifier.
+//! 3. The ID token's signature is checked against the provider's JWKS, along
+//!    with issuer, audience, expiry and the nonce of the login attempt.
+//!
+//! Endpoints come from the provider's discovery document, fetched on first
+//! use. Signing keys are cached and refetched once when a token names a key
+//! that is not in the cache, so key rotation needs no restart.
+//!
+//! Only identities with a verified email address are accepted, since the
+//! email is what links them to existing local users.
+
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    jwk::{Jwk, JwkSet},
+};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::sync::{OnceCell, RwLock};
+use tracing::{instrument, warn};
+use url::Url;
+
+use crate::domain::models::{
+    oidc::{OidcIdentity, OidcLogin},
+    user::{Role, UserEmail, UserEmailInvalidError},
+};
+
+/// Signature algorithms accepted for ID tokens. Symmetric algorithms are left
+/// out on purpose: the client secret must never be usable to forge a token.
+const ID_TOKEN_ALGORITHMS: [Algorithm; 9] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidcConfig {
+    /// Issuer identifier, compared verbatim with the discovery document and
+    /// the `iss` claim.
+    pub issuer: String,
+    pub client_id: String,
+    /// Sent with HTTP basic auth when set; public clients rely on PKCE alone.
+    pub client_secret: Option<String>,
+    /// Where the provider sends the browser back to, i.e.
+    /// `https://<blog>/auth/oidc/callback`.
+    pub redirect_url: Url,
+    pub scopes: Vec<String>,
+    /// Role given to users created on their first login.
+    pub default_role: Role,
+    /// Where the browser ends up after a successful login.
+    pub post_login_redirect: String,
+}
+
+#[derive(Debug, Error)]
+pub enum OidcError {
+    #[error("Failed to reach the identity provider: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Identity provider reports issuer {found:?}, expected {expected:?}.")]
+    IssuerMismatch { expected: String, found: String },
+    #[error("Identity provider rejected the token request with status {status}: {body}")]
+    TokenRejected { status: u16, body: String },
+    #[error("Invalid ID token: {0}")]
+    InvalidIdToken(#[from] jsonwebtoken::errors::Error),
+    #[error("ID token is signed with unsupported algorithm {0:?}.")]
+    UnsupportedAlgorithm(Algorithm),
+    #[error("No signing key of the identity provider matches the ID token.")]
+    UnknownSigningKey,
+    #[error("ID token nonce does not match the login attempt.")]
+    NonceMismatch,
+    #[error("ID token was issued to another client.")]
+    AuthorizedPartyMismatch,
+    #[error("Identity provider did not return a verified email address.")]
+    EmailNotVerified,
+    #[error(transparent)]
+    InvalidEmail(#[from] UserEmailInvalidError),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: Url,
+    token_endpoint: Url,
+    jwks_uri: Url,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Claims beyond those checked by [`Validation`].
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    /// A string or an array of strings; its contents are checked by
+    /// [`Validation`].
+    aud: serde_json::Value,
+    azp: Option<String>,
+    nonce: Option<String>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+}
+
+#[derive(Debug)]
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+    metadata: OnceCell<ProviderMetadata>,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl OidcClient {
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            metadata: OnceCell::new(),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// The provider's authorization endpoint with every parameter of `login`.
+    pub async fn authorization_url(&self, login: &OidcLogin) -> Result<Url, OidcError> {
+        let mut url = self.metadata().await?.authorization_endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", self.config.redirect_url.as_str())
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", login.state().expose())
+            .append_pair("nonce", login.nonce())
+            .append_pair("code_challenge", &login.code_verifier().challenge())
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(url)
+    }
+
+    #[instrument(name = "oidc_exchange_code", skip_all, err)]
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        login: &OidcLogin,
+    ) -> Result<OidcIdentity, OidcError> {
+        let metadata = self.metadata().await?;
+
+        let mut request = self.http.post(metadata.token_endpoint.clone()).form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_url.as_str()),
+            ("client_id", &self.config.client_id),
+            ("code_verifier", login.code_verifier().expose()),
+        ]);
+        if let Some(secret) = &self.config.client_secret {
+            request = request.basic_auth(&self.config.client_id, Some(secret));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(OidcError::TokenRejected {
+                status: response.status().as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+        let TokenResponse { id_token } = response.json().await?;
+
+        self.verify_id_token(&id_token, login.nonce()).await
+    }
+
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity, OidcError> {
+        let metadata = self.metadata().await?;
+        let header = decode_header(id_token)?;
+        if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(OidcError::UnsupportedAlgorithm(header.alg));
+        }
+
+        let key = self.signing_key(header.kid.as_deref()).await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)?.claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OidcError::NonceMismatch);
+        }
+        // With several audiences, `azp` names the client the token is for.
+        if claims
+            .aud
+            .as_array()
+            .is_some_and(|audiences| audiences.len() > 1)
+            && claims.azp.as_deref() != Some(self.config.client_id.as_str())
+        {
+            return Err(OidcError::AuthorizedPartyMismatch);
+        }
+        let email = match (claims.email, claims.email_verified) {
+            (Some(email), Some(true)) => UserEmail::try_new(&email)?,
+            _ => return Err(OidcError::EmailNotVerified),
+        };
+
+        Ok(OidcIdentity::new(claims.iss, claims.sub, email))
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, OidcError> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let metadata: ProviderMetadata = self
+                    .http
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                if metadata.issuer != self.config.issuer {
+                    return Err(OidcError::IssuerMismatch {
+                        expected: self.config.issuer.clone(),
+                        found: metadata.issuer,
+                    });
+                }
+
+                Ok(metadata)
+            })
+            .await
+    }
+
+    async fn signing_key(&self, kid: Option<&str>) -> Result<DecodingKey, OidcError> {
+        if let Some(jwks) = self.jwks.read().await.as_ref()
+            && let Some(jwk) = find_key(jwks, kid)
+        {
+            return Ok(DecodingKey::from_jwk(jwk)?);
+        }
+
+        let jwks: JwkSet = self
+            .http
+            .get(self.metadata().await?.jwks_uri.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let key = find_key(&jwks, kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()?;
+        *self.jwks.write().await = Some(jwks);
+
+        key.ok_or_else(|| {
+            warn!(
+                ?kid,
+                "ID token names a key missing from the provider's JWKS"
+            );
+            OidcError::UnknownSigningKey
+        })
+    }
+}
+
+/// Tokens without a `kid` are only accepted from providers with a single key.
+fn find_key<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
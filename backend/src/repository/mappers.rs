@@ -1,21 +1,68 @@
+use std::collections::HashMap;
+
 use anyhow::anyhow;
-use sqlx::{Error as SqlxError, error::ErrorKind};
+use chrono::{DateTime, Utc};
+use sqlx::{Error as SqlxError, error::ErrorKind, types::Json};
+use uuid::Uuid;
 
 use crate::{
-    db::models::post::{CreatePostDbInput, DbPost, UpdatePostDbInput},
+    db::models::{
+        api_token::{CreateApiTokenDbInput, DbApiToken},
+        audit::{AuditFilterDbInput, CreateAuditEntryDbInput, DbAuditEntry},
+        author::{CreateAuthorDbInput, DbAuthor, DbSocialLink},
+        change::DbPostChange,
+        idempotency::{CompleteIdempotencyKeyDbInput, DbIdempotencyKey},
+        notification::DbPostNotification,
+        oidc::{CreateOidcLoginDbInput, DbOidcLogin},
+        outbox::{CreateOutboxEventDbInput, DbOutboxEvent},
+        post::{CreatePostDbInput, DbBackupPost, DbPost, UpdatePostDbInput},
+        taxonomy::{DbPostComment, DbPostTerm, InsertCommentDbInput, InsertTermDbInput},
+        totp::DbTotp,
+        user::{CreateSessionDbInput, DbSession, DbUser},
+        webhook::{
+            CreateWebhookDbInput, DbDueWebhookDelivery, DbWebhook, DbWebhookDelivery,
+            RecordWebhookAttemptDbInput,
+        },
+    },
     domain::{
-        models::post::{CreatePostRequest, Post, PostBody, PostTitle, UpdatePostRequest},
-        repository::{CreatePostError, DeletePostError, GetPostError, UpdatePostError},
+        models::{
+            api_token::{ApiToken, ApiTokenName, ApiTokenSecret, Scope},
+            audit::{AuditAction, AuditContext, AuditEntry, AuditQuery, AuditRecord},
+            author::{Author, AuthorName, AuthorUrl, CreateAuthorRequest, SocialLink},
+            change_feed::FeedChange,
+            event::{PostChangeKind, PostChangeNotice},
+            idempotency::{IdempotencyRecord, IdempotentResponse, RequestFingerprint},
+            oidc::{OidcLogin, OidcState, PkceVerifier},
+            outbox::{DomainEvent, OutboxEvent},
+            post::{
+                CreatePostRequest, ImportPostRequest, Post, PostBody, PostComment, PostTerm,
+                PostTitle, TermKind, UpdatePostRequest,
+            },
+            totp::{Totp, TotpSecret},
+            user::{PasswordHash, Session, SessionToken, User, UserEmail},
+            webhook::{
+                DeliveryStatus, DueWebhookDelivery, Webhook, WebhookAttempt, WebhookDelivery,
+                WebhookSecret, WebhookUrl,
+            },
+        },
+        repository::{
+            CreateApiTokenError, CreateAuthorError, CreatePostError, CreateTotpError,
+            CreateUserError, DeleteApiTokenError, DeletePostError, DeleteWebhookError,
+            GetAuthorError, GetPostError, GetSessionError, GetTotpError, GetUserError,
+            GetWebhookError, ImportPostsError, RedeliverWebhookError, RestorePostError,
+            TakeOidcLoginError, UpdatePostError, UseApiTokenError, UseSecondFactorError,
+        },
     },
-    ids::PostId,
+    ids::{ApiTokenId, AuthorId, PostId, UserId, WebhookId},
+    request_id::RequestId,
 };
 
-impl From<&CreatePostRequest> for CreatePostDbInput {
-    fn from(value: &CreatePostRequest) -> Self {
+impl From<(&CreatePostRequest, UserId)> for CreatePostDbInput {
+    fn from((value, created_by): (&CreatePostRequest, UserId)) -> Self {
         let title = value.title().to_string();
         let body = value.body().to_string();
 
-        Self::new(title, body)
+        Self::new(title, body, created_by)
     }
 }
 
@@ -35,12 +82,208 @@ impl From<DbPost> for Post {
             title,
             body,
             created_at,
+            updated_at,
+            created_by,
+            deleted_at,
+            version,
+            authors,
         }: DbPost,
     ) -> Self {
         let title = PostTitle::new(&title);
         let body = PostBody::new(&body);
 
         Self::new(id, title, body, created_at)
+            .with_updated_at(updated_at)
+            .with_created_by(created_by)
+            .with_deleted_at(deleted_at)
+            .with_version(version)
+            .with_authors(authors.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<&Post> for DbPost {
+    fn from(value: &Post) -> Self {
+        Self {
+            id: value.id(),
+            title: value.title().to_string(),
+            body: value.body().to_string(),
+            created_at: value.created_at(),
+            updated_at: value.updated_at(),
+            created_by: value.created_by(),
+            deleted_at: value.deleted_at(),
+            version: value.version(),
+            authors: value.authors().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&PostTerm> for InsertTermDbInput {
+    fn from(value: &PostTerm) -> Self {
+        Self {
+            kind: value.kind().as_str().to_string(),
+            name: value.name().to_string(),
+            slug: value.slug().map(str::to_string),
+        }
+    }
+}
+
+impl From<&PostComment> for InsertCommentDbInput {
+    fn from(value: &PostComment) -> Self {
+        Self {
+            id: value.id(),
+            parent_id: value.parent_id(),
+            author_name: value.author_name().map(str::to_string),
+            author_email: value.author_email().map(str::to_string),
+            author_url: value.author_url().map(str::to_string),
+            body: value.body().to_string(),
+            approved: value.approved(),
+            created_at: value.created_at(),
+        }
+    }
+}
+
+impl TryFrom<DbPostTerm> for PostTerm {
+    type Error = anyhow::Error;
+
+    fn try_from(db_term: DbPostTerm) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            db_term.kind.parse::<TermKind>()?,
+            &db_term.name,
+            db_term.slug.as_deref(),
+        ))
+    }
+}
+
+impl From<DbPostComment> for PostComment {
+    fn from(db_comment: DbPostComment) -> Self {
+        Self::new(db_comment.id, &db_comment.body, db_comment.created_at)
+            .with_parent_id(db_comment.parent_id)
+            .with_author(
+                db_comment.author_name,
+                db_comment.author_email,
+                db_comment.author_url,
+            )
+            .with_approved(db_comment.approved)
+    }
+}
+
+impl TryFrom<DbBackupPost> for ImportPostRequest {
+    type Error = anyhow::Error;
+
+    fn try_from(db_post: DbBackupPost) -> Result<Self, Self::Error> {
+        let terms = db_post
+            .terms
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(db_post.post.into())
+            .with_terms(terms)
+            .with_comments(db_post.comments.into_iter().map(Into::into).collect()))
+    }
+}
+
+impl From<DbAuthor> for Author {
+    fn from(
+        DbAuthor {
+            id,
+            user_id,
+            display_name,
+            bio,
+            avatar_url,
+            social_links,
+            import_key,
+        }: DbAuthor,
+    ) -> Self {
+        // URLs are validated on the way in; anything that no longer parses is
+        // left out rather than failing the whole post.
+        let avatar_url = avatar_url.and_then(|url| AuthorUrl::try_new(&url).ok());
+        let social_links = social_links
+            .into_iter()
+            .filter_map(|DbSocialLink { label, url }| {
+                SocialLink::try_new(&label, AuthorUrl::try_new(&url).ok()?).ok()
+            })
+            .collect();
+
+        Self::new(id, AuthorName::new(&display_name))
+            .with_user_id(user_id)
+            .with_bio(bio)
+            .with_avatar_url(avatar_url)
+            .with_social_links(social_links)
+            .with_import_key(import_key)
+    }
+}
+
+impl From<&Author> for DbAuthor {
+    fn from(value: &Author) -> Self {
+        Self {
+            id: value.id(),
+            user_id: value.user_id(),
+            display_name: value.name().to_string(),
+            bio: value.bio().map(ToString::to_string),
+            avatar_url: value.avatar_url().map(ToString::to_string),
+            social_links: value.social_links().iter().map(Into::into).collect(),
+            import_key: value.import_key().map(str::to_string),
+        }
+    }
+}
+
+impl From<&SocialLink> for DbSocialLink {
+    fn from(value: &SocialLink) -> Self {
+        Self {
+            label: value.label().to_string(),
+            url: value.url().to_string(),
+        }
+    }
+}
+
+impl From<&CreateAuthorRequest> for CreateAuthorDbInput {
+    fn from(value: &CreateAuthorRequest) -> Self {
+        Self {
+            id: AuthorId::new(),
+            user_id: value.user_id(),
+            display_name: value.name().to_string(),
+            bio: value.bio(),
+            avatar_url: value.avatar_url().map(|url| url.to_string()),
+            social_links: Json(value.social_links().iter().map(Into::into).collect()),
+            import_key: None,
+        }
+    }
+}
+
+impl From<DbAuthor> for CreateAuthorDbInput {
+    fn from(value: DbAuthor) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            display_name: value.display_name,
+            bio: value.bio,
+            avatar_url: value.avatar_url,
+            social_links: Json(value.social_links),
+            import_key: value.import_key,
+        }
+    }
+}
+
+impl From<(SqlxError, Option<UserId>)> for CreateAuthorError {
+    fn from((error, user_id): (SqlxError, Option<UserId>)) -> Self {
+        match (&error, user_id) {
+            (SqlxError::Database(e), Some(user_id)) => match e.kind() {
+                ErrorKind::UniqueViolation => Self::Duplicate { user_id },
+                ErrorKind::ForeignKeyViolation => Self::UserNotFound { user_id },
+                _ => Self::Unknown(anyhow!(error)),
+            },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(SqlxError, AuthorId)> for GetAuthorError {
+    fn from((error, id): (SqlxError, AuthorId)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::AuthorNotFound { id },
+            _ => Self::Unknown(anyhow!(error)),
+        }
     }
 }
 
@@ -49,6 +292,7 @@ impl From<(SqlxError, PostTitle)> for CreatePostError {
         match &error {
             SqlxError::Database(e) => match e.kind() {
                 ErrorKind::UniqueViolation => Self::Duplicate { title },
+                ErrorKind::ForeignKeyViolation => Self::AuthorNotFound,
                 // TODO: Cover other variants
                 _ => Self::Unknown(anyhow!(error)),
             },
@@ -57,6 +301,18 @@ impl From<(SqlxError, PostTitle)> for CreatePostError {
     }
 }
 
+impl From<(SqlxError, PostTitle)> for ImportPostsError {
+    fn from((error, title): (SqlxError, PostTitle)) -> Self {
+        match &error {
+            SqlxError::Database(e) => match e.kind() {
+                ErrorKind::UniqueViolation => Self::Duplicate { title },
+                _ => Self::Unknown(anyhow!(error)),
+            },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
 impl From<(SqlxError, PostId)> for GetPostError {
     fn from((error, id): (SqlxError, PostId)) -> Self {
         match &error {
@@ -70,6 +326,22 @@ impl From<(SqlxError, PostId)> for UpdatePostError {
     fn from((error, id): (SqlxError, PostId)) -> Self {
         match &error {
             SqlxError::RowNotFound => Self::PostNotFound { id },
+            SqlxError::Database(e) if e.kind() == ErrorKind::ForeignKeyViolation => {
+                Self::AuthorNotFound
+            }
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(SqlxError, PostId)> for RestorePostError {
+    fn from((error, id): (SqlxError, PostId)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::PostNotFound { id },
+            SqlxError::Database(e) => match e.kind() {
+                ErrorKind::UniqueViolation => Self::TitleTaken { id },
+                _ => Self::Unknown(anyhow!(error)),
+            },
             _ => Self::Unknown(anyhow!(error)),
         }
     }
@@ -83,3 +355,498 @@ impl From<(SqlxError, PostId)> for DeletePostError {
         }
     }
 }
+
+impl TryFrom<DbUser> for User {
+    type Error = anyhow::Error;
+
+    /// A role this build does not know would otherwise grant whatever the
+    /// fallback grants, so it fails the lookup instead.
+    fn try_from(
+        DbUser {
+            id,
+            email,
+            role,
+            created_at,
+            second_factor,
+            ..
+        }: DbUser,
+    ) -> Result<Self, Self::Error> {
+        Ok(
+            Self::new(id, UserEmail::new(&email), role.parse()?, created_at)
+                .with_second_factor(second_factor),
+        )
+    }
+}
+
+impl TryFrom<DbUser> for (User, Option<PasswordHash>) {
+    type Error = anyhow::Error;
+
+    fn try_from(db_user: DbUser) -> Result<Self, Self::Error> {
+        let password_hash = db_user.password_hash.as_deref().map(PasswordHash::new);
+        Ok((db_user.try_into()?, password_hash))
+    }
+}
+
+impl From<&Session> for CreateSessionDbInput {
+    fn from(session: &Session) -> Self {
+        Self {
+            token_hash: session.token().hash(),
+            user_id: session.user().id(),
+            expires_at: session.expires_at(),
+            second_factor_pending: session.is_second_factor_pending(),
+        }
+    }
+}
+
+impl TryFrom<(DbSession, &SessionToken)> for Session {
+    type Error = anyhow::Error;
+
+    fn try_from((db_session, token): (DbSession, &SessionToken)) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            token.clone(),
+            db_session.user.try_into()?,
+            db_session.expires_at,
+        )
+        .with_second_factor_pending(db_session.second_factor_pending))
+    }
+}
+
+impl From<(SqlxError, UserEmail)> for CreateUserError {
+    fn from((error, email): (SqlxError, UserEmail)) -> Self {
+        match &error {
+            SqlxError::Database(e) => match e.kind() {
+                ErrorKind::UniqueViolation => Self::Duplicate { email },
+                _ => Self::Unknown(anyhow!(error)),
+            },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(SqlxError, UserEmail)> for GetUserError {
+    fn from((error, email): (SqlxError, UserEmail)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::UserNotFound { email },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<SqlxError> for GetSessionError {
+    fn from(error: SqlxError) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::SessionNotFound,
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<&OidcLogin> for CreateOidcLoginDbInput {
+    fn from(login: &OidcLogin) -> Self {
+        Self {
+            state_hash: login.state().hash(),
+            nonce: login.nonce().to_string(),
+            code_verifier: login.code_verifier().expose().to_string(),
+            expires_at: login.expires_at(),
+        }
+    }
+}
+
+impl From<DbIdempotencyKey> for IdempotencyRecord {
+    fn from(db_key: DbIdempotencyKey) -> Self {
+        let response = match (
+            db_key.response_status,
+            db_key.response_headers,
+            db_key.response_body,
+        ) {
+            (Some(status), Some(headers), Some(body)) => {
+                Some(IdempotentResponse::new(status as u16, headers.0, body))
+            }
+            _ => None,
+        };
+
+        Self::new(RequestFingerprint::from_hash(db_key.fingerprint), response)
+    }
+}
+
+impl From<&IdempotentResponse> for CompleteIdempotencyKeyDbInput {
+    fn from(response: &IdempotentResponse) -> Self {
+        Self {
+            // HTTP status codes have three digits.
+            status: response.status() as i16,
+            headers: Json(response.headers().to_vec()),
+            body: response.body().to_vec(),
+        }
+    }
+}
+
+impl From<(DbOidcLogin, &OidcState)> for OidcLogin {
+    fn from((db_login, state): (DbOidcLogin, &OidcState)) -> Self {
+        Self::new(
+            state.clone(),
+            db_login.nonce,
+            PkceVerifier::new(&db_login.code_verifier),
+            db_login.expires_at,
+        )
+    }
+}
+
+impl From<SqlxError> for TakeOidcLoginError {
+    fn from(error: SqlxError) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::LoginNotFound,
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<DbTotp> for Totp {
+    fn from(db_totp: DbTotp) -> Self {
+        Self::new(TotpSecret::new(&db_totp.secret), db_totp.confirmed_at)
+    }
+}
+
+impl From<SqlxError> for CreateTotpError {
+    fn from(error: SqlxError) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::AlreadyEnabled,
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<SqlxError> for GetTotpError {
+    fn from(error: SqlxError) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::TotpNotFound,
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<SqlxError> for UseSecondFactorError {
+    fn from(error: SqlxError) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::Rejected,
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl TryFrom<DbApiToken> for ApiToken {
+    type Error = anyhow::Error;
+
+    fn try_from(db_token: DbApiToken) -> Result<Self, Self::Error> {
+        let scopes = db_token
+            .scopes
+            .iter()
+            .map(|scope| scope.parse::<Scope>())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(
+            db_token.id,
+            db_token.user_id,
+            ApiTokenName::new(&db_token.name),
+            scopes,
+            db_token.created_at,
+            db_token.expires_at,
+            db_token.last_used_at,
+        ))
+    }
+}
+
+impl From<(&ApiToken, &ApiTokenSecret)> for CreateApiTokenDbInput {
+    fn from((token, secret): (&ApiToken, &ApiTokenSecret)) -> Self {
+        Self {
+            id: token.id(),
+            user_id: token.user_id(),
+            name: token.name().to_string(),
+            token_hash: secret.hash(),
+            scopes: token.scopes().iter().map(ToString::to_string).collect(),
+            expires_at: token.expires_at(),
+        }
+    }
+}
+
+impl From<(SqlxError, ApiTokenName)> for CreateApiTokenError {
+    fn from((error, name): (SqlxError, ApiTokenName)) -> Self {
+        match &error {
+            SqlxError::Database(e) => match e.kind() {
+                ErrorKind::UniqueViolation => Self::Duplicate { name },
+                _ => Self::Unknown(anyhow!(error)),
+            },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(SqlxError, ApiTokenId)> for DeleteApiTokenError {
+    fn from((error, id): (SqlxError, ApiTokenId)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::ApiTokenNotFound { id },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<SqlxError> for UseApiTokenError {
+    fn from(error: SqlxError) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::ApiTokenNotFound,
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(AuditRecord, Vec<u8>, Vec<u8>)> for CreateAuditEntryDbInput {
+    fn from((record, prev_hash, hash): (AuditRecord, Vec<u8>, Vec<u8>)) -> Self {
+        Self {
+            actor_id: record.context().actor_id(),
+            action: record.action().to_string(),
+            post_id: record.post_id(),
+            before_snapshot: record.before().cloned(),
+            after_snapshot: record.after().cloned(),
+            request_id: record.context().request_id().map(ToString::to_string),
+            created_at: record.created_at(),
+            prev_hash,
+            hash,
+        }
+    }
+}
+
+impl TryFrom<DbPostNotification> for PostChangeNotice {
+    type Error = anyhow::Error;
+
+    fn try_from(notification: DbPostNotification) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            notification.sequence,
+            notification.change.parse::<PostChangeKind>()?,
+            notification.post_id,
+        ))
+    }
+}
+
+impl TryFrom<DbAuditEntry> for AuditEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(db_entry: DbAuditEntry) -> Result<Self, Self::Error> {
+        let context = AuditContext::new(
+            db_entry.actor_id,
+            db_entry.request_id.as_deref().map(RequestId::new),
+        );
+        let record = AuditRecord::new(
+            context,
+            db_entry.action.parse::<AuditAction>()?,
+            db_entry.post_id,
+            db_entry.before_snapshot,
+            db_entry.after_snapshot,
+            db_entry.created_at,
+        );
+
+        Ok(Self::new(
+            db_entry.id,
+            record,
+            db_entry.prev_hash,
+            db_entry.hash,
+        ))
+    }
+}
+
+impl From<&AuditQuery> for AuditFilterDbInput {
+    fn from(value: &AuditQuery) -> Self {
+        Self {
+            actor_id: value.actor_id(),
+            post_id: value.post_id(),
+            action: value.action().map(|action| action.to_string()),
+            since: value.since(),
+            until: value.until(),
+            before_id: value.before_id(),
+            limit: value.limit(),
+        }
+    }
+}
+
+impl TryFrom<DbWebhook> for Webhook {
+    type Error = anyhow::Error;
+
+    fn try_from(db_webhook: DbWebhook) -> Result<Self, Self::Error> {
+        let events = db_webhook
+            .events
+            .iter()
+            .map(|event| event.parse::<PostChangeKind>())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(
+            db_webhook.id,
+            WebhookUrl::try_new(&db_webhook.url)?,
+            events,
+            db_webhook.created_by,
+            db_webhook.created_at,
+        ))
+    }
+}
+
+impl From<(&Webhook, &WebhookSecret)> for CreateWebhookDbInput {
+    fn from((webhook, secret): (&Webhook, &WebhookSecret)) -> Self {
+        Self {
+            id: webhook.id(),
+            url: webhook.url().to_string(),
+            secret: secret.expose().to_string(),
+            events: webhook.events().iter().map(ToString::to_string).collect(),
+            created_by: webhook.created_by(),
+        }
+    }
+}
+
+impl TryFrom<DbWebhookDelivery> for WebhookDelivery {
+    type Error = anyhow::Error;
+
+    fn try_from(db_delivery: DbWebhookDelivery) -> Result<Self, Self::Error> {
+        let last_attempt = db_delivery.last_attempt_at.map(|attempted_at| {
+            match (db_delivery.last_response_status, db_delivery.last_error) {
+                (Some(status), _) => WebhookAttempt::responded(attempted_at, status as u16),
+                (None, error) => WebhookAttempt::failed(attempted_at, error.unwrap_or_default()),
+            }
+        });
+
+        Ok(Self::new(
+            db_delivery.id,
+            db_delivery.webhook_id,
+            db_delivery.event.parse::<PostChangeKind>()?,
+            db_delivery.created_at,
+        )
+        .with_status(db_delivery.status.parse::<DeliveryStatus>()?)
+        .with_attempts(db_delivery.attempts as u32)
+        .with_next_attempt_at(db_delivery.next_attempt_at)
+        .with_last_attempt(last_attempt)
+        .with_delivered_at(db_delivery.delivered_at))
+    }
+}
+
+impl TryFrom<DbDueWebhookDelivery> for DueWebhookDelivery {
+    type Error = anyhow::Error;
+
+    fn try_from(db_delivery: DbDueWebhookDelivery) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            db_delivery.id,
+            db_delivery.event.parse::<PostChangeKind>()?,
+            db_delivery.attempts as u32,
+            WebhookUrl::try_new(&db_delivery.url)?,
+            WebhookSecret::new(&db_delivery.secret),
+            db_delivery.payload,
+        ))
+    }
+}
+
+impl From<(&WebhookAttempt, Option<DateTime<Utc>>)> for RecordWebhookAttemptDbInput {
+    fn from((attempt, retry_at): (&WebhookAttempt, Option<DateTime<Utc>>)) -> Self {
+        let status = match retry_at {
+            _ if attempt.is_success() => DeliveryStatus::Delivered,
+            Some(_) => DeliveryStatus::Pending,
+            None => DeliveryStatus::Dead,
+        };
+
+        Self {
+            status: status.to_string(),
+            next_attempt_at: retry_at.filter(|_| status == DeliveryStatus::Pending),
+            attempted_at: attempt.attempted_at(),
+            // HTTP status codes have three digits.
+            response_status: attempt.response_status().map(|status| status as i16),
+            error: attempt.error().map(ToString::to_string),
+        }
+    }
+}
+
+/// A new outbox entry for `event`, under an id of its own.
+impl From<(&DomainEvent, DateTime<Utc>)> for CreateOutboxEventDbInput {
+    fn from((event, occurred_at): (&DomainEvent, DateTime<Utc>)) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            aggregate_id: event.aggregate_id(),
+            event_type: event.kind().to_string(),
+            payload: event.post().map(|post| {
+                serde_json::to_value(DbPost::from(post)).expect("Posts serialize to JSON")
+            }),
+            occurred_at,
+        }
+    }
+}
+
+impl TryFrom<DbOutboxEvent> for OutboxEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(db_event: DbOutboxEvent) -> Result<Self, Self::Error> {
+        let kind = db_event.event_type.parse::<PostChangeKind>()?;
+        let event = match (kind, db_event.payload) {
+            (PostChangeKind::Deleted, _) => DomainEvent::PostDeleted {
+                post_id: db_event.aggregate_id,
+            },
+            (kind, Some(post)) => {
+                let post = serde_json::from_value::<DbPost>(post)?;
+                DomainEvent::new(kind, db_event.aggregate_id, Some(post.into()))
+            }
+            (_, None) => return Err(anyhow!("Outbox event {} has no post", db_event.id)),
+        };
+
+        Ok(Self::new(
+            db_event.sequence,
+            db_event.id,
+            event,
+            db_event.occurred_at,
+        ))
+    }
+}
+
+/// Upserts take their post from those loaded along with the changes, read
+/// from the same snapshot.
+impl TryFrom<(DbPostChange, &mut HashMap<PostId, DbPost>)> for FeedChange {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        (change, posts): (DbPostChange, &mut HashMap<PostId, DbPost>),
+    ) -> Result<Self, Self::Error> {
+        if change.deleted {
+            return Ok(Self::Tombstone {
+                sequence: change.sequence,
+                post_id: change.post_id,
+                deleted_at: change.changed_at,
+            });
+        }
+
+        let post = posts
+            .remove(&change.post_id)
+            .ok_or_else(|| anyhow!("Changed post {} was not found", change.post_id))?;
+
+        Ok(Self::Upsert {
+            sequence: change.sequence,
+            post: post.into(),
+        })
+    }
+}
+
+impl From<(SqlxError, WebhookId)> for GetWebhookError {
+    fn from((error, id): (SqlxError, WebhookId)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::WebhookNotFound { id },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(SqlxError, WebhookId)> for DeleteWebhookError {
+    fn from((error, id): (SqlxError, WebhookId)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::WebhookNotFound { id },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
+
+impl From<(SqlxError, i64)> for RedeliverWebhookError {
+    fn from((error, id): (SqlxError, i64)) -> Self {
+        match &error {
+            SqlxError::RowNotFound => Self::DeliveryNotFound { id },
+            _ => Self::Unknown(anyhow!(error)),
+        }
+    }
+}
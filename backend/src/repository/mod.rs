@@ -1,27 +1,97 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt, stream::BoxStream};
+use sqlx::PgConnection;
 use tracing::{error, instrument};
 
 use crate::{
-    db::{postgres::Postgres, query},
+    db::{
+        models::{
+            idempotency::ClaimIdempotencyKeyDbInput, notification::DbPostSignal, post::DbPost,
+            user::CreateUserDbInput,
+        },
+        postgres::{PostNotifier, Postgres},
+        query,
+    },
     domain::{
-        models::post::{CreatePostRequest, Post, UpdatePostRequest},
+        models::{
+            api_token::{ApiToken, ApiTokenSecret},
+            audit::{
+                AUDIT_GENESIS_HASH, AuditAction, AuditContext, AuditEntry, AuditQuery, AuditRecord,
+            },
+            author::{Author, CreateAuthorRequest},
+            change_feed::{ChangeFeedPage, ChangeFeedQuery, FeedChange},
+            event::{EventId, PostChangeKind, PostChangeNotice, PostChangeSignal},
+            idempotency::{
+                IdempotencyKey, IdempotencyRecord, IdempotentResponse, RequestFingerprint,
+            },
+            oidc::{OidcIdentity, OidcLogin, OidcState},
+            outbox::{DomainEvent, OutboxEvent},
+            post::{
+                CreatePostRequest, ImportPostRequest, Post, PostComment, PostPage, PostPageQuery,
+                PostTerm, UpdatePostRequest,
+            },
+            totp::{RecoveryCode, Totp, TotpSecret},
+            user::{PasswordHash, Role, Session, SessionToken, User, UserEmail},
+            webhook::{
+                DueWebhookDelivery, Webhook, WebhookAttempt, WebhookDelivery, WebhookEvent,
+                WebhookSecret,
+            },
+        },
         repository::{
-            CreatePostError, DeletePostError, GetPostError, Repository, RepositoryError,
-            UpdatePostError,
+            CreateApiTokenError, CreateAuthorError, CreatePostError, CreateTotpError,
+            CreateUserError, DeleteApiTokenError, DeletePostError, DeleteWebhookError,
+            GetAuthorError, GetChangesError, GetPostError, GetSessionError, GetTotpError,
+            GetUserError, GetWebhookError, ImportPostsError, RedeliverWebhookError, Repository,
+            RepositoryError, RestorePostError, TakeOidcLoginError, UpdatePostError,
+            UseApiTokenError, UseSecondFactorError,
         },
     },
-    ids::PostId,
+    ids::{ApiTokenId, AuthorId, PostId, UserId, WebhookId},
 };
 
 pub mod mappers;
 
 #[async_trait]
 impl Repository for Postgres {
-    #[instrument(name = "repository_create_post", skip(self, input), err)]
-    async fn create_post(&self, input: &CreatePostRequest) -> Result<Post, CreatePostError> {
-        let db_input = input.into();
+    #[instrument(name = "repository_create_post", skip(self, input, audit), err)]
+    async fn create_post(
+        &self,
+        input: &CreatePostRequest,
+        created_by: UserId,
+        audit: &AuditContext,
+    ) -> Result<Post, CreatePostError> {
+        let db_input = (input, created_by).into();
+
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            let post_id = query::post::create_post(&mut tx, db_input).await?;
+            match input.authors() {
+                Some(authors) => query::post::set_post_authors(&mut tx, post_id, authors).await?,
+                None => query::post::set_default_post_author(&mut tx, post_id, created_by).await?,
+            }
+            let after = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            append_audit_entry(
+                &mut tx,
+                self.notifier(),
+                audit,
+                AuditAction::PostCreated,
+                post_id,
+                None,
+                Some(after),
+            )
+            .await?;
+            let db_post = query::post::get_post_by_id(&mut *tx, post_id).await?;
+
+            tx.commit().await?;
+            Ok(db_post)
+        }
+        .await;
 
-        match query::post::create_post(self.pool(), db_input).await {
+        match result {
             Ok(db_post) => {
                 let post: Post = db_post.into();
                 Ok(post)
@@ -47,6 +117,35 @@ impl Repository for Postgres {
         }
     }
 
+    #[instrument(name = "repository_get_posts_page", skip(self), err)]
+    async fn get_posts_page(&self, query: &PostPageQuery) -> Result<PostPage, RepositoryError> {
+        let after = query.after().map(|after| (after.created_at(), after.id()));
+        let mut posts = query::post::get_posts_page(self.pool(), after, query.limit() + 1)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to get a page of posts from database");
+                RepositoryError::Unknown(err.into())
+            })?;
+
+        let has_more = posts.len() as i64 > query.limit();
+        posts.truncate(query.limit() as usize);
+
+        Ok(PostPage::new(
+            posts.into_iter().map(Into::into).collect(),
+            has_more,
+        ))
+    }
+
+    #[instrument(name = "repository_get_posts_last_modified", skip(self), err)]
+    async fn get_posts_last_modified(&self) -> Result<Option<DateTime<Utc>>, RepositoryError> {
+        query::change::get_last_changed_at(self.pool())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to get the last post change from database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
     #[instrument(name = "repository_get_post_by_id", skip(self, post_id), err)]
     async fn get_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError> {
         match query::post::get_post_by_id(self.pool(), post_id).await {
@@ -58,33 +157,248 @@ impl Repository for Postgres {
         }
     }
 
-    #[instrument(name = "repository_update_post", skip(self, post_id, input), err)]
+    #[instrument(name = "repository_get_post_terms", skip(self), err)]
+    async fn get_post_terms(&self, post_id: PostId) -> Result<Vec<PostTerm>, GetPostError> {
+        self.get_post_by_id(post_id).await?;
+
+        query::taxonomy::get_post_terms(self.pool(), post_id)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to get terms of post {post_id} from database");
+                GetPostError::Unknown(err.into())
+            })?
+            .into_iter()
+            .map(|db_term| db_term.try_into().map_err(GetPostError::Unknown))
+            .collect()
+    }
+
+    #[instrument(name = "repository_get_post_comments", skip(self), err)]
+    async fn get_post_comments(&self, post_id: PostId) -> Result<Vec<PostComment>, GetPostError> {
+        self.get_post_by_id(post_id).await?;
+
+        match query::taxonomy::get_approved_post_comments(self.pool(), post_id).await {
+            Ok(db_comments) => Ok(db_comments.into_iter().map(Into::into).collect()),
+            Err(err) => {
+                error!(
+                    ?err,
+                    "Failed to get comments of post {post_id} from database"
+                );
+                Err(GetPostError::Unknown(err.into()))
+            }
+        }
+    }
+
+    #[instrument(
+        name = "repository_update_post",
+        skip(self, post_id, expected_version, input, audit),
+        err
+    )]
     async fn update_post(
         &self,
         post_id: PostId,
+        expected_version: i64,
         input: &UpdatePostRequest,
+        audit: &AuditContext,
     ) -> Result<Post, UpdatePostError> {
         let db_input = input.into();
 
-        if let Some(title) = input.title() {
-            if query::post::get_post_by_title(self.pool(), title.to_string().as_str())
+        if let Some(title) = input.title()
+            && query::post::get_post_by_title(self.pool(), title.to_string().as_str())
                 .await
                 .is_ok()
-            {
-                return Err(UpdatePostError::Duplicate { title });
+        {
+            return Err(UpdatePostError::Duplicate { title });
+        }
+
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            if query::post::lock_post_version(&mut tx, post_id).await? != expected_version {
+                return Ok(None);
             }
+            let before = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            query::post::update_post(&mut tx, post_id, db_input).await?;
+            if let Some(authors) = input.authors() {
+                query::post::set_post_authors(&mut tx, post_id, authors).await?;
+            }
+            let after = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            append_audit_entry(
+                &mut tx,
+                self.notifier(),
+                audit,
+                AuditAction::PostUpdated,
+                post_id,
+                Some(before),
+                Some(after),
+            )
+            .await?;
+            let db_post = query::post::get_post_by_id(&mut *tx, post_id).await?;
+
+            tx.commit().await?;
+            Ok(Some(db_post))
         }
+        .await;
 
-        match query::post::update_post(self.pool(), post_id, db_input).await {
-            Ok(db_post) => Ok(db_post.into()),
+        match result {
+            Ok(Some(db_post)) => Ok(db_post.into()),
+            Ok(None) => Err(UpdatePostError::VersionConflict {
+                id: post_id,
+                expected_version,
+            }),
             Err(err) => Err(UpdatePostError::from((err, post_id))),
         }
     }
 
-    #[instrument(name = "repository_delete_post", skip(self, post_id), err)]
-    async fn delete_post(&self, post_id: PostId) -> Result<(), DeletePostError> {
-        match query::post::delete_post(self.pool(), post_id).await {
-            Ok(_) => Ok(()),
+    #[instrument(
+        name = "repository_trash_post",
+        skip(self, post_id, expected_version, audit),
+        err
+    )]
+    async fn trash_post(
+        &self,
+        post_id: PostId,
+        expected_version: i64,
+        audit: &AuditContext,
+    ) -> Result<(), DeletePostError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            if query::post::lock_post_version(&mut tx, post_id).await? != expected_version {
+                return Ok(false);
+            }
+            let before = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            query::post::trash_post(&mut tx, post_id).await?;
+            let after = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            append_audit_entry(
+                &mut tx,
+                self.notifier(),
+                audit,
+                AuditAction::PostTrashed,
+                post_id,
+                Some(before),
+                Some(after),
+            )
+            .await?;
+
+            tx.commit().await?;
+            Ok(true)
+        }
+        .await;
+
+        match result {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(DeletePostError::VersionConflict {
+                id: post_id,
+                expected_version,
+            }),
+            Err(err) => {
+                error!(?err, "Failed to move post with id {post_id} to the trash");
+                Err(DeletePostError::from((err, post_id)))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_trashed_posts", skip(self), err)]
+    async fn get_trashed_posts(&self) -> Result<Vec<Post>, RepositoryError> {
+        match query::post::get_trashed_posts(self.pool()).await {
+            Ok(db_posts) => Ok(db_posts.into_iter().map(Into::into).collect()),
+            Err(err) => {
+                error!(?err, "Failed to get trashed posts from database");
+                Err(RepositoryError::Unknown(err.into()))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_trashed_post_by_id", skip(self, post_id), err)]
+    async fn get_trashed_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError> {
+        query::post::get_trashed_post_by_id(self.pool(), post_id)
+            .await
+            .map(Into::into)
+            .map_err(|err| GetPostError::from((err, post_id)))
+    }
+
+    #[instrument(name = "repository_restore_post", skip(self, post_id, audit), err)]
+    async fn restore_post(
+        &self,
+        post_id: PostId,
+        audit: &AuditContext,
+    ) -> Result<Post, RestorePostError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            let before = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            query::post::restore_post(&mut tx, post_id).await?;
+            let after = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            append_audit_entry(
+                &mut tx,
+                self.notifier(),
+                audit,
+                AuditAction::PostRestored,
+                post_id,
+                Some(before),
+                Some(after),
+            )
+            .await?;
+            let db_post = query::post::get_post_by_id(&mut *tx, post_id).await?;
+
+            tx.commit().await?;
+            Ok(db_post)
+        }
+        .await;
+
+        match result {
+            Ok(db_post) => Ok(db_post.into()),
+            Err(err) => {
+                error!(
+                    ?err,
+                    "Failed to restore post with id {post_id} from the trash"
+                );
+                Err(RestorePostError::from((err, post_id)))
+            }
+        }
+    }
+
+    #[instrument(
+        name = "repository_delete_post",
+        skip(self, post_id, expected_version, audit),
+        err
+    )]
+    async fn delete_post(
+        &self,
+        post_id: PostId,
+        expected_version: i64,
+        audit: &AuditContext,
+    ) -> Result<(), DeletePostError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            if query::post::lock_post_version(&mut tx, post_id).await? != expected_version {
+                return Ok(false);
+            }
+            let before = query::post::get_post_snapshot(&mut tx, post_id).await?;
+            query::post::delete_post(&mut tx, post_id).await?;
+            append_audit_entry(
+                &mut tx,
+                self.notifier(),
+                audit,
+                AuditAction::PostDeleted,
+                post_id,
+                Some(before),
+                None,
+            )
+            .await?;
+
+            tx.commit().await?;
+            Ok(true)
+        }
+        .await;
+
+        match result {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(DeletePostError::VersionConflict {
+                id: post_id,
+                expected_version,
+            }),
             Err(err) => {
                 error!(
                     ?err,
@@ -94,4 +408,976 @@ impl Repository for Postgres {
             }
         }
     }
+
+    #[instrument(name = "repository_purge_trash", skip(self, audit), err)]
+    async fn purge_trash(
+        &self,
+        deleted_before: DateTime<Utc>,
+        audit: &AuditContext,
+    ) -> Result<u64, RepositoryError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            let expired = query::post::get_trashed_post_snapshots(&mut tx, deleted_before).await?;
+            for (post_id, before) in &expired {
+                query::post::delete_post(&mut tx, *post_id).await?;
+                append_audit_entry(
+                    &mut tx,
+                    self.notifier(),
+                    audit,
+                    AuditAction::PostDeleted,
+                    *post_id,
+                    Some(before.clone()),
+                    None,
+                )
+                .await?;
+            }
+
+            tx.commit().await?;
+            Ok(expired.len() as u64)
+        }
+        .await;
+
+        result.map_err(|err| {
+            error!(?err, "Failed to purge trash");
+            RepositoryError::Unknown(err.into())
+        })
+    }
+
+    #[instrument(name = "repository_import_posts", skip(self, posts, audit), fields(count = posts.len()), err)]
+    async fn import_posts(
+        &self,
+        posts: &[ImportPostRequest],
+        audit: &AuditContext,
+    ) -> Result<Vec<Post>, ImportPostsError> {
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ImportPostsError::Unknown(err.into()))?;
+
+        let imported = insert_posts(&mut tx, self.notifier(), posts, audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ImportPostsError::Unknown(err.into()))?;
+
+        Ok(imported)
+    }
+
+    fn stream_posts(&self) -> BoxStream<'static, Result<Post, RepositoryError>> {
+        query::post::stream_posts(self.pool().clone())
+            .map_ok(Into::into)
+            .map_err(|err| {
+                error!(?err, "Failed to stream posts from database");
+                RepositoryError::Unknown(err.into())
+            })
+            .boxed()
+    }
+
+    fn stream_backup_posts(
+        &self,
+    ) -> BoxStream<'static, Result<ImportPostRequest, RepositoryError>> {
+        query::post::stream_backup_posts(self.pool().clone())
+            .map_err(|err| {
+                error!(?err, "Failed to stream posts for backup from database");
+                RepositoryError::Unknown(err.into())
+            })
+            .and_then(|db_post| async move { Ok(db_post.try_into()?) })
+            .boxed()
+    }
+
+    async fn watch_post_changes(&self) -> BoxStream<'static, PostChangeSignal> {
+        self.notifier()
+            .subscribe(self.pool())
+            .await
+            .filter_map(|signal| async move {
+                match signal {
+                    DbPostSignal::Notification(notification) => {
+                        PostChangeNotice::try_from(notification)
+                            .inspect_err(|err| {
+                                error!(?err, "Failed to read post change notification")
+                            })
+                            .ok()
+                            .map(PostChangeSignal::Notice)
+                    }
+                    DbPostSignal::Missed => Some(PostChangeSignal::Missed),
+                }
+            })
+            .boxed()
+    }
+
+    #[instrument(name = "repository_get_latest_change_id", skip(self), err)]
+    async fn get_latest_change_id(&self) -> Result<EventId, RepositoryError> {
+        query::change::get_latest_sequence(self.pool())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to get latest post change from database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_get_changes", skip(self), err)]
+    async fn get_changes(
+        &self,
+        query: &ChangeFeedQuery,
+    ) -> Result<ChangeFeedPage, GetChangesError> {
+        let since = query.since().unwrap_or(0);
+
+        // Compacted changes are only missed by callers continuing from a
+        // cursor; a sync from the start has nothing to forget.
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+            query::change::read_from_snapshot(&mut tx).await?;
+
+            let compacted_through = query::change::get_compacted_through(&mut tx).await?;
+            if query.since().is_some_and(|since| since < compacted_through) {
+                return Ok(Err(compacted_through));
+            }
+
+            let changes =
+                query::change::get_post_changes(&mut tx, since, query.limit() + 1).await?;
+            let upserted: Vec<_> = changes
+                .iter()
+                .filter(|change| !change.deleted)
+                .map(|change| change.post_id)
+                .collect();
+            let posts = query::post::get_posts_by_ids(&mut *tx, &upserted).await?;
+
+            tx.commit().await?;
+            Ok(Ok((changes, posts)))
+        }
+        .await;
+
+        let (mut changes, posts) = result
+            .map_err(|err| {
+                error!(?err, "Failed to get post changes from database");
+                GetChangesError::Unknown(err.into())
+            })?
+            .map_err(|compacted_through| GetChangesError::ResyncRequired {
+                since,
+                compacted_through,
+            })?;
+
+        let has_more = changes.len() as i64 > query.limit();
+        changes.truncate(query.limit() as usize);
+        let mut posts: HashMap<_, _> = posts.into_iter().map(|post| (post.id, post)).collect();
+        let changes = changes
+            .into_iter()
+            .map(|change| FeedChange::try_from((change, &mut posts)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ChangeFeedPage::new(changes, since, has_more))
+    }
+
+    #[instrument(name = "repository_compact_changes", skip(self), err)]
+    async fn compact_changes(&self, deleted_before: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        query::change::compact_post_changes(self.pool(), deleted_before)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to compact post changes in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_restore_posts", skip(self, posts, audit), fields(count = posts.len()), err)]
+    async fn restore_posts(
+        &self,
+        posts: &[ImportPostRequest],
+        audit: &AuditContext,
+    ) -> Result<Vec<Post>, ImportPostsError> {
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ImportPostsError::Unknown(err.into()))?;
+
+        let cleared: Result<_, sqlx::Error> = async {
+            let versions: HashMap<_, _> = query::post::get_post_versions(&mut tx)
+                .await?
+                .into_iter()
+                .collect();
+            for (post_id, before) in query::post::get_all_post_snapshots(&mut tx).await? {
+                append_audit_entry(
+                    &mut tx,
+                    self.notifier(),
+                    audit,
+                    AuditAction::PostDeleted,
+                    post_id,
+                    Some(before),
+                    None,
+                )
+                .await?;
+            }
+            query::post::delete_all_posts(&mut tx).await?;
+
+            Ok(versions)
+        }
+        .await;
+        let versions = cleared.map_err(|err| {
+            error!(?err, "Failed to clear posts before restore");
+            ImportPostsError::Unknown(err.into())
+        })?;
+
+        // A post changed since the backup goes on from where it was, so that
+        // its old ETags cannot match the restored content.
+        let posts: Vec<_> = posts
+            .iter()
+            .map(|request| match versions.get(&request.post().id()) {
+                Some(&current) if current > request.post().version() => {
+                    ImportPostRequest::new(request.post().clone().with_version(current + 1))
+                        .with_terms(request.terms().to_vec())
+                        .with_comments(request.comments().to_vec())
+                }
+                _ => request.clone(),
+            })
+            .collect();
+        let restored = insert_posts(&mut tx, self.notifier(), &posts, audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ImportPostsError::Unknown(err.into()))?;
+
+        Ok(restored)
+    }
+
+    #[instrument(name = "repository_get_audit_entries", skip(self), err)]
+    async fn get_audit_entries(
+        &self,
+        query: &AuditQuery,
+    ) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let db_entries = query::audit::get_audit_entries(self.pool(), query.into())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to get audit entries from database");
+                RepositoryError::Unknown(err.into())
+            })?;
+
+        Ok(db_entries
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn stream_audit_entries(&self) -> BoxStream<'static, Result<AuditEntry, RepositoryError>> {
+        query::audit::stream_audit_entries(self.pool().clone())
+            .map_err(|err| {
+                error!(?err, "Failed to stream audit entries from database");
+                RepositoryError::Unknown(err.into())
+            })
+            .and_then(|db_entry| async move { Ok(db_entry.try_into()?) })
+            .boxed()
+    }
+
+    #[instrument(name = "repository_create_author", skip(self, input), fields(name = %input.name()), err)]
+    async fn create_author(
+        &self,
+        input: &CreateAuthorRequest,
+    ) -> Result<Author, CreateAuthorError> {
+        match query::author::create_author(self.pool(), input.into()).await {
+            Ok(db_author) => Ok(db_author.into()),
+            Err(err) => {
+                error!(?err, "Failed to create author in database");
+                Err(CreateAuthorError::from((err, input.user_id())))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_author_by_id", skip(self), err)]
+    async fn get_author_by_id(&self, author_id: AuthorId) -> Result<Author, GetAuthorError> {
+        query::author::get_author_by_id(self.pool(), author_id)
+            .await
+            .map(Into::into)
+            .map_err(|err| GetAuthorError::from((err, author_id)))
+    }
+
+    #[instrument(name = "repository_get_posts_by_author", skip(self), err)]
+    async fn get_posts_by_author(&self, author_id: AuthorId) -> Result<Vec<Post>, GetAuthorError> {
+        self.get_author_by_id(author_id).await?;
+
+        match query::post::get_posts_by_author(self.pool(), author_id).await {
+            Ok(db_posts) => Ok(db_posts.into_iter().map(Into::into).collect()),
+            Err(err) => {
+                error!(
+                    ?err,
+                    "Failed to get posts of author {author_id} from database"
+                );
+                Err(GetAuthorError::Unknown(err.into()))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_posts_by_authors", skip(self), err)]
+    async fn get_posts_by_authors(
+        &self,
+        author_ids: &[AuthorId],
+        page: &PostPageQuery,
+    ) -> Result<Vec<Post>, RepositoryError> {
+        let before = page.after().map(|after| (after.created_at(), after.id()));
+        match query::post::get_posts_by_authors(self.pool(), author_ids, before, page.limit()).await
+        {
+            Ok(db_posts) => Ok(db_posts.into_iter().map(Into::into).collect()),
+            Err(err) => {
+                error!(?err, "Failed to get posts of authors from database");
+                Err(RepositoryError::Unknown(err.into()))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_create_user", skip(self, password_hash), err)]
+    async fn create_user(
+        &self,
+        email: &UserEmail,
+        password_hash: &PasswordHash,
+        role: Role,
+    ) -> Result<User, CreateUserError> {
+        let db_input = CreateUserDbInput::new(
+            email.to_string(),
+            Some(password_hash.to_string()),
+            role.to_string(),
+        );
+
+        match query::user::create_user(self.pool(), db_input).await {
+            Ok(db_user) => Ok(db_user.try_into()?),
+            Err(err) => {
+                error!(?err, "Failed to create user in database");
+                Err(CreateUserError::from((err, email.clone())))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_user_credentials", skip(self), err)]
+    async fn get_user_credentials(
+        &self,
+        email: &UserEmail,
+    ) -> Result<(User, Option<PasswordHash>), GetUserError> {
+        match query::user::get_user_by_email(self.pool(), &email.to_string()).await {
+            Ok(db_user) => Ok(db_user.try_into()?),
+            Err(err) => Err(GetUserError::from((err, email.clone()))),
+        }
+    }
+
+    #[instrument(name = "repository_get_or_create_identity_user", skip(self, identity), fields(issuer = identity.issuer()), err)]
+    async fn get_or_create_identity_user(
+        &self,
+        identity: &OidcIdentity,
+        role: Role,
+    ) -> Result<User, RepositoryError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            let db_user = match query::user::get_user_by_identity(
+                &mut *tx,
+                identity.issuer(),
+                identity.subject(),
+            )
+            .await
+            {
+                Ok(db_user) => db_user,
+                Err(sqlx::Error::RowNotFound) => {
+                    let email = identity.email().to_string();
+                    let db_user = match query::user::get_user_by_email(&mut *tx, &email).await {
+                        Ok(db_user) => db_user,
+                        Err(sqlx::Error::RowNotFound) => {
+                            let db_input = CreateUserDbInput::new(email, None, role.to_string());
+                            query::user::create_user(&mut *tx, db_input).await?
+                        }
+                        Err(err) => return Err(err),
+                    };
+                    query::user::create_user_identity(
+                        &mut *tx,
+                        identity.issuer(),
+                        identity.subject(),
+                        db_user.id,
+                    )
+                    .await?;
+                    db_user
+                }
+                Err(err) => return Err(err),
+            };
+
+            tx.commit().await?;
+            Ok(db_user)
+        }
+        .await;
+
+        let db_user = result.map_err(|err| {
+            error!(?err, "Failed to resolve identity user in database");
+            RepositoryError::Unknown(err.into())
+        })?;
+
+        db_user.try_into().map_err(RepositoryError::Unknown)
+    }
+
+    #[instrument(name = "repository_create_oidc_login", skip(self, login), err)]
+    async fn create_oidc_login(&self, login: &OidcLogin) -> Result<(), RepositoryError> {
+        query::oidc::create_oidc_login(self.pool(), login.into())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to create OIDC login in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_take_oidc_login", skip(self, state), err)]
+    async fn take_oidc_login(&self, state: &OidcState) -> Result<OidcLogin, TakeOidcLoginError> {
+        query::oidc::take_oidc_login(self.pool(), &state.hash())
+            .await
+            .map(|db_login| (db_login, state).into())
+            .map_err(TakeOidcLoginError::from)
+    }
+
+    #[instrument(name = "repository_create_session", skip(self, session), err)]
+    async fn create_session(&self, session: &Session) -> Result<(), RepositoryError> {
+        query::session::create_session(self.pool(), session.into())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to create session in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_get_session", skip(self, token), err)]
+    async fn get_session(&self, token: &SessionToken) -> Result<Session, GetSessionError> {
+        let db_session = query::session::get_session(self.pool(), &token.hash())
+            .await
+            .map_err(GetSessionError::from)?;
+
+        Ok((db_session, token).try_into()?)
+    }
+
+    #[instrument(name = "repository_complete_session", skip(self, token), err)]
+    async fn complete_session(&self, token: &SessionToken) -> Result<(), GetSessionError> {
+        query::session::complete_session(self.pool(), &token.hash())
+            .await
+            .map_err(GetSessionError::from)
+    }
+
+    #[instrument(name = "repository_delete_session", skip(self, token), err)]
+    async fn delete_session(&self, token: &SessionToken) -> Result<(), RepositoryError> {
+        query::session::delete_session(self.pool(), &token.hash())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to delete session from database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_create_totp", skip(self, secret), err)]
+    async fn create_totp(
+        &self,
+        user_id: UserId,
+        secret: &TotpSecret,
+    ) -> Result<(), CreateTotpError> {
+        query::totp::upsert_totp(self.pool(), user_id, secret.as_bytes())
+            .await
+            .map_err(CreateTotpError::from)
+    }
+
+    #[instrument(name = "repository_get_totp", skip(self), err)]
+    async fn get_totp(&self, user_id: UserId) -> Result<Totp, GetTotpError> {
+        query::totp::get_totp(self.pool(), user_id)
+            .await
+            .map(Into::into)
+            .map_err(GetTotpError::from)
+    }
+
+    #[instrument(name = "repository_confirm_totp", skip(self, recovery_codes), err)]
+    async fn confirm_totp(
+        &self,
+        user_id: UserId,
+        step: i64,
+        recovery_codes: &[RecoveryCode],
+    ) -> Result<(), GetTotpError> {
+        let code_hashes: Vec<_> = recovery_codes.iter().map(RecoveryCode::hash).collect();
+
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            query::totp::confirm_totp(&mut tx, user_id, step).await?;
+            query::totp::replace_recovery_codes(&mut tx, user_id, &code_hashes).await?;
+
+            tx.commit().await
+        }
+        .await;
+
+        result.map_err(GetTotpError::from)
+    }
+
+    #[instrument(name = "repository_start_second_factor_attempt", skip(self), err)]
+    async fn start_second_factor_attempt(
+        &self,
+        user_id: UserId,
+        max_attempts: u32,
+        locked_until: DateTime<Utc>,
+    ) -> Result<(), UseSecondFactorError> {
+        let max_attempts = i32::try_from(max_attempts).unwrap_or(i32::MAX);
+
+        query::totp::start_second_factor_attempt(self.pool(), user_id, max_attempts, locked_until)
+            .await
+            .map_err(UseSecondFactorError::from)
+    }
+
+    #[instrument(name = "repository_use_totp_step", skip(self), err)]
+    async fn use_totp_step(&self, user_id: UserId, step: i64) -> Result<(), UseSecondFactorError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            query::totp::use_totp_step(&mut tx, user_id, step).await?;
+            query::totp::reset_second_factor_attempts(&mut tx, user_id).await?;
+
+            tx.commit().await
+        }
+        .await;
+
+        result.map_err(UseSecondFactorError::from)
+    }
+
+    #[instrument(name = "repository_use_recovery_code", skip(self, code), err)]
+    async fn use_recovery_code(
+        &self,
+        user_id: UserId,
+        code: &RecoveryCode,
+    ) -> Result<(), UseSecondFactorError> {
+        let result: Result<_, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            query::totp::use_recovery_code(&mut tx, user_id, &code.hash()).await?;
+            query::totp::reset_second_factor_attempts(&mut tx, user_id).await?;
+
+            tx.commit().await
+        }
+        .await;
+
+        result.map_err(UseSecondFactorError::from)
+    }
+
+    #[instrument(name = "repository_create_api_token", skip(self, token, secret), fields(name = %token.name()), err)]
+    async fn create_api_token(
+        &self,
+        token: &ApiToken,
+        secret: &ApiTokenSecret,
+    ) -> Result<ApiToken, CreateApiTokenError> {
+        match query::api_token::create_api_token(self.pool(), (token, secret).into()).await {
+            Ok(db_token) => Ok(db_token.try_into()?),
+            Err(err) => {
+                error!(?err, "Failed to create API token in database");
+                Err(CreateApiTokenError::from((err, token.name())))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_api_tokens", skip(self), err)]
+    async fn get_api_tokens(&self, user_id: UserId) -> Result<Vec<ApiToken>, RepositoryError> {
+        query::api_token::get_api_tokens_by_user(self.pool(), user_id)
+            .await
+            .map_err(|err| RepositoryError::Unknown(err.into()))?
+            .into_iter()
+            .map(|db_token| db_token.try_into().map_err(RepositoryError::Unknown))
+            .collect()
+    }
+
+    #[instrument(name = "repository_delete_api_token", skip(self), err)]
+    async fn delete_api_token(
+        &self,
+        user_id: UserId,
+        token_id: ApiTokenId,
+    ) -> Result<(), DeleteApiTokenError> {
+        query::api_token::delete_api_token(self.pool(), user_id, token_id)
+            .await
+            .map_err(|err| DeleteApiTokenError::from((err, token_id)))
+    }
+
+    #[instrument(name = "repository_use_api_token", skip(self, secret), err)]
+    async fn use_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+    ) -> Result<(User, ApiToken), UseApiTokenError> {
+        let (db_user, db_token) = query::api_token::use_api_token(self.pool(), &secret.hash())
+            .await
+            .map_err(UseApiTokenError::from)?;
+
+        Ok((db_user.try_into()?, db_token.try_into()?))
+    }
+
+    #[instrument(
+        name = "repository_claim_idempotency_key",
+        skip(self, key, fingerprint),
+        err
+    )]
+    async fn claim_idempotency_key(
+        &self,
+        user_id: UserId,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+        expires_at: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError> {
+        let db_input = ClaimIdempotencyKeyDbInput {
+            user_id,
+            key: key.as_str().to_string(),
+            fingerprint: fingerprint.as_bytes().to_vec(),
+            expires_at,
+            locked_until,
+        };
+
+        query::idempotency::claim_idempotency_key(self.pool(), db_input)
+            .await
+            .map(|db_key| db_key.map(Into::into))
+            .map_err(|err| {
+                error!(?err, "Failed to claim idempotency key in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_delete_expired_idempotency_keys", skip(self), err)]
+    async fn delete_expired_idempotency_keys(&self) -> Result<u64, RepositoryError> {
+        query::idempotency::delete_expired_idempotency_keys(self.pool())
+            .await
+            .map_err(|err| {
+                error!(
+                    ?err,
+                    "Failed to delete expired idempotency keys from database"
+                );
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(
+        name = "repository_complete_idempotency_key",
+        skip(self, key, response),
+        err
+    )]
+    async fn complete_idempotency_key(
+        &self,
+        user_id: UserId,
+        key: &IdempotencyKey,
+        response: &IdempotentResponse,
+    ) -> Result<(), RepositoryError> {
+        query::idempotency::complete_idempotency_key(
+            self.pool(),
+            user_id,
+            key.as_str(),
+            response.into(),
+        )
+        .await
+        .map_err(|err| {
+            error!(?err, "Failed to store idempotent response in database");
+            RepositoryError::Unknown(err.into())
+        })
+    }
+
+    #[instrument(name = "repository_release_idempotency_key", skip(self, key), err)]
+    async fn release_idempotency_key(
+        &self,
+        user_id: UserId,
+        key: &IdempotencyKey,
+    ) -> Result<(), RepositoryError> {
+        query::idempotency::release_idempotency_key(self.pool(), user_id, key.as_str())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to release idempotency key in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_create_webhook", skip(self, webhook, secret), fields(url = %webhook.url()), err)]
+    async fn create_webhook(
+        &self,
+        webhook: &Webhook,
+        secret: &WebhookSecret,
+    ) -> Result<Webhook, RepositoryError> {
+        match query::webhook::create_webhook(self.pool(), (webhook, secret).into()).await {
+            Ok(db_webhook) => db_webhook.try_into().map_err(RepositoryError::Unknown),
+            Err(err) => {
+                error!(?err, "Failed to create webhook in database");
+                Err(RepositoryError::Unknown(err.into()))
+            }
+        }
+    }
+
+    #[instrument(name = "repository_get_webhooks", skip(self), err)]
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, RepositoryError> {
+        query::webhook::get_webhooks(self.pool())
+            .await
+            .map_err(|err| RepositoryError::Unknown(err.into()))?
+            .into_iter()
+            .map(|db_webhook| db_webhook.try_into().map_err(RepositoryError::Unknown))
+            .collect()
+    }
+
+    #[instrument(name = "repository_delete_webhook", skip(self), err)]
+    async fn delete_webhook(&self, webhook_id: WebhookId) -> Result<(), DeleteWebhookError> {
+        query::webhook::delete_webhook(self.pool(), webhook_id)
+            .await
+            .map_err(|err| DeleteWebhookError::from((err, webhook_id)))
+    }
+
+    #[instrument(name = "repository_get_webhook_deliveries", skip(self), err)]
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookId,
+    ) -> Result<Vec<WebhookDelivery>, GetWebhookError> {
+        query::webhook::get_webhook_by_id(self.pool(), webhook_id)
+            .await
+            .map_err(|err| GetWebhookError::from((err, webhook_id)))?;
+
+        query::webhook::get_webhook_deliveries(self.pool(), webhook_id)
+            .await
+            .map_err(|err| GetWebhookError::Unknown(err.into()))?
+            .into_iter()
+            .map(|db_delivery| db_delivery.try_into().map_err(GetWebhookError::Unknown))
+            .collect()
+    }
+
+    #[instrument(name = "repository_redeliver_webhook", skip(self), err)]
+    async fn redeliver_webhook(
+        &self,
+        webhook_id: WebhookId,
+        delivery_id: i64,
+    ) -> Result<WebhookDelivery, RedeliverWebhookError> {
+        let redelivered =
+            query::webhook::redeliver_webhook_delivery(self.pool(), webhook_id, delivery_id)
+                .await
+                .map_err(|err| RedeliverWebhookError::from((err, delivery_id)))?;
+        let Some(db_delivery) = redelivered else {
+            let exists =
+                query::webhook::webhook_delivery_exists(self.pool(), webhook_id, delivery_id)
+                    .await
+                    .map_err(|err| RedeliverWebhookError::from((err, delivery_id)))?;
+            return Err(if exists {
+                RedeliverWebhookError::DeliveryInProgress { id: delivery_id }
+            } else {
+                RedeliverWebhookError::DeliveryNotFound { id: delivery_id }
+            });
+        };
+
+        Ok(db_delivery.try_into()?)
+    }
+
+    #[instrument(name = "repository_claim_webhook_deliveries", skip(self), err)]
+    async fn claim_webhook_deliveries(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<DueWebhookDelivery>, RepositoryError> {
+        query::webhook::claim_due_webhook_deliveries(self.pool(), limit, lease_until)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to claim webhook deliveries in database");
+                RepositoryError::Unknown(err.into())
+            })?
+            .into_iter()
+            .map(|db_delivery| db_delivery.try_into().map_err(RepositoryError::Unknown))
+            .collect()
+    }
+
+    #[instrument(name = "repository_record_webhook_attempt", skip(self, attempt), err)]
+    async fn record_webhook_attempt(
+        &self,
+        delivery_id: i64,
+        attempt: &WebhookAttempt,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), RepositoryError> {
+        query::webhook::record_webhook_attempt(self.pool(), delivery_id, (attempt, retry_at).into())
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to record webhook attempt in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_purge_webhook_deliveries", skip(self), err)]
+    async fn purge_webhook_deliveries(
+        &self,
+        finished_before: DateTime<Utc>,
+    ) -> Result<u64, RepositoryError> {
+        query::webhook::delete_finished_webhook_deliveries(self.pool(), finished_before)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to purge webhook deliveries in database");
+                RepositoryError::Unknown(err.into())
+            })
+    }
+
+    #[instrument(name = "repository_claim_outbox_events", skip(self), err)]
+    async fn claim_outbox_events(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<OutboxEvent>, RepositoryError> {
+        query::outbox::claim_outbox_events(self.pool(), limit, lease_until)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to claim outbox events in database");
+                RepositoryError::Unknown(err.into())
+            })?
+            .into_iter()
+            .map(|db_event| db_event.try_into().map_err(RepositoryError::Unknown))
+            .collect()
+    }
+
+    #[instrument(name = "repository_complete_outbox_event", skip(self, event), fields(sequence = event.sequence()), err)]
+    async fn complete_outbox_event(&self, event: &OutboxEvent) -> Result<bool, RepositoryError> {
+        let webhook_event = WebhookEvent::from(event);
+        let result: Result<bool, sqlx::Error> = async {
+            let mut tx = self.begin().await?;
+
+            // Completed elsewhere once its lease ran out, deliveries and all.
+            let completed = query::outbox::delete_outbox_event(&mut tx, event.sequence()).await?;
+            if completed {
+                query::webhook::enqueue_webhook_deliveries(
+                    &mut tx,
+                    webhook_event.kind().as_str(),
+                    &webhook_event.to_payload(),
+                )
+                .await?;
+            }
+
+            tx.commit().await?;
+            Ok(completed)
+        }
+        .await;
+
+        result.map_err(|err| {
+            error!(?err, "Failed to complete outbox event in database");
+            RepositoryError::Unknown(err.into())
+        })
+    }
+}
+
+async fn insert_posts(
+    conn: &mut PgConnection,
+    notifier: &PostNotifier,
+    posts: &[ImportPostRequest],
+    audit: &AuditContext,
+) -> Result<Vec<Post>, ImportPostsError> {
+    let mut inserted = Vec::with_capacity(posts.len());
+
+    for request in posts {
+        let post = request.post();
+        match import_post(conn, notifier, request, audit).await {
+            Ok(db_post) => inserted.push(db_post.into()),
+            Err(err) => {
+                error!(
+                    ?err,
+                    "Failed to insert post with id {} into database",
+                    post.id()
+                );
+                return Err(ImportPostsError::from((err, post.title())));
+            }
+        }
+    }
+
+    Ok(inserted)
+}
+
+/// Inserts a post together with its authors, creating or refreshing the
+/// author profiles it is credited to.
+async fn insert_post(
+    conn: &mut PgConnection,
+    notifier: &PostNotifier,
+    mut post: DbPost,
+    audit: &AuditContext,
+) -> Result<DbPost, sqlx::Error> {
+    let authors = std::mem::take(&mut post.authors);
+    let mut author_ids: Vec<AuthorId> = Vec::with_capacity(authors.len());
+
+    for author in authors {
+        author_ids.push(query::author::upsert_author(conn, author.into()).await?);
+    }
+    query::post::insert_post(conn, &post).await?;
+    query::post::set_post_authors(conn, post.id, &author_ids).await?;
+
+    let after = query::post::get_post_snapshot(conn, post.id).await?;
+    append_audit_entry(
+        conn,
+        notifier,
+        audit,
+        AuditAction::PostCreated,
+        post.id,
+        None,
+        Some(after),
+    )
+    .await?;
+
+    // Posts restored to the trash are read back from there.
+    match post.deleted_at {
+        Some(_) => query::post::get_trashed_post_by_id(conn, post.id).await,
+        None => query::post::get_post_by_id(conn, post.id).await,
+    }
+}
+
+/// Inserts an imported or restored post along with its categories, tags and
+/// comments.
+async fn import_post(
+    conn: &mut PgConnection,
+    notifier: &PostNotifier,
+    request: &ImportPostRequest,
+    audit: &AuditContext,
+) -> Result<DbPost, sqlx::Error> {
+    let db_post = insert_post(conn, notifier, request.post().into(), audit).await?;
+
+    let terms = request.terms().iter().map(Into::into).collect();
+    query::taxonomy::insert_post_terms(conn, db_post.id, terms).await?;
+    let comments = request.comments().iter().map(Into::into).collect();
+    query::taxonomy::insert_post_comments(conn, db_post.id, comments).await?;
+
+    Ok(db_post)
+}
+
+/// Links a new entry to the head of the audit log. Runs in the transaction of
+/// the change it records, so the entry is written if and only if the change
+/// is committed. The change as readers see it is numbered for the change
+/// feed, announced to every instance under that number, and written to the
+/// outbox to be relayed, with the post as it is after the change.
+async fn append_audit_entry(
+    conn: &mut PgConnection,
+    notifier: &PostNotifier,
+    audit: &AuditContext,
+    action: AuditAction,
+    post_id: PostId,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+) -> Result<(), sqlx::Error> {
+    let change = PostChangeKind::between(is_visible(&before), is_visible(&after));
+    if let Some(change) = change {
+        let post = match change {
+            PostChangeKind::Created | PostChangeKind::Updated => Some(
+                query::post::get_post_by_id(&mut *conn, post_id)
+                    .await?
+                    .into(),
+            ),
+            PostChangeKind::Deleted => None,
+        };
+        let event = DomainEvent::new(change, post_id, post);
+        query::outbox::insert_outbox_event(conn, (&event, Utc::now()).into()).await?;
+    }
+
+    let record = AuditRecord::new(audit.clone(), action, post_id, before, after, Utc::now());
+    let prev_hash = query::audit::lock_audit_head(conn)
+        .await?
+        .unwrap_or_else(|| AUDIT_GENESIS_HASH.to_vec());
+    let hash = record.chain_hash(&prev_hash);
+
+    query::audit::insert_audit_entry(conn, (record, prev_hash, hash).into()).await?;
+
+    // Numbered under the audit log lock, so in commit order. Notifications
+    // are delivered in commit order too.
+    if let Some(change) = change {
+        let deleted = change == PostChangeKind::Deleted;
+        let sequence =
+            query::change::record_post_change(conn, post_id, deleted, Utc::now()).await?;
+        notifier
+            .notify(conn, sequence, change.as_str(), post_id)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Whether readers see the post of a snapshot, i.e. it exists and is not in
+/// the trash.
+fn is_visible(snapshot: &Option<serde_json::Value>) -> bool {
+    snapshot
+        .as_ref()
+        .is_some_and(|snapshot| snapshot["deleted_at"].is_null())
 }
@@ -0,0 +1,45 @@
+//! Correlation id of the HTTP request being handled.
+//!
+//! Every request carries an `x-request-id` header, generated unless the
+//! client sent one, which is echoed in the response. While the request is
+//! handled the id is available through [`current`], so the service can record
+//! it without passing it through every call.
+
+use std::{fmt::Display, future::Future};
+
+/// Longer ids sent by clients are cut off at this many characters.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static CURRENT: RequestId;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    pub fn new(raw: &str) -> Self {
+        Self(raw.trim().chars().take(MAX_REQUEST_ID_LENGTH).collect())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Runs `f` with `id` as the current request id.
+pub async fn scope<F: Future>(id: RequestId, f: F) -> F::Output {
+    CURRENT.scope(id, f).await
+}
+
+/// The id of the request being handled, if any. Commands run from the CLI
+/// have none.
+pub fn current() -> Option<RequestId> {
+    CURRENT.try_with(Clone::clone).ok()
+}
@@ -1,35 +1,155 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use axum::{Router, extract::Request};
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, Request},
+    middleware,
+};
 use tokio::{net::TcpListener, signal};
-use tower_http::trace::TraceLayer;
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
 use tracing::info_span;
 
 use crate::{
-    api::{health, post},
-    domain::service::Service,
+    api::{
+        admin, api_token, auth, author,
+        cache::CacheConfig,
+        changes, events, graphql, health, metrics,
+        middleware::{
+            bearer_auth, http_cache, http_metrics, idempotency_key, plain_error_problem,
+            problem_instance, request_id_scope,
+        },
+        oidc, openapi, post,
+        responses::route_not_found,
+        totp, webhook,
+    },
+    domain::{models::post::PostConstraints, service::Service},
+    metrics::Metrics,
+    oidc::{OidcClient, OidcConfig},
 };
 
+/// How often an idle event stream gets a comment, well below the idle
+/// timeouts of common proxies.
+pub const DEFAULT_SSE_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// The request body limit axum applies by default.
+const DEFAULT_MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// Largest request accepted, so that a post body of the largest size allowed
+/// gets through even with every character escaped in JSON, along with its
+/// title and byline. Never less than axum's default.
+pub fn max_request_bytes(post_constraints: &PostConstraints) -> usize {
+    post_constraints
+        .body_max_bytes()
+        .saturating_mul(2)
+        .saturating_add(64 * 1024)
+        .max(DEFAULT_MAX_REQUEST_BYTES)
+}
+
 #[derive(Debug, Clone)]
 pub struct AppState<S: Service> {
     pub service: Arc<S>,
+    pub secure_cookies: bool,
+    pub oidc: Option<Arc<OidcClient>>,
+    pub cache: Arc<CacheConfig>,
+    pub post_constraints: Arc<PostConstraints>,
+    pub sse_heartbeat: Duration,
+    pub metrics: Metrics,
 }
 
 impl<S: Service> AppState<S> {
-    pub fn new(service: S) -> Self {
+    pub fn new(service: S, secure_cookies: bool) -> Self {
         Self {
             service: Arc::new(service),
+            secure_cookies,
+            oidc: None,
+            cache: Arc::default(),
+            post_constraints: Arc::default(),
+            sse_heartbeat: DEFAULT_SSE_HEARTBEAT,
+            metrics: Metrics::default(),
         }
     }
 
+    pub fn with_oidc(mut self, oidc: Option<OidcClient>) -> Self {
+        self.oidc = oidc.map(Arc::new);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: CacheConfig) -> Self {
+        self.cache = Arc::new(cache);
+        self
+    }
+
+    pub fn with_post_constraints(mut self, post_constraints: PostConstraints) -> Self {
+        self.post_constraints = Arc::new(post_constraints);
+        self
+    }
+
+    pub fn with_sse_heartbeat(mut self, sse_heartbeat: Duration) -> Self {
+        self.sse_heartbeat = sse_heartbeat;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
     pub fn service(&self) -> &Arc<S> {
         &self.service
     }
+
+    pub fn secure_cookies(&self) -> bool {
+        self.secure_cookies
+    }
+
+    pub fn oidc(&self) -> Option<&OidcClient> {
+        self.oidc.as_deref()
+    }
+
+    pub fn cache(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    pub fn post_constraints(&self) -> &PostConstraints {
+        &self.post_constraints
+    }
+
+    pub fn max_request_bytes(&self) -> usize {
+        max_request_bytes(&self.post_constraints)
+    }
+
+    pub fn sse_heartbeat(&self) -> Duration {
+        self.sse_heartbeat
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct HttpServerConfig<'a> {
     pub port: &'a str,
+    /// Marks the session cookie `Secure`. Only disable when serving plain HTTP
+    /// during local development.
+    pub secure_cookies: bool,
+    /// Enables login through an OpenID Connect provider.
+    pub oidc: Option<OidcConfig>,
+    /// How responses of read endpoints may be cached by clients and the CDN.
+    pub cache: CacheConfig,
+    /// Limits on the title and body of posts submitted through the API.
+    pub post_constraints: PostConstraints,
+    /// Serves the GraphiQL playground on `GET /graphql`, for development.
+    pub graphiql: bool,
+    /// How often `GET /events` sends a comment while nothing happens.
+    pub sse_heartbeat: Duration,
+    /// Where requests are counted and timed.
+    pub metrics: Metrics,
+    /// Serves `GET /metrics` on this port as well, without authentication.
+    pub metrics_route: bool,
 }
 
 pub struct HttpServer {
@@ -44,15 +164,66 @@ impl HttpServer {
     ) -> Result<Self, anyhow::Error> {
         let trace_layer = TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
             let uri = request.uri().to_string();
-            info_span!("http_request", method = ?request.method(), uri)
+            let request_id = request
+                .headers()
+                .get("x-request-id")
+                .and_then(|id| id.to_str().ok())
+                .unwrap_or_default();
+            info_span!("http_request", method = ?request.method(), uri, request_id)
         });
 
-        let state = AppState::new(service);
+        let state = AppState::new(service, config.secure_cookies)
+            .with_oidc(config.oidc.map(OidcClient::new))
+            .with_cache(config.cache)
+            .with_post_constraints(config.post_constraints)
+            .with_sse_heartbeat(config.sse_heartbeat)
+            .with_metrics(config.metrics);
 
-        let router = Router::new()
+        let mut router = Router::new()
             .merge(health::routes::<S>())
+            .merge(auth::routes::<S>())
+            .merge(oidc::routes::<S>())
+            .merge(totp::routes::<S>())
+            .merge(api_token::routes::<S>())
+            .merge(author::routes::<S>())
             .merge(post::routes::<S>())
+            .merge(changes::routes::<S>())
+            .merge(admin::routes::<S>())
+            .merge(webhook::routes::<S>())
+            .merge(events::routes::<S>())
+            .merge(graphql::routes::<S>(config.graphiql))
+            .merge(openapi::routes::<S>());
+        if config.metrics_route {
+            router = router.merge(metrics::routes::<S>());
+        }
+
+        let router = router
+            .fallback(route_not_found)
+            .layer(DefaultBodyLimit::max(state.max_request_bytes()))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                http_cache::<S>,
+            ))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                idempotency_key::<S>,
+            ))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                bearer_auth::<S>,
+            ))
+            .layer(middleware::from_fn(plain_error_problem))
+            .layer(middleware::from_fn(problem_instance))
+            .layer(middleware::from_fn(request_id_scope))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                http_metrics::<S>,
+            ))
             .layer(trace_layer)
+            // Outermost, so the id is set before anything else sees the
+            // request and echoed on every response.
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
             .with_state(state);
 
         let addr = format!("127.0.0.1:{}", config.port);
@@ -63,14 +234,6 @@ impl HttpServer {
     }
 
     pub async fn run(self) -> Result<(), anyhow::Error> {
-        tracing_subscriber::fmt()
-            .with_env_filter("info")
-            .with_target(false)
-            .with_writer(std::io::stdout)
-            .init();
-
-        tracing::info!("🔧 Tracing initialized");
-
         axum::serve(self.listener, self.router.into_make_service())
             .with_graceful_shutdown(Self::shutdown_signal())
             .await?;
@@ -101,3 +264,38 @@ impl HttpServer {
         println!("🔌 Shutdown signal received.");
     }
 }
+
+/// Serves `GET /metrics` alone, on a port that can be kept from the public.
+pub struct MetricsServer {
+    router: Router,
+    listener: TcpListener,
+}
+
+impl MetricsServer {
+    pub async fn try_new(metrics: Metrics, port: &str) -> Result<Self, anyhow::Error> {
+        let addr = format!("127.0.0.1:{port}");
+
+        let listener = TcpListener::bind(&addr).await?;
+
+        Ok(Self {
+            router: metrics::admin_routes(metrics),
+            listener,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, anyhow::Error> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serves until the task is dropped.
+    pub async fn run(self) -> Result<(), anyhow::Error> {
+        tracing::info!(
+            "Metrics server listening on {}",
+            self.listener.local_addr()?
+        );
+
+        axum::serve(self.listener, self.router.into_make_service()).await?;
+
+        Ok(())
+    }
+}
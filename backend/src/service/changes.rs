@@ -0,0 +1,24 @@
+//! Background compaction of the change feed.
+
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+use crate::domain::service::Service;
+
+/// Removes tombstones whose retention has run out every `interval`, starting
+/// right away. Runs until the task is dropped.
+pub async fn compact_changes_periodically<S: Service>(service: S, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match service.compact_changes().await {
+            Ok(0) => {}
+            Ok(compacted) => info!(compacted, "Compacted tombstones from the change feed"),
+            Err(err) => error!(?err, "Failed to compact the change feed"),
+        }
+    }
+}
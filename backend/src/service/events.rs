@@ -0,0 +1,247 @@
+//! Live streams of post changes, made by this instance or any other.
+//!
+//! Every committed change is announced through the database in commit order,
+//! numbered as in the change feed, and published from there. Announcements
+//! missed while the database connection was lost, or while the relay fell
+//! behind, are read back from the change feed, or, failing that, end every
+//! stream. A subscriber resuming after an event id first gets the changes
+//! since from the change feed, so nothing is lost across reconnects,
+//! restarts or instances.
+
+use std::future::Future;
+
+use async_stream::stream;
+use chrono::Utc;
+use futures::{StreamExt, stream::BoxStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::error;
+
+use crate::domain::{
+    models::{
+        change_feed::{ChangeFeedQuery, FeedChange, MAX_CHANGE_FEED_PAGE_SIZE},
+        event::{
+            EventId, PostChange, PostChangeKind, PostChangeNotice, PostChangeSignal, PostEvent,
+            StreamEvent,
+        },
+    },
+    repository::{GetChangesError, GetPostError, Repository},
+};
+
+use super::BlogService;
+
+/// How many events a subscriber may fall behind before its stream ends.
+pub const DEFAULT_EVENT_LOG_CAPACITY: usize = 1000;
+
+/// The channel events are published on to the streams of this instance.
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    sender: broadcast::Sender<Published>,
+}
+
+#[derive(Debug, Clone)]
+enum Published {
+    Event(PostEvent),
+    /// Ends every stream.
+    Interrupted,
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: PostEvent) {
+        // Nobody listening is fine.
+        let _ = self.sender.send(Published::Event(event));
+    }
+
+    /// Ends every stream, for when events were missed that cannot be read
+    /// back.
+    pub fn interrupt(&self) {
+        let _ = self.sender.send(Published::Interrupted);
+    }
+
+    /// Every event published from now on.
+    ///
+    /// A subscriber too slow to keep up, or subscribed when the log is
+    /// interrupted, sees the stream end, and picks up where it left off by
+    /// subscribing again with the last id it saw.
+    pub fn subscribe(&self) -> BoxStream<'static, PostEvent> {
+        futures::stream::unfold(self.sender.subscribe(), |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(Published::Event(event)) => Some((event, receiver)),
+                Ok(Published::Interrupted) | Err(RecvError::Lagged(_) | RecvError::Closed) => None,
+            }
+        })
+        .boxed()
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_LOG_CAPACITY)
+    }
+}
+
+impl<R: Repository> BlogService<R> {
+    /// Listens for the changes committed to posts, returning once listening,
+    /// and then publishes them to the streams of this instance until the
+    /// returned future is dropped.
+    pub async fn relay_post_changes(self) -> impl Future<Output = ()> + Send + 'static {
+        let mut signals = self.repo.watch_post_changes().await;
+        // Read once listening, so that any change missed later comes after it.
+        let mut last_id = self
+            .repo
+            .get_latest_change_id()
+            .await
+            .inspect_err(|err| error!(?err, "Failed to get the latest post change"))
+            .ok();
+
+        async move {
+            while let Some(signal) = signals.next().await {
+                match signal {
+                    // Changes are numbered in commit order, so one numbered
+                    // up to the last was read from the change feed already.
+                    PostChangeSignal::Notice(notice)
+                        if last_id.is_some_and(|last_id| notice.sequence() <= last_id) => {}
+                    PostChangeSignal::Notice(notice) => {
+                        last_id = Some(notice.sequence());
+                        if let Some(change) = self.post_change(notice).await {
+                            self.events.publish(PostEvent::new(
+                                notice.sequence(),
+                                change,
+                                Utc::now(),
+                            ));
+                        }
+                    }
+                    PostChangeSignal::Missed => {
+                        last_id = match last_id {
+                            Some(last_id) => self.catch_up(last_id).await,
+                            None => None,
+                        };
+                        if last_id.is_none() {
+                            self.events.interrupt();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Publishes the changes after `last_id` from the change feed, returning
+    /// the number of the last, or `None` if they cannot be read back. Like
+    /// [`Self::resume_post_events`], posts changed more than once come once.
+    async fn catch_up(&self, mut last_id: EventId) -> Option<EventId> {
+        loop {
+            let query = ChangeFeedQuery::new()
+                .with_since(Some(last_id))
+                .with_limit(Some(MAX_CHANGE_FEED_PAGE_SIZE));
+            match self.repo.get_changes(&query).await {
+                Ok(page) => {
+                    let has_more = page.has_more();
+                    for change in page.into_changes() {
+                        last_id = change.sequence();
+                        self.events.publish(missed_event(change));
+                    }
+                    if !has_more {
+                        return Some(last_id);
+                    }
+                }
+                Err(err) => {
+                    error!(?err, "Failed to read missed post changes");
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// The events after `last_event_id`, read from the change feed, then
+    /// every new one. Posts changed more than once since come once, as they
+    /// are now, and as `post.updated` even if they were created since.
+    pub(super) fn resume_post_events(
+        &self,
+        last_event_id: EventId,
+    ) -> BoxStream<'static, StreamEvent> {
+        // Subscribed before reading the feed, so nothing falls in between.
+        let mut live = self.events.subscribe();
+        let repo = self.repo.clone();
+
+        stream! {
+            let mut last_id = last_event_id;
+            loop {
+                let query = ChangeFeedQuery::new()
+                    .with_since(Some(last_id))
+                    .with_limit(Some(MAX_CHANGE_FEED_PAGE_SIZE));
+                match repo.get_changes(&query).await {
+                    Ok(page) => {
+                        let has_more = page.has_more();
+                        for change in page.into_changes() {
+                            last_id = change.sequence();
+                            yield StreamEvent::Post(missed_event(change));
+                        }
+                        if !has_more {
+                            break;
+                        }
+                    }
+                    Err(GetChangesError::ResyncRequired {
+                        compacted_through, ..
+                    }) => {
+                        last_id = compacted_through;
+                        yield StreamEvent::Reset(compacted_through);
+                    }
+                    Err(err) => {
+                        error!(?err, "Failed to read post changes to resume events after");
+                        return;
+                    }
+                }
+            }
+
+            while let Some(event) = live.next().await {
+                if event.id() > last_id {
+                    last_id = event.id();
+                    yield StreamEvent::Post(event);
+                }
+            }
+        }
+        .boxed()
+    }
+
+    /// The change a notice stands for, with the post as it is now. `None` if
+    /// the post has been deleted since, which is announced separately.
+    async fn post_change(&self, notice: PostChangeNotice) -> Option<PostChange> {
+        let post_id = notice.post_id();
+        let post = match notice.kind() {
+            PostChangeKind::Deleted => return Some(PostChange::Deleted(post_id)),
+            PostChangeKind::Created | PostChangeKind::Updated => {
+                self.repo.get_post_by_id(post_id).await
+            }
+        };
+
+        match (notice.kind(), post) {
+            (PostChangeKind::Created, Ok(post)) => Some(PostChange::Created(post)),
+            (_, Ok(post)) => Some(PostChange::Updated(post)),
+            (_, Err(GetPostError::PostNotFound { .. })) => None,
+            (_, Err(err)) => {
+                error!(?err, "Failed to get changed post {post_id}");
+                None
+            }
+        }
+    }
+}
+
+/// An event for a change read from the change feed.
+fn missed_event(change: FeedChange) -> PostEvent {
+    match change {
+        FeedChange::Upsert { sequence, post } => {
+            let occurred_at = post.updated_at();
+            PostEvent::new(sequence, PostChange::Updated(post), occurred_at)
+        }
+        FeedChange::Tombstone {
+            sequence,
+            post_id,
+            deleted_at,
+        } => PostEvent::new(sequence, PostChange::Deleted(post_id), deleted_at),
+    }
+}
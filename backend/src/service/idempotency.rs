@@ -0,0 +1,24 @@
+//! Background cleanup of idempotency keys.
+
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+use crate::domain::service::Service;
+
+/// Deletes idempotency keys that have expired every `interval`, starting
+/// right away. Runs until the task is dropped.
+pub async fn expire_idempotency_keys_periodically<S: Service>(service: S, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match service.expire_idempotency_keys().await {
+            Ok(0) => {}
+            Ok(expired) => info!(expired, "Deleted expired idempotency keys"),
+            Err(err) => error!(?err, "Failed to delete expired idempotency keys"),
+        }
+    }
+}
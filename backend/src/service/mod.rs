@@ -1,19 +1,103 @@
+use std::sync::LazyLock;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::{StreamExt, TryStreamExt, stream::BoxStream};
+use tracing::{error, instrument, warn};
 
 use crate::{
     domain::{
-        models::post::{CreatePostRequest, Post, UpdatePostRequest},
-        repository::{IntoRepositoryError, Repository},
+        models::{
+            api_token::{ApiToken, ApiTokenSecret, CreateApiTokenRequest, CreatedApiToken},
+            audit::{AuditContext, AuditEntry, AuditQuery, AuditVerification},
+            author::{Author, CreateAuthorRequest},
+            change_feed::{ChangeFeedPage, ChangeFeedQuery},
+            event::{EventId, StreamEvent},
+            idempotency::{IdempotencyKey, IdempotentResponse, RequestFingerprint},
+            oidc::{OidcIdentity, OidcLogin, OidcState},
+            post::{
+                CreatePostRequest, ImportPostRequest, Post, PostComment, PostPage, PostPageQuery,
+                PostTerm, UpdatePostRequest,
+            },
+            totp::{
+                MAX_SECOND_FACTOR_ATTEMPTS, RecoveryCode, SECOND_FACTOR_LOCKOUT_MINUTES,
+                SecondFactor, TotpCode, TotpEnrollment, TotpSecret,
+            },
+            user::{
+                CreateUserRequest, LoginRequest, Password, PasswordHash, Role, Session,
+                SessionToken, User,
+            },
+            webhook::{
+                CreateWebhookRequest, CreatedWebhook, Webhook, WebhookDelivery, WebhookRetryPolicy,
+                WebhookSecret, WebhookTargetPolicy,
+            },
+        },
+        policy::{self, Action, SecondFactorPolicy},
+        repository::{
+            CreateTotpError, GetPostError, GetSessionError, GetTotpError, GetUserError,
+            IntoRepositoryError, Repository, TakeOidcLoginError, UseApiTokenError,
+            UseSecondFactorError,
+        },
         service::{Service, ServiceError},
     },
-    ids::PostId,
+    ids::{ApiTokenId, AuthorId, PostId, WebhookId},
+    metrics::Metrics,
+    request_id,
 };
 
+pub mod changes;
+pub mod events;
+pub mod idempotency;
 pub mod mappers;
+pub mod outbox;
+pub mod purge;
+pub mod webhooks;
+
+use events::EventLog;
+
+pub const DEFAULT_SESSION_TTL: Duration = Duration::days(7);
+
+pub const DEFAULT_TRASH_RETENTION: Duration = Duration::days(30);
+
+/// Longest configurable retention, in days. Anything longer would not keep
+/// posts meaningfully longer but could overflow the date arithmetic.
+pub const MAX_RETENTION_DAYS: i64 = 36_500;
+
+pub const DEFAULT_TOMBSTONE_RETENTION: Duration = Duration::days(90);
+
+pub const DEFAULT_WEBHOOK_DELIVERY_RETENTION: Duration = Duration::days(30);
+
+pub const DEFAULT_IDEMPOTENCY_KEY_TTL: Duration = Duration::hours(24);
+
+/// How long a request may take before a retry with its idempotency key is
+/// handled again, in case it never finished.
+pub const IDEMPOTENCY_KEY_LEASE: Duration = Duration::minutes(5);
+
+/// How long a user has to finish logging in at the identity provider.
+pub const OIDC_LOGIN_TTL: Duration = Duration::minutes(10);
+
+/// Verified against when the email is unknown, so a failed login takes as
+/// long whether or not the account exists.
+static DUMMY_PASSWORD_HASH: LazyLock<PasswordHash> = LazyLock::new(|| {
+    PasswordHash::generate(&Password::new("dummy password for timing"))
+        .expect("Failed to hash dummy password")
+});
 
 #[derive(Debug, Clone)]
 pub struct BlogService<R: Repository> {
     repo: R,
+    session_ttl: Duration,
+    trash_retention: Duration,
+    tombstone_retention: Duration,
+    idempotency_key_ttl: Duration,
+    second_factor_policy: SecondFactorPolicy,
+    events: EventLog,
+    metrics: Metrics,
+    webhook_retry: WebhookRetryPolicy,
+    webhook_targets: WebhookTargetPolicy,
+    webhook_timeout: std::time::Duration,
+    webhook_client: reqwest::Client,
+    webhook_delivery_retention: Duration,
 }
 
 impl<R> BlogService<R>
@@ -21,7 +105,176 @@ where
     R: Repository,
 {
     pub fn new(repo: R) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            session_ttl: DEFAULT_SESSION_TTL,
+            trash_retention: DEFAULT_TRASH_RETENTION,
+            tombstone_retention: DEFAULT_TOMBSTONE_RETENTION,
+            idempotency_key_ttl: DEFAULT_IDEMPOTENCY_KEY_TTL,
+            second_factor_policy: SecondFactorPolicy::default(),
+            events: EventLog::default(),
+            metrics: Metrics::default(),
+            webhook_retry: WebhookRetryPolicy::default(),
+            webhook_targets: WebhookTargetPolicy::default(),
+            webhook_timeout: webhooks::DEFAULT_WEBHOOK_TIMEOUT,
+            webhook_client: webhooks::webhook_client(
+                webhooks::DEFAULT_WEBHOOK_TIMEOUT,
+                WebhookTargetPolicy::default(),
+            ),
+            webhook_delivery_retention: DEFAULT_WEBHOOK_DELIVERY_RETENTION,
+        }
+    }
+
+    pub fn with_session_ttl(mut self, session_ttl: Duration) -> Self {
+        self.session_ttl = session_ttl;
+        self
+    }
+
+    /// How long deleted posts stay in the trash before they are purged.
+    pub fn with_trash_retention(mut self, trash_retention: Duration) -> Self {
+        self.trash_retention = trash_retention;
+        self
+    }
+
+    /// How long the change feed remembers deleted posts. Clients that have
+    /// not synced for longer have to start over.
+    pub fn with_tombstone_retention(mut self, tombstone_retention: Duration) -> Self {
+        self.tombstone_retention = tombstone_retention;
+        self
+    }
+
+    /// How long responses are kept for retries with the same idempotency key.
+    pub fn with_idempotency_key_ttl(mut self, idempotency_key_ttl: Duration) -> Self {
+        self.idempotency_key_ttl = idempotency_key_ttl;
+        self
+    }
+
+    /// Roles that must enroll in two-factor authentication before acting.
+    /// Nobody is required to by default.
+    pub fn with_second_factor_policy(mut self, policy: SecondFactorPolicy) -> Self {
+        self.second_factor_policy = policy;
+        self
+    }
+
+    /// How many post events a stream may fall behind before it ends.
+    pub fn with_event_log_capacity(mut self, capacity: usize) -> Self {
+        self.events = EventLog::new(capacity);
+        self
+    }
+
+    /// Where changes made to posts are counted.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// How many times, and how far apart, failed webhook deliveries are
+    /// attempted before they are dead.
+    pub fn with_webhook_retry_policy(mut self, policy: WebhookRetryPolicy) -> Self {
+        self.webhook_retry = policy;
+        self
+    }
+
+    /// How long a webhook receiver has to respond before the attempt fails.
+    pub fn with_webhook_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.webhook_timeout = timeout;
+        self.webhook_client = webhooks::webhook_client(timeout, self.webhook_targets.clone());
+        self
+    }
+
+    /// Which hosts webhooks may call besides those on public addresses.
+    pub fn with_webhook_target_policy(mut self, policy: WebhookTargetPolicy) -> Self {
+        self.webhook_client = webhooks::webhook_client(self.webhook_timeout, policy.clone());
+        self.webhook_targets = policy;
+        self
+    }
+
+    /// How long delivered and dead webhook deliveries are kept in the
+    /// delivery log.
+    pub fn with_webhook_delivery_retention(mut self, retention: Duration) -> Self {
+        self.webhook_delivery_retention = retention;
+        self
+    }
+
+    fn authorize(&self, actor: &User, action: Action<'_>) -> Result<(), ServiceError> {
+        if self.second_factor_policy.blocks(actor) {
+            return Err(ServiceError::SecondFactorEnrollmentRequired);
+        }
+
+        if policy::can(actor, action) {
+            Ok(())
+        } else {
+            Err(ServiceError::Forbidden)
+        }
+    }
+
+    /// Attributes a change to `actor`, if any, and the request being handled.
+    fn audit_context(actor: Option<&User>) -> AuditContext {
+        AuditContext::new(actor.map(User::id), request_id::current())
+    }
+
+    /// Starts a session that stays pending until `user` presents a second
+    /// factor, unless `second_factor_verified`.
+    async fn start_session(
+        &self,
+        user: User,
+        second_factor_verified: bool,
+    ) -> Result<Session, ServiceError> {
+        let pending = user.has_second_factor() && !second_factor_verified;
+        let session = Session::new(
+            SessionToken::generate(),
+            user,
+            Utc::now() + self.session_ttl,
+        )
+        .with_second_factor_pending(pending);
+        self.repo.create_session(&session).await?;
+
+        Ok(session)
+    }
+
+    /// Checks a one-time code or recovery code of `user`, consuming it. Every
+    /// attempt counts towards a lockout, which is reported like a wrong code
+    /// so as not to tell a guesser when to stop.
+    async fn verify_second_factor(
+        &self,
+        user: &User,
+        factor: &SecondFactor,
+    ) -> Result<(), ServiceError> {
+        let locked_until = Utc::now() + Duration::minutes(SECOND_FACTOR_LOCKOUT_MINUTES);
+        let result = self
+            .repo
+            .start_second_factor_attempt(user.id(), MAX_SECOND_FACTOR_ATTEMPTS, locked_until)
+            .await;
+        match result {
+            Ok(()) => {}
+            Err(UseSecondFactorError::Rejected) => return Err(ServiceError::InvalidSecondFactor),
+            Err(err) => return Err(err.into_repository_error().into()),
+        }
+
+        let result = match factor {
+            SecondFactor::Totp(code) => {
+                let totp = match self.repo.get_totp(user.id()).await {
+                    Ok(totp) if totp.is_confirmed() => totp,
+                    Ok(_) | Err(GetTotpError::TotpNotFound) => {
+                        return Err(ServiceError::InvalidSecondFactor);
+                    }
+                    Err(err) => return Err(err.into_repository_error().into()),
+                };
+                let step = totp
+                    .secret()
+                    .verify(code, Utc::now())
+                    .ok_or(ServiceError::InvalidSecondFactor)?;
+
+                self.repo.use_totp_step(user.id(), step).await
+            }
+            SecondFactor::RecoveryCode(code) => self.repo.use_recovery_code(user.id(), code).await,
+        };
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(UseSecondFactorError::Rejected) => Err(ServiceError::InvalidSecondFactor),
+            Err(err) => Err(err.into_repository_error().into()),
+        }
     }
 }
 
@@ -30,10 +283,16 @@ impl<R> Service for BlogService<R>
 where
     R: Repository,
 {
-    async fn create_post(&self, input: &CreatePostRequest) -> Result<Post, ServiceError> {
+    async fn create_post(
+        &self,
+        actor: &User,
+        input: &CreatePostRequest,
+    ) -> Result<Post, ServiceError> {
+        self.authorize(actor, Action::CreatePost)?;
+
         Ok(self
             .repo
-            .create_post(input)
+            .create_post(input, actor.id(), &Self::audit_context(Some(actor)))
             .await
             .map_err(IntoRepositoryError::into_repository_error)?)
     }
@@ -42,6 +301,14 @@ where
         Ok(self.repo.get_all_posts().await?)
     }
 
+    async fn get_posts_page(&self, query: &PostPageQuery) -> Result<PostPage, ServiceError> {
+        Ok(self.repo.get_posts_page(query).await?)
+    }
+
+    async fn get_posts_last_modified(&self) -> Result<Option<DateTime<Utc>>, ServiceError> {
+        Ok(self.repo.get_posts_last_modified().await?)
+    }
+
     async fn get_posts_by_id(&self, id: PostId) -> Result<Post, ServiceError> {
         Ok(self
             .repo
@@ -50,84 +317,1713 @@ where
             .map_err(IntoRepositoryError::into_repository_error)?)
     }
 
+    async fn get_post_terms(&self, id: PostId) -> Result<Vec<PostTerm>, ServiceError> {
+        Ok(self
+            .repo
+            .get_post_terms(id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    async fn get_post_comments(&self, id: PostId) -> Result<Vec<PostComment>, ServiceError> {
+        Ok(self
+            .repo
+            .get_post_comments(id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
     async fn update_post(
         &self,
+        actor: &User,
         post_id: PostId,
+        expected_version: i64,
         input: &UpdatePostRequest,
     ) -> Result<Post, ServiceError> {
+        let post = self.get_posts_by_id(post_id).await?;
+        self.authorize(actor, Action::UpdatePost(&post))?;
+
         Ok(self
             .repo
-            .update_post(post_id, input)
+            .update_post(
+                post_id,
+                expected_version,
+                input,
+                &Self::audit_context(Some(actor)),
+            )
             .await
             .map_err(IntoRepositoryError::into_repository_error)?)
     }
 
-    async fn delete_post(&self, post_id: PostId) -> Result<(), ServiceError> {
+    async fn delete_post(
+        &self,
+        actor: &User,
+        post_id: PostId,
+        expected_version: i64,
+    ) -> Result<(), ServiceError> {
+        let post = self.get_posts_by_id(post_id).await?;
+        self.authorize(actor, Action::DeletePost(&post))?;
+
+        self.repo
+            .trash_post(post_id, expected_version, &Self::audit_context(Some(actor)))
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+
+        Ok(())
+    }
+
+    async fn delete_post_permanently(
+        &self,
+        actor: &User,
+        post_id: PostId,
+        expected_version: i64,
+    ) -> Result<(), ServiceError> {
+        let post = match self.repo.get_post_by_id(post_id).await {
+            Err(GetPostError::PostNotFound { .. }) => {
+                self.repo.get_trashed_post_by_id(post_id).await
+            }
+            result => result,
+        }
+        .map_err(IntoRepositoryError::into_repository_error)?;
+        self.authorize(actor, Action::DeletePost(&post))?;
+
+        self.repo
+            .delete_post(post_id, expected_version, &Self::audit_context(Some(actor)))
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+
+        Ok(())
+    }
+
+    async fn get_trash(&self, actor: &User) -> Result<Vec<Post>, ServiceError> {
+        let posts = self.repo.get_trashed_posts().await?;
+
+        Ok(posts
+            .into_iter()
+            .filter(|post| policy::can(actor, Action::RestorePost(post)))
+            .collect())
+    }
+
+    async fn restore_post(&self, actor: &User, post_id: PostId) -> Result<Post, ServiceError> {
+        let post = self
+            .repo
+            .get_trashed_post_by_id(post_id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+        self.authorize(actor, Action::RestorePost(&post))?;
+
         Ok(self
             .repo
-            .delete_post(post_id)
+            .restore_post(post_id, &Self::audit_context(Some(actor)))
             .await
             .map_err(IntoRepositoryError::into_repository_error)?)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use chrono::Utc;
-    use mockall::predicate::*;
-    use mockall::*;
+    async fn purge_trash(&self) -> Result<u64, ServiceError> {
+        let deleted_before = Utc::now() - self.trash_retention;
 
-    use crate::domain::models::post::{PostBody, PostTitle};
-    use crate::domain::repository::{
-        CreatePostError, DeletePostError, GetPostError, RepositoryError, UpdatePostError,
-    };
+        Ok(self
+            .repo
+            .purge_trash(deleted_before, &Self::audit_context(None))
+            .await?)
+    }
 
-    use super::*;
+    async fn get_changes(&self, query: &ChangeFeedQuery) -> Result<ChangeFeedPage, ServiceError> {
+        Ok(self
+            .repo
+            .get_changes(query)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
 
-    mock! {
-        Repository {}
-        impl Clone for Repository {
-            fn clone(&self) -> Self;
+    async fn compact_changes(&self) -> Result<u64, ServiceError> {
+        let deleted_before = Utc::now() - self.tombstone_retention;
+
+        Ok(self.repo.compact_changes(deleted_before).await?)
+    }
+
+    async fn import_posts(&self, posts: &[ImportPostRequest]) -> Result<Vec<Post>, ServiceError> {
+        Ok(self
+            .repo
+            .import_posts(posts, &Self::audit_context(None))
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    fn stream_posts(&self) -> BoxStream<'static, Result<Post, ServiceError>> {
+        Box::pin(self.repo.stream_posts().map_err(ServiceError::from))
+    }
+
+    fn stream_backup_posts(&self) -> BoxStream<'static, Result<ImportPostRequest, ServiceError>> {
+        Box::pin(self.repo.stream_backup_posts().map_err(ServiceError::from))
+    }
+
+    fn subscribe_post_events(
+        &self,
+        last_event_id: Option<EventId>,
+    ) -> BoxStream<'static, StreamEvent> {
+        match last_event_id {
+            Some(last_event_id) => self.resume_post_events(last_event_id),
+            None => self.events.subscribe().map(StreamEvent::Post).boxed(),
         }
+    }
 
-        #[async_trait]
-        impl Repository for Repository {
-            async fn create_post(&self, input: &CreatePostRequest) -> Result<Post, CreatePostError>;
-            async fn get_all_posts(&self) -> Result<Vec<Post>, RepositoryError>;
-            async fn get_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError>;
-            async fn update_post(
-                &self,
-                post_id: PostId,
-                input: &UpdatePostRequest,
-            ) -> Result<Post, UpdatePostError>;
-            async fn delete_post(&self, post_id: PostId) -> Result<(), DeletePostError>;
+    fn export_posts(
+        &self,
+        actor: &User,
+    ) -> Result<BoxStream<'static, Result<ImportPostRequest, ServiceError>>, ServiceError> {
+        self.authorize(actor, Action::ExportBackup)?;
+
+        Ok(self.stream_backup_posts())
+    }
+
+    async fn restore_posts(&self, posts: &[ImportPostRequest]) -> Result<Vec<Post>, ServiceError> {
+        Ok(self
+            .repo
+            .restore_posts(posts, &Self::audit_context(None))
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    async fn get_audit_entries(
+        &self,
+        actor: &User,
+        query: &AuditQuery,
+    ) -> Result<Vec<AuditEntry>, ServiceError> {
+        self.authorize(actor, Action::ViewAuditLog)?;
+
+        Ok(self.repo.get_audit_entries(query).await?)
+    }
+
+    async fn verify_audit_log(&self, actor: &User) -> Result<AuditVerification, ServiceError> {
+        self.authorize(actor, Action::ViewAuditLog)?;
+
+        let mut verification = AuditVerification::new();
+        let mut entries = self.repo.stream_audit_entries();
+        while let Some(entry) = entries.try_next().await? {
+            verification.check(&entry);
         }
+
+        Ok(verification)
     }
 
-    #[tokio::test]
-    async fn test_blog_service_create_post_success() {
-        let mut mock_repo = MockRepository::new();
+    async fn create_author(
+        &self,
+        actor: &User,
+        input: &CreateAuthorRequest,
+    ) -> Result<Author, ServiceError> {
+        self.authorize(actor, Action::ManageAuthors)?;
 
-        let title = PostTitle::new("Test title");
-        let body = PostBody::new("Test body");
-        let create_req = CreatePostRequest::new(title.clone(), body.clone());
+        Ok(self
+            .repo
+            .create_author(input)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
 
-        let expected_post = Post::new(PostId::new(), title.clone(), body.clone(), Utc::now());
+    async fn get_author(&self, id: AuthorId) -> Result<Author, ServiceError> {
+        Ok(self
+            .repo
+            .get_author_by_id(id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
 
-        mock_repo
-            .expect_create_post()
-            .with(predicate::always())
-            .returning(move |_| Ok(expected_post.clone()));
+    async fn get_author_posts(&self, id: AuthorId) -> Result<Vec<Post>, ServiceError> {
+        Ok(self
+            .repo
+            .get_posts_by_author(id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
 
-        let service = BlogService::new(mock_repo);
+    async fn get_posts_by_authors(
+        &self,
+        ids: &[AuthorId],
+        page: &PostPageQuery,
+    ) -> Result<Vec<Post>, ServiceError> {
+        Ok(self.repo.get_posts_by_authors(ids, page).await?)
+    }
 
-        let result = service.create_post(&create_req).await;
+    #[instrument(name = "service_create_user", skip(self, input), fields(email = %input.email()), err)]
+    async fn create_user(&self, input: &CreateUserRequest) -> Result<User, ServiceError> {
+        let password = input.password();
+        let password_hash = tokio::task::spawn_blocking(move || PasswordHash::generate(&password))
+            .await
+            .map_err(anyhow::Error::from)??;
 
-        assert!(result.is_ok());
+        Ok(self
+            .repo
+            .create_user(&input.email(), &password_hash, input.role())
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
 
-        let post = result.unwrap();
+    #[instrument(name = "service_login", skip(self, input), fields(email = %input.email()), err)]
+    async fn login(&self, input: &LoginRequest) -> Result<Session, ServiceError> {
+        let credentials = match self.repo.get_user_credentials(&input.email()).await {
+            Ok((user, password_hash)) => Some((user, password_hash)),
+            Err(GetUserError::UserNotFound { .. }) => None,
+            Err(err) => return Err(err.into_repository_error().into()),
+        };
 
-        assert_eq!(post.title(), title);
-        assert_eq!(post.body(), body);
+        let password = input.password();
+        let password_hash = credentials
+            .as_ref()
+            .and_then(|(_, password_hash)| password_hash.clone())
+            .unwrap_or_else(|| DUMMY_PASSWORD_HASH.clone());
+        let verified = tokio::task::spawn_blocking(move || password_hash.verify(&password))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        let user = match credentials {
+            Some((user, Some(_))) if verified => user,
+            _ => return Err(ServiceError::InvalidCredentials),
+        };
+
+        let second_factor_verified = match input.second_factor() {
+            Some(factor) if user.has_second_factor() => {
+                self.verify_second_factor(&user, factor).await?;
+                true
+            }
+            _ => false,
+        };
+
+        self.start_session(user, second_factor_verified).await
+    }
+
+    async fn start_oidc_login(&self) -> Result<OidcLogin, ServiceError> {
+        let login = OidcLogin::generate(Utc::now() + OIDC_LOGIN_TTL);
+        self.repo.create_oidc_login(&login).await?;
+
+        Ok(login)
+    }
+
+    async fn take_oidc_login(&self, state: &OidcState) -> Result<OidcLogin, ServiceError> {
+        match self.repo.take_oidc_login(state).await {
+            Ok(login) => Ok(login),
+            Err(TakeOidcLoginError::LoginNotFound) => Err(ServiceError::Unauthenticated),
+            Err(err) => Err(err.into_repository_error().into()),
+        }
+    }
+
+    #[instrument(name = "service_login_with_identity", skip(self, identity), fields(issuer = identity.issuer()), err)]
+    async fn login_with_identity(
+        &self,
+        identity: &OidcIdentity,
+        role: Role,
+    ) -> Result<Session, ServiceError> {
+        let user = self
+            .repo
+            .get_or_create_identity_user(identity, role)
+            .await?;
+
+        self.start_session(user, false).await
+    }
+
+    #[instrument(name = "service_complete_login", skip_all, err)]
+    async fn complete_login(
+        &self,
+        token: &SessionToken,
+        factor: &SecondFactor,
+    ) -> Result<Session, ServiceError> {
+        let session = match self.repo.get_session(token).await {
+            Ok(session) => session,
+            Err(GetSessionError::SessionNotFound) => return Err(ServiceError::Unauthenticated),
+            Err(err) => return Err(err.into_repository_error().into()),
+        };
+        if !session.is_second_factor_pending() {
+            return Ok(session);
+        }
+
+        self.verify_second_factor(&session.user(), factor).await?;
+        self.repo
+            .complete_session(token)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+
+        Ok(session.with_second_factor_pending(false))
+    }
+
+    async fn enroll_totp(&self, user: &User) -> Result<TotpEnrollment, ServiceError> {
+        let secret = TotpSecret::generate();
+        self.repo
+            .create_totp(user.id(), &secret)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+
+        Ok(TotpEnrollment::new(secret, &user.email()))
+    }
+
+    #[instrument(name = "service_confirm_totp", skip(self, user, code), fields(user_id = %user.id()), err)]
+    async fn confirm_totp(
+        &self,
+        user: &User,
+        code: &TotpCode,
+    ) -> Result<Vec<RecoveryCode>, ServiceError> {
+        let totp = self
+            .repo
+            .get_totp(user.id())
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+        if totp.is_confirmed() {
+            return Err(CreateTotpError::AlreadyEnabled
+                .into_repository_error()
+                .into());
+        }
+        let step = totp
+            .secret()
+            .verify(code, Utc::now())
+            .ok_or(ServiceError::InvalidSecondFactor)?;
+
+        let recovery_codes = RecoveryCode::generate_set();
+        self.repo
+            .confirm_totp(user.id(), step, &recovery_codes)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+
+        Ok(recovery_codes)
+    }
+
+    async fn logout(&self, token: &SessionToken) -> Result<(), ServiceError> {
+        Ok(self.repo.delete_session(token).await?)
+    }
+
+    async fn authenticate(&self, token: &SessionToken) -> Result<User, ServiceError> {
+        match self.repo.get_session(token).await {
+            Ok(session) if session.is_second_factor_pending() => {
+                Err(ServiceError::SecondFactorRequired)
+            }
+            Ok(session) => Ok(session.user()),
+            Err(GetSessionError::SessionNotFound) => Err(ServiceError::Unauthenticated),
+            Err(err) => Err(err.into_repository_error().into()),
+        }
+    }
+
+    #[instrument(name = "service_create_api_token", skip(self, user, input), fields(user_id = %user.id()), err)]
+    async fn create_api_token(
+        &self,
+        user: &User,
+        input: &CreateApiTokenRequest,
+    ) -> Result<CreatedApiToken, ServiceError> {
+        let secret = ApiTokenSecret::generate();
+        let token = ApiToken::new(
+            ApiTokenId::new(),
+            user.id(),
+            input.name(),
+            input.scopes(),
+            Utc::now(),
+            input.expires_at(),
+            None,
+        );
+
+        let token = self
+            .repo
+            .create_api_token(&token, &secret)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?;
+
+        Ok(CreatedApiToken::new(token, secret))
+    }
+
+    async fn get_api_tokens(&self, user: &User) -> Result<Vec<ApiToken>, ServiceError> {
+        Ok(self.repo.get_api_tokens(user.id()).await?)
+    }
+
+    async fn revoke_api_token(
+        &self,
+        user: &User,
+        token_id: ApiTokenId,
+    ) -> Result<(), ServiceError> {
+        Ok(self
+            .repo
+            .delete_api_token(user.id(), token_id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    async fn authenticate_api_token(
+        &self,
+        secret: &ApiTokenSecret,
+    ) -> Result<(User, ApiToken), ServiceError> {
+        match self.repo.use_api_token(secret).await {
+            Ok(authenticated) => Ok(authenticated),
+            Err(UseApiTokenError::ApiTokenNotFound) => Err(ServiceError::Unauthenticated),
+            Err(err) => Err(err.into_repository_error().into()),
+        }
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        actor: &User,
+        key: &IdempotencyKey,
+        fingerprint: &RequestFingerprint,
+    ) -> Result<Option<IdempotentResponse>, ServiceError> {
+        let now = Utc::now();
+        let Some(record) = self
+            .repo
+            .claim_idempotency_key(
+                actor.id(),
+                key,
+                fingerprint,
+                now + self.idempotency_key_ttl,
+                now + IDEMPOTENCY_KEY_LEASE,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        if record.fingerprint() != fingerprint {
+            return Err(ServiceError::IdempotencyKeyReused);
+        }
+
+        match record.response() {
+            Some(response) => Ok(Some(response.clone())),
+            None => Err(ServiceError::IdempotencyKeyInProgress),
+        }
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        actor: &User,
+        key: &IdempotencyKey,
+        response: &IdempotentResponse,
+    ) -> Result<(), ServiceError> {
+        self.repo
+            .complete_idempotency_key(actor.id(), key, response)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn release_idempotency_key(
+        &self,
+        actor: &User,
+        key: &IdempotencyKey,
+    ) -> Result<(), ServiceError> {
+        self.repo.release_idempotency_key(actor.id(), key).await?;
+
+        Ok(())
+    }
+
+    async fn expire_idempotency_keys(&self) -> Result<u64, ServiceError> {
+        Ok(self.repo.delete_expired_idempotency_keys().await?)
+    }
+
+    #[instrument(name = "service_create_webhook", skip(self, actor, input), fields(url = %input.url()), err)]
+    async fn create_webhook(
+        &self,
+        actor: &User,
+        input: &CreateWebhookRequest,
+    ) -> Result<CreatedWebhook, ServiceError> {
+        self.authorize(actor, Action::ManageWebhooks)?;
+        self.webhook_targets.check(&input.url())?;
+
+        let secret = WebhookSecret::generate();
+        let webhook = Webhook::new(
+            WebhookId::new(),
+            input.url(),
+            input.events(),
+            Some(actor.id()),
+            Utc::now(),
+        );
+        let webhook = self.repo.create_webhook(&webhook, &secret).await?;
+
+        Ok(CreatedWebhook::new(webhook, secret))
+    }
+
+    async fn get_webhooks(&self, actor: &User) -> Result<Vec<Webhook>, ServiceError> {
+        self.authorize(actor, Action::ManageWebhooks)?;
+
+        Ok(self.repo.get_webhooks().await?)
+    }
+
+    async fn delete_webhook(
+        &self,
+        actor: &User,
+        webhook_id: WebhookId,
+    ) -> Result<(), ServiceError> {
+        self.authorize(actor, Action::ManageWebhooks)?;
+
+        Ok(self
+            .repo
+            .delete_webhook(webhook_id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    async fn get_webhook_deliveries(
+        &self,
+        actor: &User,
+        webhook_id: WebhookId,
+    ) -> Result<Vec<WebhookDelivery>, ServiceError> {
+        self.authorize(actor, Action::ManageWebhooks)?;
+
+        Ok(self
+            .repo
+            .get_webhook_deliveries(webhook_id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    async fn redeliver_webhook(
+        &self,
+        actor: &User,
+        webhook_id: WebhookId,
+        delivery_id: i64,
+    ) -> Result<WebhookDelivery, ServiceError> {
+        self.authorize(actor, Action::ManageWebhooks)?;
+
+        Ok(self
+            .repo
+            .redeliver_webhook(webhook_id, delivery_id)
+            .await
+            .map_err(IntoRepositoryError::into_repository_error)?)
+    }
+
+    async fn dispatch_outbox(&self) -> Result<u64, ServiceError> {
+        let mut relayed = 0;
+
+        // Only the oldest event of each post is claimed at a time, so keep
+        // claiming until the later ones have been relayed too.
+        loop {
+            let lease_until = Utc::now() + outbox::OUTBOX_LEASE;
+            let events = self
+                .repo
+                .claim_outbox_events(outbox::OUTBOX_BATCH, lease_until)
+                .await?;
+            if events.is_empty() {
+                return Ok(relayed);
+            }
+
+            // One that fails is left to be relayed again once its lease runs
+            // out, along with the later events of its post.
+            for event in &events {
+                match self.relay_outbox_event(event).await {
+                    Ok(true) => relayed += 1,
+                    Ok(false) => {}
+                    Err(err) => error!(
+                        ?err,
+                        sequence = event.sequence(),
+                        "Failed to relay outbox event"
+                    ),
+                }
+            }
+        }
+    }
+
+    async fn deliver_webhooks(&self) -> Result<u64, ServiceError> {
+        // The timeout is bounded when configured, but not when set in code.
+        let lease_until = self
+            .webhook_timeout
+            .checked_add(webhooks::WEBHOOK_LEASE_MARGIN)
+            .and_then(|lease| Duration::from_std(lease).ok())
+            .and_then(|lease| Utc::now().checked_add_signed(lease))
+            .ok_or_else(|| {
+                anyhow::anyhow!("Webhook timeout {:?} is out of range", self.webhook_timeout)
+            })?;
+        let deliveries = self
+            .repo
+            .claim_webhook_deliveries(webhooks::WEBHOOK_DELIVERY_BATCH, lease_until)
+            .await?;
+
+        let attempts =
+            futures::future::join_all(deliveries.iter().map(|delivery| {
+                webhooks::send(&self.webhook_client, &self.webhook_targets, delivery)
+            }))
+            .await;
+
+        for (delivery, attempt) in deliveries.iter().zip(attempts) {
+            let retry_at = self
+                .webhook_retry
+                .retry_at(delivery.attempt(), attempt.attempted_at());
+            if !attempt.is_success() && retry_at.is_none() {
+                warn!(
+                    delivery_id = delivery.id(),
+                    attempts = delivery.attempt(),
+                    "Webhook delivery failed for the last time"
+                );
+            }
+
+            // Left to be sent again once its lease runs out, like one whose
+            // sender died, rather than losing the outcomes of the others.
+            if let Err(err) = self
+                .repo
+                .record_webhook_attempt(delivery.id(), &attempt, retry_at)
+                .await
+            {
+                error!(
+                    ?err,
+                    delivery_id = delivery.id(),
+                    "Failed to record webhook attempt"
+                );
+            }
+        }
+
+        Ok(deliveries.len() as u64)
+    }
+
+    async fn purge_webhook_deliveries(&self) -> Result<u64, ServiceError> {
+        let finished_before = Utc::now() - self.webhook_delivery_retention;
+
+        Ok(self.repo.purge_webhook_deliveries(finished_before).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use mockall::predicate::*;
+    use mockall::*;
+
+    use crate::domain::models::change_feed::FeedChange;
+    use crate::domain::models::event::{
+        EventId, PostChange, PostChangeKind, PostChangeNotice, PostChangeSignal, StreamEvent,
+    };
+    use crate::domain::models::idempotency::IdempotencyRecord;
+    use crate::domain::models::outbox::{DomainEvent, OutboxEvent};
+    use crate::domain::models::post::{PostBody, PostTitle};
+    use crate::domain::models::totp::{TOTP_PERIOD, Totp};
+    use crate::domain::models::user::UserEmail;
+    use crate::domain::models::webhook::{DueWebhookDelivery, WebhookAttempt, WebhookUrl};
+    use crate::domain::repository::{
+        CreateApiTokenError, CreateAuthorError, CreatePostError, CreateUserError,
+        DeleteApiTokenError, DeletePostError, DeleteWebhookError, GetAuthorError, GetChangesError,
+        GetWebhookError, ImportPostsError, RedeliverWebhookError, RepositoryError,
+        RestorePostError, UpdatePostError,
+    };
+    use crate::ids::UserId;
+    use crate::request_id::RequestId;
+
+    use super::*;
+
+    mock! {
+        Repository {}
+        impl Clone for Repository {
+            fn clone(&self) -> Self;
+        }
+
+        #[async_trait]
+        impl Repository for Repository {
+            async fn create_post(
+                &self,
+                input: &CreatePostRequest,
+                created_by: UserId,
+                audit: &AuditContext,
+            ) -> Result<Post, CreatePostError>;
+            async fn get_all_posts(&self) -> Result<Vec<Post>, RepositoryError>;
+            async fn get_posts_page(
+                &self,
+                query: &PostPageQuery,
+            ) -> Result<PostPage, RepositoryError>;
+            async fn get_posts_last_modified(
+                &self,
+            ) -> Result<Option<DateTime<Utc>>, RepositoryError>;
+            async fn get_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError>;
+            async fn get_post_terms(&self, post_id: PostId) -> Result<Vec<PostTerm>, GetPostError>;
+            async fn get_post_comments(
+                &self,
+                post_id: PostId,
+            ) -> Result<Vec<PostComment>, GetPostError>;
+            async fn update_post(
+                &self,
+                post_id: PostId,
+                expected_version: i64,
+                input: &UpdatePostRequest,
+                audit: &AuditContext,
+            ) -> Result<Post, UpdatePostError>;
+            async fn trash_post(
+                &self,
+                post_id: PostId,
+                expected_version: i64,
+                audit: &AuditContext,
+            ) -> Result<(), DeletePostError>;
+            async fn get_trashed_posts(&self) -> Result<Vec<Post>, RepositoryError>;
+            async fn get_trashed_post_by_id(&self, post_id: PostId) -> Result<Post, GetPostError>;
+            async fn restore_post(
+                &self,
+                post_id: PostId,
+                audit: &AuditContext,
+            ) -> Result<Post, RestorePostError>;
+            async fn delete_post(
+                &self,
+                post_id: PostId,
+                expected_version: i64,
+                audit: &AuditContext,
+            ) -> Result<(), DeletePostError>;
+            async fn purge_trash(
+                &self,
+                deleted_before: DateTime<Utc>,
+                audit: &AuditContext,
+            ) -> Result<u64, RepositoryError>;
+            async fn import_posts(
+                &self,
+                posts: &[ImportPostRequest],
+                audit: &AuditContext,
+            ) -> Result<Vec<Post>, ImportPostsError>;
+            fn stream_posts(&self) -> BoxStream<'static, Result<Post, RepositoryError>>;
+            fn stream_backup_posts(
+                &self,
+            ) -> BoxStream<'static, Result<ImportPostRequest, RepositoryError>>;
+            async fn watch_post_changes(&self) -> BoxStream<'static, PostChangeSignal>;
+            async fn get_latest_change_id(&self) -> Result<EventId, RepositoryError>;
+            async fn get_changes(
+                &self,
+                query: &ChangeFeedQuery,
+            ) -> Result<ChangeFeedPage, GetChangesError>;
+            async fn compact_changes(
+                &self,
+                deleted_before: DateTime<Utc>,
+            ) -> Result<u64, RepositoryError>;
+            async fn restore_posts(
+                &self,
+                posts: &[ImportPostRequest],
+                audit: &AuditContext,
+            ) -> Result<Vec<Post>, ImportPostsError>;
+            async fn get_audit_entries(
+                &self,
+                query: &AuditQuery,
+            ) -> Result<Vec<AuditEntry>, RepositoryError>;
+            fn stream_audit_entries(
+                &self,
+            ) -> BoxStream<'static, Result<AuditEntry, RepositoryError>>;
+            async fn create_author(
+                &self,
+                input: &CreateAuthorRequest,
+            ) -> Result<Author, CreateAuthorError>;
+            async fn get_author_by_id(&self, author_id: AuthorId) -> Result<Author, GetAuthorError>;
+            async fn get_posts_by_author(
+                &self,
+                author_id: AuthorId,
+            ) -> Result<Vec<Post>, GetAuthorError>;
+            async fn get_posts_by_authors(
+                &self,
+                author_ids: &[AuthorId],
+                page: &PostPageQuery,
+            ) -> Result<Vec<Post>, RepositoryError>;
+            async fn create_user(
+                &self,
+                email: &UserEmail,
+                password_hash: &PasswordHash,
+                role: Role,
+            ) -> Result<User, CreateUserError>;
+            async fn get_user_credentials(
+                &self,
+                email: &UserEmail,
+            ) -> Result<(User, Option<PasswordHash>), GetUserError>;
+            async fn get_or_create_identity_user(
+                &self,
+                identity: &OidcIdentity,
+                role: Role,
+            ) -> Result<User, RepositoryError>;
+            async fn create_oidc_login(&self, login: &OidcLogin) -> Result<(), RepositoryError>;
+            async fn take_oidc_login(
+                &self,
+                state: &OidcState,
+            ) -> Result<OidcLogin, TakeOidcLoginError>;
+            async fn create_session(&self, session: &Session) -> Result<(), RepositoryError>;
+            async fn get_session(&self, token: &SessionToken) -> Result<Session, GetSessionError>;
+            async fn complete_session(&self, token: &SessionToken) -> Result<(), GetSessionError>;
+            async fn delete_session(&self, token: &SessionToken) -> Result<(), RepositoryError>;
+            async fn create_totp(
+                &self,
+                user_id: UserId,
+                secret: &TotpSecret,
+            ) -> Result<(), CreateTotpError>;
+            async fn get_totp(&self, user_id: UserId) -> Result<Totp, GetTotpError>;
+            async fn confirm_totp(
+                &self,
+                user_id: UserId,
+                step: i64,
+                recovery_codes: &[RecoveryCode],
+            ) -> Result<(), GetTotpError>;
+            async fn start_second_factor_attempt(
+                &self,
+                user_id: UserId,
+                max_attempts: u32,
+                locked_until: DateTime<Utc>,
+            ) -> Result<(), UseSecondFactorError>;
+            async fn use_totp_step(
+                &self,
+                user_id: UserId,
+                step: i64,
+            ) -> Result<(), UseSecondFactorError>;
+            async fn use_recovery_code(
+                &self,
+                user_id: UserId,
+                code: &RecoveryCode,
+            ) -> Result<(), UseSecondFactorError>;
+            async fn create_api_token(
+                &self,
+                token: &ApiToken,
+                secret: &ApiTokenSecret,
+            ) -> Result<ApiToken, CreateApiTokenError>;
+            async fn get_api_tokens(&self, user_id: UserId) -> Result<Vec<ApiToken>, RepositoryError>;
+            async fn delete_api_token(
+                &self,
+                user_id: UserId,
+                token_id: ApiTokenId,
+            ) -> Result<(), DeleteApiTokenError>;
+            async fn use_api_token(
+                &self,
+                secret: &ApiTokenSecret,
+            ) -> Result<(User, ApiToken), UseApiTokenError>;
+            async fn claim_idempotency_key(
+                &self,
+                user_id: UserId,
+                key: &IdempotencyKey,
+                fingerprint: &RequestFingerprint,
+                expires_at: DateTime<Utc>,
+                locked_until: DateTime<Utc>,
+            ) -> Result<Option<IdempotencyRecord>, RepositoryError>;
+            async fn delete_expired_idempotency_keys(&self) -> Result<u64, RepositoryError>;
+            async fn complete_idempotency_key(
+                &self,
+                user_id: UserId,
+                key: &IdempotencyKey,
+                response: &IdempotentResponse,
+            ) -> Result<(), RepositoryError>;
+            async fn release_idempotency_key(
+                &self,
+                user_id: UserId,
+                key: &IdempotencyKey,
+            ) -> Result<(), RepositoryError>;
+
+            async fn create_webhook(
+                &self,
+                webhook: &Webhook,
+                secret: &WebhookSecret,
+            ) -> Result<Webhook, RepositoryError>;
+
+            async fn get_webhooks(&self) -> Result<Vec<Webhook>, RepositoryError>;
+
+            async fn delete_webhook(&self, webhook_id: WebhookId) -> Result<(), DeleteWebhookError>;
+
+            async fn get_webhook_deliveries(
+                &self,
+                webhook_id: WebhookId,
+            ) -> Result<Vec<WebhookDelivery>, GetWebhookError>;
+
+            async fn redeliver_webhook(
+                &self,
+                webhook_id: WebhookId,
+                delivery_id: i64,
+            ) -> Result<WebhookDelivery, RedeliverWebhookError>;
+
+            async fn claim_webhook_deliveries(
+                &self,
+                limit: i64,
+                lease_until: DateTime<Utc>,
+            ) -> Result<Vec<DueWebhookDelivery>, RepositoryError>;
+
+            async fn purge_webhook_deliveries(
+                &self,
+                finished_before: DateTime<Utc>,
+            ) -> Result<u64, RepositoryError>;
+
+            async fn record_webhook_attempt(
+                &self,
+                delivery_id: i64,
+                attempt: &WebhookAttempt,
+                retry_at: Option<DateTime<Utc>>,
+            ) -> Result<(), RepositoryError>;
+
+
+            async fn claim_outbox_events(
+                &self,
+                limit: i64,
+                lease_until: DateTime<Utc>,
+            ) -> Result<Vec<OutboxEvent>, RepositoryError>;
+
+            async fn complete_outbox_event(&self, event: &OutboxEvent) -> Result<bool, RepositoryError>;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_create_post_success() {
+        let mut mock_repo = MockRepository::new();
+
+        let title = PostTitle::new("Test title");
+        let body = PostBody::new("Test body");
+        let create_req = CreatePostRequest::new(title.clone(), body.clone());
+
+        let expected_post = Post::new(PostId::new(), title.clone(), body.clone(), Utc::now());
+
+        let actor = user(Role::Author);
+        let actor_id = actor.id();
+        mock_repo
+            .expect_create_post()
+            .with(
+                predicate::always(),
+                predicate::eq(actor_id),
+                predicate::function(move |audit: &AuditContext| {
+                    audit.actor_id() == Some(actor_id) && audit.request_id().is_none()
+                }),
+            )
+            .returning(move |_, _, _| Ok(expected_post.clone()));
+
+        let service = BlogService::new(mock_repo);
+
+        let result = service.create_post(&actor, &create_req).await;
+
+        assert!(result.is_ok());
+
+        let post = result.unwrap();
+
+        assert_eq!(post.title(), title);
+        assert_eq!(post.body(), body);
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_create_post_forbidden_for_readers() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo.expect_create_post().never();
+
+        let service = BlogService::new(mock_repo);
+        let create_req =
+            CreatePostRequest::new(PostTitle::new("Test title"), PostBody::new("Test body"));
+
+        let result = service.create_post(&user(Role::Reader), &create_req).await;
+
+        assert!(matches!(result, Err(ServiceError::Forbidden)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_update_post_forbidden_for_other_authors() {
+        let mut mock_repo = MockRepository::new();
+        let post = Post::new(
+            PostId::new(),
+            PostTitle::new("Test title"),
+            PostBody::new("Test body"),
+            Utc::now(),
+        )
+        .with_created_by(Some(UserId::new()));
+        let post_id = post.id();
+
+        mock_repo
+            .expect_get_post_by_id()
+            .returning(move |_| Ok(post.clone()));
+        mock_repo.expect_update_post().never();
+
+        let service = BlogService::new(mock_repo);
+        let update_req = UpdatePostRequest::new(None, Some(PostBody::new("Edited")));
+
+        let result = service
+            .update_post(&user(Role::Author), post_id, 1, &update_req)
+            .await;
+
+        assert!(matches!(result, Err(ServiceError::Forbidden)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_delete_post_is_attributed_to_current_request() {
+        let mut mock_repo = MockRepository::new();
+        let actor = user(Role::Editor);
+        let actor_id = actor.id();
+        let post = Post::new(
+            PostId::new(),
+            PostTitle::new("Test title"),
+            PostBody::new("Test body"),
+            Utc::now(),
+        );
+        let post_id = post.id();
+
+        mock_repo
+            .expect_get_post_by_id()
+            .returning(move |_| Ok(post.clone()));
+        mock_repo
+            .expect_trash_post()
+            .with(
+                predicate::eq(post_id),
+                predicate::eq(1),
+                predicate::eq(AuditContext::new(
+                    Some(actor_id),
+                    Some(RequestId::new("req-42")),
+                )),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = BlogService::new(mock_repo);
+
+        let result = request_id::scope(
+            RequestId::new("req-42"),
+            service.delete_post(&actor, post_id, 1),
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_restore_post_forbidden_for_other_authors() {
+        let mut mock_repo = MockRepository::new();
+        let post = Post::new(
+            PostId::new(),
+            PostTitle::new("Test title"),
+            PostBody::new("Test body"),
+            Utc::now(),
+        )
+        .with_created_by(Some(UserId::new()))
+        .with_deleted_at(Some(Utc::now()));
+        let post_id = post.id();
+
+        mock_repo
+            .expect_get_trashed_post_by_id()
+            .returning(move |_| Ok(post.clone()));
+        mock_repo.expect_restore_post().never();
+
+        let service = BlogService::new(mock_repo);
+
+        let result = service.restore_post(&user(Role::Author), post_id).await;
+
+        assert!(matches!(result, Err(ServiceError::Forbidden)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_trash_shows_authors_only_their_own_posts() {
+        let mut mock_repo = MockRepository::new();
+        let author = user(Role::Author);
+        let trashed = |created_by| {
+            Post::new(
+                PostId::new(),
+                PostTitle::new("Test title"),
+                PostBody::new("Test body"),
+                Utc::now(),
+            )
+            .with_created_by(Some(created_by))
+            .with_deleted_at(Some(Utc::now()))
+        };
+        let own = trashed(author.id());
+        let own_id = own.id();
+        let other = trashed(UserId::new());
+
+        mock_repo
+            .expect_get_trashed_posts()
+            .returning(move || Ok(vec![own.clone(), other.clone()]));
+
+        let service = BlogService::new(mock_repo);
+
+        let trash = service.get_trash(&author).await.unwrap();
+
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id(), own_id);
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_audit_log_is_admin_only() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo.expect_get_audit_entries().never();
+
+        let service = BlogService::new(mock_repo);
+
+        let result = service
+            .get_audit_entries(&user(Role::Editor), &AuditQuery::new())
+            .await;
+
+        assert!(matches!(result, Err(ServiceError::Forbidden)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_webhooks_are_admin_only() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo.expect_create_webhook().never();
+        mock_repo.expect_get_webhooks().never();
+
+        let service = BlogService::new(mock_repo);
+        let input = CreateWebhookRequest::new(
+            WebhookUrl::try_new("https://example.com/hooks").unwrap(),
+            [PostChangeKind::Created].into(),
+        );
+
+        let created = service.create_webhook(&user(Role::Editor), &input).await;
+        let listed = service.get_webhooks(&user(Role::Author)).await;
+
+        assert!(matches!(created, Err(ServiceError::Forbidden)));
+        assert!(matches!(listed, Err(ServiceError::Forbidden)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_deliver_webhooks_refuses_timeouts_out_of_range() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo.expect_claim_webhook_deliveries().never();
+
+        let service = BlogService::new(mock_repo)
+            .with_webhook_timeout(std::time::Duration::from_secs(u64::MAX));
+
+        assert!(matches!(
+            service.deliver_webhooks().await,
+            Err(ServiceError::Unknown(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_dispatch_outbox_completes_events_once_relayed() {
+        let mut mock_repo = MockRepository::new();
+        let mut seq = Sequence::new();
+        let post_id = PostId::new();
+        let events = vec![
+            OutboxEvent::new(
+                1,
+                uuid::Uuid::new_v4(),
+                DomainEvent::PostCreated {
+                    post_id,
+                    post: Post::new(
+                        post_id,
+                        PostTitle::new("Title"),
+                        PostBody::new("Body"),
+                        Utc::now(),
+                    ),
+                },
+                Utc::now(),
+            ),
+            OutboxEvent::new(
+                2,
+                uuid::Uuid::new_v4(),
+                DomainEvent::PostDeleted { post_id },
+                Utc::now(),
+            ),
+        ];
+
+        for event in events {
+            mock_repo
+                .expect_claim_outbox_events()
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(move |_, _| Ok(vec![event.clone()]));
+            mock_repo
+                .expect_complete_outbox_event()
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_| Ok(true));
+        }
+        mock_repo
+            .expect_claim_outbox_events()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(vec![]));
+
+        let service = BlogService::new(mock_repo);
+
+        assert_eq!(service.dispatch_outbox().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_dispatch_outbox_keeps_events_that_failed_to_relay() {
+        let mut mock_repo = MockRepository::new();
+        let mut seq = Sequence::new();
+        let events: Vec<_> = (1..=2)
+            .map(|sequence| {
+                OutboxEvent::new(
+                    sequence,
+                    uuid::Uuid::new_v4(),
+                    DomainEvent::PostDeleted {
+                        post_id: PostId::new(),
+                    },
+                    Utc::now(),
+                )
+            })
+            .collect();
+
+        mock_repo
+            .expect_claim_outbox_events()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _| Ok(events.clone()));
+        mock_repo
+            .expect_complete_outbox_event()
+            .withf(|event| event.sequence() == 1)
+            .times(1)
+            .returning(|_| Err(RepositoryError::Unknown(anyhow::anyhow!("connection lost"))));
+        mock_repo
+            .expect_complete_outbox_event()
+            .withf(|event| event.sequence() == 2)
+            .times(1)
+            .returning(|_| Ok(true));
+        // The failed event stays leased, so the next claim finds nothing.
+        mock_repo
+            .expect_claim_outbox_events()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(vec![]));
+
+        let service = BlogService::new(mock_repo);
+
+        assert_eq!(service.dispatch_outbox().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_dispatch_outbox_skips_events_completed_elsewhere() {
+        let mut mock_repo = MockRepository::new();
+        let mut seq = Sequence::new();
+        let event = OutboxEvent::new(
+            1,
+            uuid::Uuid::new_v4(),
+            DomainEvent::PostDeleted {
+                post_id: PostId::new(),
+            },
+            Utc::now(),
+        );
+
+        mock_repo
+            .expect_claim_outbox_events()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _| Ok(vec![event.clone()]));
+        // The lease ran out and another instance completed the event first.
+        mock_repo
+            .expect_complete_outbox_event()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(false));
+        mock_repo
+            .expect_claim_outbox_events()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(vec![]));
+
+        let service = BlogService::new(mock_repo);
+
+        assert_eq!(service.dispatch_outbox().await.unwrap(), 0);
+    }
+
+    fn user(role: Role) -> User {
+        User::new(
+            UserId::new(),
+            UserEmail::new("tommy@example.com"),
+            role,
+            Utc::now(),
+        )
+    }
+
+    fn user_with_password(password: &str) -> (User, Option<PasswordHash>) {
+        let password_hash = PasswordHash::generate(&Password::new(password)).unwrap();
+        (user(Role::Author), Some(password_hash))
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_deliver_webhooks_records_every_attempt() {
+        let mut mock_repo = MockRepository::new();
+        let deliveries: Vec<_> = (1..=2)
+            .map(|id| {
+                DueWebhookDelivery::new(
+                    id,
+                    PostChangeKind::Created,
+                    1,
+                    WebhookUrl::try_new("http://169.254.169.254/latest/meta-data").unwrap(),
+                    WebhookSecret::generate(),
+                    "{}".to_string(),
+                )
+            })
+            .collect();
+
+        mock_repo
+            .expect_claim_webhook_deliveries()
+            .times(1)
+            .returning(move |_, _| Ok(deliveries.clone()));
+        mock_repo
+            .expect_record_webhook_attempt()
+            .withf(|id, attempt, _| *id == 1 && !attempt.is_success())
+            .times(1)
+            .returning(|_, _, _| Err(RepositoryError::Unknown(anyhow::anyhow!("Lost"))));
+        mock_repo
+            .expect_record_webhook_attempt()
+            .withf(|id, attempt, _| *id == 2 && !attempt.is_success())
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = BlogService::new(mock_repo);
+
+        assert_eq!(service.deliver_webhooks().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_compact_changes_keeps_tombstones_for_retention() {
+        let mut mock_repo = MockRepository::new();
+        let retention = Duration::days(7);
+        let started = Utc::now();
+
+        mock_repo
+            .expect_compact_changes()
+            .withf(move |deleted_before| {
+                *deleted_before >= started - retention && *deleted_before <= Utc::now() - retention
+            })
+            .times(1)
+            .returning(|_| Ok(3));
+
+        let service = BlogService::new(mock_repo).with_tombstone_retention(retention);
+
+        assert_eq!(service.compact_changes().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_resumed_events_reset_past_compacted_changes() {
+        let mut mock_repo = MockRepository::new();
+        let post_id = PostId::new();
+        let deleted_at = Utc::now();
+
+        mock_repo.expect_clone().times(1).returning(move || {
+            let mut repo = MockRepository::new();
+            let mut seq = Sequence::new();
+            repo.expect_get_changes()
+                .withf(|query| query.since() == Some(1))
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(|_| {
+                    Err(GetChangesError::ResyncRequired {
+                        since: 1,
+                        compacted_through: 100,
+                    })
+                });
+            repo.expect_get_changes()
+                .withf(|query| query.since() == Some(100))
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(move |_| {
+                    let tombstone = FeedChange::Tombstone {
+                        sequence: 101,
+                        post_id,
+                        deleted_at,
+                    };
+                    Ok(ChangeFeedPage::new(vec![tombstone], 100, false))
+                });
+            repo
+        });
+
+        let service = BlogService::new(mock_repo);
+        let events: Vec<_> = service
+            .subscribe_post_events(Some(1))
+            .take(2)
+            .collect()
+            .await;
+
+        assert!(matches!(events[0], StreamEvent::Reset(100)));
+        assert!(matches!(
+            &events[1],
+            StreamEvent::Post(event)
+                if event.id() == 101 && event.change() == &PostChange::Deleted(post_id)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_relay_catches_up_on_missed_changes() {
+        let mut mock_repo = MockRepository::new();
+        let post_ids: Vec<_> = (0..3).map(|_| PostId::new()).collect();
+        let deleted_at = Utc::now();
+
+        let ids = post_ids.clone();
+        mock_repo.expect_clone().times(1).returning(move || {
+            let mut repo = MockRepository::new();
+            let missed = ids[1];
+            let ids = ids.clone();
+            repo.expect_watch_post_changes()
+                .times(1)
+                .returning(move || {
+                    let notice = |sequence, post_id| {
+                        PostChangeSignal::Notice(PostChangeNotice::new(
+                            sequence,
+                            PostChangeKind::Deleted,
+                            post_id,
+                        ))
+                    };
+                    // The change numbered 6 is announced once caught up on.
+                    futures::stream::iter([
+                        notice(5, ids[0]),
+                        PostChangeSignal::Missed,
+                        notice(6, ids[1]),
+                        notice(7, ids[2]),
+                    ])
+                    .boxed()
+                });
+            repo.expect_get_latest_change_id()
+                .times(1)
+                .returning(|| Ok(4));
+            repo.expect_get_changes()
+                .withf(|query| query.since() == Some(5))
+                .times(1)
+                .returning(move |_| {
+                    let tombstone = FeedChange::Tombstone {
+                        sequence: 6,
+                        post_id: missed,
+                        deleted_at,
+                    };
+                    Ok(ChangeFeedPage::new(vec![tombstone], 5, false))
+                });
+            repo
+        });
+
+        let service = BlogService::new(mock_repo);
+        let events = service.subscribe_post_events(None);
+        service.clone().relay_post_changes().await.await;
+        let events: Vec<_> = events.take(3).collect().await;
+
+        let changes: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                StreamEvent::Post(event) => (event.id(), event.change().clone()),
+                StreamEvent::Reset(_) => panic!("Unexpected reset"),
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (5, PostChange::Deleted(post_ids[0])),
+                (6, PostChange::Deleted(post_ids[1])),
+                (7, PostChange::Deleted(post_ids[2])),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_relay_ends_streams_when_missed_changes_are_gone() {
+        let mut mock_repo = MockRepository::new();
+
+        mock_repo.expect_clone().times(1).returning(|| {
+            let mut repo = MockRepository::new();
+            repo.expect_watch_post_changes()
+                .times(1)
+                .returning(|| futures::stream::iter([PostChangeSignal::Missed]).boxed());
+            repo.expect_get_latest_change_id()
+                .times(1)
+                .returning(|| Ok(4));
+            repo.expect_get_changes().times(1).returning(|_| {
+                Err(GetChangesError::ResyncRequired {
+                    since: 4,
+                    compacted_through: 10,
+                })
+            });
+            repo
+        });
+
+        let service = BlogService::new(mock_repo);
+        let events = service.subscribe_post_events(None);
+        service.clone().relay_post_changes().await.await;
+
+        assert!(events.collect::<Vec<_>>().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_login_success_creates_session() {
+        let mut mock_repo = MockRepository::new();
+        let credentials = user_with_password("correct horse battery staple");
+        let user = credentials.0.clone();
+
+        mock_repo
+            .expect_get_user_credentials()
+            .returning(move |_| Ok(credentials.clone()));
+        mock_repo
+            .expect_create_session()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = BlogService::new(mock_repo);
+        let login_req = LoginRequest::new(
+            UserEmail::new("tommy@example.com"),
+            Password::new("correct horse battery staple"),
+        );
+
+        let session = service.login(&login_req).await.unwrap();
+
+        assert_eq!(session.user(), user);
+        assert!(session.expires_at() > Utc::now());
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_login_wrong_password_is_rejected() {
+        let mut mock_repo = MockRepository::new();
+        let credentials = user_with_password("correct horse battery staple");
+
+        mock_repo
+            .expect_get_user_credentials()
+            .returning(move |_| Ok(credentials.clone()));
+        mock_repo.expect_create_session().never();
+
+        let service = BlogService::new(mock_repo);
+        let login_req = LoginRequest::new(
+            UserEmail::new("tommy@example.com"),
+            Password::new("wrong password"),
+        );
+
+        let result = service.login(&login_req).await;
+
+        assert!(matches!(result, Err(ServiceError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_login_unknown_email_is_rejected() {
+        let mut mock_repo = MockRepository::new();
+
+        mock_repo.expect_get_user_credentials().returning(|email| {
+            Err(GetUserError::UserNotFound {
+                email: email.clone(),
+            })
+        });
+        mock_repo.expect_create_session().never();
+
+        let service = BlogService::new(mock_repo);
+        let login_req = LoginRequest::new(
+            UserEmail::new("nobody@example.com"),
+            Password::new("whatever password"),
+        );
+
+        let result = service.login(&login_req).await;
+
+        assert!(matches!(result, Err(ServiceError::InvalidCredentials)));
+    }
+
+    fn user_with_totp(password: &str) -> (User, Option<PasswordHash>) {
+        let (user, password_hash) = user_with_password(password);
+        (user.with_second_factor(true), password_hash)
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_login_without_code_starts_pending_session() {
+        let mut mock_repo = MockRepository::new();
+        let credentials = user_with_totp("correct horse battery staple");
+
+        mock_repo
+            .expect_get_user_credentials()
+            .returning(move |_| Ok(credentials.clone()));
+        mock_repo
+            .expect_create_session()
+            .withf(Session::is_second_factor_pending)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = BlogService::new(mock_repo);
+        let login_req = LoginRequest::new(
+            UserEmail::new("tommy@example.com"),
+            Password::new("correct horse battery staple"),
+        );
+
+        let session = service.login(&login_req).await.unwrap();
+
+        assert!(session.is_second_factor_pending());
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_login_rejects_replayed_code() {
+        let mut mock_repo = MockRepository::new();
+        let secret = TotpSecret::generate();
+        let credentials = user_with_totp("correct horse battery staple");
+        let code = secret.code_at(Utc::now().timestamp() / TOTP_PERIOD);
+
+        mock_repo
+            .expect_get_user_credentials()
+            .returning(move |_| Ok(credentials.clone()));
+        mock_repo
+            .expect_get_totp()
+            .returning(move |_| Ok(Totp::new(secret.clone(), Some(Utc::now()))));
+        mock_repo
+            .expect_start_second_factor_attempt()
+            .returning(|_, _, _| Ok(()));
+        mock_repo
+            .expect_use_totp_step()
+            .returning(|_, _| Err(UseSecondFactorError::Rejected));
+        mock_repo.expect_create_session().never();
+
+        let service = BlogService::new(mock_repo);
+        let login_req = LoginRequest::new(
+            UserEmail::new("tommy@example.com"),
+            Password::new("correct horse battery staple"),
+        )
+        .with_second_factor(Some(SecondFactor::Totp(code)));
+
+        let result = service.login(&login_req).await;
+
+        assert!(matches!(result, Err(ServiceError::InvalidSecondFactor)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_login_rejects_correct_code_while_locked_out() {
+        let mut mock_repo = MockRepository::new();
+        let secret = TotpSecret::generate();
+        let credentials = user_with_totp("correct horse battery staple");
+        let code = secret.code_at(Utc::now().timestamp() / TOTP_PERIOD);
+
+        mock_repo
+            .expect_get_user_credentials()
+            .returning(move |_| Ok(credentials.clone()));
+        mock_repo
+            .expect_start_second_factor_attempt()
+            .withf(|_, max_attempts, _| *max_attempts == MAX_SECOND_FACTOR_ATTEMPTS)
+            .times(1)
+            .returning(|_, _, _| Err(UseSecondFactorError::Rejected));
+        mock_repo.expect_get_totp().never();
+        mock_repo.expect_use_totp_step().never();
+        mock_repo.expect_create_session().never();
+
+        let service = BlogService::new(mock_repo);
+        let login_req = LoginRequest::new(
+            UserEmail::new("tommy@example.com"),
+            Password::new("correct horse battery staple"),
+        )
+        .with_second_factor(Some(SecondFactor::Totp(code)));
+
+        let result = service.login(&login_req).await;
+
+        assert!(matches!(result, Err(ServiceError::InvalidSecondFactor)));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_requires_enrollment_for_policy_roles() {
+        let mut mock_repo = MockRepository::new();
+        mock_repo.expect_create_post().never();
+
+        let service = BlogService::new(mock_repo)
+            .with_second_factor_policy(SecondFactorPolicy::new([Role::Admin]));
+        let create_req =
+            CreatePostRequest::new(PostTitle::new("Test title"), PostBody::new("Test body"));
+
+        let result = service.create_post(&user(Role::Admin), &create_req).await;
+
+        assert!(matches!(
+            result,
+            Err(ServiceError::SecondFactorEnrollmentRequired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_replays_completed_idempotent_request() {
+        let mut mock_repo = MockRepository::new();
+        let fingerprint = RequestFingerprint::new("POST", "/posts", b"{}");
+        let response = IdempotentResponse::new(201, Vec::new(), b"{}".to_vec());
+        let record = IdempotencyRecord::new(fingerprint.clone(), Some(response.clone()));
+
+        mock_repo
+            .expect_claim_idempotency_key()
+            .returning(move |_, _, _, _, _| Ok(Some(record.clone())));
+
+        let service = BlogService::new(mock_repo);
+        let key = IdempotencyKey::try_new("publish-42").unwrap();
+
+        let replayed = service
+            .claim_idempotency_key(&user(Role::Author), &key, &fingerprint)
+            .await
+            .unwrap();
+
+        assert_eq!(replayed, Some(response));
+    }
+
+    #[tokio::test]
+    async fn test_blog_service_rejects_idempotency_key_reused_for_other_request() {
+        let mut mock_repo = MockRepository::new();
+        let first = RequestFingerprint::new("POST", "/posts", br#"{"title":"A"}"#);
+        let record = IdempotencyRecord::new(first, None);
+
+        mock_repo
+            .expect_claim_idempotency_key()
+            .returning(move |_, _, _, _, _| Ok(Some(record.clone())));
+
+        let service = BlogService::new(mock_repo);
+        let key = IdempotencyKey::try_new("publish-42").unwrap();
+        let second = RequestFingerprint::new("POST", "/posts", br#"{"title":"B"}"#);
+
+        let result = service
+            .claim_idempotency_key(&user(Role::Author), &key, &second)
+            .await;
+
+        assert!(matches!(result, Err(ServiceError::IdempotencyKeyReused)));
     }
 }
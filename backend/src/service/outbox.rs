@@ -0,0 +1,62 @@
+//! Relaying of the outbox, the domain events written in the transaction of
+//! each change to posts.
+//!
+//! Events are relayed at least once: one whose relaying fails, or whose
+//! dispatcher dies, is relayed again once its lease runs out. The events of
+//! a post are relayed in the order they were committed. Webhook deliveries
+//! are queued in the transaction that completes the event, so only once;
+//! event streams hear of changes from the database as they are committed,
+//! see [`super::events`].
+
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error};
+
+use crate::domain::{
+    models::outbox::OutboxEvent,
+    repository::Repository,
+    service::{Service, ServiceError},
+};
+
+use super::BlogService;
+
+/// How many events are claimed at a time.
+pub(super) const OUTBOX_BATCH: i64 = 100;
+
+/// How long claimed events are left alone before they are relayed again.
+pub(super) const OUTBOX_LEASE: chrono::Duration = chrono::Duration::seconds(60);
+
+/// Relays the outbox every `interval`, starting right away. Runs until the
+/// task is dropped.
+pub async fn dispatch_outbox_periodically<S: Service>(service: S, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match service.dispatch_outbox().await {
+            Ok(0) => {}
+            Ok(relayed) => debug!(relayed, "Relayed outbox events"),
+            Err(err) => error!(?err, "Failed to relay outbox events"),
+        }
+    }
+}
+
+impl<R: Repository> BlogService<R> {
+    /// Hands an event to everything consuming the outbox, returning whether
+    /// it was this relay that completed it. Counted once its webhook
+    /// deliveries are queued, so the changes of every instance, imports and
+    /// restores included, are counted once, by whichever relays them first.
+    pub(super) async fn relay_outbox_event(
+        &self,
+        event: &OutboxEvent,
+    ) -> Result<bool, ServiceError> {
+        let completed = self.repo.complete_outbox_event(event).await?;
+        if completed {
+            self.metrics.record_post_change(event.event().kind());
+        }
+
+        Ok(completed)
+    }
+}
@@ -0,0 +1,24 @@
+//! Background purge of the trash.
+
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+use crate::domain::service::Service;
+
+/// Deletes posts whose trash retention has run out every `interval`, starting
+/// right away. Runs until the task is dropped.
+pub async fn purge_trash_periodically<S: Service>(service: S, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match service.purge_trash().await {
+            Ok(0) => {}
+            Ok(purged) => info!(purged, "Purged expired posts from the trash"),
+            Err(err) => error!(?err, "Failed to purge the trash"),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Sending of webhook deliveries, signed with the secret of their webhook.
+//!
+//! A delivery is a `POST` of the JSON payload with these headers:
+//!
+//! - `Webhook-Id`: the id of the delivery, the same on every attempt, so
+//!   receivers can drop duplicates.
+//! - `Webhook-Event`: `post.created`, `post.updated` or `post.deleted`.
+//! - `Webhook-Timestamp`: when the attempt was made, in Unix seconds.
+//! - `Webhook-Signature`: `v1=` and the hex encoded HMAC-SHA256 of
+//!   `{timestamp}.{body}`, keyed with the webhook secret.
+//!
+//! Any 2xx response counts as delivered; redirects are not followed.
+//! Deliveries only go to the addresses the [`WebhookTargetPolicy`] allows,
+//! checked after the host is resolved, so a name saved while public cannot
+//! later be pointed inside the network.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use reqwest::{
+    Client,
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect,
+};
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+use crate::domain::{
+    models::webhook::{DueWebhookDelivery, WebhookAttempt, WebhookTargetPolicy},
+    service::Service,
+};
+
+pub const WEBHOOK_ID_HEADER: &str = "webhook-id";
+pub const WEBHOOK_EVENT_HEADER: &str = "webhook-event";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "webhook-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "webhook-signature";
+
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest configurable timeout, in seconds. A delivery whose sender dies
+/// waits this long before it is sent again, so much longer helps nobody.
+pub const MAX_WEBHOOK_TIMEOUT_SECONDS: u64 = 3600;
+
+/// How many deliveries are claimed, and sent concurrently, at a time.
+pub(super) const WEBHOOK_DELIVERY_BATCH: i64 = 32;
+
+/// Time on top of the request timeout before a claimed delivery whose
+/// outcome was never recorded is sent again.
+pub(super) const WEBHOOK_LEASE_MARGIN: Duration = Duration::from_secs(60);
+
+/// Sends the webhook deliveries that are due every `interval`, starting right
+/// away. Runs until the task is dropped.
+pub async fn deliver_webhooks_periodically<S: Service>(service: S, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match service.deliver_webhooks().await {
+            Ok(0) => {}
+            Ok(attempted) => info!(attempted, "Sent webhook deliveries"),
+            Err(err) => error!(?err, "Failed to send webhook deliveries"),
+        }
+    }
+}
+
+/// How often delivered and dead deliveries past their retention are deleted.
+pub const WEBHOOK_DELIVERY_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Deletes the webhook deliveries whose retention has run out every
+/// `interval`, starting right away. Runs until the task is dropped.
+pub async fn purge_webhook_deliveries_periodically<S: Service>(service: S, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match service.purge_webhook_deliveries().await {
+            Ok(0) => {}
+            Ok(purged) => info!(purged, "Purged old webhook deliveries"),
+            Err(err) => error!(?err, "Failed to purge webhook deliveries"),
+        }
+    }
+}
+
+pub(super) fn webhook_client(timeout: Duration, targets: WebhookTargetPolicy) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .redirect(redirect::Policy::none())
+        .dns_resolver(Arc::new(TargetResolver(targets)))
+        .build()
+        .expect("Failed to build the webhook HTTP client")
+}
+
+/// Resolves the hosts of deliveries to the addresses the policy allows.
+struct TargetResolver(WebhookTargetPolicy);
+
+impl Resolve for TargetResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let targets = self.0.clone();
+
+        Box::pin(async move {
+            let host = name.as_str();
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|address| targets.allows_address(host, address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{host} does not resolve to a public address").into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Makes one attempt at a delivery. Errors are part of the outcome, to be
+/// recorded and retried.
+pub(super) async fn send(
+    client: &Client,
+    targets: &WebhookTargetPolicy,
+    delivery: &DueWebhookDelivery,
+) -> WebhookAttempt {
+    let attempted_at = Utc::now();
+    // Addresses in the URL are not resolved, and may have been saved before
+    // the policy changed.
+    if let Err(err) = targets.check(delivery.url()) {
+        return WebhookAttempt::failed(attempted_at, err.to_string());
+    }
+    let timestamp = attempted_at.timestamp();
+    let signature = delivery
+        .secret()
+        .sign(timestamp, delivery.payload().as_bytes());
+
+    let result = client
+        .post(delivery.url().as_url().clone())
+        .header(CONTENT_TYPE, "application/json")
+        .header(WEBHOOK_ID_HEADER, delivery.id())
+        .header(WEBHOOK_EVENT_HEADER, delivery.event().as_str())
+        .header(WEBHOOK_TIMESTAMP_HEADER, timestamp)
+        .header(WEBHOOK_SIGNATURE_HEADER, format!("v1={signature}"))
+        .body(delivery.payload().to_string())
+        .send()
+        .await;
+
+    match result {
+        Ok(response) => WebhookAttempt::responded(attempted_at, response.status().as_u16()),
+        Err(err) => WebhookAttempt::failed(attempted_at, format!("{:#}", anyhow::Error::from(err))),
+    }
+}
@@ -1,81 +1,37 @@
-use axum::{
-    Router,
-    body::Body,
-    http::{Request, Response},
-};
+//! The database and service behind every test, shared with the tests of
+//! the workspace's other crates.
+
+use std::sync::Once;
+
 use backend::{
-    server::{HttpServer, HttpServerConfig},
+    db::postgres::MIGRATOR,
+    domain::{
+        models::{
+            user::{CreateUserRequest, Password, Role, User, UserEmail},
+            webhook::WebhookTargetPolicy,
+        },
+        service::Service,
+    },
+    metrics::Metrics,
     service::BlogService,
 };
-use serde::de::DeserializeOwned;
-use serde_json::Value;
 use sqlx::{PgPool, postgres::PgPoolOptions};
-use std::{fmt::Display, sync::Once};
 use testcontainers::{ContainerAsync, runners::AsyncRunner};
 use testcontainers_modules::postgres::Postgres;
-use tower::ServiceExt;
 use uuid::Uuid;
 
 static INIT: Once = Once::new();
 
-pub struct TestApp {
-    router: Router,
-    _fixture: TestFixture,
-}
-
-impl TestApp {
-    pub async fn new() -> Self {
-        let fixture = TestFixture::new().await;
-        let config = HttpServerConfig { port: "0" };
-        let server = HttpServer::try_new(fixture.service.clone(), config)
-            .await
-            .expect("Failed to create server.");
-
-        Self {
-            router: server.router,
-            _fixture: fixture,
-        }
-    }
-
-    pub async fn call(&self, uri: &str, method: Method, body: Option<Value>) -> Response<Body> {
-        let body = match &body {
-            Some(value) => {
-                Body::from(serde_json::to_string(value).expect("Failed to stringiy body."))
-            }
-            None => Body::from(""),
-        };
-
-        self.router
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method(method.to_string().as_str())
-                    .uri(uri)
-                    .header("Content-Type", "application/json")
-                    .body(body)
-                    .expect("Failed to build request."),
-            )
-            .await
-            .unwrap_or_else(|_| panic!("Failed to call endpoint {uri}"))
-    }
-
-    pub async fn parse_response<T>(&self, resp: Response<Body>) -> T
-    where
-        T: DeserializeOwned,
-    {
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .expect("Failed to parse body.");
-        let json_resp: Value = serde_json::from_slice(&body).expect("Failed to stringify body.");
-        let result: T = serde_json::from_value(json_resp).expect("Failed to deserialize value.");
-        result
-    }
-}
+pub const TEST_USER_EMAIL: &str = "tommy@example.com";
+pub const TEST_USER_PASSWORD: &str = "correct horse battery staple";
 
 pub struct TestFixture {
     pub pool: PgPool,
     pub schema: String,
+    pub database_url: String,
     pub service: BlogService<backend::db::postgres::Postgres>,
+    /// Shared by the service and the apps built on the fixture.
+    pub metrics: Metrics,
     _postgres: ContainerAsync<Postgres>,
 }
 
@@ -131,10 +87,7 @@ impl TestFixture {
             .expect("Failed to set search path");
 
         // Run migrations
-        sqlx::migrate!("./migrations")
-            .run(&pool)
-            .await
-            .expect("Failed to run migrations");
+        MIGRATOR.run(&pool).await.expect("Failed to run migrations");
 
         // Create repository
         let postgres_repo = backend::db::postgres::Postgres::try_new(&database_url)
@@ -142,17 +95,37 @@ impl TestFixture {
             .expect("Failed to initialize repository.");
 
         // Create service
-        let service = BlogService::new(postgres_repo);
+        let metrics = Metrics::new().with_pool(postgres_repo.pool().clone());
+        // Webhook receivers of the tests listen on this machine.
+        let service = BlogService::new(postgres_repo.with_metrics(metrics.clone()))
+            .with_metrics(metrics.clone())
+            .with_webhook_target_policy(WebhookTargetPolicy::new(["127.0.0.1".to_string()]));
 
         Self {
             pool,
             schema,
+            database_url,
             service,
+            metrics,
             _postgres: postgres_container,
         }
     }
 }
 
+impl TestFixture {
+    /// Creates a user whose password is [`TEST_USER_PASSWORD`].
+    pub async fn create_user(&self, email: &str, role: Role) -> User {
+        self.service
+            .create_user(&CreateUserRequest::new(
+                UserEmail::new(email),
+                Password::new(TEST_USER_PASSWORD),
+                role,
+            ))
+            .await
+            .expect("Failed to create test user.")
+    }
+}
+
 impl Drop for TestFixture {
     fn drop(&mut self) {
         // Schedule cleanup of schema
@@ -166,25 +139,3 @@ impl Drop for TestFixture {
         });
     }
 }
-
-pub enum Method {
-    Get,
-    Post,
-    Patch,
-    Delete,
-}
-
-impl Display for Method {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Get => "GET",
-                Self::Post => "POST",
-                Self::Patch => "PATCH",
-                Self::Delete => "DELETE",
-            }
-        )
-    }
-}
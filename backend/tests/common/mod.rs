@@ -0,0 +1,245 @@
+#![allow(dead_code)]
+
+mod fixture;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderName, Request, Response, header},
+};
+use backend::{
+    api::cache::CacheConfig,
+    domain::models::{
+        post::PostConstraints,
+        user::{Role, User},
+    },
+    oidc::OidcConfig,
+    server::{HttpServer, HttpServerConfig},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::{fmt::Display, time::Duration};
+use tower::ServiceExt;
+
+pub use fixture::{TEST_USER_EMAIL, TEST_USER_PASSWORD, TestFixture};
+
+/// Short, so tests see idle event streams kept alive without waiting long.
+pub const SSE_HEARTBEAT: Duration = Duration::from_millis(200);
+
+pub struct TestApp {
+    router: Router,
+    session_cookie: Option<String>,
+    fixture: TestFixture,
+}
+
+impl TestApp {
+    /// Creates an app with a test admin who is already logged in, so `call`
+    /// sends the session cookie along with every request.
+    pub async fn new() -> Self {
+        Self::anonymous().await.logged_in().await
+    }
+
+    /// Creates an app with a test admin who is not logged in.
+    pub async fn anonymous() -> Self {
+        Self::with_oidc(None).await
+    }
+
+    /// Creates an app with a test admin who is not logged in, with single
+    /// sign-on against the given provider.
+    pub async fn with_oidc(oidc: Option<OidcConfig>) -> Self {
+        Self::with_config(oidc, CacheConfig::default()).await
+    }
+
+    /// Creates an app with a test admin who is already logged in, caching
+    /// responses as configured.
+    pub async fn with_cache(cache: CacheConfig) -> Self {
+        Self::with_config(None, cache).await.logged_in().await
+    }
+
+    async fn with_config(oidc: Option<OidcConfig>, cache: CacheConfig) -> Self {
+        let fixture = TestFixture::new().await;
+        fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+        tokio::spawn(fixture.service.clone().relay_post_changes().await);
+
+        let config = HttpServerConfig {
+            port: "0",
+            secure_cookies: false,
+            oidc,
+            cache,
+            post_constraints: PostConstraints::new(),
+            graphiql: false,
+            sse_heartbeat: SSE_HEARTBEAT,
+            metrics: fixture.metrics.clone(),
+            metrics_route: true,
+        };
+        let server = HttpServer::try_new(fixture.service.clone(), config)
+            .await
+            .expect("Failed to create server.");
+
+        Self {
+            router: server.router,
+            session_cookie: None,
+            fixture,
+        }
+    }
+
+    async fn logged_in(mut self) -> Self {
+        let resp = self.login(TEST_USER_EMAIL, TEST_USER_PASSWORD).await;
+        self.session_cookie = Some(session_cookie(&resp).expect("Login did not set a cookie."));
+        self
+    }
+
+    pub fn fixture(&self) -> &TestFixture {
+        &self.fixture
+    }
+
+    /// Creates a user with the given role and returns their session cookie.
+    pub async fn login_as(&self, email: &str, role: Role) -> (User, String) {
+        let user = self.fixture.create_user(email, role).await;
+        let resp = self.login(email, TEST_USER_PASSWORD).await;
+        let cookie = session_cookie(&resp).expect("Login did not set a cookie.");
+        (user, cookie)
+    }
+
+    pub async fn login(&self, email: &str, password: &str) -> Response<Body> {
+        let body = json!({ "email": email, "password": password });
+        self.call_with_cookie("/auth/login", Method::Post, Some(body), None)
+            .await
+    }
+
+    pub async fn call(&self, uri: &str, method: Method, body: Option<Value>) -> Response<Body> {
+        self.call_with_cookie(uri, method, body, self.session_cookie.as_deref())
+            .await
+    }
+
+    /// Like [`TestApp::call`], for a change to a post last seen at `version`.
+    pub async fn call_if_match(
+        &self,
+        uri: &str,
+        method: Method,
+        body: Option<Value>,
+        version: i64,
+    ) -> Response<Body> {
+        let cookie = self
+            .session_cookie
+            .clone()
+            .map(|cookie| (header::COOKIE, cookie));
+        self.call_with_headers(
+            uri,
+            method,
+            body,
+            cookie.into_iter().chain([if_match(version)]),
+        )
+        .await
+    }
+
+    pub async fn call_anonymous(
+        &self,
+        uri: &str,
+        method: Method,
+        body: Option<Value>,
+    ) -> Response<Body> {
+        self.call_with_cookie(uri, method, body, None).await
+    }
+
+    pub async fn call_with_cookie(
+        &self,
+        uri: &str,
+        method: Method,
+        body: Option<Value>,
+        cookie: Option<&str>,
+    ) -> Response<Body> {
+        let headers = cookie.map(|cookie| (header::COOKIE, cookie.to_string()));
+        self.call_with_headers(uri, method, body, headers).await
+    }
+
+    pub async fn call_with_bearer(
+        &self,
+        uri: &str,
+        method: Method,
+        body: Option<Value>,
+        token: &str,
+    ) -> Response<Body> {
+        let headers = [(header::AUTHORIZATION, format!("Bearer {token}"))];
+        self.call_with_headers(uri, method, body, headers).await
+    }
+
+    pub async fn call_with_headers(
+        &self,
+        uri: &str,
+        method: Method,
+        body: Option<Value>,
+        headers: impl IntoIterator<Item = (HeaderName, String)>,
+    ) -> Response<Body> {
+        let body = match &body {
+            Some(value) => {
+                Body::from(serde_json::to_string(value).expect("Failed to stringiy body."))
+            }
+            None => Body::from(""),
+        };
+
+        let mut request = Request::builder()
+            .method(method.to_string().as_str())
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        self.router
+            .clone()
+            .oneshot(request.body(body).expect("Failed to build request."))
+            .await
+            .unwrap_or_else(|_| panic!("Failed to call endpoint {uri}"))
+    }
+
+    pub async fn parse_response<T>(&self, resp: Response<Body>) -> T
+    where
+        T: DeserializeOwned,
+    {
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("Failed to parse body.");
+        let json_resp: Value = serde_json::from_slice(&body).expect("Failed to stringify body.");
+        let result: T = serde_json::from_value(json_resp).expect("Failed to deserialize value.");
+        result
+    }
+}
+
+/// The `If-Match` header for a post at `version`.
+pub fn if_match(version: i64) -> (HeaderName, String) {
+    (header::IF_MATCH, format!("\"{version}\""))
+}
+
+/// Returns the `name=value` pair of the cookie set by a response.
+pub fn session_cookie(resp: &Response<Body>) -> Option<String> {
+    resp.headers()
+        .get(header::SET_COOKIE)?
+        .to_str()
+        .ok()?
+        .split(';')
+        .next()
+        .map(str::to_string)
+}
+
+pub enum Method {
+    Get,
+    Post,
+    Patch,
+    Delete,
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Get => "GET",
+                Self::Post => "POST",
+                Self::Patch => "PATCH",
+                Self::Delete => "DELETE",
+            }
+        )
+    }
+}
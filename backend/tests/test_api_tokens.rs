@@ -0,0 +1,172 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use backend::api::api_token::{BulkApiTokenResponse, CreatedApiTokenResponse};
+use backend::api::post::{CreatePostRequest as CreatePostRequestDTO, PostResponse};
+use common::{Method, TestApp, if_match};
+use serde_json::{Value, json};
+
+async fn create_token(app: &TestApp, body: Value) -> CreatedApiTokenResponse {
+    let resp = app.call("/auth/tokens", Method::Post, Some(body)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+fn post_body(title: &str) -> Value {
+    json!(CreatePostRequestDTO {
+        title: title.to_string(),
+        body: "Release notes".to_string(),
+        authors: None,
+    })
+}
+
+#[tokio::test]
+async fn test_create_api_token_endpoint_returns_secret_once() {
+    // Arrange
+    let app = TestApp::new().await;
+
+    // Act
+    let created = create_token(
+        &app,
+        json!({ "name": "CI", "scopes": ["posts:read", "posts:write"] }),
+    )
+    .await;
+    let list_resp = app.call("/auth/tokens", Method::Get, None).await;
+
+    // Assert
+    assert!(created.secret.starts_with("blog_pat_"));
+    assert_eq!(created.token.scopes, vec!["posts:read", "posts:write"]);
+    assert_eq!(list_resp.status(), StatusCode::OK);
+
+    let list: String = axum::body::to_bytes(list_resp.into_body(), usize::MAX)
+        .await
+        .map(|bytes| String::from_utf8(bytes.to_vec()).unwrap())
+        .unwrap();
+    let tokens: BulkApiTokenResponse = serde_json::from_str(&list).unwrap();
+    assert_eq!(tokens.data, vec![created.token]);
+    assert!(!list.contains(&created.secret));
+}
+
+#[tokio::test]
+async fn test_create_api_token_endpoint_rejects_invalid_requests() {
+    // Arrange
+    let app = TestApp::new().await;
+
+    // Act
+    let unknown_scope = app
+        .call(
+            "/auth/tokens",
+            Method::Post,
+            Some(json!({ "name": "CI", "scopes": ["posts:publish"] })),
+        )
+        .await;
+    let no_scopes = app
+        .call(
+            "/auth/tokens",
+            Method::Post,
+            Some(json!({ "name": "CI", "scopes": [] })),
+        )
+        .await;
+    let expired = app
+        .call(
+            "/auth/tokens",
+            Method::Post,
+            Some(json!({
+                "name": "CI",
+                "scopes": ["posts:write"],
+                "expires_at": "2000-01-01T00:00:00Z"
+            })),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(unknown_scope.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(no_scopes.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(expired.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_bearer_token_authenticates_within_its_scopes() {
+    // Arrange
+    let app = TestApp::new().await;
+    let created = create_token(&app, json!({ "name": "CI", "scopes": ["posts:write"] })).await;
+
+    // Act
+    let create_resp = app
+        .call_with_bearer(
+            "/posts",
+            Method::Post,
+            Some(post_body("v1.0")),
+            &created.secret,
+        )
+        .await;
+    let post: PostResponse = app.parse_response(create_resp).await;
+    let delete_resp = app
+        .call_with_headers(
+            &format!("/posts/{}", post.id),
+            Method::Delete,
+            None,
+            [
+                (header::AUTHORIZATION, format!("Bearer {}", created.secret)),
+                if_match(post.version),
+            ],
+        )
+        .await;
+    let mint_resp = app
+        .call_with_bearer(
+            "/auth/tokens",
+            Method::Post,
+            Some(json!({ "name": "Escalated", "scopes": ["posts:delete"] })),
+            &created.secret,
+        )
+        .await;
+    let list_resp = app.call("/auth/tokens", Method::Get, None).await;
+
+    // Assert
+    assert_eq!(post.title, "v1.0");
+    assert_eq!(delete_resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(mint_resp.status(), StatusCode::FORBIDDEN);
+
+    let tokens: BulkApiTokenResponse = app.parse_response(list_resp).await;
+    assert!(tokens.data[0].last_used_at.is_some());
+}
+
+#[tokio::test]
+async fn test_revoked_and_unknown_tokens_are_rejected() {
+    // Arrange
+    let app = TestApp::new().await;
+    let created = create_token(&app, json!({ "name": "CI", "scopes": ["posts:write"] })).await;
+
+    // Act
+    let revoke_resp = app
+        .call(
+            &format!("/auth/tokens/{}", created.token.id),
+            Method::Delete,
+            None,
+        )
+        .await;
+    let revoked_resp = app
+        .call_with_bearer(
+            "/posts",
+            Method::Post,
+            Some(post_body("Revoked")),
+            &created.secret,
+        )
+        .await;
+    let unknown_resp = app
+        .call_with_bearer("/posts", Method::Get, None, "blog_pat_unknown")
+        .await;
+    let revoke_again_resp = app
+        .call(
+            &format!("/auth/tokens/{}", created.token.id),
+            Method::Delete,
+            None,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(revoke_resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(revoked_resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown_resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(revoke_again_resp.status(), StatusCode::NOT_FOUND);
+}
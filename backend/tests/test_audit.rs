@@ -0,0 +1,240 @@
+mod common;
+
+use axum::http::{HeaderName, StatusCode, header};
+use backend::api::{
+    admin::{AuditEntryResponse, AuditVerificationResponse, BulkAuditEntryResponse},
+    post::PostResponse,
+};
+use backend::domain::models::user::Role;
+use common::{Method, TestApp, if_match};
+use serde_json::{Value, json};
+
+const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+async fn create_post(app: &TestApp, cookie: &str, title: &str) -> PostResponse {
+    let resp = app
+        .call_with_cookie(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body" })),
+            Some(cookie),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+async fn audit_log(app: &TestApp, cookie: &str, query: &str) -> BulkAuditEntryResponse {
+    let resp = app
+        .call_with_cookie(
+            &format!("/admin/audit{query}"),
+            Method::Get,
+            None,
+            Some(cookie),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+async fn verify(app: &TestApp, cookie: &str) -> AuditVerificationResponse {
+    let resp = app
+        .call_with_cookie("/admin/audit/verify", Method::Get, None, Some(cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+/// Runs `sql` against the tables the app uses, bypassing the API.
+async fn execute(app: &TestApp, sql: &str) -> Result<(), sqlx::Error> {
+    let mut conn = app.fixture().pool.acquire().await?;
+    // The fixture points its pool at a scratch schema; the app uses the
+    // default one.
+    sqlx::query("RESET search_path").execute(&mut *conn).await?;
+    sqlx::query(sql).execute(&mut *conn).await?;
+    Ok(())
+}
+
+fn titles(entries: &[AuditEntryResponse]) -> Vec<Value> {
+    entries
+        .iter()
+        .map(|entry| entry.after.as_ref().unwrap()["title"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_post_changes_are_recorded_with_snapshots() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (admin, cookie) = app.login_as("admin@example.com", Role::Admin).await;
+    let post = create_post(&app, &cookie, "Original").await;
+    let uri = format!("/posts/{}", post.id);
+    let resp = app
+        .call_with_headers(
+            &uri,
+            Method::Patch,
+            Some(json!({ "title": "Renamed" })),
+            [(header::COOKIE, cookie.clone()), if_match(post.version)],
+        )
+        .await;
+    let renamed: PostResponse = app.parse_response(resp).await;
+
+    // Act
+    let delete = app
+        .call_with_headers(
+            &format!("{uri}?permanent=true"),
+            Method::Delete,
+            None,
+            [
+                (header::COOKIE, cookie.clone()),
+                if_match(renamed.version),
+                (REQUEST_ID, "delete-request".to_string()),
+            ],
+        )
+        .await;
+    let entries = audit_log(&app, &cookie, &format!("?post_id={}", post.id))
+        .await
+        .data;
+
+    // Assert
+    assert_eq!(delete.status(), StatusCode::NO_CONTENT);
+    assert_eq!(delete.headers()[&REQUEST_ID], "delete-request");
+    let actions: Vec<_> = entries.iter().map(|entry| entry.action.as_str()).collect();
+    assert_eq!(actions, ["post.deleted", "post.updated", "post.created"]);
+    assert!(
+        entries
+            .iter()
+            .all(|entry| entry.actor_id == Some(admin.id()))
+    );
+    assert!(entries.iter().all(|entry| entry.request_id.is_some()));
+
+    let [deleted, updated, created] = &entries[..] else {
+        unreachable!()
+    };
+    assert_eq!(deleted.request_id.as_deref(), Some("delete-request"));
+    assert_eq!(deleted.before.as_ref().unwrap()["title"], "Renamed");
+    assert_eq!(deleted.after, None);
+    assert_eq!(updated.before.as_ref().unwrap()["title"], "Original");
+    assert_eq!(updated.after.as_ref().unwrap()["title"], "Renamed");
+    assert_eq!(created.before, None);
+    assert_eq!(created.after.as_ref().unwrap()["body"], "Body");
+    assert_eq!(deleted.prev_hash, updated.hash);
+    assert_eq!(updated.prev_hash, created.hash);
+}
+
+#[tokio::test]
+async fn test_audit_log_is_filtered_and_paged() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, admin_cookie) = app.login_as("admin@example.com", Role::Admin).await;
+    let (author, author_cookie) = app.login_as("alice@example.com", Role::Author).await;
+    create_post(&app, &admin_cookie, "By the admin").await;
+    for title in ["First", "Second", "Third"] {
+        create_post(&app, &author_cookie, title).await;
+    }
+    let by_author = format!("?actor_id={}&action=post.created&limit=2", author.id());
+
+    // Act
+    let first_page = audit_log(&app, &admin_cookie, &by_author).await.data;
+    let second_page = audit_log(
+        &app,
+        &admin_cookie,
+        &format!("{by_author}&before_id={}", first_page[1].id),
+    )
+    .await
+    .data;
+    let unknown_action = app
+        .call_with_cookie(
+            "/admin/audit?action=post.published",
+            Method::Get,
+            None,
+            Some(&admin_cookie),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(titles(&first_page), [json!("Third"), json!("Second")]);
+    assert_eq!(titles(&second_page), [json!("First")]);
+    assert_eq!(unknown_action.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_audit_log_is_admin_only() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, editor_cookie) = app.login_as("eve@example.com", Role::Editor).await;
+
+    // Act
+    let list = app
+        .call_with_cookie("/admin/audit", Method::Get, None, Some(&editor_cookie))
+        .await;
+    let verify = app
+        .call_with_cookie(
+            "/admin/audit/verify",
+            Method::Get,
+            None,
+            Some(&editor_cookie),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(list.status(), StatusCode::FORBIDDEN);
+    assert_eq!(verify.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_audit_entries_cannot_be_changed() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("admin@example.com", Role::Admin).await;
+    create_post(&app, &cookie, "Audited").await;
+
+    // Act
+    let update = execute(&app, "UPDATE audit_log SET actor_id = NULL").await;
+    let delete = execute(&app, "DELETE FROM audit_log").await;
+    let truncate = execute(&app, "TRUNCATE audit_log").await;
+
+    // Assert
+    for result in [update, delete, truncate] {
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("audit_log is append-only"), "{err}");
+    }
+    assert_eq!(audit_log(&app, &cookie, "").await.data.len(), 1);
+}
+
+#[tokio::test]
+async fn test_verify_detects_tampering() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("admin@example.com", Role::Admin).await;
+    for title in ["First", "Second", "Third"] {
+        create_post(&app, &cookie, title).await;
+    }
+    let before_tampering = verify(&app, &cookie).await;
+    let second = audit_log(&app, &cookie, "").await.data[1].id;
+
+    // Someone with enough privileges to bypass the triggers rewrites history.
+    execute(
+        &app,
+        "ALTER TABLE audit_log DISABLE TRIGGER audit_log_append_only",
+    )
+    .await
+    .unwrap();
+    execute(
+        &app,
+        &format!(
+            r#"UPDATE audit_log SET after_snapshot = after_snapshot || '{{"title": "Forged"}}' WHERE id = {second}"#
+        ),
+    )
+    .await
+    .unwrap();
+
+    // Act
+    let after_tampering = verify(&app, &cookie).await;
+
+    // Assert
+    assert!(before_tampering.intact);
+    assert_eq!(before_tampering.checked, 3);
+    assert!(!after_tampering.intact);
+    assert_eq!(after_tampering.first_broken_id, Some(second));
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use backend::api::auth::UserResponse;
+use backend::api::post::CreatePostRequest as CreatePostRequestDTO;
+use backend::domain::models::user::{CreateUserRequest, Password, Role, UserEmail};
+use backend::domain::repository::{CreateUserError, RepositoryError};
+use backend::domain::service::{Service, ServiceError};
+use common::{Method, TEST_USER_EMAIL, TEST_USER_PASSWORD, TestApp, TestFixture, session_cookie};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_service_create_user_with_duplicate_email_triggers_error() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let create_req = CreateUserRequest::new(
+        UserEmail::try_new("tommy@example.com").unwrap(),
+        Password::try_new("correct horse battery staple").unwrap(),
+        Role::Author,
+    );
+
+    // Act
+    let first_result = fixture.service.create_user(&create_req).await;
+    let duplicate_result = fixture.service.create_user(&create_req).await;
+
+    // Assert
+    assert!(first_result.is_ok());
+
+    match duplicate_result.unwrap_err() {
+        ServiceError::RepositoryError(RepositoryError::CreateUserError(
+            CreateUserError::Duplicate { email },
+        )) => assert_eq!(email.to_string(), "tommy@example.com"),
+        err => panic!("Expected duplicate error, got: {:?}", err),
+    }
+}
+
+#[tokio::test]
+async fn test_login_endpoint_sets_session_cookie() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let resp = app.login(TEST_USER_EMAIL, TEST_USER_PASSWORD).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let set_cookie = resp
+        .headers()
+        .get(header::SET_COOKIE)
+        .expect("Login did not set a cookie.")
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(set_cookie.starts_with("blog_session="));
+    assert!(set_cookie.contains("HttpOnly"));
+    assert!(set_cookie.contains("SameSite=Lax"));
+    assert!(set_cookie.contains("Expires="));
+
+    let user: UserResponse = app.parse_response(resp).await;
+    assert_eq!(user.email, TEST_USER_EMAIL);
+}
+
+#[tokio::test]
+async fn test_login_endpoint_rejects_wrong_password_and_unknown_email() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let wrong_password = app.login(TEST_USER_EMAIL, "not the password").await;
+    let unknown_email = app.login("nobody@example.com", TEST_USER_PASSWORD).await;
+
+    // Assert
+    assert_eq!(wrong_password.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown_email.status(), StatusCode::UNAUTHORIZED);
+    assert!(session_cookie(&wrong_password).is_none());
+}
+
+#[tokio::test]
+async fn test_me_endpoint_returns_current_user() {
+    // Arrange
+    let app = TestApp::new().await;
+
+    // Act
+    let resp = app.call("/auth/me", Method::Get, None).await;
+    let anonymous_resp = app.call_anonymous("/auth/me", Method::Get, None).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(anonymous_resp.status(), StatusCode::UNAUTHORIZED);
+
+    let user: UserResponse = app.parse_response(resp).await;
+    assert_eq!(user.email, TEST_USER_EMAIL);
+    assert_eq!(user.role, "admin");
+}
+
+#[tokio::test]
+async fn test_logout_endpoint_invalidates_session() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let login_resp = app.login(TEST_USER_EMAIL, TEST_USER_PASSWORD).await;
+    let cookie = session_cookie(&login_resp).unwrap();
+
+    // Act
+    let logout_resp = app
+        .call_with_cookie("/auth/logout", Method::Post, None, Some(&cookie))
+        .await;
+    let me_resp = app
+        .call_with_cookie("/auth/me", Method::Get, None, Some(&cookie))
+        .await;
+
+    // Assert
+    assert_eq!(logout_resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(me_resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_write_endpoints_require_authentication() {
+    // Arrange
+    let app = TestApp::new().await;
+    let body = json!(CreatePostRequestDTO {
+        title: "Title".to_string(),
+        body: "Body".to_string(),
+        authors: None,
+    });
+    let post_id = uuid::Uuid::new_v4();
+
+    // Act
+    let create_resp = app
+        .call_anonymous("/posts", Method::Post, Some(body.clone()))
+        .await;
+    let update_resp = app
+        .call_anonymous(&format!("/posts/{post_id}"), Method::Patch, Some(body))
+        .await;
+    let delete_resp = app
+        .call_anonymous(&format!("/posts/{post_id}"), Method::Delete, None)
+        .await;
+    let export_resp = app.call_anonymous("/admin/export", Method::Get, None).await;
+    let read_resp = app.call_anonymous("/posts", Method::Get, None).await;
+
+    // Assert
+    assert_eq!(create_resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(update_resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(delete_resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(export_resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(read_resp.status(), StatusCode::OK);
+}
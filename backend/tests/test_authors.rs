@@ -0,0 +1,195 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::api::author::{AuthorResponse, CreateAuthorRequest, SocialLink};
+use backend::api::post::{
+    BulkPostResponse, CreatePostRequest as CreatePostRequestDTO, PostResponse,
+};
+use backend::domain::models::user::Role;
+use backend::ids::{AuthorId, UserId};
+use common::{Method, TestApp};
+use serde_json::json;
+
+fn author_body(display_name: &str, user_id: Option<UserId>) -> CreateAuthorRequest {
+    CreateAuthorRequest {
+        display_name: display_name.to_string(),
+        user_id,
+        bio: Some("Writes about Rust.".to_string()),
+        avatar_url: Some("https://example.com/avatar.png".to_string()),
+        social_links: vec![SocialLink {
+            label: "GitHub".to_string(),
+            url: "https://github.com/example".to_string(),
+        }],
+    }
+}
+
+async fn create_author(
+    app: &TestApp,
+    display_name: &str,
+    user_id: Option<UserId>,
+) -> AuthorResponse {
+    let resp = app
+        .call(
+            "/authors",
+            Method::Post,
+            Some(json!(author_body(display_name, user_id))),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+fn post_body(title: &str, authors: Option<Vec<AuthorId>>) -> CreatePostRequestDTO {
+    CreatePostRequestDTO {
+        title: title.to_string(),
+        body: "Body".to_string(),
+        authors,
+    }
+}
+
+#[tokio::test]
+async fn test_create_and_get_author() {
+    // Arrange
+    let app = TestApp::new().await;
+    let author = create_author(&app, "Ada Lovelace", None).await;
+
+    // Act
+    let resp = app
+        .call(&format!("/authors/{}", author.id), Method::Get, None)
+        .await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::OK);
+    let fetched: AuthorResponse = app.parse_response(resp).await;
+    assert_eq!(fetched, author);
+    assert_eq!(fetched.display_name, "Ada Lovelace");
+    assert_eq!(fetched.social_links.len(), 1);
+}
+
+#[tokio::test]
+async fn test_posts_are_credited_to_the_creators_author_profile() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (alice, alice_cookie) = app.login_as("alice@example.com", Role::Author).await;
+    let (_, admin_cookie) = app.login_as("admin@example.com", Role::Admin).await;
+    let resp = app
+        .call_with_cookie(
+            "/authors",
+            Method::Post,
+            Some(json!(author_body("Alice", Some(alice.id())))),
+            Some(&admin_cookie),
+        )
+        .await;
+    let profile: AuthorResponse = app.parse_response(resp).await;
+
+    // Act
+    let resp = app
+        .call_with_cookie(
+            "/posts",
+            Method::Post,
+            Some(json!(post_body("Alice's post", None))),
+            Some(&alice_cookie),
+        )
+        .await;
+    let post: PostResponse = app.parse_response(resp).await;
+    let resp = app
+        .call_anonymous(&format!("/authors/{}/posts", profile.id), Method::Get, None)
+        .await;
+
+    // Assert
+    assert_eq!(post.authors, vec![profile]);
+    assert_eq!(resp.status(), StatusCode::OK);
+    let posts: BulkPostResponse = app.parse_response(resp).await;
+    assert_eq!(posts.data, vec![post]);
+}
+
+#[tokio::test]
+async fn test_co_authors_keep_their_order() {
+    // Arrange
+    let app = TestApp::new().await;
+    let first = create_author(&app, "First", None).await;
+    let second = create_author(&app, "Second", None).await;
+
+    // Act
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!(post_body(
+                "Co-authored",
+                Some(vec![second.id, first.id])
+            ))),
+        )
+        .await;
+    let created: PostResponse = app.parse_response(resp).await;
+    let resp = app
+        .call_if_match(
+            &format!("/posts/{}", created.id),
+            Method::Patch,
+            Some(json!({ "authors": [first.id, second.id] })),
+            created.version,
+        )
+        .await;
+    let updated: PostResponse = app.parse_response(resp).await;
+
+    // Assert
+    assert_eq!(created.authors, vec![second.clone(), first.clone()]);
+    assert_eq!(updated.authors, vec![first, second]);
+}
+
+#[tokio::test]
+async fn test_unknown_authors_are_rejected() {
+    // Arrange
+    let app = TestApp::new().await;
+    let unknown = AuthorId::new();
+
+    // Act
+    let create = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!(post_body("Ghost written", Some(vec![unknown])))),
+        )
+        .await;
+    let get = app
+        .call(&format!("/authors/{unknown}"), Method::Get, None)
+        .await;
+    let posts = app
+        .call(&format!("/authors/{unknown}/posts"), Method::Get, None)
+        .await;
+
+    // Assert
+    assert_eq!(create.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(get.status(), StatusCode::NOT_FOUND);
+    assert_eq!(posts.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_only_admins_and_editors_can_create_authors() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, author_cookie) = app.login_as("alice@example.com", Role::Author).await;
+    let body = json!(author_body("Alice", None));
+
+    // Act
+    let resp = app
+        .call_with_cookie("/authors", Method::Post, Some(body), Some(&author_cookie))
+        .await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_invalid_author_urls_are_rejected() {
+    // Arrange
+    let app = TestApp::new().await;
+    let mut body = author_body("Mallory", None);
+    body.avatar_url = Some("javascript:alert(1)".to_string());
+
+    // Act
+    let resp = app.call("/authors", Method::Post, Some(json!(body))).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,264 @@
+mod common;
+
+use std::io::Cursor;
+
+use axum::http::{StatusCode, header};
+use backend::api::post::CreatePostRequest as CreatePostRequestDTO;
+use backend::backup::{self, BACKUP_FORMAT, BackupError, BackupRecord};
+use backend::domain::models::post::{CreatePostRequest, PostBody, PostTitle, UpdatePostRequest};
+use backend::domain::models::user::{Role, User};
+use backend::domain::service::Service;
+use backend::import::wordpress::WordPressImporter;
+use common::{Method, TEST_USER_EMAIL, TestApp, TestFixture};
+use futures::TryStreamExt;
+use serde_json::json;
+
+const WORDPRESS_EXPORT: &str = include_str!("fixtures/wordpress.xml");
+
+async fn seed(fixture: &TestFixture, author: &User, titles: &[&str]) {
+    for title in titles {
+        let req = CreatePostRequest::new(PostTitle::new(title), PostBody::new("Body"));
+        fixture.service.create_post(author, &req).await.unwrap();
+    }
+}
+
+async fn export(fixture: &TestFixture) -> String {
+    backup::export(fixture.service.stream_backup_posts())
+        .try_collect::<Vec<_>>()
+        .await
+        .expect("Failed to export backup.")
+        .concat()
+}
+
+#[tokio::test]
+async fn test_export_endpoint_streams_ndjson_backup() {
+    // Arrange
+    let app = TestApp::new().await;
+
+    for title in ["First", "Second"] {
+        let body = CreatePostRequestDTO {
+            title: title.to_string(),
+            body: "Body".to_string(),
+            authors: None,
+        };
+        app.call("/posts", Method::Post, Some(json!(body))).await;
+    }
+
+    // Act
+    let resp = app.call("/admin/export", Method::Get, None).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/x-ndjson"
+    );
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let records: Vec<BackupRecord> = String::from_utf8(body.to_vec())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(records.len(), 3);
+    match &records[0] {
+        BackupRecord::Header(header) => assert_eq!(header.format, BACKUP_FORMAT),
+        record => panic!("Expected header, got: {:?}", record),
+    }
+    assert!(
+        records[1..]
+            .iter()
+            .all(|record| matches!(record, BackupRecord::Post(_)))
+    );
+}
+
+#[tokio::test]
+async fn test_restore_replaces_posts_with_backup() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let admin = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+    seed(&fixture, &admin, &["First", "Second"]).await;
+    let original = fixture.service.get_all_posts().await.unwrap();
+    let exported = export(&fixture).await;
+
+    seed(&fixture, &admin, &["Written after backup"]).await;
+    let second = original
+        .iter()
+        .find(|p| p.title().to_string() == "Second")
+        .unwrap();
+    fixture
+        .service
+        .delete_post(&admin, second.id(), second.version())
+        .await
+        .unwrap();
+
+    // Act
+    let restored = backup::restore(&fixture.service, exported.as_bytes())
+        .await
+        .expect("Failed to restore backup.");
+
+    // Assert
+    assert_eq!(restored.len(), 2);
+
+    let mut posts = fixture.service.get_all_posts().await.unwrap();
+    posts.sort();
+    // Second was deleted after the backup, which took it to the next version.
+    let second_id = second.id();
+    let mut expected: Vec<_> = original
+        .into_iter()
+        .map(|p| {
+            if p.id() == second_id {
+                let version = p.version() + 2;
+                p.with_version(version)
+            } else {
+                p
+            }
+        })
+        .collect();
+    expected.sort();
+    assert_eq!(posts, expected);
+}
+
+#[tokio::test]
+async fn test_failed_restore_leaves_posts_untouched() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let admin = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+    seed(&fixture, &admin, &["First"]).await;
+    let exported = export(&fixture).await;
+    seed(&fixture, &admin, &["Second"]).await;
+    let mut before = fixture.service.get_all_posts().await.unwrap();
+    before.sort();
+
+    // Postgres refuses NUL characters in text, so the archive reads fine but
+    // the restore fails once the existing posts are already deleted and the
+    // first ones inserted.
+    let corrupted = format!(
+        "{exported}{}\n",
+        json!({
+            "type": "post",
+            "id": uuid::Uuid::new_v4(),
+            "title": "Unstorable",
+            "body": "Nul \u{0} byte",
+            "created_at": "2024-01-01T00:00:00Z",
+        })
+    );
+    assert!(backup::read(corrupted.as_bytes()).is_ok());
+
+    // Act
+    let result = backup::restore(&fixture.service, corrupted.as_bytes()).await;
+
+    // Assert
+    assert!(matches!(result, Err(BackupError::Service(_))));
+    let mut after = fixture.service.get_all_posts().await.unwrap();
+    after.sort();
+    assert_eq!(after, before);
+}
+
+#[tokio::test]
+async fn test_restore_keeps_the_trash() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let admin = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+    seed(&fixture, &admin, &["Kept", "Trashed"]).await;
+    let trashed = fixture
+        .service
+        .get_all_posts()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|p| p.title().to_string() == "Trashed")
+        .unwrap();
+    fixture
+        .service
+        .delete_post(&admin, trashed.id(), trashed.version())
+        .await
+        .unwrap();
+    let trash = fixture.service.get_trash(&admin).await.unwrap();
+    let exported = export(&fixture).await;
+
+    // Act
+    backup::restore(&fixture.service, exported.as_bytes())
+        .await
+        .expect("Failed to restore backup.");
+
+    // Assert
+    assert_eq!(fixture.service.get_trash(&admin).await.unwrap(), trash);
+    let posts = fixture.service.get_all_posts().await.unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].title().to_string(), "Kept");
+}
+
+#[tokio::test]
+async fn test_restore_keeps_categories_tags_and_comments() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let report = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(WORDPRESS_EXPORT))
+        .await
+        .expect("Failed to import WXR export.");
+    let post_id = report.posts[0].post_id;
+    let terms = fixture.service.get_post_terms(post_id).await.unwrap();
+    let comments = fixture.service.get_post_comments(post_id).await.unwrap();
+    let exported = export(&fixture).await;
+
+    // Act
+    backup::restore(&fixture.service, exported.as_bytes())
+        .await
+        .expect("Failed to restore backup.");
+
+    // Assert
+    assert!(!terms.is_empty());
+    assert!(!comments.is_empty());
+    assert_eq!(
+        fixture.service.get_post_terms(post_id).await.unwrap(),
+        terms
+    );
+    assert_eq!(
+        fixture.service.get_post_comments(post_id).await.unwrap(),
+        comments
+    );
+}
+
+#[tokio::test]
+async fn test_restore_keeps_etags_from_matching_other_content() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let admin = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+    seed(&fixture, &admin, &["Unchanged", "Changed"]).await;
+    let posts = fixture.service.get_all_posts().await.unwrap();
+    let unchanged = posts
+        .iter()
+        .find(|p| p.title().to_string() == "Unchanged")
+        .unwrap();
+    let changed = posts
+        .iter()
+        .find(|p| p.title().to_string() == "Changed")
+        .unwrap();
+    let exported = export(&fixture).await;
+    let edit = UpdatePostRequest::new(None, Some(PostBody::new("Edited")));
+    let edited = fixture
+        .service
+        .update_post(&admin, changed.id(), changed.version(), &edit)
+        .await
+        .unwrap();
+
+    // Act
+    backup::restore(&fixture.service, exported.as_bytes())
+        .await
+        .expect("Failed to restore backup.");
+
+    // Assert
+    let restored = fixture.service.get_posts_by_id(changed.id()).await.unwrap();
+    assert_eq!(restored.body().to_string(), "Body");
+    assert_eq!(restored.version(), edited.version() + 1);
+    let kept = fixture
+        .service
+        .get_posts_by_id(unchanged.id())
+        .await
+        .unwrap();
+    assert_eq!(kept.version(), unchanged.version());
+}
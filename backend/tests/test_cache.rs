@@ -0,0 +1,171 @@
+mod common;
+
+use axum::{
+    body::to_bytes,
+    http::{HeaderName, StatusCode, header},
+};
+use backend::api::{
+    cache::{CacheConfig, CachePolicy, SURROGATE_KEY},
+    post::{POST_PATH, POSTS_PATH, POSTS_SURROGATE_KEY, PostResponse},
+};
+use common::{Method, TestApp};
+use serde_json::json;
+
+const POST_LIST_CACHE_CONTROL: &str = "public, max-age=0, s-maxage=300";
+
+const POST_CACHE_CONTROL: &str = "public, max-age=0, s-maxage=3600";
+
+async fn cached_app() -> TestApp {
+    TestApp::with_cache(
+        CacheConfig::new()
+            .with_route(
+                POSTS_PATH,
+                CachePolicy::try_new(POST_LIST_CACHE_CONTROL)
+                    .unwrap()
+                    .with_surrogate_key(POSTS_SURROGATE_KEY),
+            )
+            .with_route(POST_PATH, CachePolicy::try_new(POST_CACHE_CONTROL).unwrap()),
+    )
+    .await
+}
+
+async fn create_post(app: &TestApp, title: &str) -> PostResponse {
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body" })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+async fn get(
+    app: &TestApp,
+    uri: &str,
+    condition: Option<(HeaderName, String)>,
+) -> (StatusCode, axum::http::HeaderMap, usize) {
+    let resp = app
+        .call_with_headers(uri, Method::Get, None, condition)
+        .await;
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    (status, headers, body.len())
+}
+
+#[tokio::test]
+async fn test_post_list_is_revalidated_by_etag() {
+    // Arrange
+    let app = cached_app().await;
+    create_post(&app, "First").await;
+    let (_, headers, _) = get(&app, "/posts", None).await;
+    let etag = headers[header::ETAG].to_str().unwrap().to_string();
+
+    // Act
+    let unchanged = get(&app, "/posts", Some((header::IF_NONE_MATCH, etag.clone()))).await;
+    create_post(&app, "Second").await;
+    let changed = get(&app, "/posts", Some((header::IF_NONE_MATCH, etag.clone()))).await;
+
+    // Assert
+    assert!(etag.starts_with("W/\""));
+    assert_eq!(headers[header::CACHE_CONTROL], POST_LIST_CACHE_CONTROL);
+    assert_eq!(headers[SURROGATE_KEY], POSTS_SURROGATE_KEY);
+    assert_eq!(unchanged.0, StatusCode::NOT_MODIFIED);
+    assert_eq!(unchanged.1[header::ETAG], etag.as_str());
+    assert_eq!(unchanged.2, 0);
+    assert_eq!(changed.0, StatusCode::OK);
+    assert_ne!(changed.1[header::ETAG], etag.as_str());
+}
+
+#[tokio::test]
+async fn test_post_list_is_revalidated_by_date() {
+    // Arrange
+    let app = cached_app().await;
+    let (_, empty_headers, _) = get(&app, "/posts", None).await;
+    create_post(&app, "First").await;
+    let (_, headers, _) = get(&app, "/posts", None).await;
+    let last_modified = headers[header::LAST_MODIFIED].to_str().unwrap().to_string();
+
+    // Act
+    let not_modified_since = get(
+        &app,
+        "/posts",
+        Some((header::IF_MODIFIED_SINCE, last_modified.clone())),
+    )
+    .await;
+
+    // Assert
+    assert!(!empty_headers.contains_key(header::LAST_MODIFIED));
+    assert_eq!(not_modified_since.0, StatusCode::NOT_MODIFIED);
+    assert_eq!(
+        not_modified_since.1[header::LAST_MODIFIED],
+        last_modified.as_str()
+    );
+}
+
+#[tokio::test]
+async fn test_post_is_revalidated_by_version_and_date() {
+    // Arrange
+    let app = cached_app().await;
+    let post = create_post(&app, "Cached").await;
+    let uri = format!("/posts/{}", post.id);
+    let (_, headers, _) = get(&app, &uri, None).await;
+    let etag = headers[header::ETAG].to_str().unwrap().to_string();
+    let last_modified = headers[header::LAST_MODIFIED].to_str().unwrap().to_string();
+
+    // Act
+    let weak_match = get(
+        &app,
+        &uri,
+        Some((header::IF_NONE_MATCH, format!("W/{etag}"))),
+    )
+    .await;
+    let not_modified_since = get(
+        &app,
+        &uri,
+        Some((header::IF_MODIFIED_SINCE, last_modified.clone())),
+    )
+    .await;
+    app.call_if_match(
+        &uri,
+        Method::Patch,
+        Some(json!({ "body": "Edited" })),
+        post.version,
+    )
+    .await;
+    let edited = get(&app, &uri, Some((header::IF_NONE_MATCH, etag.clone()))).await;
+
+    // Assert
+    assert!(etag.starts_with("\"1-"));
+    assert_eq!(headers[header::CACHE_CONTROL], POST_CACHE_CONTROL);
+    assert_eq!(headers[SURROGATE_KEY], format!("post-{}", post.id).as_str());
+    assert_eq!(weak_match.0, StatusCode::NOT_MODIFIED);
+    assert_eq!(not_modified_since.0, StatusCode::NOT_MODIFIED);
+    assert_eq!(
+        not_modified_since.1[header::LAST_MODIFIED],
+        last_modified.as_str()
+    );
+    assert_eq!(edited.0, StatusCode::OK);
+    assert!(edited.1[header::ETAG].to_str().unwrap().starts_with("\"2-"));
+    assert!(edited.2 > 0);
+}
+
+#[tokio::test]
+async fn test_other_responses_are_not_cached() {
+    // Arrange
+    let app = cached_app().await;
+    let missing = format!("/posts/{}", uuid::Uuid::new_v4());
+
+    // Act
+    let (not_found, not_found_headers, _) = get(&app, &missing, None).await;
+    let trash = app.call("/trash", Method::Get, None).await;
+
+    // Assert
+    assert_eq!(not_found, StatusCode::NOT_FOUND);
+    assert!(!not_found_headers.contains_key(header::CACHE_CONTROL));
+    assert_eq!(trash.status(), StatusCode::OK);
+    assert!(!trash.headers().contains_key(header::CACHE_CONTROL));
+    assert!(!trash.headers().contains_key(header::ETAG));
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::api::{
+    changes::{ChangeFeedResponse, ChangeResponse},
+    post::PostResponse,
+    responses::ProblemDetails,
+};
+use backend::domain::service::Service;
+use chrono::Duration;
+use common::{Method, TestApp};
+use serde_json::json;
+
+async fn create_post(app: &TestApp, title: &str) -> PostResponse {
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body" })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+async fn update_post(app: &TestApp, post: &PostResponse, body: &str) -> PostResponse {
+    let resp = app
+        .call_if_match(
+            &format!("/posts/{}", post.id),
+            Method::Patch,
+            Some(json!({ "body": body })),
+            post.version,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+async fn delete_post(app: &TestApp, post: &PostResponse) {
+    let resp = app
+        .call_if_match(
+            &format!("/posts/{}", post.id),
+            Method::Delete,
+            None,
+            post.version,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+async fn changes(app: &TestApp, uri: &str) -> ChangeFeedResponse {
+    let resp = app.call_anonymous(uri, Method::Get, None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+#[tokio::test]
+async fn test_changes_list_the_latest_change_of_each_post_in_order() {
+    // Arrange
+    let app = TestApp::new().await;
+    let first = create_post(&app, "First").await;
+    let second = create_post(&app, "Second").await;
+    let first = update_post(&app, &first, "Changed").await;
+    delete_post(&app, &second).await;
+
+    // Act
+    let feed = changes(&app, "/changes").await;
+
+    // Assert
+    assert_eq!(feed.changes.len(), 2);
+    assert!(matches!(
+        &feed.changes[0],
+        ChangeResponse::Upsert { post, .. } if *post == first
+    ));
+    assert!(matches!(
+        &feed.changes[1],
+        ChangeResponse::Tombstone { post_id, sequence, .. }
+            if *post_id == second.id && *sequence == feed.next_cursor
+    ));
+    assert!(!feed.has_more);
+}
+
+#[tokio::test]
+async fn test_changes_continue_from_the_cursor() {
+    // Arrange
+    let app = TestApp::new().await;
+    create_post(&app, "First").await;
+    create_post(&app, "Second").await;
+
+    // Act
+    let first_page = changes(&app, "/changes?limit=1").await;
+    let second_page = changes(
+        &app,
+        &format!("/changes?since={}&limit=1", first_page.next_cursor),
+    )
+    .await;
+    let caught_up = changes(&app, &format!("/changes?since={}", second_page.next_cursor)).await;
+    let third = create_post(&app, "Third").await;
+    let new_page = changes(&app, &format!("/changes?since={}", caught_up.next_cursor)).await;
+
+    // Assert
+    assert_eq!(first_page.changes.len(), 1);
+    assert!(first_page.has_more);
+    assert_eq!(second_page.changes.len(), 1);
+    assert!(second_page.next_cursor > first_page.next_cursor);
+
+    assert!(caught_up.changes.is_empty());
+    assert!(!caught_up.has_more);
+    assert_eq!(caught_up.next_cursor, second_page.next_cursor);
+
+    assert_eq!(new_page.changes.len(), 1);
+    assert!(matches!(
+        &new_page.changes[0],
+        ChangeResponse::Upsert { post, .. } if *post == third
+    ));
+}
+
+#[tokio::test]
+async fn test_cursors_behind_compacted_tombstones_require_a_resync() {
+    // Arrange
+    let app = TestApp::new().await;
+    let post = create_post(&app, "Forgotten").await;
+    let kept = create_post(&app, "Kept").await;
+    let before_delete = changes(&app, "/changes").await;
+    delete_post(&app, &post).await;
+    let after_delete = changes(&app, "/changes").await;
+
+    let service = app
+        .fixture()
+        .service
+        .clone()
+        .with_tombstone_retention(Duration::zero());
+    let compacted = service.compact_changes().await.unwrap();
+
+    // Act
+    let stale_resp = app
+        .call_anonymous(
+            &format!("/changes?since={}", before_delete.next_cursor),
+            Method::Get,
+            None,
+        )
+        .await;
+    let current = changes(
+        &app,
+        &format!("/changes?since={}", after_delete.next_cursor),
+    )
+    .await;
+    let resync = changes(&app, "/changes").await;
+
+    // Assert
+    assert_eq!(compacted, 1);
+
+    assert_eq!(stale_resp.status(), StatusCode::GONE);
+    let problem: ProblemDetails = app.parse_response(stale_resp).await;
+    assert_eq!(problem.code, "resync_required");
+
+    assert!(current.changes.is_empty());
+
+    assert_eq!(resync.changes.len(), 1);
+    assert!(matches!(
+        &resync.changes[0],
+        ChangeResponse::Upsert { post, .. } if *post == kept
+    ));
+}
+
+#[tokio::test]
+async fn test_changes_reject_negative_cursors() {
+    // Arrange
+    let app = TestApp::new().await;
+
+    // Act
+    let resp = app
+        .call_anonymous("/changes?since=-1", Method::Get, None)
+        .await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
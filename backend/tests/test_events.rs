@@ -0,0 +1,214 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::{Body, BodyDataStream},
+    http::{StatusCode, header},
+};
+use backend::api::{events::EVENTS_PATH, post::PostResponse};
+use common::{Method, SSE_HEARTBEAT, TestApp};
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+/// One message of an event stream; comments have only `comment` set.
+#[derive(Debug, Default)]
+struct Message {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<Value>,
+    comment: bool,
+}
+
+struct EventStream {
+    body: BodyDataStream,
+    buffer: String,
+}
+
+impl EventStream {
+    fn new(body: Body) -> Self {
+        Self {
+            body: body.into_data_stream(),
+            buffer: String::new(),
+        }
+    }
+
+    async fn next(&mut self) -> Message {
+        loop {
+            if let Some((block, rest)) = self.buffer.split_once("\n\n") {
+                let message = parse(block);
+                self.buffer = rest.to_string();
+                return message;
+            }
+            let chunk = tokio::time::timeout(Duration::from_secs(5), self.body.next())
+                .await
+                .expect("Timed out waiting for an event.")
+                .expect("Event stream ended.")
+                .expect("Failed to read event stream.");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+
+    /// The next message that is not a comment.
+    async fn next_event(&mut self) -> Message {
+        loop {
+            let message = self.next().await;
+            if !message.comment {
+                return message;
+            }
+        }
+    }
+}
+
+fn parse(block: &str) -> Message {
+    let mut message = Message::default();
+    for line in block.lines() {
+        match line.split_once(':') {
+            Some(("", _)) => message.comment = true,
+            Some(("id", value)) => message.id = Some(value.trim().to_string()),
+            Some(("event", value)) => message.event = Some(value.trim().to_string()),
+            Some(("data", value)) => message.data = serde_json::from_str(value.trim()).ok(),
+            _ => {}
+        }
+    }
+    message
+}
+
+async fn subscribe(app: &TestApp, last_event_id: Option<&str>) -> EventStream {
+    let headers = last_event_id.map(|id| {
+        (
+            header::HeaderName::from_static("last-event-id"),
+            id.to_string(),
+        )
+    });
+    let resp = app
+        .call_with_headers(EVENTS_PATH, Method::Get, None, headers)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "text/event-stream");
+    EventStream::new(resp.into_body())
+}
+
+async fn create_post(app: &TestApp, title: &str) -> PostResponse {
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body" })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+async fn update_post(app: &TestApp, post: &PostResponse) -> PostResponse {
+    let resp = app
+        .call_if_match(
+            &format!("/posts/{}", post.id),
+            Method::Patch,
+            Some(json!({ "body": "Changed" })),
+            post.version,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+async fn delete_post(app: &TestApp, post: &PostResponse) {
+    let resp = app
+        .call_if_match(
+            &format!("/posts/{}", post.id),
+            Method::Delete,
+            None,
+            post.version,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+fn event_id(message: &Message) -> i64 {
+    message.id.as_deref().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_post_changes_are_streamed() {
+    // Arrange
+    let app = TestApp::new().await;
+    let mut events = subscribe(&app, None).await;
+
+    // Act
+    let post = create_post(&app, "Live").await;
+    let created = events.next_event().await;
+    let post = update_post(&app, &post).await;
+    let updated = events.next_event().await;
+    delete_post(&app, &post).await;
+    let deleted = events.next_event().await;
+
+    // Assert
+    assert_eq!(created.event.as_deref(), Some("post.created"));
+    assert_eq!(created.data.as_ref().unwrap()["title"], "Live");
+    assert_eq!(updated.event.as_deref(), Some("post.updated"));
+    assert_eq!(updated.data.as_ref().unwrap()["body"], "Changed");
+    assert_eq!(deleted.event.as_deref(), Some("post.deleted"));
+    assert_eq!(deleted.data.as_ref().unwrap(), &json!({ "id": post.id }));
+    assert!(event_id(&created) < event_id(&updated));
+    assert!(event_id(&updated) < event_id(&deleted));
+}
+
+#[tokio::test]
+async fn test_last_event_id_resumes_after_it() {
+    // Arrange
+    let app = TestApp::new().await;
+    let mut seen = subscribe(&app, None).await;
+    create_post(&app, "Seen").await;
+    let last_seen = seen.next_event().await.id.unwrap();
+    let missed = create_post(&app, "Missed").await;
+    let missed = update_post(&app, &missed).await;
+
+    // Act
+    let mut events = subscribe(&app, Some(&last_seen)).await;
+    let mut invalid = subscribe(&app, Some("not-an-id")).await;
+    create_post(&app, "Live").await;
+
+    // Assert
+    let resumed = events.next_event().await;
+    assert_eq!(resumed.event.as_deref(), Some("post.updated"));
+    assert_eq!(resumed.data.unwrap()["id"], json!(missed.id));
+    let live = events.next_event().await;
+    assert_eq!(live.data.as_ref().unwrap()["title"], "Live");
+    assert_eq!(invalid.next_event().await.id, live.id);
+}
+
+#[tokio::test]
+async fn test_streams_that_cannot_resume_are_reset() {
+    // Arrange
+    let app = TestApp::new().await;
+    sqlx::query("UPDATE post_change_compaction SET compacted_through = 100")
+        .execute(&app.fixture().pool)
+        .await
+        .unwrap();
+
+    // Act
+    let mut events = subscribe(&app, Some("1")).await;
+
+    // Assert
+    let reset = events.next_event().await;
+    assert_eq!(reset.event.as_deref(), Some("reset"));
+    assert_eq!(reset.id.as_deref(), Some("100"));
+    assert_eq!(reset.data.unwrap()["code"], "resync_required");
+}
+
+#[tokio::test]
+async fn test_idle_stream_gets_heartbeats() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let mut events = subscribe(&app, None).await;
+
+    // Act
+    let message = tokio::time::timeout(SSE_HEARTBEAT * 10, events.next())
+        .await
+        .expect("No heartbeat was sent.");
+
+    // Assert
+    assert!(message.comment);
+}
@@ -0,0 +1,298 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::api::{author::AuthorResponse, graphql::GRAPHQL_PATH, post::PostResponse};
+use backend::ids::{AuthorId, PostId};
+use common::{Method, TestApp};
+use serde_json::{Value, json};
+
+async fn graphql(app: &TestApp, query: &str, variables: Value) -> Value {
+    let resp = app
+        .call(
+            GRAPHQL_PATH,
+            Method::Post,
+            Some(json!({ "query": query, "variables": variables })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+async fn create_author(app: &TestApp, display_name: &str) -> AuthorResponse {
+    let resp = app
+        .call(
+            "/authors",
+            Method::Post,
+            Some(json!({ "display_name": display_name })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+async fn create_post(app: &TestApp, title: &str, authors: &[AuthorId]) -> PostResponse {
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body", "authors": authors })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+const CREATE_POST: &str = r#"
+    mutation ($input: CreatePostInput!) {
+        createPost(input: $input) { id title version authors { displayName } }
+    }
+"#;
+
+#[tokio::test]
+async fn test_post_with_authors_and_related_posts_in_one_request() {
+    // Arrange
+    let app = TestApp::new().await;
+    let ada = create_author(&app, "Ada").await;
+    let grace = create_author(&app, "Grace").await;
+    let post = create_post(&app, "Both", &[ada.id, grace.id]).await;
+    let by_ada = create_post(&app, "Ada alone", &[ada.id]).await;
+    let by_grace = create_post(&app, "Grace alone", &[grace.id]).await;
+    create_post(
+        &app,
+        "Nobody we know",
+        &[create_author(&app, "Alan").await.id],
+    )
+    .await;
+
+    // Act
+    let resp = graphql(
+        &app,
+        r#"
+            query ($id: PostId!) {
+                post(id: $id) {
+                    title
+                    authors { displayName posts { title } }
+                    relatedPosts { id }
+                }
+            }
+        "#,
+        json!({ "id": post.id }),
+    )
+    .await;
+
+    // Assert
+    assert!(resp.get("errors").is_none(), "{resp}");
+    let fetched = &resp["data"]["post"];
+    assert_eq!(fetched["title"], "Both");
+    assert_eq!(fetched["authors"][0]["displayName"], "Ada");
+    assert_eq!(
+        fetched["authors"][0]["posts"],
+        json!([{ "title": "Ada alone" }, { "title": "Both" }])
+    );
+    assert_eq!(
+        fetched["relatedPosts"],
+        json!([{ "id": by_grace.id }, { "id": by_ada.id }])
+    );
+}
+
+#[tokio::test]
+async fn test_posts_come_in_pages() {
+    // Arrange
+    let app = TestApp::new().await;
+    for title in ["One", "Two", "Three"] {
+        create_post(&app, title, &[]).await;
+    }
+    let query = r#"
+        query ($after: String) {
+            posts(first: 2, after: $after) { title cursor }
+        }
+    "#;
+
+    // Act
+    let first = graphql(&app, query, json!({})).await;
+    let cursor = first["data"]["posts"][1]["cursor"].clone();
+    let second = graphql(&app, query, json!({ "after": cursor })).await;
+
+    // Assert
+    let titles = |resp: &Value| {
+        resp["data"]["posts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|post| post["title"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&first), ["One", "Two"]);
+    assert_eq!(titles(&second), ["Three"]);
+}
+
+#[tokio::test]
+async fn test_author_posts_come_in_pages_per_author() {
+    // Arrange
+    let app = TestApp::new().await;
+    let ada = create_author(&app, "Ada").await;
+    let grace = create_author(&app, "Grace").await;
+    for title in ["One", "Two", "Three"] {
+        create_post(&app, title, &[ada.id]).await;
+    }
+    create_post(&app, "Both", &[ada.id, grace.id]).await;
+    create_post(&app, "Grace alone", &[grace.id]).await;
+    let query = r#"
+        query ($ada: AuthorId!, $grace: AuthorId!, $after: String) {
+            ada: author(id: $ada) { posts(first: 2, after: $after) { title cursor } }
+            grace: author(id: $grace) { posts(first: 2, after: $after) { title cursor } }
+        }
+    "#;
+    let ids = json!({ "ada": ada.id, "grace": grace.id });
+
+    // Act
+    let first = graphql(&app, query, ids.clone()).await;
+    let mut variables = ids;
+    variables["after"] = first["data"]["ada"]["posts"][1]["cursor"].clone();
+    let second = graphql(&app, query, variables).await;
+
+    // Assert
+    assert!(first.get("errors").is_none(), "{first}");
+    let titles = |resp: &Value, author: &str| {
+        resp["data"][author]["posts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|post| post["title"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&first, "ada"), ["Both", "Three"]);
+    assert_eq!(titles(&first, "grace"), ["Grace alone", "Both"]);
+    assert_eq!(titles(&second, "ada"), ["Two", "One"]);
+    assert!(titles(&second, "grace").is_empty());
+}
+
+#[tokio::test]
+async fn test_queries_for_too_many_posts_are_rejected() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let resp = graphql(
+        &app,
+        "{ posts(first: 100) { relatedPosts(first: 100) { title } } }",
+        json!({}),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(resp["data"], Value::Null);
+    assert!(
+        resp["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("complex"),
+        "{resp}"
+    );
+}
+
+#[tokio::test]
+async fn test_missing_post_is_null() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let resp = graphql(
+        &app,
+        "query ($id: PostId!) { post(id: $id) { title } }",
+        json!({ "id": PostId::new() }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(resp["data"]["post"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_create_and_update_post() {
+    // Arrange
+    let app = TestApp::new().await;
+    let resp = graphql(
+        &app,
+        CREATE_POST,
+        json!({ "input": { "title": "  From GraphQL ", "body": "Body" } }),
+    )
+    .await;
+    let created = &resp["data"]["createPost"];
+
+    // Act
+    let resp = graphql(
+        &app,
+        r#"
+            mutation ($id: PostId!, $version: Int!) {
+                updatePost(id: $id, version: $version, input: { body: "Changed" }) {
+                    title body version
+                }
+            }
+        "#,
+        json!({ "id": created["id"], "version": created["version"] }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(created["title"], "From GraphQL");
+    let updated = &resp["data"]["updatePost"];
+    assert_eq!(updated["body"], "Changed");
+    assert_eq!(updated["version"], created["version"].as_i64().unwrap() + 1);
+}
+
+#[tokio::test]
+async fn test_failures_carry_problem_codes() {
+    // Arrange
+    let app = TestApp::new().await;
+    let post = create_post(&app, "Taken", &[]).await;
+
+    // Act
+    let invalid = graphql(
+        &app,
+        CREATE_POST,
+        json!({ "input": { "title": " ", "body": "" } }),
+    )
+    .await;
+    let stale = graphql(
+        &app,
+        "mutation ($id: PostId!) { deletePost(id: $id, version: 0) }",
+        json!({ "id": post.id }),
+    )
+    .await;
+    let anonymous = graphql(
+        &TestApp::anonymous().await,
+        CREATE_POST,
+        json!({ "input": { "title": "Anonymous", "body": "Body" } }),
+    )
+    .await;
+
+    // Assert
+    let extensions = &invalid["errors"][0]["extensions"];
+    assert_eq!(extensions["code"], "validation_failed");
+    assert_eq!(extensions["status"], 422);
+    let pointers: Vec<_> = extensions["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| error["pointer"].as_str().unwrap())
+        .collect();
+    assert_eq!(pointers, ["/title", "/body"]);
+    assert_eq!(stale["errors"][0]["extensions"]["code"], "version_conflict");
+    assert_eq!(
+        anonymous["errors"][0]["extensions"]["code"],
+        "unauthenticated"
+    );
+}
+
+#[tokio::test]
+async fn test_graphiql_is_off_by_default() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let resp = app.call(GRAPHQL_PATH, Method::Get, None).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
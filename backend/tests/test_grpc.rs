@@ -0,0 +1,247 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use backend::domain::{
+    models::{
+        api_token::{ApiTokenName, CreateApiTokenRequest, Scope},
+        post::PostConstraints,
+        user::Role,
+    },
+    service::Service,
+};
+use backend::grpc::{
+    GrpcServer, GrpcServerConfig,
+    mappers::ERROR_CODE,
+    proto::{
+        AuthorIds, CreatePostRequest, DeletePostRequest, GetPostRequest, ListPostsRequest,
+        UpdatePostRequest, blog_service_client::BlogServiceClient,
+    },
+};
+use backend::ids::PostId;
+use common::{TEST_USER_EMAIL, TestFixture};
+use futures::TryStreamExt;
+use tokio::{sync::oneshot, task::JoinHandle};
+use tonic::{Code, Request, Status, transport::Channel};
+
+/// The gRPC server on a local port, with an admin holding an API token with
+/// every scope.
+struct TestGrpcServer {
+    client: BlogServiceClient<Channel>,
+    token: String,
+    shutdown: oneshot::Sender<()>,
+    server: JoinHandle<Result<(), anyhow::Error>>,
+    _fixture: TestFixture,
+}
+
+impl TestGrpcServer {
+    async fn new() -> Self {
+        let fixture = TestFixture::new().await;
+        let user = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+        let token = fixture
+            .service
+            .create_api_token(
+                &user,
+                &CreateApiTokenRequest::new(
+                    ApiTokenName::try_new("gRPC tests").unwrap(),
+                    BTreeSet::from(Scope::ALL),
+                    None,
+                ),
+            )
+            .await
+            .expect("Failed to create API token.");
+
+        let config = GrpcServerConfig {
+            port: "0",
+            post_constraints: PostConstraints::new(),
+        };
+        let server = GrpcServer::try_new(fixture.service.clone(), config)
+            .await
+            .expect("Failed to create gRPC server.");
+        let addr = server.local_addr().unwrap();
+        let (shutdown, stopped) = oneshot::channel();
+        let server = tokio::spawn(server.run(async {
+            stopped.await.ok();
+        }));
+        let client = BlogServiceClient::connect(format!("http://{addr}"))
+            .await
+            .expect("Failed to connect to gRPC server.");
+
+        Self {
+            client,
+            token: token.secret().expose().to_string(),
+            shutdown,
+            server,
+            _fixture: fixture,
+        }
+    }
+
+    fn authorized<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {}", self.token).parse().unwrap(),
+        );
+        request
+    }
+}
+
+fn create_request(title: &str) -> CreatePostRequest {
+    CreatePostRequest {
+        title: title.to_string(),
+        body: "Body".to_string(),
+        author_ids: vec![],
+    }
+}
+
+fn error_code(status: &Status) -> &str {
+    status.metadata().get(ERROR_CODE).unwrap().to_str().unwrap()
+}
+
+#[tokio::test]
+async fn test_post_round_trip() {
+    // Arrange
+    let server = TestGrpcServer::new().await;
+    let mut client = server.client.clone();
+    let created = client
+        .create_post(server.authorized(create_request("  Over gRPC ")))
+        .await
+        .unwrap()
+        .into_inner();
+
+    // Act
+    let updated = client
+        .update_post(server.authorized(UpdatePostRequest {
+            id: created.id.clone(),
+            version: created.version,
+            title: None,
+            body: Some("Changed".to_string()),
+            authors: Some(AuthorIds { ids: vec![] }),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let fetched = client
+        .get_post(GetPostRequest {
+            id: created.id.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    client
+        .delete_post(server.authorized(DeletePostRequest {
+            id: created.id.clone(),
+            version: updated.version,
+            permanent: false,
+        }))
+        .await
+        .unwrap();
+    let missing = client
+        .get_post(GetPostRequest {
+            id: created.id.clone(),
+        })
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert_eq!(created.title, "Over gRPC");
+    assert_eq!(updated.body, "Changed");
+    assert_eq!(updated.version, created.version + 1);
+    assert!(updated.authors.is_empty());
+    assert_eq!(fetched, updated);
+    assert_eq!(missing.code(), Code::NotFound);
+    assert_eq!(error_code(&missing), "post_not_found");
+}
+
+#[tokio::test]
+async fn test_list_posts_streams_every_post() {
+    // Arrange
+    let server = TestGrpcServer::new().await;
+    let mut client = server.client.clone();
+    for title in ["First", "Second", "Third"] {
+        client
+            .create_post(server.authorized(create_request(title)))
+            .await
+            .unwrap();
+    }
+
+    // Act
+    let posts: Vec<_> = client
+        .list_posts(ListPostsRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .try_collect()
+        .await
+        .unwrap();
+
+    // Assert
+    let mut titles: Vec<_> = posts.iter().map(|post| post.title.as_str()).collect();
+    titles.sort();
+    assert_eq!(titles, ["First", "Second", "Third"]);
+}
+
+#[tokio::test]
+async fn test_failures_map_to_status_codes() {
+    // Arrange
+    let server = TestGrpcServer::new().await;
+    let mut client = server.client.clone();
+    let post = client
+        .create_post(server.authorized(create_request("Taken")))
+        .await
+        .unwrap()
+        .into_inner();
+
+    // Act
+    let anonymous = client
+        .create_post(create_request("Anonymous"))
+        .await
+        .unwrap_err();
+    let invalid = client
+        .create_post(server.authorized(create_request(" ")))
+        .await
+        .unwrap_err();
+    let malformed_id = client
+        .get_post(GetPostRequest {
+            id: "not-a-uuid".to_string(),
+        })
+        .await
+        .unwrap_err();
+    let stale = client
+        .delete_post(server.authorized(DeletePostRequest {
+            id: post.id,
+            version: 0,
+            permanent: true,
+        }))
+        .await
+        .unwrap_err();
+    let missing = client
+        .get_post(GetPostRequest {
+            id: PostId::new().to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    // Assert
+    assert_eq!(anonymous.code(), Code::Unauthenticated);
+    assert_eq!(error_code(&anonymous), "unauthenticated");
+    assert_eq!(invalid.code(), Code::InvalidArgument);
+    assert_eq!(error_code(&invalid), "validation_failed");
+    assert_eq!(malformed_id.code(), Code::InvalidArgument);
+    assert_eq!(stale.code(), Code::Aborted);
+    assert_eq!(error_code(&stale), "version_conflict");
+    assert_eq!(missing.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_server_stops_on_shutdown() {
+    // Arrange
+    let server = TestGrpcServer::new().await;
+
+    // Act
+    server.shutdown.send(()).unwrap();
+    let result = server.server.await.unwrap();
+
+    // Assert
+    assert!(result.is_ok());
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Response, StatusCode, header},
+};
+use backend::{
+    api::{
+        idempotency::{IDEMPOTENCY_KEY, IDEMPOTENT_REPLAYED},
+        post::{BulkPostResponse, PostResponse},
+    },
+    domain::models::user::Role,
+};
+use common::{Method, TestApp};
+use serde_json::json;
+
+async fn create_post(app: &TestApp, cookie: &str, key: &str, title: &str) -> Response<Body> {
+    app.call_with_headers(
+        "/posts",
+        Method::Post,
+        Some(json!({ "title": title, "body": "Body" })),
+        [
+            (header::COOKIE, cookie.to_string()),
+            (IDEMPOTENCY_KEY, key.to_string()),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_retried_post_is_replayed() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("alice@example.com", Role::Author).await;
+    let first = create_post(&app, &cookie, "publish-1", "Once").await;
+    let first_etag = first.headers()[header::ETAG].clone();
+    let first: PostResponse = app.parse_response(first).await;
+
+    // Act
+    let retry = create_post(&app, &cookie, "publish-1", "Once").await;
+
+    // Assert
+    assert_eq!(retry.status(), StatusCode::CREATED);
+    assert_eq!(retry.headers()[IDEMPOTENT_REPLAYED], "true");
+    assert_eq!(retry.headers()[header::ETAG], first_etag);
+    let retried: PostResponse = app.parse_response(retry).await;
+    assert_eq!(retried, first);
+
+    let posts: BulkPostResponse = app
+        .parse_response(app.call("/posts", Method::Get, None).await)
+        .await;
+    assert_eq!(posts.data.len(), 1);
+}
+
+#[tokio::test]
+async fn test_key_reused_for_different_request_is_rejected() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("alice@example.com", Role::Author).await;
+    create_post(&app, &cookie, "publish-1", "First").await;
+
+    // Act
+    let reused = create_post(&app, &cookie, "publish-1", "Second").await;
+    let invalid = create_post(&app, &cookie, "not a key", "Second").await;
+
+    // Assert
+    assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(!reused.headers().contains_key(IDEMPOTENT_REPLAYED));
+    assert_eq!(invalid.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_keys_belong_to_their_user() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, alice) = app.login_as("alice@example.com", Role::Author).await;
+    let (_, bob) = app.login_as("bob@example.com", Role::Author).await;
+    create_post(&app, &alice, "publish-1", "By Alice").await;
+
+    // Act
+    let resp = create_post(&app, &bob, "publish-1", "By Bob").await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert!(!resp.headers().contains_key(IDEMPOTENT_REPLAYED));
+    let post: PostResponse = app.parse_response(resp).await;
+    assert_eq!(post.title, "By Bob");
+}
+
+#[tokio::test]
+async fn test_responses_with_secrets_are_not_kept() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (user, cookie) = app.login_as("alice@example.com", Role::Author).await;
+    let create_token = || {
+        app.call_with_headers(
+            "/auth/tokens",
+            Method::Post,
+            Some(json!({ "name": "CI", "scopes": ["posts:write"] })),
+            [
+                (header::COOKIE, cookie.to_string()),
+                (IDEMPOTENCY_KEY, "token-1".to_string()),
+            ],
+        )
+    };
+
+    // Act
+    let first = create_token().await;
+    let second = create_token().await;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert_eq!(second.status(), StatusCode::CREATED);
+    assert!(!second.headers().contains_key(IDEMPOTENT_REPLAYED));
+    let stored: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM idempotency_keys WHERE user_id = $1")
+            .bind(user.id())
+            .fetch_one(&app.fixture().pool)
+            .await
+            .unwrap();
+    assert_eq!(stored, 0);
+}
+
+#[tokio::test]
+async fn test_claim_of_a_request_that_never_finished_lapses() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (user, cookie) = app.login_as("alice@example.com", Role::Author).await;
+    create_post(&app, &cookie, "publish-1", "Once").await;
+    // As if the request had died before its response was kept.
+    sqlx::query(
+        "UPDATE idempotency_keys
+         SET response_status = NULL, response_headers = NULL, response_body = NULL
+         WHERE user_id = $1",
+    )
+    .bind(user.id())
+    .execute(&app.fixture().pool)
+    .await
+    .unwrap();
+    let in_progress = create_post(&app, &cookie, "publish-1", "Once").await;
+    sqlx::query("UPDATE idempotency_keys SET locked_until = now() WHERE user_id = $1")
+        .bind(user.id())
+        .execute(&app.fixture().pool)
+        .await
+        .unwrap();
+
+    // Act
+    let retry = create_post(&app, &cookie, "publish-1", "Once").await;
+
+    // Assert
+    assert_eq!(in_progress.status(), StatusCode::CONFLICT);
+    assert!(!retry.headers().contains_key(IDEMPOTENT_REPLAYED));
+    // Handled afresh, so the title is taken by the first attempt, and that
+    // response is kept this time.
+    assert_eq!(retry.status(), StatusCode::CONFLICT);
+    let kept: Option<i16> =
+        sqlx::query_scalar("SELECT response_status FROM idempotency_keys WHERE user_id = $1")
+            .bind(user.id())
+            .fetch_one(&app.fixture().pool)
+            .await
+            .unwrap();
+    assert_eq!(kept, Some(409));
+}
@@ -0,0 +1,278 @@
+mod common;
+
+use std::io::Cursor;
+
+use axum::http::StatusCode;
+use backend::domain::models::post::{CreatePostRequest, PostBody, PostTitle};
+use backend::domain::models::user::Role;
+use backend::domain::service::Service;
+use backend::import::wordpress::{SkipReason, WordPressImporter};
+use chrono::{TimeZone, Utc};
+use common::{Method, TEST_USER_EMAIL, TestApp, TestFixture};
+use serde_json::{Value, json};
+
+const EXPORT: &str = include_str!("fixtures/wordpress.xml");
+
+#[tokio::test]
+async fn test_wordpress_import_creates_published_posts() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+
+    // Act
+    let report = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(EXPORT))
+        .await
+        .expect("Failed to import WXR export.");
+
+    // Assert
+    assert_eq!(report.posts.len(), 2);
+    assert_eq!(report.skipped.len(), 2);
+    assert!(
+        report
+            .skipped
+            .iter()
+            .all(|item| item.reason == SkipReason::NotAPublishedPost)
+    );
+    assert_eq!(report.authors.len(), 1);
+
+    let hello = &report.posts[0];
+    let second = &report.posts[1];
+    assert_eq!(hello.wordpress_id, Some(10));
+    assert_eq!(hello.path, format!("/posts/{}", hello.post_id));
+    assert_eq!(
+        hello.urls,
+        vec![
+            "https://legacy.example.com/2020/01/hello-world/".to_string(),
+            "https://legacy.example.com/?p=10".to_string(),
+        ]
+    );
+    assert_eq!(hello.categories, vec!["News".to_string()]);
+    assert_eq!(hello.tags, vec!["Intro".to_string()]);
+    assert_eq!(hello.comments.len(), 1);
+
+    let post = fixture
+        .service
+        .get_posts_by_id(hello.post_id)
+        .await
+        .unwrap();
+    assert_eq!(post.title().to_string(), "Hello World");
+    assert_eq!(
+        post.created_at(),
+        Utc.with_ymd_and_hms(2020, 1, 2, 10, 0, 0).unwrap()
+    );
+
+    let body = post.body().to_string();
+    assert!(body.contains("*old*"));
+    assert!(body.contains(&format!("(/posts/{}#details)", second.post_id)));
+    assert!(body.contains(&format!("(/posts/{})", second.post_id)));
+
+    let post = fixture
+        .service
+        .get_posts_by_id(second.post_id)
+        .await
+        .unwrap();
+    assert!(
+        post.body()
+            .to_string()
+            .contains(&format!("(/posts/{})", hello.post_id))
+    );
+
+    let terms: Vec<(String, String)> = sqlx::query_as(
+        "SELECT kind, name FROM post_terms WHERE post_id = $1 ORDER BY kind, position",
+    )
+    .bind(hello.post_id)
+    .fetch_all(&fixture.pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        terms,
+        vec![
+            ("category".to_string(), "News".to_string()),
+            ("tag".to_string(), "Intro".to_string()),
+        ]
+    );
+
+    let comments: Vec<(String, Option<String>, bool)> =
+        sqlx::query_as("SELECT body, author_name, approved FROM post_comments WHERE post_id = $1")
+            .bind(hello.post_id)
+            .fetch_all(&fixture.pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        comments,
+        vec![("First!".to_string(), Some("A Reader".to_string()), true)]
+    );
+}
+
+#[tokio::test]
+async fn test_wordpress_import_dry_run_writes_nothing() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+
+    // Act
+    let report = WordPressImporter::new(&fixture.service)
+        .dry_run(true)
+        .import(Cursor::new(EXPORT))
+        .await
+        .expect("Failed to import WXR export.");
+
+    // Assert
+    assert_eq!(report.posts.len(), 2);
+    assert!(fixture.service.get_all_posts().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_wordpress_import_skips_posts_whose_title_is_taken() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let existing =
+        CreatePostRequest::new(PostTitle::new("Second Post"), PostBody::new("Already here"));
+    let author = fixture.create_user(TEST_USER_EMAIL, Role::Author).await;
+    let existing = fixture
+        .service
+        .create_post(&author, &existing)
+        .await
+        .unwrap();
+
+    // Act
+    let report = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(EXPORT))
+        .await
+        .expect("Failed to import WXR export.");
+    let again = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(EXPORT))
+        .await
+        .expect("Failed to import WXR export again.");
+
+    // Assert
+    assert_eq!(report.posts.len(), 1);
+    let skipped = report
+        .skipped
+        .iter()
+        .find(|item| item.reason == SkipReason::TitleTaken)
+        .expect("Colliding post was not skipped.");
+    assert_eq!(skipped.title, "Second Post");
+    assert_eq!(skipped.post_id, Some(existing.id()));
+
+    let hello = fixture
+        .service
+        .get_posts_by_id(report.posts[0].post_id)
+        .await
+        .unwrap();
+    assert!(
+        hello
+            .body()
+            .to_string()
+            .contains(&format!("(/posts/{})", existing.id()))
+    );
+
+    assert!(again.posts.is_empty());
+    assert_eq!(fixture.service.get_all_posts().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_wordpress_import_finds_authors_imported_before() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let first = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(EXPORT))
+        .await
+        .expect("Failed to import WXR export.");
+    let later_export = EXPORT
+        .replace("Hello World", "Hello Again")
+        .replace("Second Post", "Second Again");
+
+    // Act
+    let later = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(later_export))
+        .await
+        .expect("Failed to import later WXR export.");
+
+    // Assert
+    assert_eq!(later.posts.len(), 2);
+    let author_id = first.posts[0].author_id.expect("Post has no author.");
+    assert!(
+        later
+            .posts
+            .iter()
+            .all(|post| post.author_id == Some(author_id))
+    );
+
+    let authors: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM authors")
+        .fetch_one(&fixture.pool)
+        .await
+        .unwrap();
+    assert_eq!(authors, 1);
+}
+
+#[tokio::test]
+async fn test_wordpress_import_stores_titles_as_validated() {
+    // Arrange
+    let fixture = TestFixture::new().await;
+    let export = EXPORT.replace(
+        "<title>Hello World</title>",
+        "<title>  Hello World  </title>",
+    );
+
+    // Act
+    let report = WordPressImporter::new(&fixture.service)
+        .import(Cursor::new(export))
+        .await
+        .expect("Failed to import WXR export.");
+
+    // Assert
+    let hello = &report.posts[0];
+    assert_eq!(hello.title, "Hello World");
+    let post = fixture
+        .service
+        .get_posts_by_id(hello.post_id)
+        .await
+        .unwrap();
+    assert_eq!(post.title().to_string(), "Hello World");
+}
+
+#[tokio::test]
+async fn test_wordpress_import_serves_categories_tags_and_comments() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let report = WordPressImporter::new(&app.fixture().service)
+        .import(Cursor::new(EXPORT))
+        .await
+        .expect("Failed to import WXR export.");
+    let hello = &report.posts[0];
+
+    // Act
+    let terms = app
+        .call(
+            &format!("/posts/{}/terms", hello.post_id),
+            Method::Get,
+            None,
+        )
+        .await;
+    let comments = app
+        .call(
+            &format!("/posts/{}/comments", hello.post_id),
+            Method::Get,
+            None,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(terms.status(), StatusCode::OK);
+    let terms: Value = app.parse_response(terms).await;
+    assert_eq!(
+        terms,
+        json!({
+            "categories": [{"name": "News", "slug": "news"}],
+            "tags": [{"name": "Intro", "slug": "intro"}],
+        })
+    );
+
+    assert_eq!(comments.status(), StatusCode::OK);
+    let comments: Value = app.parse_response(comments).await;
+    let comments = comments["data"].as_array().unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0]["body"], "First!");
+    assert_eq!(comments[0]["author_name"], "A Reader");
+    assert!(comments[0].get("author_email").is_none());
+}
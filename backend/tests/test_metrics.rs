@@ -0,0 +1,127 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use backend::api::post::{POST_PATH, PostResponse};
+use backend::domain::service::Service;
+use backend::ids::PostId;
+use backend::server::MetricsServer;
+use common::{Method, TestApp};
+use serde_json::json;
+
+async fn scrape(app: &TestApp) -> String {
+    let resp = app.call_anonymous("/metrics", Method::Get, None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(
+        resp.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_requests_are_counted_by_matched_route_and_status() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let uri = format!("/posts/{}", PostId::new());
+    app.call_anonymous(&uri, Method::Get, None).await;
+    app.call_anonymous(&uri, Method::Get, None).await;
+    app.call_anonymous("/no/such/route", Method::Get, None)
+        .await;
+
+    // Act
+    let metrics = scrape(&app).await;
+
+    // Assert
+    assert!(metrics.contains(&format!(
+        r#"http_requests_total{{method="GET",route="{POST_PATH}",status="404"}} 2"#
+    )));
+    assert!(metrics.contains(&format!(
+        r#"http_request_duration_seconds_count{{method="GET",route="{POST_PATH}",status="404"}} 2"#
+    )));
+    assert!(
+        metrics.contains(r#"http_requests_total{method="GET",route="unmatched",status="404"} 1"#)
+    );
+    assert!(!metrics.contains("/no/such/route"));
+}
+
+#[tokio::test]
+async fn test_post_changes_are_counted() {
+    // Arrange
+    let app = TestApp::new().await;
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": "Counted", "body": "Body" })),
+        )
+        .await;
+    let post: PostResponse = app.parse_response(resp).await;
+    let resp = app
+        .call_if_match(
+            &format!("/posts/{}", post.id),
+            Method::Patch,
+            Some(json!({ "body": "Changed" })),
+            post.version,
+        )
+        .await;
+    let post: PostResponse = app.parse_response(resp).await;
+    app.call_if_match(
+        &format!("/posts/{}", post.id),
+        Method::Delete,
+        None,
+        post.version,
+    )
+    .await;
+
+    // Act
+    app.fixture().service.dispatch_outbox().await.unwrap();
+    let metrics = scrape(&app).await;
+
+    // Assert
+    assert!(metrics.contains("posts_created_total 1"));
+    assert!(metrics.contains("posts_updated_total 1"));
+    assert!(metrics.contains("posts_deleted_total 1"));
+}
+
+#[tokio::test]
+async fn test_database_pool_is_reported() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let metrics = scrape(&app).await;
+
+    // Assert
+    for gauge in [
+        "db_pool_connections ",
+        "db_pool_idle_connections ",
+        "db_pool_max_connections ",
+        "db_pool_acquire_seconds_count ",
+    ] {
+        assert!(metrics.contains(gauge), "{gauge} missing");
+    }
+    assert!(!metrics.contains("db_pool_max_connections 0"));
+}
+
+#[tokio::test]
+async fn test_metrics_can_be_served_on_a_port_of_their_own() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let server = MetricsServer::try_new(app.fixture().metrics.clone(), "0")
+        .await
+        .unwrap();
+    let url = format!("http://{}/metrics", server.local_addr().unwrap());
+    tokio::spawn(server.run());
+
+    // Act
+    let resp = reqwest::get(&url).await.unwrap();
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.text().await.unwrap().contains("posts_created_total"));
+}
@@ -0,0 +1,194 @@
+mod common;
+
+use std::time::Duration;
+
+use backend::db::postgres::{POST_CHANGES_CHANNEL, Postgres};
+use backend::domain::{
+    models::{
+        event::{PostChange, PostEvent, StreamEvent},
+        post::{CreatePostRequest, Post, PostBody, PostTitle, UpdatePostRequest},
+        user::{Role, User},
+    },
+    service::Service,
+};
+use backend::service::BlogService;
+use common::{TEST_USER_EMAIL, TestFixture};
+use futures::{StreamExt, stream::BoxStream};
+
+/// Two instances sharing one database, like replicas behind a load balancer.
+struct Replicas {
+    fixture: TestFixture,
+    other: BlogService<Postgres>,
+    user: User,
+}
+
+impl Replicas {
+    async fn new() -> Self {
+        let fixture = TestFixture::new().await;
+        let user = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+        let other = BlogService::new(
+            Postgres::try_new(&fixture.database_url)
+                .await
+                .expect("Failed to initialize repository."),
+        );
+
+        Self {
+            fixture,
+            other,
+            user,
+        }
+    }
+
+    fn this(&self) -> &BlogService<Postgres> {
+        &self.fixture.service
+    }
+
+    /// Relays the changes of both instances to the events of this one, once
+    /// they are being listened for.
+    async fn relay(&self) -> BoxStream<'static, PostEvent> {
+        tokio::spawn(self.this().clone().relay_post_changes().await);
+
+        self.this()
+            .subscribe_post_events(None)
+            .filter_map(|event| async move {
+                match event {
+                    StreamEvent::Post(event) => Some(event),
+                    StreamEvent::Reset(_) => None,
+                }
+            })
+            .boxed()
+    }
+}
+
+async fn create_post(service: &BlogService<Postgres>, user: &User, title: &str) -> Post {
+    service
+        .create_post(
+            user,
+            &CreatePostRequest::new(PostTitle::new(title), PostBody::new("Body")),
+        )
+        .await
+        .expect("Failed to create post.")
+}
+
+async fn next_event(
+    events: &mut BoxStream<'static, PostEvent>,
+    timeout: Duration,
+) -> Option<PostEvent> {
+    tokio::time::timeout(timeout, events.next())
+        .await
+        .ok()
+        .flatten()
+}
+
+#[tokio::test]
+async fn test_changes_of_other_instances_reach_event_streams() {
+    // Arrange
+    let replicas = Replicas::new().await;
+    let mut events = replicas.relay().await;
+
+    let timeout = Duration::from_secs(5);
+
+    // Act
+    let post = create_post(&replicas.other, &replicas.user, "Elsewhere").await;
+    let created = next_event(&mut events, timeout).await.unwrap();
+    let update = UpdatePostRequest::new(None, Some(PostBody::new("Changed")));
+    let updated = replicas
+        .other
+        .update_post(&replicas.user, post.id(), post.version(), &update)
+        .await
+        .unwrap();
+    let changed = next_event(&mut events, timeout).await.unwrap();
+    replicas
+        .other
+        .delete_post(&replicas.user, post.id(), updated.version())
+        .await
+        .unwrap();
+    let deleted = next_event(&mut events, timeout).await.unwrap();
+
+    // Assert
+    assert_eq!(created.change(), &PostChange::Created(post.clone()));
+    assert_eq!(changed.change(), &PostChange::Updated(updated));
+    assert_eq!(deleted.change(), &PostChange::Deleted(post.id()));
+    assert!(created.id() < changed.id() && changed.id() < deleted.id());
+}
+
+#[tokio::test]
+async fn test_own_changes_are_published_once() {
+    // Arrange
+    let replicas = Replicas::new().await;
+    let mut events = replicas.relay().await;
+
+    // Act
+    let post = create_post(replicas.this(), &replicas.user, "Here").await;
+
+    // Assert
+    let created = next_event(&mut events, Duration::from_secs(5)).await;
+    assert_eq!(created.unwrap().change(), &PostChange::Created(post));
+    assert!(
+        next_event(&mut events, Duration::from_secs(1))
+            .await
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_listener_reconnects_after_losing_its_connection() {
+    // Arrange
+    let replicas = Replicas::new().await;
+    let mut events = replicas.relay().await;
+
+    // Act
+    let terminated: Vec<bool> = sqlx::query_scalar(
+        r#"
+            SELECT pg_terminate_backend(pid) FROM pg_stat_activity
+            WHERE datname = current_database() AND query = $1
+        "#,
+    )
+    .bind(format!(r#"LISTEN "{POST_CHANGES_CHANNEL}""#))
+    .fetch_all(&replicas.fixture.pool)
+    .await
+    .unwrap();
+
+    // Assert
+    assert!(!terminated.is_empty());
+    let mut received = None;
+    for attempt in 0..50 {
+        create_post(&replicas.other, &replicas.user, &format!("After {attempt}")).await;
+        received = next_event(&mut events, Duration::from_millis(500)).await;
+        if received.is_some() {
+            break;
+        }
+    }
+    assert!(
+        received.is_some(),
+        "No changes were relayed after reconnecting."
+    );
+}
+
+#[tokio::test]
+async fn test_changes_made_while_reconnecting_are_caught_up() {
+    // Arrange
+    let replicas = Replicas::new().await;
+    let mut events = replicas.relay().await;
+
+    // Act
+    sqlx::query(
+        r#"
+            SELECT pg_terminate_backend(pid) FROM pg_stat_activity
+            WHERE datname = current_database() AND query = $1
+        "#,
+    )
+    .bind(format!(r#"LISTEN "{POST_CHANGES_CHANNEL}""#))
+    .execute(&replicas.fixture.pool)
+    .await
+    .unwrap();
+    // Announced while nothing listens, so only the change feed has it.
+    let post = create_post(&replicas.other, &replicas.user, "Meanwhile").await;
+
+    // Assert
+    let received = next_event(&mut events, Duration::from_secs(10)).await;
+    assert_eq!(
+        received.map(|event| event.change().post_id()),
+        Some(post.id())
+    );
+}
@@ -0,0 +1,349 @@
+mod common;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Form, Json, Router,
+    body::Body,
+    extract::State,
+    http::{Response, StatusCode, header},
+    routing::{get, post},
+};
+use backend::{api::auth::UserResponse, domain::models::user::Role, oidc::OidcConfig};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use common::{Method, TestApp, session_cookie};
+use jsonwebtoken::{
+    Algorithm, EncodingKey, Header, encode,
+    jwk::{Jwk, JwkSet},
+};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+const CLIENT_ID: &str = "blog";
+const KEY_ID: &str = "test-key";
+
+/// Code handed out by the mock provider, waiting to be exchanged.
+struct PendingCode {
+    code_challenge: String,
+    claims: Value,
+}
+
+struct IdpState {
+    issuer: String,
+    key: EncodingKey,
+    jwk: Jwk,
+    codes: Mutex<HashMap<String, PendingCode>>,
+}
+
+/// A minimal OpenID provider serving discovery, JWKS and the token endpoint.
+/// Tests play the part of the browser at the authorization endpoint by
+/// registering a code with [`MockIdp::issue_code`].
+struct MockIdp {
+    state: Arc<IdpState>,
+}
+
+impl MockIdp {
+    async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock identity provider.");
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let key = EncodingKey::from_rsa_pem(include_bytes!("fixtures/oidc_signing_key.pem"))
+            .expect("Failed to load signing key.");
+        let mut jwk = Jwk::from_encoding_key(&key, Algorithm::RS256).unwrap();
+        jwk.common.key_id = Some(KEY_ID.to_string());
+
+        let state = Arc::new(IdpState {
+            issuer,
+            key,
+            jwk,
+            codes: Mutex::new(HashMap::new()),
+        });
+        let router = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(state.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Self { state }
+    }
+
+    fn config(&self) -> OidcConfig {
+        OidcConfig {
+            issuer: self.state.issuer.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: None,
+            redirect_url: Url::parse("http://localhost/auth/oidc/callback").unwrap(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+            default_role: Role::Reader,
+            post_login_redirect: "/".to_string(),
+        }
+    }
+
+    /// Approves the login at `authorization_url` for `subject`, returning the
+    /// code to send back to the blog. `overrides` replace claims of the ID
+    /// token.
+    fn issue_code(
+        &self,
+        authorization_url: &Url,
+        subject: &str,
+        email: &str,
+        overrides: Value,
+    ) -> String {
+        let params: HashMap<_, _> = authorization_url.query_pairs().into_owned().collect();
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        let now = Utc::now().timestamp();
+        let mut claims = json!({
+            "iss": self.state.issuer,
+            "sub": subject,
+            "aud": CLIENT_ID,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": params["nonce"],
+            "email": email,
+            "email_verified": true,
+        });
+        for (name, value) in overrides.as_object().unwrap() {
+            claims[name] = value.clone();
+        }
+
+        let code = uuid::Uuid::new_v4().to_string();
+        self.state.codes.lock().unwrap().insert(
+            code.clone(),
+            PendingCode {
+                code_challenge: params["code_challenge"].clone(),
+                claims,
+            },
+        );
+        code
+    }
+}
+
+async fn discovery(State(state): State<Arc<IdpState>>) -> Json<Value> {
+    Json(json!({
+        "issuer": state.issuer,
+        "authorization_endpoint": format!("{}/authorize", state.issuer),
+        "token_endpoint": format!("{}/token", state.issuer),
+        "jwks_uri": format!("{}/jwks", state.issuer),
+    }))
+}
+
+async fn jwks(State(state): State<Arc<IdpState>>) -> Json<JwkSet> {
+    Json(JwkSet {
+        keys: vec![state.jwk.clone()],
+    })
+}
+
+async fn token(
+    State(state): State<Arc<IdpState>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<Json<Value>, StatusCode> {
+    let pending = state
+        .codes
+        .lock()
+        .unwrap()
+        .remove(&form["code"])
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(form["code_verifier"].as_bytes()));
+    if challenge != pending.code_challenge {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(KEY_ID.to_string());
+    let id_token = encode(&header, &pending.claims, &state.key).unwrap();
+
+    Ok(Json(
+        json!({ "id_token": id_token, "token_type": "Bearer" }),
+    ))
+}
+
+/// Starts a login, returning the authorization URL and the state cookie.
+async fn start_login(app: &TestApp) -> (Url, String) {
+    let resp = app
+        .call_anonymous("/auth/oidc/login", Method::Get, None)
+        .await;
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+
+    let location = resp.headers()[header::LOCATION].to_str().unwrap();
+    let url = Url::parse(location).unwrap();
+    let cookie = session_cookie(&resp).expect("Login did not set the state cookie.");
+    (url, cookie)
+}
+
+async fn callback(app: &TestApp, code: &str, url: &Url, cookie: Option<&str>) -> Response<Body> {
+    let state = url
+        .query_pairs()
+        .find(|(name, _)| name == "state")
+        .map(|(_, value)| value.into_owned())
+        .unwrap();
+    let uri = format!("/auth/oidc/callback?code={code}&state={state}");
+    app.call_with_cookie(&uri, Method::Get, None, cookie).await
+}
+
+/// Returns the `name=value` pair of the cookie called `name` set by a response.
+fn cookie_named(resp: &Response<Body>, name: &str) -> Option<String> {
+    resp.headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok()?.split(';').next())
+        .find(|pair| pair.starts_with(&format!("{name}=")) && !pair.ends_with('='))
+        .map(str::to_string)
+}
+
+async fn me(app: &TestApp, session: &str) -> UserResponse {
+    let resp = app
+        .call_with_cookie("/auth/me", Method::Get, None, Some(session))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response(resp).await
+}
+
+#[tokio::test]
+async fn test_oidc_login_provisions_user_with_default_role() {
+    // Arrange
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_oidc(Some(idp.config())).await;
+
+    // Act
+    let (url, cookie) = start_login(&app).await;
+    let code = idp.issue_code(&url, "alice-1", "alice@example.com", json!({}));
+    let first = callback(&app, &code, &url, Some(&cookie)).await;
+
+    let (url, cookie) = start_login(&app).await;
+    let code = idp.issue_code(&url, "alice-1", "alice@example.com", json!({}));
+    let second = callback(&app, &code, &url, Some(&cookie)).await;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::SEE_OTHER);
+    assert_eq!(first.headers()[header::LOCATION], "/");
+
+    let first_user = me(&app, &cookie_named(&first, "blog_session").unwrap()).await;
+    assert_eq!(first_user.email, "alice@example.com");
+    assert_eq!(first_user.role, "reader");
+
+    let second_user = me(&app, &cookie_named(&second, "blog_session").unwrap()).await;
+    assert_eq!(second_user.id, first_user.id);
+}
+
+#[tokio::test]
+async fn test_oidc_login_links_existing_user_by_verified_email() {
+    // Arrange
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_oidc(Some(idp.config())).await;
+    let existing = app
+        .fixture()
+        .create_user("editor@example.com", Role::Editor)
+        .await;
+
+    // Act
+    let (url, cookie) = start_login(&app).await;
+    let code = idp.issue_code(&url, "editor-1", "editor@example.com", json!({}));
+    let resp = callback(&app, &code, &url, Some(&cookie)).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    let user = me(&app, &cookie_named(&resp, "blog_session").unwrap()).await;
+    assert_eq!(user.id, existing.id());
+    assert_eq!(user.role, "editor");
+}
+
+#[tokio::test]
+async fn test_oidc_callback_requires_state_cookie_of_same_browser() {
+    // Arrange
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_oidc(Some(idp.config())).await;
+    let (url, _) = start_login(&app).await;
+    let (_, other_cookie) = start_login(&app).await;
+    let code = idp.issue_code(&url, "bob-1", "bob@example.com", json!({}));
+
+    // Act
+    let missing = callback(&app, &code, &url, None).await;
+    let mismatched = callback(&app, &code, &url, Some(&other_cookie)).await;
+
+    // Assert
+    assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(mismatched.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_oidc_callback_cannot_be_replayed() {
+    // Arrange
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_oidc(Some(idp.config())).await;
+    let (url, cookie) = start_login(&app).await;
+    let code = idp.issue_code(&url, "carol-1", "carol@example.com", json!({}));
+    let first = callback(&app, &code, &url, Some(&cookie)).await;
+
+    // Act
+    let replay = callback(&app, &code, &url, Some(&cookie)).await;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::SEE_OTHER);
+    assert_eq!(replay.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_oidc_callback_rejects_nonce_mismatch() {
+    // Arrange
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_oidc(Some(idp.config())).await;
+    let (url, cookie) = start_login(&app).await;
+    let code = idp.issue_code(
+        &url,
+        "dave-1",
+        "dave@example.com",
+        json!({ "nonce": "replayed" }),
+    );
+
+    // Act
+    let resp = callback(&app, &code, &url, Some(&cookie)).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert!(cookie_named(&resp, "blog_session").is_none());
+}
+
+#[tokio::test]
+async fn test_oidc_callback_rejects_unverified_email() {
+    // Arrange
+    let idp = MockIdp::start().await;
+    let app = TestApp::with_oidc(Some(idp.config())).await;
+    let (url, cookie) = start_login(&app).await;
+    let code = idp.issue_code(
+        &url,
+        "eve-1",
+        "tommy@example.com",
+        json!({ "email_verified": false }),
+    );
+
+    // Act
+    let resp = callback(&app, &code, &url, Some(&cookie)).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_oidc_login_is_not_found_without_provider() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let resp = app
+        .call_anonymous("/auth/oidc/login", Method::Get, None)
+        .await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
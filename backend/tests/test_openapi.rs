@@ -0,0 +1,90 @@
+mod common;
+
+use axum::{
+    body::to_bytes,
+    http::{StatusCode, header},
+};
+use backend::api::openapi::{DOCS_PATH, OPENAPI_PATH};
+use common::{Method, TestApp};
+use serde_json::Value;
+
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/openapi.json");
+
+/// Set to rewrite the snapshot after a deliberate change to the API.
+const UPDATE_SNAPSHOT: &str = "UPDATE_OPENAPI_SNAPSHOT";
+
+#[tokio::test]
+async fn test_openapi_document_matches_snapshot() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let response = app.call(OPENAPI_PATH, Method::Get, None).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let document: Value = app.parse_response(response).await;
+    assert_eq!(document["openapi"], "3.1.0");
+
+    if std::env::var_os(UPDATE_SNAPSHOT).is_some() {
+        let pretty = serde_json::to_string_pretty(&document).unwrap();
+        std::fs::write(SNAPSHOT, pretty + "\n").unwrap();
+        return;
+    }
+    let snapshot: Value =
+        serde_json::from_str(&std::fs::read_to_string(SNAPSHOT).unwrap()).unwrap();
+    assert_eq!(
+        document, snapshot,
+        "The OpenAPI document drifted from tests/fixtures/openapi.json; \
+         rerun with {UPDATE_SNAPSHOT}=1 and commit the result if the change is intended"
+    );
+}
+
+#[tokio::test]
+async fn test_openapi_documents_every_error_as_problem_details() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let response = app.call(OPENAPI_PATH, Method::Get, None).await;
+
+    // Assert
+    let document: Value = app.parse_response(response).await;
+    let operations = document["paths"]
+        .as_object()
+        .unwrap()
+        .values()
+        .flat_map(|path| path.as_object().unwrap().values());
+    for operation in operations {
+        for (status, response) in operation["responses"].as_object().unwrap() {
+            if status.starts_with('4') || status.starts_with('5') {
+                assert_eq!(
+                    response["content"]["application/problem+json"]["schema"]["$ref"],
+                    "#/components/schemas/ProblemDetails",
+                    "{} {status}",
+                    operation["operationId"]
+                );
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_swagger_ui_is_served() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+
+    // Act
+    let response = app.call(&format!("{DOCS_PATH}/"), Method::Get, None).await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html")
+    );
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("swagger"));
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use backend::db::postgres::Postgres;
+use backend::domain::{
+    models::{
+        event::PostChangeKind,
+        outbox::{DomainEvent, OutboxEvent},
+        post::{CreatePostRequest, Post, PostBody, PostTitle, UpdatePostRequest},
+        user::{Role, User},
+        webhook::{Webhook, WebhookSecret, WebhookUrl},
+    },
+    repository::Repository,
+    service::{Service, ServiceError},
+};
+use backend::ids::WebhookId;
+use chrono::{Duration, Utc};
+use common::{TEST_USER_EMAIL, TestFixture};
+
+struct Outbox {
+    fixture: TestFixture,
+    repo: Postgres,
+    user: User,
+}
+
+impl Outbox {
+    async fn new() -> Self {
+        let fixture = TestFixture::new().await;
+        let user = fixture.create_user(TEST_USER_EMAIL, Role::Admin).await;
+        let repo = Postgres::try_new(&fixture.database_url)
+            .await
+            .expect("Failed to initialize repository.");
+
+        Self {
+            fixture,
+            repo,
+            user,
+        }
+    }
+
+    async fn create_post(&self, title: &str) -> Post {
+        self.fixture
+            .service
+            .create_post(
+                &self.user,
+                &CreatePostRequest::new(PostTitle::new(title), PostBody::new("Body")),
+            )
+            .await
+            .expect("Failed to create post.")
+    }
+
+    async fn update_post(&self, post: &Post, body: &str) -> Result<Post, ServiceError> {
+        self.fixture
+            .service
+            .update_post(
+                &self.user,
+                post.id(),
+                post.version(),
+                &UpdatePostRequest::new(None, Some(PostBody::new(body))),
+            )
+            .await
+    }
+
+    /// Claims what is due, leased for a minute.
+    async fn claim(&self) -> Vec<OutboxEvent> {
+        self.repo
+            .claim_outbox_events(100, Utc::now() + Duration::minutes(1))
+            .await
+            .unwrap()
+    }
+
+    async fn complete(&self, events: &[OutboxEvent]) {
+        for event in events {
+            self.repo.complete_outbox_event(event).await.unwrap();
+        }
+    }
+}
+
+fn kinds(events: &[OutboxEvent]) -> Vec<PostChangeKind> {
+    events.iter().map(|event| event.event().kind()).collect()
+}
+
+#[tokio::test]
+async fn test_events_of_a_post_are_claimed_one_at_a_time_in_order() {
+    // Arrange
+    let outbox = Outbox::new().await;
+    let post = outbox.create_post("Ordered").await;
+    let updated = outbox.update_post(&post, "Changed").await.unwrap();
+    outbox
+        .fixture
+        .service
+        .delete_post(&outbox.user, post.id(), updated.version())
+        .await
+        .unwrap();
+
+    // Act
+    let mut claimed = Vec::new();
+    loop {
+        let events = outbox.claim().await;
+        if events.is_empty() {
+            break;
+        }
+        assert_eq!(events.len(), 1);
+        outbox.complete(&events).await;
+        claimed.extend(events);
+    }
+
+    // Assert
+    assert_eq!(
+        kinds(&claimed),
+        vec![
+            PostChangeKind::Created,
+            PostChangeKind::Updated,
+            PostChangeKind::Deleted
+        ]
+    );
+    assert!(
+        claimed
+            .iter()
+            .all(|event| event.event().aggregate_id() == post.id())
+    );
+    assert!(matches!(
+        claimed[1].event(),
+        DomainEvent::PostUpdated { post, .. } if post.body().to_string() == "Changed"
+    ));
+}
+
+#[tokio::test]
+async fn test_events_of_different_posts_are_claimed_together() {
+    // Arrange
+    let outbox = Outbox::new().await;
+    let first = outbox.create_post("First").await;
+    let second = outbox.create_post("Second").await;
+
+    // Act
+    let events = outbox.claim().await;
+
+    // Assert
+    let aggregates: Vec<_> = events
+        .iter()
+        .map(|event| event.event().aggregate_id())
+        .collect();
+    assert_eq!(aggregates, vec![first.id(), second.id()]);
+}
+
+#[tokio::test]
+async fn test_failed_changes_leave_no_events() {
+    // Arrange
+    let outbox = Outbox::new().await;
+    let post = outbox.create_post("Contested").await;
+    outbox.complete(&outbox.claim().await).await;
+    outbox.update_post(&post, "First").await.unwrap();
+    outbox.complete(&outbox.claim().await).await;
+
+    // Act
+    let stale = outbox.update_post(&post, "Second").await;
+
+    // Assert
+    assert!(stale.is_err());
+    assert!(outbox.claim().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_claimed_events_are_claimed_again_once_their_lease_runs_out() {
+    // Arrange
+    let outbox = Outbox::new().await;
+    outbox.create_post("Leased").await;
+    let expired = outbox
+        .repo
+        .claim_outbox_events(100, Utc::now() - Duration::seconds(1))
+        .await
+        .unwrap();
+
+    // Act
+    let reclaimed = outbox.claim().await;
+    let leased = outbox.claim().await;
+
+    // Assert
+    assert_eq!(expired.len(), 1);
+    assert_eq!(reclaimed, expired);
+    assert!(leased.is_empty());
+}
+
+#[tokio::test]
+async fn test_dispatch_relays_every_event() {
+    // Arrange
+    let outbox = Outbox::new().await;
+    let post = outbox.create_post("Relayed").await;
+    outbox.update_post(&post, "Changed").await.unwrap();
+    outbox.create_post("Another").await;
+
+    // Act
+    let relayed = outbox.fixture.service.dispatch_outbox().await.unwrap();
+
+    // Assert
+    assert_eq!(relayed, 3);
+    assert!(outbox.claim().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_events_completed_twice_queue_their_deliveries_once() {
+    // Arrange
+    let outbox = Outbox::new().await;
+    let webhook = Webhook::new(
+        WebhookId::new(),
+        WebhookUrl::try_new("https://example.com/hooks").unwrap(),
+        BTreeSet::from([PostChangeKind::Created]),
+        None,
+        Utc::now(),
+    );
+    outbox
+        .repo
+        .create_webhook(&webhook, &WebhookSecret::generate())
+        .await
+        .unwrap();
+    outbox.create_post("Relayed twice").await;
+    let events = outbox.claim().await;
+
+    // Act
+    outbox.complete(&events).await;
+    outbox.complete(&events).await;
+
+    // Assert
+    let deliveries = outbox
+        .repo
+        .get_webhook_deliveries(webhook.id())
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+}
@@ -1,26 +1,28 @@
 mod common;
 
-use axum::http::StatusCode;
+use axum::http::{StatusCode, header};
 use backend::api::post::{
     BulkPostResponse, CreatePostRequest as CreatePostRequestDTO, PostResponse, UpdatePostRequest,
 };
-use backend::api::responses::ApiErrorData;
+use backend::api::responses::ProblemDetails;
 use backend::domain::models::post::{CreatePostRequest, PostBody, PostTitle};
+use backend::domain::models::user::Role;
 use backend::domain::repository::{CreatePostError, RepositoryError};
 use backend::domain::service::{Service, ServiceError};
-use common::{Method, TestApp, TestFixture};
+use common::{Method, TEST_USER_EMAIL, TestApp, TestFixture};
 use serde_json::json;
 
 #[tokio::test]
 async fn test_service_create_post_works() {
     // Arrange
     let fixture = TestFixture::new().await;
+    let author = fixture.create_user(TEST_USER_EMAIL, Role::Author).await;
     let title = PostTitle::new("Test title");
     let body = PostBody::new("Test body");
     let create_req = CreatePostRequest::new(title.clone(), body.clone());
 
     // Act
-    let res = fixture.service.create_post(&create_req).await;
+    let res = fixture.service.create_post(&author, &create_req).await;
 
     // Assert
     assert!(res.is_ok());
@@ -28,20 +30,22 @@ async fn test_service_create_post_works() {
     let data = res.unwrap();
     assert_eq!(title, data.title());
     assert_eq!(body, data.body());
+    assert_eq!(Some(author.id()), data.created_by());
 }
 
 #[tokio::test]
 async fn test_service_create_post_with_duplicate_title_triggers_error() {
     // Arrange
     let fixture = TestFixture::new().await;
+    let author = fixture.create_user(TEST_USER_EMAIL, Role::Author).await;
     let post_title = PostTitle::new("Duplicate Title Test");
     let post_body = PostBody::new("This is a test post body");
     let create_req = CreatePostRequest::new(post_title, post_body);
 
     // Act
-    let first_result = fixture.service.create_post(&create_req).await;
+    let first_result = fixture.service.create_post(&author, &create_req).await;
 
-    let duplicate_result = fixture.service.create_post(&create_req).await;
+    let duplicate_result = fixture.service.create_post(&author, &create_req).await;
 
     // Assert
     assert!(first_result.is_ok());
@@ -64,6 +68,7 @@ async fn test_create_post_endpoint() {
     let body = CreatePostRequestDTO {
         title: "My Test Post".to_string(),
         body: "This is a test post body".to_string(),
+        authors: None,
     };
     let body_value = json!(body);
 
@@ -88,6 +93,7 @@ async fn test_create_post_endpoint_duplicate_title() {
     let body = CreatePostRequestDTO {
         title: "Duplicate Title".to_string(),
         body: "This is a test post body".to_string(),
+        authors: None,
     };
 
     let body_value = json!(body);
@@ -104,10 +110,57 @@ async fn test_create_post_endpoint_duplicate_title() {
 
     // Assert - Check status code for conflict
     assert_eq!(duplicate_response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        duplicate_response.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+
+    let problem: ProblemDetails = app.parse_response(duplicate_response).await;
+
+    assert_eq!(problem.code, "post_duplicate");
+    assert_eq!(problem.problem_type, "urn:problem-type:post_duplicate");
+    assert_eq!(problem.status, 409);
+    assert_eq!(problem.title, "Conflict");
+    assert!(problem.detail.contains("Duplicate Title"));
+    assert_eq!(problem.instance.as_deref(), Some("/posts"));
+    assert!(problem.request_id.is_some());
+}
 
-    let error_data: ApiErrorData = app.parse_response(duplicate_response).await;
+#[tokio::test]
+async fn test_rejected_requests_are_problems() {
+    // Arrange
+    let app = TestApp::new().await;
 
-    assert!(error_data.message.contains("Duplicate Title"));
+    // Act
+    let empty_body = app.call("/posts", Method::Post, None).await;
+    let bad_path = app.call("/posts/not-a-uuid", Method::Get, None).await;
+    let no_route = app.call("/nowhere", Method::Get, None).await;
+    let wrong_method = app.call("/posts", Method::Patch, None).await;
+
+    // Assert
+    for (resp, status, code) in [
+        (empty_body, StatusCode::BAD_REQUEST, "malformed_request"),
+        (bad_path, StatusCode::BAD_REQUEST, "malformed_request"),
+        (no_route, StatusCode::NOT_FOUND, "route_not_found"),
+        (
+            wrong_method,
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+        ),
+    ] {
+        assert_eq!(resp.status(), status);
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+        if status == StatusCode::METHOD_NOT_ALLOWED {
+            assert!(resp.headers().contains_key(header::ALLOW));
+        }
+        let problem: ProblemDetails = app.parse_response(resp).await;
+        assert_eq!(problem.code, code);
+        assert!(!problem.detail.is_empty());
+        assert!(problem.instance.is_some());
+    }
 }
 
 #[tokio::test]
@@ -118,6 +171,7 @@ async fn test_get_posts_endpoint() {
     let body = CreatePostRequestDTO {
         title: "Title".to_string(),
         body: "Body".to_string(),
+        authors: None,
     };
 
     let body_value = json!(body);
@@ -140,6 +194,40 @@ async fn test_get_posts_endpoint() {
     assert_eq!(post.body, body.body);
 }
 
+#[tokio::test]
+async fn test_get_posts_pages_follow_the_cursor() {
+    // Arrange
+    let app = TestApp::new().await;
+    for title in ["One", "Two", "Three"] {
+        let body = json!({ "title": title, "body": "Body" });
+        app.call("/posts", Method::Post, Some(body)).await;
+    }
+
+    // Act
+    let first: BulkPostResponse = app
+        .parse_response(app.call("/posts?limit=2", Method::Get, None).await)
+        .await;
+    let cursor = first.next_cursor.clone().expect("A second page follows.");
+    let second: BulkPostResponse = app
+        .parse_response(
+            app.call(&format!("/posts?limit=2&after={cursor}"), Method::Get, None)
+                .await,
+        )
+        .await;
+    let malformed = app.call("/posts?after=nonsense", Method::Get, None).await;
+
+    // Assert
+    let titles: Vec<_> = first
+        .data
+        .iter()
+        .chain(&second.data)
+        .map(|post| post.title.as_str())
+        .collect();
+    assert_eq!(titles, ["One", "Two", "Three"]);
+    assert_eq!(second.next_cursor, None);
+    assert_eq!(malformed.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
 #[tokio::test]
 async fn test_get_post_by_id_endpoint() {
     // Arrange
@@ -148,6 +236,7 @@ async fn test_get_post_by_id_endpoint() {
     let body = CreatePostRequestDTO {
         title: "Title".to_string(),
         body: "Body".to_string(),
+        authors: None,
     };
 
     let body_value = json!(body);
@@ -176,6 +265,7 @@ async fn test_patch_post_endpoint() {
     let body = CreatePostRequestDTO {
         title: "Title".to_string(),
         body: "Body".to_string(),
+        authors: None,
     };
     let body_value = json!(body);
 
@@ -189,15 +279,17 @@ async fn test_patch_post_endpoint() {
     let patch = UpdatePostRequest {
         title: Some("New title".to_string()),
         body: None,
+        authors: None,
     };
 
     let patch_value = json!(patch);
 
     let resp = app
-        .call(
+        .call_if_match(
             format!("/posts/{id}").as_str(),
             Method::Patch,
             Some(patch_value),
+            post.version,
         )
         .await;
 
@@ -211,15 +303,17 @@ async fn test_patch_post_endpoint() {
     let patch_to_fail = UpdatePostRequest {
         title: Some("New title".to_string()),
         body: None,
+        authors: None,
     };
 
     let patch_value_to_fail = json!(patch_to_fail);
 
     let resp = app
-        .call(
+        .call_if_match(
             format!("/posts/{id}").as_str(),
             Method::Patch,
             Some(patch_value_to_fail),
+            post.version,
         )
         .await;
 
@@ -234,6 +328,7 @@ async fn test_delete_post_endpoint() {
     let body = CreatePostRequestDTO {
         title: "Title".to_string(),
         body: "Body".to_string(),
+        authors: None,
     };
 
     let body_value = json!(body);
@@ -245,7 +340,12 @@ async fn test_delete_post_endpoint() {
 
     // Act
     let resp = app
-        .call(&format!("/posts/{}", id), Method::Delete, None)
+        .call_if_match(
+            &format!("/posts/{}", id),
+            Method::Delete,
+            None,
+            post.version,
+        )
         .await;
 
     // Assert
@@ -257,3 +357,193 @@ async fn test_delete_post_endpoint() {
 
     assert!(posts.data.is_empty());
 }
+
+#[tokio::test]
+async fn test_post_is_served_with_etag() {
+    // Arrange
+    let app = TestApp::new().await;
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": "Title", "body": "Body" })),
+        )
+        .await;
+    let created_etag = resp.headers()[header::ETAG].clone();
+    let post: PostResponse = app.parse_response(resp).await;
+    let uri = format!("/posts/{}", post.id);
+
+    // Act
+    let get = app.call(&uri, Method::Get, None).await;
+    let patch = app
+        .call_if_match(
+            &uri,
+            Method::Patch,
+            Some(json!({ "body": "Edited" })),
+            post.version,
+        )
+        .await;
+
+    // Assert
+    assert!(created_etag.to_str().unwrap().starts_with("\"1-"));
+    assert_eq!(get.headers()[header::ETAG], created_etag);
+    assert_eq!(patch.status(), StatusCode::OK);
+    assert!(
+        patch.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .starts_with("\"2-")
+    );
+    let patched: PostResponse = app.parse_response(patch).await;
+    assert_eq!(patched.version, 2);
+}
+
+#[tokio::test]
+async fn test_changes_without_if_match_are_rejected() {
+    // Arrange
+    let app = TestApp::new().await;
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": "Title", "body": "Body" })),
+        )
+        .await;
+    let post: PostResponse = app.parse_response(resp).await;
+    let uri = format!("/posts/{}", post.id);
+
+    // Act
+    let patch = app
+        .call(&uri, Method::Patch, Some(json!({ "body": "Edited" })))
+        .await;
+    let delete = app.call(&uri, Method::Delete, None).await;
+
+    // Assert
+    assert_eq!(patch.status(), StatusCode::PRECONDITION_REQUIRED);
+    assert_eq!(delete.status(), StatusCode::PRECONDITION_REQUIRED);
+    let request_id = patch.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let problem: ProblemDetails = app.parse_response(patch).await;
+    assert_eq!(problem.code, "if_match_required");
+    assert_eq!(problem.instance, Some(uri));
+    assert_eq!(problem.request_id, Some(request_id));
+}
+
+#[tokio::test]
+async fn test_stale_changes_are_rejected() {
+    // Arrange
+    let app = TestApp::new().await;
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": "Title", "body": "Body" })),
+        )
+        .await;
+    let post: PostResponse = app.parse_response(resp).await;
+    let uri = format!("/posts/{}", post.id);
+    // Two editors read the post; the first one saves.
+    app.call_if_match(
+        &uri,
+        Method::Patch,
+        Some(json!({ "body": "First edit" })),
+        post.version,
+    )
+    .await;
+
+    // Act
+    let stale_patch = app
+        .call_if_match(
+            &uri,
+            Method::Patch,
+            Some(json!({ "body": "Second edit" })),
+            post.version,
+        )
+        .await;
+    let stale_delete = app
+        .call_if_match(&uri, Method::Delete, None, post.version)
+        .await;
+    let get = app.call(&uri, Method::Get, None).await;
+
+    // Assert
+    assert_eq!(stale_patch.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(stale_delete.status(), StatusCode::PRECONDITION_FAILED);
+    let problem: ProblemDetails = app.parse_response(stale_patch).await;
+    assert_eq!(problem.code, "version_conflict");
+    let current: PostResponse = app.parse_response(get).await;
+    assert_eq!(current.body, "First edit");
+    assert_eq!(current.version, 2);
+}
+
+#[tokio::test]
+async fn test_invalid_fields_are_reported_together() {
+    // Arrange
+    let app = TestApp::new().await;
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": "Valid", "body": "Body" })),
+        )
+        .await;
+    let post: PostResponse = app.parse_response(resp).await;
+
+    // Act
+    let create = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": "  ", "body": "Ring the bell\u{7}" })),
+        )
+        .await;
+    let update = app
+        .call_if_match(
+            &format!("/posts/{}", post.id),
+            Method::Patch,
+            Some(json!({ "title": "x".repeat(201) })),
+            post.version,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(create.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let problem: ProblemDetails = app.parse_response(create).await;
+    assert_eq!(problem.code, "validation_failed");
+    let errors: Vec<_> = problem
+        .errors
+        .iter()
+        .map(|error| (error.pointer.as_str(), error.code.as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        [("/title", "empty"), ("/body", "control_character")]
+    );
+
+    assert_eq!(update.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let problem: ProblemDetails = app.parse_response(update).await;
+    assert_eq!(problem.errors.len(), 1);
+    assert_eq!(problem.errors[0].pointer, "/title");
+    assert_eq!(problem.errors[0].code, "too_long");
+}
+
+#[tokio::test]
+async fn test_post_text_is_normalized() {
+    // Arrange
+    let app = TestApp::new().await;
+
+    // Act
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": " Cafe\u{301} ", "body": "Body" })),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let post: PostResponse = app.parse_response(resp).await;
+    assert_eq!(post.title, "Caf\u{e9}");
+}
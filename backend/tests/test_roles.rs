@@ -0,0 +1,137 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use backend::api::post::{CreatePostRequest as CreatePostRequestDTO, PostResponse};
+use backend::domain::models::user::Role;
+use common::{Method, TestApp, if_match};
+use serde_json::{Value, json};
+
+fn post_body(title: &str) -> Value {
+    json!(CreatePostRequestDTO {
+        title: title.to_string(),
+        body: "Body".to_string(),
+        authors: None,
+    })
+}
+
+async fn create_post(app: &TestApp, cookie: &str, title: &str) -> PostResponse {
+    let resp = app
+        .call_with_cookie("/posts", Method::Post, Some(post_body(title)), Some(cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+#[tokio::test]
+async fn test_authors_can_only_edit_their_own_posts() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (alice, alice_cookie) = app.login_as("alice@example.com", Role::Author).await;
+    let (_, bob_cookie) = app.login_as("bob@example.com", Role::Author).await;
+    let post = create_post(&app, &alice_cookie, "Alice's post").await;
+    let uri = format!("/posts/{}", post.id);
+    let edit = json!({ "body": "Edited" });
+
+    // Act
+    let bob_update = app
+        .call_with_headers(
+            &uri,
+            Method::Patch,
+            Some(edit.clone()),
+            [(header::COOKIE, bob_cookie.clone()), if_match(post.version)],
+        )
+        .await;
+    let bob_delete = app
+        .call_with_headers(
+            &uri,
+            Method::Delete,
+            None,
+            [(header::COOKIE, bob_cookie), if_match(post.version)],
+        )
+        .await;
+    let alice_update = app
+        .call_with_headers(
+            &uri,
+            Method::Patch,
+            Some(edit),
+            [
+                (header::COOKIE, alice_cookie.clone()),
+                if_match(post.version),
+            ],
+        )
+        .await;
+
+    // Assert
+    assert_eq!(post.created_by, Some(alice.id()));
+    assert_eq!(bob_update.status(), StatusCode::FORBIDDEN);
+    assert_eq!(bob_delete.status(), StatusCode::FORBIDDEN);
+    assert_eq!(alice_update.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_editors_can_edit_any_post() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, author_cookie) = app.login_as("alice@example.com", Role::Author).await;
+    let (_, editor_cookie) = app.login_as("eve@example.com", Role::Editor).await;
+    let post = create_post(&app, &author_cookie, "Alice's post").await;
+    let uri = format!("/posts/{}", post.id);
+
+    // Act
+    let update = app
+        .call_with_headers(
+            &uri,
+            Method::Patch,
+            Some(json!({ "title": "Copy edited" })),
+            [
+                (header::COOKIE, editor_cookie.clone()),
+                if_match(post.version),
+            ],
+        )
+        .await;
+    let update_status = update.status();
+    let updated: PostResponse = app.parse_response(update).await;
+    let delete = app
+        .call_with_headers(
+            &uri,
+            Method::Delete,
+            None,
+            [
+                (header::COOKIE, editor_cookie.clone()),
+                if_match(updated.version),
+            ],
+        )
+        .await;
+    let export = app
+        .call_with_cookie("/admin/export", Method::Get, None, Some(&editor_cookie))
+        .await;
+
+    // Assert
+    assert_eq!(update_status, StatusCode::OK);
+    assert_eq!(delete.status(), StatusCode::NO_CONTENT);
+    assert_eq!(export.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_readers_cannot_write() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, reader_cookie) = app.login_as("rita@example.com", Role::Reader).await;
+
+    // Act
+    let create = app
+        .call_with_cookie(
+            "/posts",
+            Method::Post,
+            Some(post_body("Not allowed")),
+            Some(&reader_cookie),
+        )
+        .await;
+    let read = app
+        .call_with_cookie("/posts", Method::Get, None, Some(&reader_cookie))
+        .await;
+
+    // Assert
+    assert_eq!(create.status(), StatusCode::FORBIDDEN);
+    assert_eq!(read.status(), StatusCode::OK);
+}
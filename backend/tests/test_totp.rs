@@ -0,0 +1,196 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::{
+    api::totp::{RecoveryCodesResponse, TotpEnrollmentResponse},
+    domain::models::totp::{MAX_SECOND_FACTOR_ATTEMPTS, TOTP_PERIOD, TotpSecret},
+};
+use chrono::Utc;
+use common::{Method, TEST_USER_EMAIL, TEST_USER_PASSWORD, TestApp, session_cookie};
+use serde_json::json;
+
+/// Decodes the base32 secret shown to users, as an authenticator app would.
+fn decode_base32(encoded: &str) -> Vec<u8> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in encoded.bytes() {
+        let value = ALPHABET.iter().position(|&a| a == c).unwrap() as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    bytes
+}
+
+fn current_step() -> i64 {
+    Utc::now().timestamp() / TOTP_PERIOD
+}
+
+/// Enrolls the logged in test admin, confirming with the code of the current
+/// step, and returns the secret and recovery codes.
+async fn enroll(app: &TestApp) -> (TotpSecret, Vec<String>, i64) {
+    let resp = app.call("/auth/totp/enroll", Method::Post, None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let enrollment: TotpEnrollmentResponse = app.parse_response(resp).await;
+    assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"));
+
+    let secret = TotpSecret::new(&decode_base32(&enrollment.secret));
+    let step = current_step();
+    let code = secret.code_at(step);
+    let resp = app
+        .call(
+            "/auth/totp/confirm",
+            Method::Post,
+            Some(json!({ "code": code.as_str() })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let RecoveryCodesResponse { recovery_codes } = app.parse_response(resp).await;
+
+    (secret, recovery_codes, step)
+}
+
+async fn login_with(app: &TestApp, second_factor: serde_json::Value) -> StatusCode {
+    let mut body = json!({ "email": TEST_USER_EMAIL, "password": TEST_USER_PASSWORD });
+    body.as_object_mut()
+        .unwrap()
+        .extend(second_factor.as_object().unwrap().clone());
+
+    app.call_anonymous("/auth/login", Method::Post, Some(body))
+        .await
+        .status()
+}
+
+#[tokio::test]
+async fn test_password_login_waits_for_code_after_enrollment() {
+    // Arrange
+    let app = TestApp::new().await;
+    let (secret, recovery_codes, step) = enroll(&app).await;
+
+    // Act
+    let login_resp = app.login(TEST_USER_EMAIL, TEST_USER_PASSWORD).await;
+    let login_status = login_resp.status();
+    let cookie = session_cookie(&login_resp).unwrap();
+    let pending_me = app
+        .call_with_cookie("/auth/me", Method::Get, None, Some(&cookie))
+        .await;
+    let verify_resp = app
+        .call_with_cookie(
+            "/auth/totp/verify",
+            Method::Post,
+            Some(json!({ "totp_code": secret.code_at(step + 1).as_str() })),
+            Some(&cookie),
+        )
+        .await;
+    let me = app
+        .call_with_cookie("/auth/me", Method::Get, None, Some(&cookie))
+        .await;
+
+    // Assert
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(login_status, StatusCode::ACCEPTED);
+    assert_eq!(pending_me.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(verify_resp.status(), StatusCode::OK);
+    assert_eq!(me.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_totp_codes_cannot_be_replayed() {
+    // Arrange
+    let app = TestApp::new().await;
+    let (secret, _, step) = enroll(&app).await;
+    let next_code = secret.code_at(step + 1);
+
+    // Act
+    let confirm_code_status =
+        login_with(&app, json!({ "totp_code": secret.code_at(step).as_str() })).await;
+    let first_status = login_with(&app, json!({ "totp_code": next_code.as_str() })).await;
+    let replay_status = login_with(&app, json!({ "totp_code": next_code.as_str() })).await;
+
+    // Assert
+    assert_eq!(confirm_code_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(first_status, StatusCode::OK);
+    assert_eq!(replay_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_recovery_codes_are_single_use() {
+    // Arrange
+    let app = TestApp::new().await;
+    let (_, recovery_codes, _) = enroll(&app).await;
+    let recovery_code = recovery_codes[0].to_uppercase();
+
+    // Act
+    let first_status = login_with(&app, json!({ "recovery_code": recovery_code })).await;
+    let second_status = login_with(&app, json!({ "recovery_code": recovery_code })).await;
+    let wrong_status = login_with(&app, json!({ "recovery_code": "aaaaa-aaaaa" })).await;
+
+    // Assert
+    assert_eq!(first_status, StatusCode::OK);
+    assert_eq!(second_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(wrong_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_repeated_wrong_codes_lock_out_the_right_one() {
+    // Arrange
+    let app = TestApp::new().await;
+    let (secret, recovery_codes, step) = enroll(&app).await;
+    let wrong_code = secret.code_at(step + 100);
+    for _ in 0..MAX_SECOND_FACTOR_ATTEMPTS {
+        login_with(&app, json!({ "totp_code": wrong_code.as_str() })).await;
+    }
+
+    // Act
+    let code_status = login_with(
+        &app,
+        json!({ "totp_code": secret.code_at(step + 1).as_str() }),
+    )
+    .await;
+    let recovery_status = login_with(&app, json!({ "recovery_code": recovery_codes[0] })).await;
+
+    // Assert
+    assert_eq!(code_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(recovery_status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_enrolling_again_after_confirmation_conflicts() {
+    // Arrange
+    let app = TestApp::new().await;
+    enroll(&app).await;
+
+    // Act
+    let resp = app.call("/auth/totp/enroll", Method::Post, None).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_confirm_rejects_wrong_code() {
+    // Arrange
+    let app = TestApp::new().await;
+    app.call("/auth/totp/enroll", Method::Post, None).await;
+
+    // Act
+    let resp = app
+        .call(
+            "/auth/totp/confirm",
+            Method::Post,
+            Some(json!({ "code": "000000" })),
+        )
+        .await;
+    let login_status = login_with(&app, json!({})).await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(login_status, StatusCode::OK);
+}
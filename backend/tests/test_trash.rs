@@ -0,0 +1,209 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use backend::{
+    api::post::{BulkPostResponse, PostResponse},
+    domain::{models::user::Role, service::Service},
+};
+use common::{Method, TestApp, if_match};
+use serde_json::json;
+
+async fn create_post(app: &TestApp, cookie: &str, title: &str) -> PostResponse {
+    let resp = app
+        .call_with_cookie(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body" })),
+            Some(cookie),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+async fn trash(app: &TestApp, cookie: &str) -> Vec<PostResponse> {
+    let resp = app
+        .call_with_cookie("/trash", Method::Get, None, Some(cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response::<BulkPostResponse>(resp).await.data
+}
+
+/// Deletes `post`, moving it to the trash unless `permanent`.
+async fn delete(app: &TestApp, cookie: &str, post: &PostResponse, permanent: bool) -> StatusCode {
+    app.call_with_headers(
+        &format!("/posts/{}?permanent={permanent}", post.id),
+        Method::Delete,
+        None,
+        [(header::COOKIE, cookie.to_string()), if_match(post.version)],
+    )
+    .await
+    .status()
+}
+
+/// Runs `sql` against the tables the app uses, bypassing the API.
+async fn execute(app: &TestApp, sql: &str) -> Result<(), sqlx::Error> {
+    let mut conn = app.fixture().pool.acquire().await?;
+    // The fixture points its pool at a scratch schema; the app uses the
+    // default one.
+    sqlx::query("RESET search_path").execute(&mut *conn).await?;
+    sqlx::query(sql).execute(&mut *conn).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deleted_post_moves_to_trash() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("editor@example.com", Role::Editor).await;
+    let post = create_post(&app, &cookie, "Trashed").await;
+    let uri = format!("/posts/{}", post.id);
+
+    // Act
+    let delete = delete(&app, &cookie, &post, false).await;
+    let get = app
+        .call_with_cookie(&uri, Method::Get, None, Some(&cookie))
+        .await;
+    let list = app
+        .call_with_cookie("/posts", Method::Get, None, Some(&cookie))
+        .await;
+    let list: BulkPostResponse = app.parse_response(list).await;
+    let trashed = trash(&app, &cookie).await;
+
+    // Assert
+    assert_eq!(delete, StatusCode::NO_CONTENT);
+    assert_eq!(get.status(), StatusCode::NOT_FOUND);
+    assert!(list.data.is_empty());
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].id, post.id);
+    assert!(trashed[0].deleted_at.is_some());
+}
+
+#[tokio::test]
+async fn test_restore_post_brings_it_back() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("editor@example.com", Role::Editor).await;
+    let post = create_post(&app, &cookie, "Restored").await;
+    let uri = format!("/posts/{}", post.id);
+    delete(&app, &cookie, &post, false).await;
+
+    // Act
+    let restore = app
+        .call_with_cookie(&format!("{uri}/restore"), Method::Post, None, Some(&cookie))
+        .await;
+    let restore_status = restore.status();
+    let restored: PostResponse = app.parse_response(restore).await;
+    let get = app
+        .call_with_cookie(&uri, Method::Get, None, Some(&cookie))
+        .await;
+
+    // Assert
+    assert_eq!(restore_status, StatusCode::OK);
+    assert_eq!(restored.title, "Restored");
+    assert!(restored.deleted_at.is_none());
+    assert_eq!(get.status(), StatusCode::OK);
+    assert!(trash(&app, &cookie).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_restore_post_conflicts_with_reused_title() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("editor@example.com", Role::Editor).await;
+    let post = create_post(&app, &cookie, "Taken").await;
+    let uri = format!("/posts/{}", post.id);
+    delete(&app, &cookie, &post, false).await;
+    create_post(&app, &cookie, "Taken").await;
+
+    // Act
+    let restore = app
+        .call_with_cookie(&format!("{uri}/restore"), Method::Post, None, Some(&cookie))
+        .await;
+
+    // Assert
+    assert_eq!(restore.status(), StatusCode::CONFLICT);
+    assert_eq!(trash(&app, &cookie).await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_restore_post_forbidden_for_other_authors() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, alice) = app.login_as("alice@example.com", Role::Author).await;
+    let (_, bob) = app.login_as("bob@example.com", Role::Author).await;
+    let post = create_post(&app, &alice, "Alice's post").await;
+    let uri = format!("/posts/{}", post.id);
+    delete(&app, &alice, &post, false).await;
+
+    // Act
+    let restore = app
+        .call_with_cookie(&format!("{uri}/restore"), Method::Post, None, Some(&bob))
+        .await;
+
+    // Assert
+    assert_eq!(restore.status(), StatusCode::FORBIDDEN);
+    assert!(trash(&app, &bob).await.is_empty());
+    assert_eq!(trash(&app, &alice).await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_permanent_delete_skips_and_empties_trash() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("editor@example.com", Role::Editor).await;
+    let live = create_post(&app, &cookie, "Live").await;
+    let trashed = create_post(&app, &cookie, "Trashed").await;
+    delete(&app, &cookie, &trashed, false).await;
+    // Moving it to the trash was a change too.
+    let trashed = trash(&app, &cookie).await.remove(0);
+
+    // Act
+    let mut statuses = Vec::new();
+    for post in [&live, &trashed] {
+        statuses.push(delete(&app, &cookie, post, true).await);
+    }
+    let restore = app
+        .call_with_cookie(
+            &format!("/posts/{}/restore", trashed.id),
+            Method::Post,
+            None,
+            Some(&cookie),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(statuses, [StatusCode::NO_CONTENT, StatusCode::NO_CONTENT]);
+    assert_eq!(restore.status(), StatusCode::NOT_FOUND);
+    assert!(trash(&app, &cookie).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_purge_removes_only_expired_posts() {
+    // Arrange
+    let app = TestApp::anonymous().await;
+    let (_, cookie) = app.login_as("editor@example.com", Role::Editor).await;
+    let expired = create_post(&app, &cookie, "Expired").await;
+    let recent = create_post(&app, &cookie, "Recent").await;
+    for post in [&expired, &recent] {
+        delete(&app, &cookie, post, false).await;
+    }
+    execute(
+        &app,
+        &format!(
+            "UPDATE posts SET deleted_at = now() - INTERVAL '31 days' WHERE id = '{}'",
+            expired.id
+        ),
+    )
+    .await
+    .unwrap();
+
+    // Act
+    let purged = app.fixture().service.purge_trash().await.unwrap();
+
+    // Assert
+    assert_eq!(purged, 1);
+    let remaining = trash(&app, &cookie).await;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, recent.id);
+}
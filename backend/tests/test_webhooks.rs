@@ -0,0 +1,406 @@
+mod common;
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU16, Ordering},
+};
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
+use backend::api::webhook::{
+    BulkWebhookDeliveryResponse, BulkWebhookResponse, CreatedWebhookResponse,
+    WebhookDeliveryResponse,
+};
+use backend::api::{post::PostResponse, responses::ProblemDetails};
+use backend::domain::{
+    models::{
+        user::Role,
+        webhook::{WebhookRetryPolicy, WebhookSecret},
+    },
+    service::Service,
+};
+use backend::service::webhooks::{
+    WEBHOOK_EVENT_HEADER, WEBHOOK_ID_HEADER, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER,
+};
+use chrono::Duration;
+use common::{Method, TestApp};
+use serde_json::{Value, json};
+
+/// A webhook receiver on a local port, answering every request with the
+/// current `status`.
+#[derive(Clone)]
+struct Receiver {
+    url: String,
+    status: Arc<AtomicU16>,
+    requests: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+}
+
+impl Receiver {
+    async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let receiver = Self {
+            url: format!("http://{}/hooks", listener.local_addr().unwrap()),
+            status: Arc::new(AtomicU16::new(200)),
+            requests: Arc::default(),
+        };
+
+        let router = Router::new()
+            .route("/hooks", post(receive))
+            .with_state(receiver.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        receiver
+    }
+
+    fn respond_with(&self, status: StatusCode) {
+        self.status.store(status.as_u16(), Ordering::SeqCst);
+    }
+
+    fn requests(&self) -> Vec<(HeaderMap, Bytes)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    receiver.requests.lock().unwrap().push((headers, body));
+    StatusCode::from_u16(receiver.status.load(Ordering::SeqCst)).unwrap()
+}
+
+async fn create_webhook(app: &TestApp, url: &str, events: &[&str]) -> CreatedWebhookResponse {
+    let resp = app
+        .call(
+            "/webhooks",
+            Method::Post,
+            Some(json!({ "url": url, "events": events })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.parse_response(resp).await
+}
+
+/// Creates a post and relays the outbox, queueing deliveries for it.
+async fn create_post(app: &TestApp, title: &str) -> PostResponse {
+    let resp = app
+        .call(
+            "/posts",
+            Method::Post,
+            Some(json!({ "title": title, "body": "Body" })),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    app.fixture().service.dispatch_outbox().await.unwrap();
+    app.parse_response(resp).await
+}
+
+async fn deliveries(
+    app: &TestApp,
+    webhook: &CreatedWebhookResponse,
+) -> Vec<WebhookDeliveryResponse> {
+    let resp = app
+        .call(
+            &format!("/webhooks/{}/deliveries", webhook.webhook.id),
+            Method::Get,
+            None,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    app.parse_response::<BulkWebhookDeliveryResponse>(resp)
+        .await
+        .data
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers.get(name).unwrap().to_str().unwrap()
+}
+
+#[tokio::test]
+async fn test_post_changes_are_delivered_signed_to_subscribed_webhooks() {
+    // Arrange
+    let app = TestApp::new().await;
+    let receiver = Receiver::start().await;
+    let webhook = create_webhook(&app, &receiver.url, &["post.created"]).await;
+    let created = create_post(&app, "Hello webhooks").await;
+
+    // Act
+    let attempted = app.fixture().service.deliver_webhooks().await.unwrap();
+
+    // Assert
+    assert_eq!(attempted, 1);
+    let requests = receiver.requests();
+    assert_eq!(requests.len(), 1);
+
+    let (headers, body) = &requests[0];
+    let timestamp: i64 = header(headers, WEBHOOK_TIMESTAMP_HEADER).parse().unwrap();
+    let signature = WebhookSecret::new(&webhook.secret).sign(timestamp, body);
+    assert_eq!(
+        header(headers, WEBHOOK_SIGNATURE_HEADER),
+        format!("v1={signature}")
+    );
+    assert_eq!(header(headers, WEBHOOK_EVENT_HEADER), "post.created");
+
+    let payload: Value = serde_json::from_slice(body).unwrap();
+    assert_eq!(payload["type"], "post.created");
+    let post: PostResponse = serde_json::from_value(payload["data"].clone()).unwrap();
+    assert_eq!(post, created);
+
+    let log = deliveries(&app, &webhook).await;
+    assert_eq!(log.len(), 1);
+    assert_eq!(header(headers, WEBHOOK_ID_HEADER), log[0].id.to_string());
+    assert_eq!(log[0].status, "delivered");
+    assert_eq!(log[0].attempts, 1);
+    assert_eq!(log[0].last_response_status, Some(200));
+    assert!(log[0].delivered_at.is_some());
+}
+
+#[tokio::test]
+async fn test_failing_deliveries_are_retried_until_dead_and_can_be_redelivered() {
+    // Arrange
+    let app = TestApp::new().await;
+    let receiver = Receiver::start().await;
+    receiver.respond_with(StatusCode::INTERNAL_SERVER_ERROR);
+    let webhook = create_webhook(&app, &receiver.url, &["post.created"]).await;
+    create_post(&app, "Retried").await;
+    let service = app
+        .fixture()
+        .service
+        .clone()
+        .with_webhook_retry_policy(WebhookRetryPolicy::new(2, Duration::zero()));
+
+    // Act
+    service.deliver_webhooks().await.unwrap();
+    let retrying = deliveries(&app, &webhook).await;
+    service.deliver_webhooks().await.unwrap();
+    let dead = deliveries(&app, &webhook).await;
+    let exhausted = service.deliver_webhooks().await.unwrap();
+
+    receiver.respond_with(StatusCode::NO_CONTENT);
+    let redeliver_resp = app
+        .call(
+            &format!(
+                "/webhooks/{}/deliveries/{}/redeliver",
+                webhook.webhook.id, dead[0].id
+            ),
+            Method::Post,
+            None,
+        )
+        .await;
+    service.deliver_webhooks().await.unwrap();
+    let delivered = deliveries(&app, &webhook).await;
+
+    // Assert
+    assert_eq!(retrying[0].status, "pending");
+    assert_eq!(retrying[0].attempts, 1);
+    assert_eq!(retrying[0].last_response_status, Some(500));
+
+    assert_eq!(dead[0].status, "dead");
+    assert_eq!(dead[0].attempts, 2);
+    assert_eq!(dead[0].next_attempt_at, None);
+    assert_eq!(exhausted, 0);
+
+    assert_eq!(redeliver_resp.status(), StatusCode::ACCEPTED);
+    assert_eq!(delivered[0].status, "delivered");
+    assert_eq!(delivered[0].last_response_status, Some(204));
+
+    // Every attempt sends the same payload, under the same delivery id.
+    let requests = receiver.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|(_, body)| body == &requests[0].1));
+    assert!(
+        requests
+            .iter()
+            .all(|(headers, _)| header(headers, WEBHOOK_ID_HEADER) == dead[0].id.to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_deliveries_being_sent_are_not_redelivered() {
+    // Arrange
+    let app = TestApp::new().await;
+    let receiver = Receiver::start().await;
+    let webhook = create_webhook(&app, &receiver.url, &["post.created"]).await;
+    create_post(&app, "In flight").await;
+    let delivery = &deliveries(&app, &webhook).await[0];
+    // As if an instance had claimed it and were still waiting for a response.
+    sqlx::query("UPDATE webhook_deliveries SET leased_until = now() + interval '1 minute'")
+        .execute(&app.fixture().pool)
+        .await
+        .unwrap();
+
+    // Act
+    let resp = app
+        .call(
+            &format!(
+                "/webhooks/{}/deliveries/{}/redeliver",
+                webhook.webhook.id, delivery.id
+            ),
+            Method::Post,
+            None,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let problem: ProblemDetails = app.parse_response(resp).await;
+    assert_eq!(problem.code, "webhook_delivery_in_progress");
+}
+
+#[tokio::test]
+async fn test_finished_deliveries_are_purged_after_their_retention() {
+    // Arrange
+    let app = TestApp::new().await;
+    let receiver = Receiver::start().await;
+    let webhook = create_webhook(&app, &receiver.url, &["post.created"]).await;
+    create_post(&app, "Delivered").await;
+    app.fixture().service.deliver_webhooks().await.unwrap();
+    create_post(&app, "Pending").await;
+    let service = app
+        .fixture()
+        .service
+        .clone()
+        .with_webhook_delivery_retention(Duration::zero());
+
+    // Act
+    let purged = service.purge_webhook_deliveries().await.unwrap();
+
+    // Assert
+    assert_eq!(purged, 1);
+    let log = deliveries(&app, &webhook).await;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].status, "pending");
+}
+
+#[tokio::test]
+async fn test_unreachable_receivers_are_recorded_as_failed_attempts() {
+    // Arrange
+    let app = TestApp::new().await;
+    // Nothing listens on the port once the listener is dropped.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    drop(listener);
+    let webhook = create_webhook(&app, &url, &["post.created"]).await;
+    create_post(&app, "Nobody home").await;
+
+    // Act
+    app.fixture().service.deliver_webhooks().await.unwrap();
+
+    // Assert
+    let log = deliveries(&app, &webhook).await;
+    assert_eq!(log[0].status, "pending");
+    assert_eq!(log[0].last_response_status, None);
+    assert!(log[0].last_error.is_some());
+    assert!(log[0].next_attempt_at.is_some());
+}
+
+#[tokio::test]
+async fn test_webhooks_only_receive_the_events_they_subscribed_to() {
+    // Arrange
+    let app = TestApp::new().await;
+    let receiver = Receiver::start().await;
+    let webhook = create_webhook(&app, &receiver.url, &["post.deleted"]).await;
+
+    // Act
+    create_post(&app, "Not for this webhook").await;
+    let attempted = app.fixture().service.deliver_webhooks().await.unwrap();
+
+    // Assert
+    assert_eq!(attempted, 0);
+    assert!(deliveries(&app, &webhook).await.is_empty());
+    assert!(receiver.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_webhook_endpoints_manage_subscriptions() {
+    // Arrange
+    let app = TestApp::new().await;
+    let webhook = create_webhook(
+        &app,
+        "https://example.com/hooks",
+        &["post.created", "post.deleted"],
+    )
+    .await;
+
+    // Act
+    let list_resp = app.call("/webhooks", Method::Get, None).await;
+    let list: BulkWebhookResponse = app.parse_response(list_resp).await;
+    let delete_resp = app
+        .call(
+            &format!("/webhooks/{}", webhook.webhook.id),
+            Method::Delete,
+            None,
+        )
+        .await;
+    let deleted_log_resp = app
+        .call(
+            &format!("/webhooks/{}/deliveries", webhook.webhook.id),
+            Method::Get,
+            None,
+        )
+        .await;
+
+    // Assert
+    assert!(webhook.secret.starts_with("whsec_"));
+    assert_eq!(webhook.webhook.events, vec!["post.created", "post.deleted"]);
+    assert_eq!(list.data, vec![webhook.webhook.clone()]);
+    assert_eq!(delete_resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(deleted_log_resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_webhook_endpoint_rejects_invalid_requests() {
+    // Arrange
+    let app = TestApp::new().await;
+    let (_, editor_cookie) = app.login_as("editor@example.com", Role::Editor).await;
+
+    // Act
+    let bad_url = app
+        .call(
+            "/webhooks",
+            Method::Post,
+            Some(json!({ "url": "ftp://example.com", "events": ["post.created"] })),
+        )
+        .await;
+    let unknown_event = app
+        .call(
+            "/webhooks",
+            Method::Post,
+            Some(json!({ "url": "https://example.com", "events": ["post.published"] })),
+        )
+        .await;
+    let no_events = app
+        .call(
+            "/webhooks",
+            Method::Post,
+            Some(json!({ "url": "https://example.com", "events": [] })),
+        )
+        .await;
+    let private = app
+        .call(
+            "/webhooks",
+            Method::Post,
+            Some(json!({
+                "url": "http://169.254.169.254/latest/meta-data",
+                "events": ["post.created"]
+            })),
+        )
+        .await;
+    let by_editor = app
+        .call_with_cookie(
+            "/webhooks",
+            Method::Post,
+            Some(json!({ "url": "https://example.com", "events": ["post.created"] })),
+            Some(&editor_cookie),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(bad_url.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(unknown_event.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(no_events.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(private.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(by_editor.status(), StatusCode::FORBIDDEN);
+}